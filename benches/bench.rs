@@ -3,7 +3,7 @@
 extern crate test;
 use test::Bencher;
 
-use simple_config_parser::Config;
+use simple_config_parser::{Config, ConfigRef};
 
 pub fn parse_string() -> Option<()> {
     let cfg = Config::new().text("hello = world ; Comment").ok()?;
@@ -52,6 +52,12 @@ pub fn parse_string_get_float() -> Option<()> {
     Some(())
 }
 
+pub fn parse_string_get_borrowed() -> Option<()> {
+    let cfg = ConfigRef::parse("hello = world ; Comment").ok()?;
+    cfg.get_str("hello").unwrap();
+    Some(())
+}
+
 #[bench]
 /// Basic config parsing benchmark.
 fn bench_parse(b: &mut Bencher) {
@@ -64,6 +70,12 @@ fn bench_parse_get(b: &mut Bencher) {
     b.iter(|| parse_string_get());
 }
 
+#[bench]
+/// Parse string and get value without copying it.
+fn bench_parse_get_borrowed(b: &mut Bencher) {
+    b.iter(parse_string_get_borrowed);
+}
+
 #[bench]
 /// Parse messy config string
 fn bench_parse_messy(b: &mut Bencher) {
@@ -93,3 +105,27 @@ fn bench_parse_get_int(b: &mut Bencher) {
 fn bench_parse_get_float(b: &mut Bencher) {
     b.iter(|| parse_string_get_float());
 }
+
+/// A generated config with a few thousand lines, sections and comments
+fn large_config() -> String {
+    let mut out = String::from("; Generated config\n");
+    for i in 0..200 {
+        out.push_str(&format!("\n[Section {}]\n", i));
+        for j in 0..10 {
+            out.push_str(&format!(
+                "Key Name {} = value {} ; comment {}\n",
+                j,
+                i * j,
+                j
+            ));
+        }
+    }
+    out
+}
+
+#[bench]
+/// Parse a large config
+fn bench_parse_large(b: &mut Bencher) {
+    let text = large_config();
+    b.iter(|| Config::new().text(&text).unwrap());
+}
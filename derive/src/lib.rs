@@ -0,0 +1,255 @@
+//! Derive macros for `simple_config_parser::FromConfig` and `simple_config_parser::ConfigEnum`
+//!
+//! Use them through the `derive` feature of `simple_config_parser`, see the `FromConfig` and `ConfigEnum` traits for docs.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Expr, Fields, LitStr, Type};
+
+/// Derive `FromConfig` for a struct with named fields
+#[proc_macro_derive(FromConfig, attributes(config))]
+pub fn derive_from_config(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(input) {
+        Ok(i) => i.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Derive `ConfigEnum` and `FromConfigValue` for an enum with unit variants
+#[proc_macro_derive(ConfigEnum, attributes(config))]
+pub fn derive_config_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand_enum(input) {
+        Ok(i) => i.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Options from a `#[config(...)]` attribute
+#[derive(Default)]
+struct Options {
+    rename: Option<LitStr>,
+    section: Option<LitStr>,
+    default: Option<Option<Expr>>,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input,
+                    "FromConfig can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input,
+                "FromConfig can only be derived for structs",
+            ))
+        }
+    };
+
+    let container = options(&input.attrs)?;
+    if container.rename.is_some() || container.default.is_some() {
+        return Err(syn::Error::new_spanned(
+            &input,
+            "only `section` can be used on the struct",
+        ));
+    }
+
+    let mut reads = Vec::new();
+    let mut names = Vec::new();
+    for field in fields.iter() {
+        let ident = field.ident.as_ref().unwrap();
+        let opts = options(&field.attrs)?;
+        let field_name = ident.to_string();
+        let key = match &opts.rename {
+            Some(i) => i.value(),
+            None => field_name.clone(),
+        };
+        let section = match opts.section.as_ref().or(container.section.as_ref()) {
+            Some(i) => quote!(Some(#i)),
+            None => quote!(None),
+        };
+
+        let read = match (&opts.default, option_inner(&field.ty)) {
+            (Some(Some(default)), _) => quote!(field.get_or(&mut errors, || #default)),
+            (Some(None), _) => quote!(field.get_or(&mut errors, Default::default)),
+            (None, Some(_)) => quote!(field.get_opt(&mut errors)),
+            (None, None) => quote!(field.get(&mut errors)),
+        };
+
+        let var = quote::format_ident!("__field_{}", ident);
+        reads.push(quote! {
+            let #var = {
+                let field = ::simple_config_parser::__private::Field {
+                    cfg,
+                    field: #field_name,
+                    section: #section,
+                    key: #key,
+                };
+                #read
+            };
+        });
+        names.push((ident, var));
+    }
+
+    let build = names
+        .iter()
+        .map(|(ident, var)| quote!(#ident: #var.unwrap()));
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::simple_config_parser::FromConfig for #name #ty_generics #where_clause {
+            fn from_config(
+                cfg: &::simple_config_parser::Config,
+            ) -> ::std::result::Result<Self, ::simple_config_parser::FromConfigError> {
+                let mut errors = ::std::vec::Vec::new();
+                #(#reads)*
+
+                if !errors.is_empty() {
+                    return ::std::result::Result::Err(::simple_config_parser::FromConfigError {
+                        fields: errors,
+                    });
+                }
+
+                ::std::result::Result::Ok(Self {
+                    #(#build),*
+                })
+            }
+        }
+    })
+}
+
+fn expand_enum(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let variants = match &input.data {
+        Data::Enum(data) => &data.variants,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input,
+                "ConfigEnum can only be derived for enums",
+            ))
+        }
+    };
+
+    let mut names = Vec::new();
+    let mut idents = Vec::new();
+    for variant in variants.iter() {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "ConfigEnum can only be derived for enums with unit variants",
+            ));
+        }
+
+        let opts = options(&variant.attrs)?;
+        if opts.section.is_some() || opts.default.is_some() {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "only `rename` can be used on variants",
+            ));
+        }
+        names.push(match &opts.rename {
+            Some(i) => i.value(),
+            None => snake_case(&variant.ident.to_string()),
+        });
+        idents.push(&variant.ident);
+    }
+
+    let index = 0..idents.len();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::simple_config_parser::ConfigEnum for #name #ty_generics #where_clause {
+            const VARIANTS: &'static [&'static str] = &[#(#names),*];
+
+            fn from_variant(index: usize) -> ::std::option::Option<Self> {
+                match index {
+                    #(#index => ::std::option::Option::Some(Self::#idents),)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+        }
+
+        impl #impl_generics ::simple_config_parser::FromConfigValue for #name #ty_generics #where_clause {
+            fn from_config_value(
+                value: &str,
+            ) -> ::std::result::Result<Self, ::simple_config_parser::ConfigError> {
+                <Self as ::simple_config_parser::ConfigEnum>::from_name(value)
+            }
+        }
+    })
+}
+
+/// Convert the name of a variant to `snake_case`, so `LogLevel` is `log_level` and `HTTPServer` is `http_server`
+fn snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut out = String::new();
+    for (n, i) in chars.iter().enumerate() {
+        let prev = n.checked_sub(1).map(|n| chars[n]);
+        let next = chars.get(n + 1);
+        let starts_word = i.is_uppercase()
+            && prev.is_some_and(|p| {
+                p.is_lowercase()
+                    || p.is_ascii_digit()
+                    || (p.is_uppercase() && next.is_some_and(|i| i.is_lowercase()))
+            });
+        if starts_word && prev != Some('_') {
+            out.push('_');
+        }
+        out.extend(i.to_lowercase());
+    }
+
+    out
+}
+
+/// Parse the `#[config(...)]` attributes
+fn options(attrs: &[syn::Attribute]) -> syn::Result<Options> {
+    let mut out = Options::default();
+    for attr in attrs.iter().filter(|i| i.path().is_ident("config")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                out.rename = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("section") {
+                out.section = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("default") {
+                match meta.input.peek(syn::Token![=]) {
+                    true => out.default = Some(Some(meta.value()?.parse()?)),
+                    false => out.default = Some(None),
+                }
+            } else {
+                return Err(meta.error("unknown config attribute"));
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(out)
+}
+
+/// Get the `T` of an `Option<T>` type
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let path = match ty {
+        Type::Path(i) if i.qself.is_none() => &i.path,
+        _ => return None,
+    };
+
+    let last = path.segments.last()?;
+    if last.ident != "Option" {
+        return None;
+    }
+
+    match &last.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            syn::GenericArgument::Type(i) => Some(i),
+            _ => None,
+        },
+        _ => None,
+    }
+}
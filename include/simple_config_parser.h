@@ -0,0 +1,39 @@
+/*
+ * C bindings for simple_config_parser, built with the `ffi` feature.
+ * Written from src/ffi.rs by `test_ffi_header` in tests/ffi.rs, see src/ffi.rs for how each function behaves.
+ */
+#ifndef SIMPLE_CONFIG_PARSER_H
+#define SIMPLE_CONFIG_PARSER_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Error codes */
+#define SCP_OK 0
+#define SCP_NO_ITEM 1
+#define SCP_BUFFER_TOO_SMALL 2
+#define SCP_INVALID_ARGUMENT 3
+#define SCP_ERROR 4
+#define SCP_IO_ERROR 5
+#define SCP_PANIC -1
+
+/* Opaque config handle, freed with scp_config_free */
+typedef struct ScpConfig ScpConfig;
+
+ScpConfig *scp_config_new(void);
+ScpConfig *scp_config_file(const char *path);
+ScpConfig *scp_config_text(const char *text);
+int scp_config_load_file(ScpConfig *config, const char *path);
+char *scp_config_get_str(const ScpConfig *config, const char *key);
+void scp_str_free(char *text);
+int scp_config_get(const ScpConfig *config, const char *key, char *buf, size_t len);
+void scp_config_free(ScpConfig *config);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
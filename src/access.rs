@@ -0,0 +1,68 @@
+//! Tracking the keys that are read, to find options nothing uses
+use std::sync::{Mutex, MutexGuard};
+
+use crate::config::Entry;
+use crate::Config;
+
+impl Config {
+    /// Record the keys that are read, see `Config::accessed_keys` and `Config::unused_keys`
+    ///
+    /// Keys are recorded by the getters (`get`, `get_str`...), even if their value is not valid.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .track_access(true)
+    ///     .text("port = 80\n[cache]\nsize = 10\nlegacy_mode = true")
+    ///     .unwrap();
+    ///
+    /// cfg.get::<u16>("port").unwrap();
+    /// cfg.get_str("cache.size").unwrap();
+    ///
+    /// assert_eq!(cfg.accessed_keys(), ["port", "cache.size"]);
+    /// for i in cfg.unused_keys() {
+    ///     eprintln!("[WARN] Config key `{}` is not used", i);
+    /// }
+    /// # assert_eq!(cfg.unused_keys(), ["cache.legacy_mode"]);
+    /// ```
+    pub fn track_access(mut self, track: bool) -> Self {
+        self.accessed = track.then(|| Mutex::new(Vec::new()));
+        self
+    }
+
+    /// Get the dotted names of the keys that have been read, in the order they were first read
+    ///
+    /// Always empty if `Config::track_access` is off.
+    pub fn accessed_keys(&self) -> Vec<String> {
+        self.accessed().map(|i| i.clone()).unwrap_or_default()
+    }
+
+    /// Get the dotted names of the keys that are defined but have not been read, in the order of `Config::iter_effective`
+    ///
+    /// Every key is listed if `Config::track_access` is off.
+    pub fn unused_keys(&self) -> Vec<String> {
+        let accessed = self.accessed_keys();
+        self.iter_effective()
+            .map(Entry::name)
+            .filter(|i| !accessed.contains(i))
+            .collect()
+    }
+
+    /// Note that an entry has been read, if keys are tracked
+    pub(crate) fn record_access(&self, entry: &Entry) {
+        if let Some(mut accessed) = self.accessed() {
+            let name = entry.name();
+            if !accessed.contains(&name) {
+                accessed.push(name);
+            }
+        }
+    }
+
+    /// Get the keys that have been read, even if a thread panicked while recording one
+    fn accessed(&self) -> Option<MutexGuard<'_, Vec<String>>> {
+        let accessed = self.accessed.as_ref()?;
+        Some(accessed.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
@@ -0,0 +1,71 @@
+//! Anchors for values used more than once
+//!
+//! A value starting with `&name ` defines the anchor `name` as the rest of the value,
+//! and a value of just `*name` is replaced with the value of the anchor.
+use std::collections::HashMap;
+
+use crate::config::Entry;
+use crate::{Config, ConfigError};
+
+impl Config {
+    /// Resolve anchors (`&name value`) and aliases (`*name`) in the values of sources loaded after this
+    ///
+    /// An anchor can be used by any later value of the same source (and the files it includes),
+    /// defining it again changes the value of later aliases.
+    /// Returns `ConfigError::UnknownAnchor` if an alias is for an anchor that has not been defined yet.
+    /// Saving the config keeps the anchors and aliases in values that have not been changed.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, ConfigError};
+    ///
+    /// let cfg = Config::new()
+    ///     .anchors(true)
+    ///     .text("[read]\ntimeout = &timeout 30s\n[write]\ntimeout = *timeout")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get_str("read.timeout").unwrap(), "30s");
+    /// assert_eq!(cfg.get_str("write.timeout").unwrap(), "30s");
+    ///
+    /// let err = Config::new().anchors(true).text("timeout = *missing");
+    /// assert!(matches!(err, Err(ConfigError::UnknownAnchor(_))));
+    /// ```
+    pub fn anchors(mut self, anchors: bool) -> Self {
+        self.anchors = anchors;
+        self
+    }
+}
+
+/// Check if a name can be an anchor (letters, digits, `_` and `-`)
+fn is_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|i| i.is_ascii_alphanumeric() || i == '_' || i == '-')
+}
+
+/// Define the anchors in a source's entries and replace the aliases with their values
+pub(crate) fn resolve(data: &mut [Entry]) -> Result<(), ConfigError> {
+    let mut anchors = HashMap::new();
+
+    for i in data.iter_mut() {
+        if let Some(rest) = i.value.strip_prefix('&') {
+            let (name, value) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            if is_name(name) {
+                let value = value.trim_start().to_owned();
+                anchors.insert(name.to_owned(), value.to_owned());
+                i.value = value;
+            }
+            continue;
+        }
+
+        if let Some(name) = i.value.strip_prefix('*').filter(|i| is_name(i)) {
+            i.value = anchors
+                .get(name)
+                .ok_or_else(|| ConfigError::UnknownAnchor(name.to_owned()))?
+                .to_owned();
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+//! Loading configs out of tar / zip archives (with the `archive` feature)
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use std::time::Instant;
+
+use crate::{Config, ConfigError};
+
+/// Read a member of a tar archive
+fn read_tar<R: Read>(archive: R, member: &str) -> io::Result<Option<String>> {
+    let mut archive = tar::Archive::new(archive);
+    for i in archive.entries()? {
+        let mut entry = i?;
+        if entry.path()?.as_ref() == Path::new(member) {
+            let mut out = String::new();
+            entry.read_to_string(&mut out)?;
+            return Ok(Some(out));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Read a member of a zip archive
+fn read_zip(archive: File, member: &str) -> io::Result<Option<String>> {
+    let mut archive = zip::ZipArchive::new(archive)?;
+    let mut file = match archive.by_name(member) {
+        Ok(i) => i,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut out = String::new();
+    file.read_to_string(&mut out)?;
+    Ok(Some(out))
+}
+
+/// Read a member of an archive, picking the format from its file name
+fn read_member(file: File, path: &Path, member: &str) -> io::Result<String> {
+    let name = path
+        .file_name()
+        .and_then(|i| i.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    let member = member.trim_start_matches("./");
+
+    let contents = if name.ends_with(".zip") {
+        read_zip(file, member)?
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        #[cfg(feature = "gzip")]
+        {
+            read_tar(flate2::read::GzDecoder::new(file), member)?
+        }
+        #[cfg(not(feature = "gzip"))]
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Reading gzip compressed archives needs the `gzip` feature",
+        ));
+    } else {
+        read_tar(file, member)?
+    };
+
+    contents.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("`{}` is not in the archive", member),
+        )
+    })
+}
+
+impl Config {
+    /// Reads and parses config from a file in a tar or zip archive, without extracting it
+    ///
+    /// Archives ending in `.zip` are read as zip files and everything else as tar files
+    /// (`.tar.gz` / `.tgz` archives need the `gzip` feature too).
+    /// If the archive does not exist `ConfigError::FileNotFound` is returned,
+    /// and if the member is not in it a `ConfigError::Io` with `io::ErrorKind::NotFound`.
+    /// ## Example
+    /// ```rust,no_run
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// // Read the config shipped in a build artifact
+    /// let cfg = Config::from_archive("dist/app.tar", "etc/app.cfg").unwrap();
+    /// ```
+    pub fn from_archive<T>(path: T, member: &str) -> Result<Self, ConfigError>
+    where
+        T: AsRef<Path>,
+    {
+        let start = Instant::now();
+        let file =
+            File::open(path.as_ref()).map_err(|e| ConfigError::file_read(path.as_ref(), e))?;
+        let contents = read_member(file, path.as_ref(), member)?;
+        let mut cfg = Config::new();
+        let data = cfg.parse(contents)?;
+        let source = format!("{}:{}", path.as_ref().display(), member);
+        cfg.add_source(source, start, data)?;
+        Ok(cfg)
+    }
+}
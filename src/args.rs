@@ -0,0 +1,105 @@
+//! Overriding keys with command line arguments
+//!
+//! Arguments like `--server.port=8080` set a key, and a flag without a value (`--debug`) is `true`.
+//! Other arguments are skipped, as are all arguments after `--`.
+use std::env;
+use std::time::Instant;
+
+use crate::config::Entry;
+use crate::{Config, ConfigError};
+
+impl Config {
+    /// Load the `--key=value` arguments the program was run with as a source
+    ///
+    /// The first argument (the program) is skipped, see `Config::args` for how the rest are read.
+    /// Load this after the other sources so the command line has the highest precedence.
+    /// ## Example
+    /// ```rust,no_run
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// // Run as `app --port=8080`
+    /// let cfg = Config::new()
+    ///     .text("port = 80")
+    ///     .unwrap()
+    ///     .env("APP_")
+    ///     .unwrap()
+    ///     .args_overlay()
+    ///     .unwrap();
+    /// ```
+    pub fn args_overlay(self) -> Result<Self, ConfigError> {
+        self.args(env::args().skip(1))
+    }
+
+    /// Load `--key=value` arguments as a source
+    ///
+    /// Keys are not in any section but are found by their dotted name, like JSON keys.
+    /// Arguments that don't start with `--` are skipped, and everything after a `--` argument.
+    /// Load this after the other sources so the arguments have the highest precedence.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .text("[server]\nport = 80\nhost = localhost")
+    ///     .unwrap()
+    ///     .args(["serve", "--server.port=8080", "--verbose", "--", "--debug"])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get::<u16>("server.port").unwrap(), 8080);
+    /// assert_eq!(cfg.get_str("server.host").unwrap(), "localhost");
+    /// assert!(cfg.get_bool("verbose").unwrap());
+    /// assert!(!cfg.contains_key("debug"));
+    /// ```
+    pub fn args<I, T>(mut self, args: I) -> Result<Self, ConfigError>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        self.load_args(args)?;
+        Ok(self)
+    }
+
+    /// Load arguments, leaving the config as is if it fails
+    pub(crate) fn load_args<I, T>(&mut self, args: I) -> Result<(), ConfigError>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        let start = Instant::now();
+        let mut data = parse(args, |i| self.key_style.key(i));
+        self.apply_transforms(&mut data, &mut Vec::new());
+        self.apply_processors(&mut data)?;
+        self.add_source("<args>".to_string(), start, (data, Vec::new()))
+    }
+}
+
+/// Get the entries of `--key=value` arguments, with `key` normalizing the keys
+fn parse<I, T>(args: I, key: impl Fn(&str) -> String) -> Vec<Entry>
+where
+    I: IntoIterator<Item = T>,
+    T: AsRef<str>,
+{
+    let mut out = Vec::new();
+    for i in args {
+        let arg = match i.as_ref().strip_prefix("--") {
+            Some("") => break,
+            Some(i) => i,
+            None => continue,
+        };
+
+        let (name, value) = arg.split_once('=').unwrap_or((arg, "true"));
+        if name.trim().is_empty() {
+            continue;
+        }
+
+        out.push(Entry {
+            section: None,
+            key: key(name),
+            value: value.to_owned(),
+        });
+    }
+
+    out
+}
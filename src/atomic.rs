@@ -0,0 +1,144 @@
+//! Saving files so they are never left half written
+use std::fs::{self, File, OpenOptions, Permissions};
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::Config;
+
+impl Config {
+    /// Keep the permissions of the file being replaced when saving (on by default)
+    ///
+    /// Configs are saved by writing a temporary file next to the original and renaming it over the original,
+    /// so the new file would otherwise get the default permissions.
+    /// With this off a saved file gets the default permissions, like a new file would.
+    /// ## Example
+    /// ```rust,no_run
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let mut cfg = Config::new().file("secrets.cfg").unwrap().preserve_permissions(true);
+    /// cfg.set("token", "abc");
+    ///
+    /// // Still only readable by its owner if it was before
+    /// cfg.save().unwrap();
+    /// ```
+    pub fn preserve_permissions(mut self, preserve: bool) -> Self {
+        self.preserve_permissions = preserve;
+        self
+    }
+}
+
+/// Number of temporary files made by this process, so saves on other threads never share one
+static TEMP_FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// How many times to try a new temporary file name if one is already taken
+const TEMP_ATTEMPTS: usize = 100;
+
+/// Write a file so that it has either its old or new contents, even if the process or system crashes
+///
+/// The contents are written to a temporary file in the same directory and synced to disk,
+/// which is then renamed over the file (and the rename synced on unix).
+/// Symlinks are followed, so the file they point to is replaced.
+/// The temporary file is always a new one (never one that is already there, or a symlink),
+/// so saves from other threads or processes can't write to it.
+/// On unix it's made with the permissions it's going to have, so other users can't read the contents
+/// of a file only its owner can read while it's being written.
+pub(crate) fn write(path: &Path, contents: &[u8], preserve: bool) -> io::Result<()> {
+    let path = match fs::canonicalize(path) {
+        Ok(i) => i,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => path.to_path_buf(),
+        Err(e) => return Err(e),
+    };
+
+    let permissions = match fs::metadata(&path) {
+        Ok(i) if preserve => Some(i.permissions()),
+        Err(e) if preserve && e.kind() != io::ErrorKind::NotFound => return Err(e),
+        _ => None,
+    };
+    let (temp, file) = create_temp(&path, permissions.as_ref())?;
+    let written = write_temp(&path, &temp, file, contents, permissions);
+    if written.is_err() {
+        let _ = fs::remove_file(&temp);
+        return written;
+    }
+
+    sync_dir(&path)
+}
+
+/// Write the temporary file for `path` and rename it over the file, giving it `permissions` if set
+fn write_temp(
+    path: &Path,
+    temp: &Path,
+    mut file: File,
+    contents: &[u8],
+    permissions: Option<Permissions>,
+) -> io::Result<()> {
+    // The umask can take bits away from the mode the file was made with, so they are set again before writing
+    #[cfg(unix)]
+    if let Some(i) = &permissions {
+        file.set_permissions(i.clone())?;
+    }
+
+    file.write_all(contents)?;
+    file.sync_all()?;
+    drop(file);
+
+    // Elsewhere permissions are a read-only flag, which has to be set after writing
+    #[cfg(not(unix))]
+    if let Some(i) = permissions {
+        fs::set_permissions(temp, i)?;
+    }
+
+    fs::rename(temp, path)
+}
+
+/// Create a new temporary file to write `path` to, a hidden file next to it
+///
+/// On unix the file is made with the mode of `permissions` (the default for a new file if it's `None`),
+/// so the contents are never readable by more users than the file it replaces.
+/// Names that are already taken are skipped.
+#[cfg_attr(not(unix), allow(unused_variables))]
+fn create_temp(path: &Path, permissions: Option<&Permissions>) -> io::Result<(PathBuf, File)> {
+    let name = path
+        .file_name()
+        .map(|i| i.to_string_lossy())
+        .unwrap_or_default();
+
+    let mut error = None;
+    for _ in 0..TEMP_ATTEMPTS {
+        let n = TEMP_FILES.fetch_add(1, Ordering::Relaxed);
+        let temp = path.with_file_name(format!(".{}.{}.{}.tmp", name, process::id(), n));
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        if let Some(i) = permissions {
+            options.mode(i.mode() & 0o777);
+        }
+        match options.open(&temp) {
+            Ok(file) => return Ok((temp, file)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => error = Some(e),
+            Err(e) => return Err(e),
+        }
+    }
+
+    Err(error.unwrap_or_else(|| io::ErrorKind::AlreadyExists.into()))
+}
+
+/// Sync the directory of a file, so a rename in it is on disk
+#[cfg(unix)]
+fn sync_dir(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => File::open(dir)?.sync_all(),
+        _ => File::open(".")?.sync_all(),
+    }
+}
+
+/// Directories can't be opened to sync them on other platforms, the rename is left to the file system
+#[cfg(not(unix))]
+fn sync_dir(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
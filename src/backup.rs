@@ -0,0 +1,104 @@
+//! Keeping backups of config files when they are saved (with the `file` feature)
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::atomic;
+use crate::{Config, ConfigError};
+
+impl Config {
+    /// Keep up to `count` backups of the file when saving over it
+    ///
+    /// Before a file is replaced it's copied to `<file>.bak.1`, moving older backups up
+    /// (`.bak.1` to `.bak.2` and so on) and removing the oldest past `count`.
+    /// Saving a file that would not change does not make a backup. 0 (the default) keeps no backups.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// # let path = std::env::temp_dir().join("scp-backups-doc.cfg");
+    /// std::fs::write(&path, "volume = 10").unwrap();
+    /// let mut cfg = Config::new().backups(3).file(&path).unwrap();
+    ///
+    /// cfg.set("volume", "0");
+    /// cfg.save().unwrap();
+    ///
+    /// // Undo the change on disk
+    /// cfg.restore_backup(1).unwrap();
+    /// assert_eq!(std::fs::read_to_string(&path).unwrap(), "volume = 10");
+    /// # for i in cfg.list_backups() { std::fs::remove_file(i.0).unwrap() }
+    /// # std::fs::remove_file(path).unwrap();
+    /// ```
+    pub fn backups(mut self, count: usize) -> Self {
+        self.backups = count;
+        self
+    }
+
+    /// Replace the file the config was loaded from with its `n`th backup (1 is the newest)
+    ///
+    /// The file being replaced becomes the first backup (if backups are kept), so the restore can be undone
+    /// with `restore_backup(1)`. This config is not changed, load the file again to use the restored config.
+    /// Returns `ConfigError::NoFileDefined` if no file was loaded, or an I/O error if there is no such backup.
+    pub fn restore_backup(&self, n: usize) -> Result<(), ConfigError> {
+        let path = self.path.as_ref().ok_or(ConfigError::NoFileDefined)?;
+        let contents = fs::read(backup_path(path, n))?;
+        self.write_file(path, &contents)
+    }
+
+    /// Get the backups of the file the config was loaded from, newest first, with when they were made
+    pub fn list_backups(&self) -> Vec<(PathBuf, SystemTime)> {
+        let path = match &self.path {
+            Some(i) => i,
+            None => return Vec::new(),
+        };
+
+        (1..)
+            .map(|i| backup_path(path, i))
+            .map_while(|i| {
+                let modified = fs::metadata(&i).and_then(|i| i.modified()).ok()?;
+                Some((i, modified))
+            })
+            .collect()
+    }
+
+    /// Write a file atomically, backing up the file it replaces if `Config::backups` is set
+    pub(crate) fn write_file(&self, path: &Path, contents: &[u8]) -> Result<(), ConfigError> {
+        if self.backups > 0 {
+            rotate(path, contents, self.backups)?;
+        }
+
+        atomic::write(path, contents, self.preserve_permissions)?;
+        Ok(())
+    }
+}
+
+/// Get the path of the `n`th backup of a file, `<file>.bak.<n>`
+fn backup_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".bak.{}", n));
+    PathBuf::from(name)
+}
+
+/// Copy a file to its first backup, moving the older backups up and removing the ones past `count`
+///
+/// Nothing is done if the file doesn't exist or already has the new `contents`.
+fn rotate(path: &Path, contents: &[u8], count: usize) -> io::Result<()> {
+    match fs::read(path) {
+        Ok(i) if i == contents => return Ok(()),
+        Ok(_) => {}
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    }
+
+    for i in (1..count).rev() {
+        let from = backup_path(path, i);
+        if from.exists() {
+            fs::rename(from, backup_path(path, i + 1))?;
+        }
+    }
+
+    fs::copy(path, backup_path(path, 1))?;
+    Ok(())
+}
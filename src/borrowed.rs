@@ -0,0 +1,207 @@
+//! Parsing configs without copying their text
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use crate::config::{repeated_section, Comments, KeyStyle, Syntax, Token};
+use crate::convert;
+use crate::encoding::BLOCK_QUOTE;
+use crate::interpolate;
+use crate::numbers::Numbers;
+use crate::{ConfigError, FromConfigValue};
+
+/// A read only config that borrows its keys and values from the text it was parsed from
+///
+/// Made with `ConfigRef::parse`, for reading a config once without the allocations of `Config`.
+/// Keys and values are only copied if they have to be changed, like quoted values with escapes
+/// or keys that have to be lowercased.
+/// Only the ini format with the default options is supported, without includes, `${env:NAME}` expansion or saving.
+/// It needs `std` like the rest of the crate, so it's not a way to read configs on `no_std` targets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigRef<'a> {
+    /// Raw Data of the Config
+    pub data: Vec<EntryRef<'a>>,
+}
+
+/// A single key value pair from a `ConfigRef`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryRef<'a> {
+    /// The section the entry is in (`None` if it was defined before any section)
+    pub section: Option<Cow<'a, str>>,
+    /// The key (lowercase with spaces removed)
+    pub key: Cow<'a, str>,
+    /// The value (with quotes removed and escapes decoded)
+    pub value: Cow<'a, str>,
+}
+
+/// Borrow some text if normalizing it gives the same text
+fn borrow_if_same<'a>(text: &'a str, normalized: String) -> Cow<'a, str> {
+    match normalized == text {
+        true => Cow::Borrowed(text),
+        false => Cow::Owned(normalized),
+    }
+}
+
+/// Normalize a key like `KeyStyle::key`, borrowing it if it does not change
+fn key(key: &str) -> Cow<'_, str> {
+    let trimmed = key.trim_matches(' ');
+    let plain = |i: u8| !matches!(i, b' ' | b'[') && !i.is_ascii_uppercase() && i.is_ascii();
+    match trimmed.bytes().all(plain) {
+        true => Cow::Borrowed(trimmed),
+        false => borrow_if_same(trimmed, KeyStyle::default().key(key)),
+    }
+}
+
+/// Normalize a section name like `KeyStyle::section`, borrowing it if it does not change
+fn section(name: &str) -> Cow<'_, str> {
+    let trimmed = name.trim();
+    match trimmed
+        .bytes()
+        .all(|i| i.is_ascii() && !i.is_ascii_uppercase())
+    {
+        true => Cow::Borrowed(trimmed),
+        false => borrow_if_same(trimmed, KeyStyle::default().section(name)),
+    }
+}
+
+/// Get where the line starting at `start` ends, at a `\n`, `\r` or the end of the text
+fn line_end(input: &str, start: usize) -> usize {
+    memchr::memchr2(b'\n', b'\r', &input.as_bytes()[start..]).map_or(input.len(), |i| start + i)
+}
+
+/// Get where the line after the one ending at `end` starts, counting `\r\n` as one line ending
+fn next_line(input: &str, end: usize) -> usize {
+    end + 1 + usize::from(input[end..].starts_with("\r\n"))
+}
+
+/// Get the value of a `"""` block, as `Config` reads it (with `\n` line endings)
+fn block(value: &str) -> Cow<'_, str> {
+    let value = match value.contains('\r') {
+        true => Cow::Owned(value.replace("\r\n", "\n").replace('\r', "\n")),
+        false => Cow::Borrowed(value),
+    };
+    match value {
+        Cow::Borrowed(i) => Cow::Borrowed(i.strip_prefix('\n').unwrap_or(i)),
+        Cow::Owned(i) => Cow::Owned(i.strip_prefix('\n').unwrap_or(&i).to_owned()),
+    }
+}
+
+impl<'a> ConfigRef<'a> {
+    /// Parse config text, borrowing from it
+    ///
+    /// Lines are read with the same rules as `Config::text` with the default options,
+    /// so this gives the same entries, or `ConfigError::InvalidConfig` where it would fail.
+    /// Returns `ConfigError::Restricted` if the text has an `!include`.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use std::borrow::Cow;
+    /// use simple_config_parser::ConfigRef;
+    ///
+    /// let text = "name = app\n[server]\nport = 8080 # Comment\nmotd = \"Hello\\tWorld\"";
+    /// let cfg = ConfigRef::parse(text).unwrap();
+    ///
+    /// assert_eq!(cfg.get_str("name").unwrap(), "app");
+    /// assert_eq!(cfg.get::<u16>("server.port").unwrap(), 8080);
+    /// assert_eq!(cfg.get_str("motd").unwrap(), "Hello\tWorld");
+    /// assert!(matches!(cfg.data[1].value, Cow::Borrowed("8080")));
+    /// ```
+    pub fn parse(input: &'a str) -> Result<Self, ConfigError> {
+        let comments = Comments::default();
+        let mut data = Vec::new();
+        let mut current = None;
+        let mut repeated = BTreeMap::new();
+
+        let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+        let mut pos = 0;
+        while pos < input.len() {
+            let start = pos;
+            let raw = &input[start..line_end(input, start)];
+            pos = next_line(input, start + raw.len());
+
+            let (name, at) = match Token::read(raw, Syntax::default(), &comments) {
+                Token::Entry(name, at) => (name, at),
+                Token::Text => continue,
+                Token::Include(_) => {
+                    return Err(ConfigError::Restricted(
+                        "Includes are not allowed".to_owned(),
+                    ))
+                }
+                Token::Repeated(name) => {
+                    let name = repeated_section(&mut repeated, KeyStyle::default().section(name));
+                    current = Some(Cow::Owned(name));
+                    continue;
+                }
+                Token::Section(name) => {
+                    current = Some(section(name));
+                    continue;
+                }
+                Token::Flag(_) | Token::Invalid => return Err(ConfigError::InvalidConfig),
+            };
+            let rest = &raw[at..];
+
+            let (value, after) = if rest.starts_with(BLOCK_QUOTE) {
+                // Multi-line values are still one slice of the input
+                let body = start + at + BLOCK_QUOTE.len();
+                let close = input[body..]
+                    .find(BLOCK_QUOTE)
+                    .ok_or(ConfigError::InvalidConfig)?
+                    + body;
+                let after = close + BLOCK_QUOTE.len();
+                let end = line_end(input, after);
+                if !comments.only_comment(&input[after..end]) {
+                    return Err(ConfigError::InvalidConfig);
+                }
+
+                pos = next_line(input, end);
+                (block(&input[body..close]), &input[after..end])
+            } else {
+                let (value, len) = comments.value_ref(rest);
+                (value, &rest[len..])
+            };
+
+            // Like `Config`, an `env:NAME` comment overrides the value if the variable is set
+            let value = match interpolate::env_override(after, &comments) {
+                Some((_, value)) => Cow::Owned(value),
+                None => value,
+            };
+            data.push(EntryRef {
+                section: current.clone(),
+                key: key(name),
+                value,
+            });
+        }
+
+        Ok(ConfigRef { data })
+    }
+
+    /// Get a value as a str
+    ///
+    /// Like `Config::get_str`, keys can be in any section or a dotted `section.key` name,
+    /// and the last definition is used.
+    pub fn get_str(&self, key: &str) -> Result<&str, ConfigError> {
+        let key = KeyStyle::default().lookup(key);
+        self.data
+            .iter()
+            .rev()
+            .find(|i| {
+                i.key == key
+                    || i.section.as_deref().is_some_and(|section| {
+                        key.strip_prefix(section)
+                            .and_then(|rest| rest.strip_prefix('.'))
+                            == Some(&i.key)
+                    })
+            })
+            .map(|i| i.value.as_ref())
+            .ok_or(ConfigError::NoItem)
+    }
+
+    /// Get a value as any type (That Impls FromConfigValue)
+    pub fn get<T>(&self, key: &str) -> Result<T, ConfigError>
+    where
+        T: FromConfigValue,
+    {
+        convert::convert(self.get_str(key)?, Numbers::default(), || {
+            KeyStyle::default().lookup(key).into_owned()
+        })
+    }
+}
@@ -0,0 +1,326 @@
+//! Building configs with options that have to be set before anything is loaded
+use std::borrow::Cow;
+#[cfg(feature = "file")]
+use std::path::PathBuf;
+
+use crate::config::{Comments, KeyStyle, Syntax};
+use crate::{Config, ConfigError};
+
+/// Builds a `Config`, made with `Config::builder`
+///
+/// These options change how sources are parsed, so unlike the other `Config` options
+/// they can't be changed once something has been loaded.
+/// Sources can be queued too, they are all loaded (in order) by `build`.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigBuilder {
+    key_style: KeyStyle,
+    comments: Comments,
+    syntax: Syntax,
+    sources: Vec<Source>,
+}
+
+/// A source queued to be loaded by `ConfigBuilder::build`
+#[derive(Debug, Clone)]
+enum Source {
+    #[cfg(feature = "file")]
+    File(PathBuf),
+    #[cfg(feature = "file")]
+    FileOptional(PathBuf),
+    Text(String),
+    #[cfg(feature = "json")]
+    Json(String),
+    Env(String),
+    Args(Vec<String>),
+}
+
+impl Config {
+    /// Start building a config
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::builder()
+    ///     .case_sensitive(true)
+    ///     .preserve_key_spaces(true)
+    ///     .build()
+    ///     .unwrap()
+    ///     .text("Path = /usr/bin\npath = ./bin\nfull name = Connor")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get_str("Path").unwrap(), "/usr/bin");
+    /// assert_eq!(cfg.get_str("path").unwrap(), "./bin");
+    /// assert_eq!(cfg.get_str("full name").unwrap(), "Connor");
+    /// ```
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::default()
+    }
+}
+
+impl ConfigBuilder {
+    /// Keep the case of keys and section names
+    ///
+    /// Lookups then have to use the same case. By default everything is lowercased.
+    pub fn case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.key_style.case_sensitive = case_sensitive;
+        self
+    }
+
+    /// Keep spaces inside of keys, so `full name` is not read as `fullname`
+    ///
+    /// Spaces around keys are still removed.
+    pub fn preserve_key_spaces(mut self, preserve: bool) -> Self {
+        self.key_style.preserve_spaces = preserve;
+        self
+    }
+
+    /// Set the chars that start comments (`#` and `;` by default)
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::builder()
+    ///     .comment_chars(&['#'])
+    ///     .build()
+    ///     .unwrap()
+    ///     .text("# Comment\npath = a;b # Comment")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get_str("path").unwrap(), "a;b");
+    /// ```
+    pub fn comment_chars(mut self, chars: &[char]) -> Self {
+        self.comments.chars = Cow::Owned(chars.to_vec());
+        self
+    }
+
+    /// Set if comments can come after a value on the same line (they can by default)
+    ///
+    /// If not, comment chars are only comments at the start of a line.
+    pub fn inline_comments(mut self, inline: bool) -> Self {
+        self.comments.inline = inline;
+        self
+    }
+
+    /// Set if inline comments need whitespace before them
+    ///
+    /// So values like `https://example.com/#about` are kept as is, while `port = 80 # comment` still has a comment.
+    pub fn inline_comment_space(mut self, space: bool) -> Self {
+        self.comments.space = space;
+        self
+    }
+
+    /// Read files like the Windows INI functions (`GetPrivateProfileString`) do, for configs from legacy Windows tools
+    ///
+    /// - Only `;` starts comments, and only at the start of a line (so `path = C:\a;b` is `C:\a;b`)
+    /// - `"` quotes are removed from values, keeping whitespace inside them, but `\` is not an escape
+    /// - Spaces inside of keys are kept, while keys and section names are not case sensitive
+    ///
+    /// Empty values (`key=`) are read as empty strings, as they are by default.
+    /// Windows uses the first definition of a key, see `Config::duplicates` for that.
+    /// Turning this off goes back to the default comments, quotes and keys.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::builder()
+    ///     .windows_ini(true)
+    ///     .build()
+    ///     .unwrap()
+    ///     .text("; Settings\n[Paths]\nLog Dir=C:\\new\\logs # not a comment\nPrefix=\"  \\\\server\\  \"\nIncludes=a;b\nEmpty=")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get_str("paths.log dir").unwrap(), "C:\\new\\logs # not a comment");
+    /// assert_eq!(cfg.get_str("PATHS.PREFIX").unwrap(), "  \\\\server\\  ");
+    /// assert_eq!(cfg.get_str("paths.includes").unwrap(), "a;b");
+    /// assert_eq!(cfg.get_str("paths.empty").unwrap(), "");
+    /// ```
+    pub fn windows_ini(mut self, windows: bool) -> Self {
+        self.key_style.case_sensitive = false;
+        self.key_style.preserve_spaces = windows;
+        self.comments = match windows {
+            true => Comments {
+                chars: Cow::Borrowed(&[';']),
+                inline: false,
+                space: false,
+                escapes: false,
+            },
+            false => Comments::default(),
+        };
+        self
+    }
+
+    /// Let `:` separate keys and values like `=` (as in Python's `configparser`)
+    ///
+    /// Whichever comes first on the line is used, so `url = http://localhost` still works.
+    /// Keys then can't contain a `:`.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::builder()
+    ///     .colon_separator(true)
+    ///     .build()
+    ///     .unwrap()
+    ///     .text("[server]\nhost: localhost\nurl = http://localhost:8080")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get_str("server.host").unwrap(), "localhost");
+    /// assert_eq!(cfg.get_str("server.url").unwrap(), "http://localhost:8080");
+    /// ```
+    pub fn colon_separator(mut self, colon: bool) -> Self {
+        self.syntax.colon = colon;
+        self
+    }
+
+    /// Read lines with just a key (like `verbose`) as flags, with the value `true`
+    ///
+    /// By default these lines are invalid. Setting a flag to another value writes it as `key = value`.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let mut cfg = Config::builder()
+    ///     .flags(true)
+    ///     .build()
+    ///     .unwrap()
+    ///     .text("verbose\ncolor ; Comment\nlevel = 2")
+    ///     .unwrap();
+    ///
+    /// assert!(cfg.get::<bool>("verbose").unwrap());
+    /// assert!(cfg.get::<bool>("color").unwrap());
+    ///
+    /// cfg.set("color", "false");
+    /// assert_eq!(cfg.to_string(), "verbose\ncolor = false ; Comment\nlevel = 2\n");
+    /// ```
+    pub fn flags(mut self, flags: bool) -> Self {
+        self.syntax.flags = flags;
+        self
+    }
+
+    /// Queue a file to be loaded, like `Config::file`
+    #[cfg(feature = "file")]
+    pub fn file<T>(mut self, file: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        self.sources.push(Source::File(file.into()));
+        self
+    }
+
+    /// Queue a file to be loaded if it can be, like `Config::file_optional`
+    ///
+    /// If it fails `build` does not, the failure is in the config's `load_report`.
+    #[cfg(feature = "file")]
+    pub fn file_optional<T>(mut self, file: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        self.sources.push(Source::FileOptional(file.into()));
+        self
+    }
+
+    /// Queue text to be parsed, like `Config::text`
+    pub fn text<T>(mut self, text: T) -> Self
+    where
+        T: std::fmt::Display,
+    {
+        self.sources.push(Source::Text(text.to_string()));
+        self
+    }
+
+    /// Queue a JSON object to be loaded, like `Config::json`
+    #[cfg(feature = "json")]
+    pub fn json<T>(mut self, text: T) -> Self
+    where
+        T: std::fmt::Display,
+    {
+        self.sources.push(Source::Json(text.to_string()));
+        self
+    }
+
+    /// Queue the environment variables starting with `prefix` to be loaded, like `Config::env`
+    pub fn env(mut self, prefix: &str) -> Self {
+        self.sources.push(Source::Env(prefix.to_owned()));
+        self
+    }
+
+    /// Queue `--key=value` arguments to be loaded, like `Config::args`
+    pub fn args<I, T>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        let args = args.into_iter().map(|i| i.as_ref().to_owned()).collect();
+        self.sources.push(Source::Args(args));
+        self
+    }
+
+    /// Create the config and load the queued sources, in the order they were added
+    ///
+    /// A source that fails to load is skipped and the rest are still loaded,
+    /// so every failure is returned at once in a `ConfigError::Sources`.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, ConfigError};
+    ///
+    /// let cfg = Config::builder()
+    ///     .text("hello = World")
+    ///     .args(["--port=8080"])
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(cfg.get_str("hello").unwrap(), "World");
+    /// assert_eq!(cfg.get::<u16>("port").unwrap(), 8080);
+    ///
+    /// let err = Config::builder()
+    ///     .text("[broken")
+    ///     .text("port = 8080")
+    ///     .text("[also broken")
+    ///     .build();
+    ///
+    /// match err {
+    ///     Err(ConfigError::Sources(i)) => {
+    ///         assert_eq!(i.len(), 2);
+    ///         assert_eq!(i[1].0, "<text>");
+    ///     }
+    ///     _ => panic!("Both sources should fail"),
+    /// }
+    /// ```
+    pub fn build(self) -> Result<Config, ConfigError> {
+        let mut cfg = Config::new();
+        cfg.key_style = self.key_style;
+        cfg.comments = self.comments;
+        cfg.syntax = self.syntax;
+
+        let mut errors = Vec::new();
+        for i in self.sources {
+            let (source, result) = match i {
+                #[cfg(feature = "file")]
+                Source::File(file) => (file.display().to_string(), cfg.load_file(&file)),
+                #[cfg(feature = "file")]
+                Source::FileOptional(file) => {
+                    cfg = cfg.file_optional(file);
+                    continue;
+                }
+                Source::Text(text) => ("<text>".to_owned(), cfg.load_text(text)),
+                #[cfg(feature = "json")]
+                Source::Json(text) => ("<json>".to_owned(), cfg.load_json(&text)),
+                Source::Env(prefix) => (format!("<env:{}>", prefix), cfg.load_env(&prefix)),
+                Source::Args(args) => ("<args>".to_owned(), cfg.load_args(args)),
+            };
+
+            if let Err(e) = result {
+                errors.push((source, e));
+            }
+        }
+
+        match errors.is_empty() {
+            true => Ok(cfg),
+            false => Err(ConfigError::Sources(errors)),
+        }
+    }
+}
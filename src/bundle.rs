@@ -0,0 +1,110 @@
+//! A text summary of a config to attach to bug reports
+use std::fmt::Write;
+
+use crate::config::Entry;
+use crate::{Config, Schema};
+
+/// Parts of key names that mark their values as secret
+const SECRET_WORDS: [&str; 6] = ["password", "passwd", "secret", "token", "apikey", "private"];
+
+/// Text shown for the values of secret keys
+pub(crate) const REDACTED: &str = "<redacted>";
+
+/// Check if a key is likely to hold a secret, by its name
+fn is_secret(key: &str) -> bool {
+    let key = key.to_lowercase().replace(['_', '-', ' '], "");
+    SECRET_WORDS.iter().any(|i| key.contains(i))
+}
+
+impl Config {
+    /// Check if the value of an entry should be hidden, by its name or because it is sensitive
+    pub(crate) fn is_redacted(&self, entry: &Entry) -> bool {
+        is_secret(&entry.key) || self.is_sensitive(&entry.name())
+    }
+
+    /// Summarize the config as text to attach to bug reports
+    ///
+    /// Has the fingerprint of the values in use (see `Config::fingerprint`, so two bundles can be checked for the same config),
+    /// every value in use with where it came from, the load report, deprecation and expiry warnings
+    /// and the result of validating against the schema (if given).
+    /// Values of keys with names like `password`, `secret` or `token` (or that are sensitive, see `Config::sensitive`) are redacted,
+    /// the fingerprint still changes with them.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, Schema, SchemaKey, ValueType};
+    ///
+    /// const SCHEMA: Schema = Schema::new(&[SchemaKey::required("port", ValueType::Int)]);
+    ///
+    /// let cfg = Config::new()
+    ///     .text("port = 8080\n[db]\npassword = hunter2")
+    ///     .unwrap();
+    ///
+    /// let bundle = cfg.support_bundle(Some(&SCHEMA));
+    /// assert!(bundle.contains("port = 8080 (<text> line 1)"));
+    /// assert!(bundle.contains("db.password = <redacted> (<text> line 3)"));
+    /// assert!(!bundle.contains("hunter2"));
+    /// ```
+    pub fn support_bundle(&self, schema: Option<&Schema>) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# Config support bundle");
+        let _ = writeln!(out, "fingerprint: {:016x}", self.fingerprint());
+
+        let _ = writeln!(out, "\n## Values");
+        for i in self.iter_effective() {
+            let at = self.occurrence_of(i);
+            let value = match self.is_redacted(i) {
+                true => REDACTED.to_owned(),
+                false => i.value.to_owned(),
+            };
+            let _ = match at.line {
+                Some(line) => writeln!(
+                    out,
+                    "{} = {} ({} line {})",
+                    i.name(),
+                    value,
+                    at.source,
+                    line
+                ),
+                None => writeln!(out, "{} = {} ({})", i.name(), value, at.source),
+            };
+        }
+
+        let _ = write!(out, "\n## Load report\n{}", self.load_report());
+
+        let _ = writeln!(out, "\n## Warnings");
+        let expired = self.check_expired();
+        for i in expired.iter() {
+            let _ = match i.line {
+                Some(line) => writeln!(
+                    out,
+                    " - `{}` has expired ({} line {})",
+                    i.key, i.source, line
+                ),
+                None => writeln!(out, " - `{}` has expired ({})", i.key, i.source),
+            };
+        }
+        match self.check_deprecations() {
+            Ok(i) if i.is_empty() && expired.is_empty() => out.push_str("none\n"),
+            Ok(i) => i.iter().for_each(|i| {
+                let _ = match &i.note {
+                    Some(note) => writeln!(out, " - `{}` is deprecated, {}", i.key, note),
+                    None => writeln!(out, " - `{}` is deprecated", i.key),
+                };
+            }),
+            Err(e) => {
+                let _ = writeln!(out, " - {}", e);
+            }
+        }
+
+        if let Some(schema) = schema {
+            let _ = writeln!(out, "\n## Validation");
+            match self.validate(schema) {
+                Ok(()) => out.push_str("ok\n"),
+                Err(e) => out.push_str(&e.to_string()),
+            }
+        }
+
+        out
+    }
+}
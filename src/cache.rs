@@ -0,0 +1,184 @@
+//! A cache of loaded configs with limits on its size, for services loading many configs (like one per customer)
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::{Config, ConfigError};
+
+/// A cache of loaded configs by name, shared between threads
+///
+/// When it is over its limits the least recently used configs are dropped.
+/// The size of a config is the length of its text (`Config::to_string`), so limits in bytes are rough.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use simple_config_parser::{Config, ConfigCache};
+///
+/// let cache = ConfigCache::new().max_entries(100).max_bytes(1 << 20);
+///
+/// let load = |tenant: &str| cache.get_or_load(tenant, || Config::new().text(format!("name = {}", tenant)));
+/// assert_eq!(load("acme").unwrap().get_str("name").unwrap(), "acme");
+/// assert_eq!(load("acme").unwrap().get_str("name").unwrap(), "acme");
+///
+/// let stats = cache.stats();
+/// assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+/// ```
+pub struct ConfigCache {
+    /// Most configs kept at once
+    max_entries: usize,
+    /// Most bytes of configs kept at once
+    max_bytes: usize,
+    inner: Mutex<Inner>,
+}
+
+/// Counters of a `ConfigCache`, from `ConfigCache::stats`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CacheStats {
+    /// Times a config was in the cache
+    pub hits: u64,
+    /// Times a config had to be loaded
+    pub misses: u64,
+    /// Configs dropped to stay in the limits
+    pub evictions: u64,
+    /// Configs in the cache
+    pub entries: usize,
+    /// Size of the configs in the cache
+    pub bytes: usize,
+}
+
+/// The configs of a cache and its counters
+#[derive(Default)]
+struct Inner {
+    configs: HashMap<String, Cached>,
+    stats: CacheStats,
+    /// Incremented on each use, to find the least recently used config
+    clock: u64,
+}
+
+/// A config in the cache
+struct Cached {
+    config: Arc<Config>,
+    bytes: usize,
+    used: u64,
+}
+
+impl ConfigCache {
+    /// Create a cache with no limits
+    pub fn new() -> Self {
+        ConfigCache {
+            max_entries: usize::MAX,
+            max_bytes: usize::MAX,
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    /// Set the most configs kept at once
+    pub fn max_entries(mut self, max: usize) -> Self {
+        self.max_entries = max;
+        self
+    }
+
+    /// Set the most bytes of configs kept at once
+    ///
+    /// A config bigger than this on its own is returned but not kept.
+    pub fn max_bytes(mut self, max: usize) -> Self {
+        self.max_bytes = max;
+        self
+    }
+
+    /// Get a config from the cache, loading it with `load` if it's not there
+    ///
+    /// The cache is not locked while loading, so a config being loaded by two threads at once is loaded twice.
+    /// Errors from `load` are returned and nothing is cached.
+    pub fn get_or_load<F>(&self, name: &str, load: F) -> Result<Arc<Config>, ConfigError>
+    where
+        F: FnOnce() -> Result<Config, ConfigError>,
+    {
+        if let Some(i) = self.get(name) {
+            return Ok(i);
+        }
+
+        let config = Arc::new(load()?);
+        let bytes = config.to_string().len();
+
+        let mut inner = self.inner();
+        inner.stats.misses += 1;
+        if bytes > self.max_bytes || self.max_entries == 0 {
+            return Ok(config);
+        }
+
+        inner.clock += 1;
+        let cached = Cached {
+            config: config.clone(),
+            bytes,
+            used: inner.clock,
+        };
+        if let Some(old) = inner.configs.insert(name.to_owned(), cached) {
+            inner.stats.bytes -= old.bytes;
+        }
+        inner.stats.bytes += bytes;
+
+        while inner.configs.len() > self.max_entries || inner.stats.bytes > self.max_bytes {
+            let oldest = inner
+                .configs
+                .iter()
+                .min_by_key(|(_, i)| i.used)
+                .map(|(i, _)| i.to_owned());
+            if let Some(i) = oldest.and_then(|i| inner.configs.remove(&i)) {
+                inner.stats.bytes -= i.bytes;
+                inner.stats.evictions += 1;
+            }
+        }
+
+        inner.stats.entries = inner.configs.len();
+        Ok(config)
+    }
+
+    /// Get a config if it's in the cache
+    pub fn get(&self, name: &str) -> Option<Arc<Config>> {
+        let mut inner = self.inner();
+        inner.clock += 1;
+        let clock = inner.clock;
+
+        let config = inner.configs.get_mut(name).map(|i| {
+            i.used = clock;
+            i.config.clone()
+        });
+        if config.is_some() {
+            inner.stats.hits += 1;
+        }
+        config
+    }
+
+    /// Drop a config from the cache, so it is loaded again when next used
+    pub fn invalidate(&self, name: &str) {
+        let mut inner = self.inner();
+        if let Some(i) = inner.configs.remove(name) {
+            inner.stats.bytes -= i.bytes;
+            inner.stats.entries = inner.configs.len();
+        }
+    }
+
+    /// Drop every config from the cache
+    pub fn clear(&self) {
+        let mut inner = self.inner();
+        inner.configs.clear();
+        inner.stats.bytes = 0;
+        inner.stats.entries = 0;
+    }
+
+    /// Get the counters of the cache
+    pub fn stats(&self) -> CacheStats {
+        self.inner().stats
+    }
+
+    /// Get the configs and counters, even if a thread panicked while using them
+    fn inner(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for ConfigCache {
+    fn default() -> Self {
+        ConfigCache::new()
+    }
+}
@@ -0,0 +1,204 @@
+//! Message catalogs for showing errors in other languages
+//!
+//! Every error has a stable id (like `missing-env`) and the values shown in its message.
+//! A `Catalog` turns those into text, falling back on the English `Display` message.
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::config::{self, MAX_INCLUDE_DEPTH};
+use crate::{ConfigError, SchemaError};
+
+/// Gives the text of error messages
+///
+/// Implemented for maps of ids to templates, where `{0}`, `{1}`... are replaced with the args.
+pub trait Catalog {
+    /// Get the message for an id and its args, or `None` to use the default message
+    fn message(&self, id: &str, args: &[String]) -> Option<String>;
+}
+
+impl<K, V> Catalog for HashMap<K, V>
+where
+    K: Borrow<str> + Hash + Eq,
+    V: AsRef<str>,
+{
+    fn message(&self, id: &str, args: &[String]) -> Option<String> {
+        let mut out = self.get(id)?.as_ref().to_owned();
+        for (n, i) in args.iter().enumerate() {
+            out = out.replace(&format!("{{{}}}", n), i);
+        }
+
+        Some(out)
+    }
+}
+
+impl ConfigError {
+    /// Get the id of the error, for looking up its message in a `Catalog`
+    ///
+    /// Ids won't change between versions, unlike the `Display` text.
+    pub fn id(&self) -> &'static str {
+        match self {
+            ConfigError::FileReadError => "file-read",
+            ConfigError::Io(_) => "io",
+            ConfigError::FileNotFound(_) => "file-not-found",
+            ConfigError::PermissionDenied(_) => "permission-denied",
+            ConfigError::FileRead(..) => "file-io",
+            ConfigError::NoFileDefined => "no-file-defined",
+            ConfigError::InvalidConfig => "invalid-config",
+            ConfigError::WriteProtected => "write-protected",
+            ConfigError::ParseError => "parse-error",
+            ConfigError::InvalidType(..) => "invalid-type",
+            ConfigError::InvalidUtf8(..) => "invalid-utf8",
+            ConfigError::NoItem => "no-item",
+            ConfigError::Timeout(_) => "timeout",
+            ConfigError::UnsupportedFormat(_) => "unsupported-format",
+            ConfigError::InteractiveStdin => "interactive-stdin",
+            ConfigError::RemovedKey(_) => "removed-key",
+            ConfigError::MissingEnv(_) => "missing-env",
+            ConfigError::Include(..) => "include",
+            ConfigError::IncludeDepth => "include-depth",
+            ConfigError::Restricted(_) => "restricted",
+            ConfigError::Pinned(_) => "pinned",
+            ConfigError::InvalidValue(_) => "invalid-value",
+            ConfigError::UnknownAnchor(_) => "unknown-anchor",
+            ConfigError::DuplicateKey(_) => "duplicate-key",
+            ConfigError::Unset(_) => "unset",
+            ConfigError::ExtendsCycle(_) => "extends-cycle",
+            ConfigError::Value(..) => "value",
+            ConfigError::Sources(_) => "sources",
+        }
+    }
+
+    /// Get the values shown in the error's message, in order
+    ///
+    /// Like the key for `RemovedKey`, or the file and (English) error for `Include`.
+    pub fn args(&self) -> Vec<String> {
+        match self {
+            ConfigError::Io(e) => vec![e.to_string()],
+            ConfigError::FileNotFound(i) | ConfigError::PermissionDenied(i) => {
+                vec![i.display().to_string()]
+            }
+            ConfigError::FileRead(file, e) => vec![file.display().to_string(), e.to_string()],
+            ConfigError::Timeout(i) => vec![format!("{:?}", i)],
+            ConfigError::UnsupportedFormat(i)
+            | ConfigError::RemovedKey(i)
+            | ConfigError::MissingEnv(i)
+            | ConfigError::Restricted(i)
+            | ConfigError::InvalidValue(i)
+            | ConfigError::UnknownAnchor(i)
+            | ConfigError::DuplicateKey(i)
+            | ConfigError::Unset(i) => vec![i.to_owned()],
+            ConfigError::Include(file, e) => vec![file.display().to_string(), e.to_string()],
+            ConfigError::Value(key, at, e) => vec![
+                key.to_owned(),
+                at.source.to_owned(),
+                at.line.map_or_else(String::new, |i| i.to_string()),
+                e.to_string(),
+            ],
+            ConfigError::IncludeDepth => vec![MAX_INCLUDE_DEPTH.to_string()],
+            ConfigError::ExtendsCycle(i) => vec![config::cycle(i)],
+            ConfigError::InvalidUtf8(line, offset) => vec![line.to_string(), offset.to_string()],
+            ConfigError::InvalidType(key, value, target) => {
+                vec![key.to_owned(), value.to_owned(), target.to_owned()]
+            }
+            ConfigError::Sources(i) => vec![i.len().to_string()],
+            ConfigError::Pinned(i) => vec![
+                i.key.to_owned(),
+                i.pinned_by.to_owned(),
+                i.source.to_owned(),
+            ],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Get the error's message from a catalog
+    ///
+    /// Errors the catalog has no message for use their `Display` text.
+    /// The error of an `Include` or `Value` is localized too,
+    /// as are the errors of `Sources` (with the `sources` message as the first line).
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use std::collections::HashMap;
+    /// use simple_config_parser::ConfigError;
+    ///
+    /// let mut catalog = HashMap::new();
+    /// catalog.insert("missing-env", "La variable `{0}` n'est pas définie");
+    ///
+    /// let error = ConfigError::MissingEnv("HOME".to_owned());
+    /// assert_eq!(error.localize(&catalog), "La variable `HOME` n'est pas définie");
+    /// assert_eq!(ConfigError::NoItem.localize(&catalog), ConfigError::NoItem.to_string());
+    /// ```
+    pub fn localize(&self, catalog: &dyn Catalog) -> String {
+        let mut args = self.args();
+        match self {
+            ConfigError::Include(_, e) => args[1] = e.localize(catalog),
+            ConfigError::Value(_, _, e) => args[3] = e.localize(catalog),
+            _ => {}
+        }
+
+        if let ConfigError::Sources(i) = self {
+            let mut out = catalog
+                .message(self.id(), &args)
+                .unwrap_or_else(|| format!("{} config sources failed to load", args[0]));
+            for (source, e) in i.iter() {
+                out.push_str(&format!("\n - {}: {}", source, e.localize(catalog)));
+            }
+            return out;
+        }
+
+        catalog
+            .message(self.id(), &args)
+            .unwrap_or_else(|| match self {
+                ConfigError::Include(file, _) => format!("In `{}`: {}", file.display(), args[1]),
+                ConfigError::Value(key, at, _) => match at.line {
+                    Some(line) => {
+                        format!("`{}` from {} line {}: {}", key, at.source, line, args[3])
+                    }
+                    None => format!("`{}` from {}: {}", key, at.source, args[3]),
+                },
+                _ => self.to_string(),
+            })
+    }
+}
+
+impl SchemaError {
+    /// Get the error's message from a catalog, like its `Display` text
+    ///
+    /// The ids are `schema-error` for the first line, then `schema-missing` (with the key),
+    /// `schema-invalid` (with the key and localized error) and `schema-unknown` (with the key) for each key.
+    pub fn localize(&self, catalog: &dyn Catalog) -> String {
+        let message =
+            |id, args: &[String], default: String| catalog.message(id, args).unwrap_or(default);
+
+        let mut out = message("schema-error", &[], "Invalid config:".to_owned());
+        out.push('\n');
+        for i in self.keys.iter() {
+            let key = match i.section {
+                Some(section) => format!("[{}] {}", section, i.key),
+                None => i.key.to_owned(),
+            };
+
+            let line = match i.error {
+                ConfigError::NoItem => {
+                    let default = format!("`{}` is missing", key);
+                    message("schema-missing", &[key], default)
+                }
+                _ => {
+                    let error = i.error.localize(catalog);
+                    let default = format!("`{}` is invalid ({})", key, error);
+                    message("schema-invalid", &[key, error], default)
+                }
+            };
+            out.push_str(&format!(" - {}\n", line));
+        }
+
+        for i in self.unknown.iter() {
+            let default = format!("`{}` is not a known key", i);
+            let line = message("schema-unknown", &[i.to_owned()], default);
+            out.push_str(&format!(" - {}\n", line));
+        }
+
+        out
+    }
+}
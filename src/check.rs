@@ -0,0 +1,218 @@
+//! Checking a config file for `--check-config` style flags
+use std::fmt;
+#[cfg(feature = "file")]
+use std::path::Path;
+
+#[cfg(feature = "file")]
+use crate::config::normalize_section;
+use crate::encoding;
+#[cfg(feature = "file")]
+use crate::{Config, ConfigError, Schema};
+
+/// How bad an issue is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The config works but something is likely a mistake
+    Warning,
+    /// The config can't be used
+    Error,
+}
+
+/// An issue found checking a config
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    /// How bad the issue is
+    pub severity: Severity,
+    /// The key the issue is with, as `section.key` if it is in a section
+    pub key: Option<String>,
+    /// What the issue is
+    pub message: String,
+}
+
+/// Result of checking a config file, made by `check`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckReport {
+    /// The file that was checked
+    pub file: String,
+    /// Every issue found
+    pub issues: Vec<Issue>,
+}
+
+/// Get the name of a key in a section, as `section.key`
+#[cfg(feature = "file")]
+fn dotted(section: Option<&str>, key: &str) -> String {
+    match section {
+        Some(section) => format!("{}.{}", section, key),
+        None => key.to_owned(),
+    }
+}
+
+/// Check a config file against a schema
+///
+/// The file is parsed and validated, and linted for unknown keys and keys defined more than once.
+/// Meant for flags like `myapp --check-config`, the report can be printed (`Display` or `to_json`)
+/// and turned into an exit code.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use simple_config_parser::{check, Schema, SchemaKey, ValueType};
+///
+/// const SCHEMA: Schema = Schema::new(&[
+///     SchemaKey::required("hello", ValueType::Str),
+///     SchemaKey::optional("rust", ValueType::Str),
+///     SchemaKey::optional("test", ValueType::Str),
+/// ]);
+///
+/// let report = check("config.cfg", &SCHEMA);
+/// assert!(report.is_ok());
+/// assert_eq!(report.exit_code(), 0);
+/// ```
+#[cfg(feature = "file")]
+pub fn check<T>(path: T, schema: &Schema) -> CheckReport
+where
+    T: AsRef<Path>,
+{
+    let file = path.as_ref().display().to_string();
+    let mut issues = Vec::new();
+
+    let cfg = match Config::new().file(path) {
+        Ok(i) => i,
+        Err(e) => {
+            let message = match e {
+                ConfigError::Io(_) | ConfigError::FileRead(..) => {
+                    format!("Can't read file: {}", e)
+                }
+                e => e.to_string(),
+            };
+            issues.push(Issue {
+                severity: Severity::Error,
+                key: None,
+                message,
+            });
+            return CheckReport { file, issues };
+        }
+    };
+
+    if let Err(e) = cfg.validate(schema) {
+        issues.extend(e.keys.into_iter().map(|i| Issue {
+            severity: Severity::Error,
+            key: Some(dotted(i.section, i.key)),
+            message: match i.error {
+                ConfigError::NoItem => "Required key is missing".to_owned(),
+                ConfigError::InvalidValue(e) => format!("Invalid value: {}", e),
+                e => format!("Invalid value: {}", e),
+            },
+        }));
+    }
+
+    let known = schema
+        .keys()
+        .into_iter()
+        .map(|(section, i)| (section.map(normalize_section), i.key.to_lowercase()))
+        .collect::<Vec<_>>();
+
+    let mut seen = Vec::new();
+    for i in cfg.data.iter() {
+        let name = dotted(i.section.as_deref(), &i.key);
+        if seen.contains(&name) {
+            continue;
+        }
+        seen.push(name.to_owned());
+
+        if !known
+            .iter()
+            .any(|(section, key)| *key == i.key && (section.is_none() || *section == i.section))
+        {
+            issues.push(Issue {
+                severity: Severity::Warning,
+                key: Some(name.to_owned()),
+                message: "Unknown key".to_owned(),
+            });
+        }
+
+        let count = cfg
+            .data
+            .iter()
+            .filter(|j| j.section == i.section && j.key == i.key)
+            .count();
+        if count > 1 {
+            issues.push(Issue {
+                severity: Severity::Warning,
+                key: Some(name),
+                message: format!("Defined {} times, the last one is used", count),
+            });
+        }
+    }
+
+    CheckReport { file, issues }
+}
+
+impl CheckReport {
+    /// Check if no errors were found (there may still be warnings)
+    pub fn is_ok(&self) -> bool {
+        !self.issues.iter().any(|i| i.severity == Severity::Error)
+    }
+
+    /// Get an exit code for the check
+    ///
+    /// `0` if there were no issues, `1` if there were only warnings and `2` if there were errors.
+    pub fn exit_code(&self) -> i32 {
+        match self.issues.iter().map(|i| i.severity).max() {
+            None => 0,
+            Some(Severity::Warning) => 1,
+            Some(Severity::Error) => 2,
+        }
+    }
+
+    /// Get the report as JSON
+    ///
+    /// Ex: `{"file":"app.cfg","ok":false,"issues":[{"severity":"error","key":"port","message":"..."}]}`
+    pub fn to_json(&self) -> String {
+        let issues = self
+            .issues
+            .iter()
+            .map(|i| {
+                let severity = match i.severity {
+                    Severity::Warning => "warning",
+                    Severity::Error => "error",
+                };
+                let key = match &i.key {
+                    Some(key) => encoding::json_string(key),
+                    None => "null".to_owned(),
+                };
+                format!(
+                    "{{\"severity\":\"{}\",\"key\":{},\"message\":{}}}",
+                    severity,
+                    key,
+                    encoding::json_string(&i.message)
+                )
+            })
+            .collect::<Vec<_>>();
+
+        format!(
+            "{{\"file\":{},\"ok\":{},\"issues\":[{}]}}",
+            encoding::json_string(&self.file),
+            self.is_ok(),
+            issues.join(",")
+        )
+    }
+}
+
+/// One issue per line, like `app.cfg: error: port: Invalid value: ...`
+impl fmt::Display for CheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for i in self.issues.iter() {
+            let severity = match i.severity {
+                Severity::Warning => "warning",
+                Severity::Error => "error",
+            };
+            write!(f, "{}: {}: ", self.file, severity)?;
+            if let Some(key) = &i.key {
+                write!(f, "{}: ", key)?;
+            }
+            writeln!(f, "{}", i.message)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+//! Attaching comments to keys, sections and the top of generated configs
+use crate::guard;
+use crate::layout::Line;
+use crate::Config;
+
+impl Config {
+    /// Set a value in the config (like `Config::set`) with a comment above its line
+    ///
+    /// The comment replaces any comment lines directly above the key, each line of `comment` is written as its own comment line.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let mut cfg = Config::new();
+    /// cfg.set_with_comment("port", "8080", "Port the server listens on");
+    ///
+    /// assert_eq!(cfg.to_string(), "# Port the server listens on\nport = 8080\n");
+    /// ```
+    pub fn set_with_comment(&mut self, key: &str, value: &str, comment: &str) {
+        self.set(key, value);
+
+        let key = self.key_style.key(key);
+        let index = self
+            .data
+            .indexes(&key)
+            .rev()
+            .find(|i| self.data[*i].key == key && self.data[*i].section.is_none());
+        let line = index.and_then(|index| {
+            self.layout
+                .iter()
+                .position(|i| matches!(i, Line::Entry { index: i, .. } if *i == index))
+        });
+
+        if let Some(line) = line {
+            self.attach_comment(line, comment);
+        }
+    }
+
+    /// Add a comment above the header of a section
+    ///
+    /// The comment replaces any comment lines directly above the first header of the section.
+    /// Returns `false` if the section has no header in the config.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let mut cfg = Config::new().text("[server]\nport = 80").unwrap();
+    ///
+    /// assert!(cfg.comment_section("server", "Settings for the web server"));
+    /// assert_eq!(
+    ///     cfg.to_string(),
+    ///     "# Settings for the web server\n[server]\nport = 80\n"
+    /// );
+    /// ```
+    pub fn comment_section(&mut self, section: &str, comment: &str) -> bool {
+        let section = self.key_style.section(section);
+        let line = self.layout.iter().position(|i| match i {
+            Line::Section(raw) => {
+                let name = self.comments.remove(raw.trim()).trim_end();
+                let name = name
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .to_owned();
+                self.key_style.section(&name) == section
+            }
+            _ => false,
+        });
+
+        match line {
+            Some(line) => {
+                self.attach_comment(line, comment);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set the comment at the top of the config
+    ///
+    /// The header is the comment lines at the start of the config, it is followed by an empty line to keep it apart from any comment on the first key.
+    /// Setting it again replaces it, keeping a `managed-by` marker (see `Config::set_managed_by`).
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let mut cfg = Config::new();
+    /// cfg.set_header_comment("Generated by setup\nDo not edit by hand");
+    /// cfg.set("hello", "world");
+    ///
+    /// assert_eq!(
+    ///     cfg.to_string(),
+    ///     "# Generated by setup\n# Do not edit by hand\n\nhello = world\n"
+    /// );
+    /// ```
+    pub fn set_header_comment(&mut self, comment: &str) {
+        let mut end = 0;
+        while matches!(self.layout.get(end), Some(Line::Text(i)) if self.comments.is_comment(i.trim()))
+        {
+            end += 1;
+        }
+
+        // Comment lines not followed by an empty line belong to the first key or section
+        let replaced = match self.layout.get(end) {
+            Some(Line::Text(i)) if end > 0 && i.trim().is_empty() => end + 1,
+            _ => 0,
+        };
+        let keep = self.layout[..replaced].iter().filter(|i| match i {
+            Line::Text(i) => guard::find_marker(std::iter::once(i.as_str())).is_some(),
+            _ => false,
+        });
+
+        let mut header = keep.cloned().collect::<Vec<_>>();
+        header.extend(self.comment_lines(comment));
+        header.push(Line::Text(String::new()));
+        self.layout.splice(..replaced, header);
+    }
+
+    /// Replace the comment lines directly above the layout line `line` with `comment`
+    fn attach_comment(&mut self, line: usize, comment: &str) {
+        let mut start = line;
+        while start > 0 {
+            match &self.layout[start - 1] {
+                Line::Text(raw)
+                    if !raw.trim().is_empty() && self.comments.is_comment(raw.trim()) =>
+                {
+                    start -= 1
+                }
+                _ => break,
+            }
+        }
+
+        let lines = self.comment_lines(comment);
+        self.layout.splice(start..line, lines);
+    }
+
+    /// Lines for a comment, using the first comment char of the config
+    fn comment_lines(&self, comment: &str) -> Vec<Line> {
+        let start = self.comments.chars.first().copied().unwrap_or('#');
+        comment
+            .lines()
+            .map(|i| match i.is_empty() {
+                true => Line::Text(start.to_string()),
+                false => Line::Text(format!("{} {}", start, i)),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,143 @@
+//! Reading compressed config files
+//!
+//! Files ending in `.gz` (with the `gzip` feature) or `.zst` (with the `zstd` feature)
+//! are decompressed when they are loaded.
+use std::cell::Cell;
+use std::fs;
+use std::io;
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+use std::io::Read;
+use std::path::Path;
+use std::str;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::utf8::Decoding;
+use crate::ConfigError;
+
+/// Read a config file to a string, decompressing it if needed
+pub(crate) fn read_to_string(path: &Path) -> Result<String, ConfigError> {
+    read(path, Decoding::Strict).map(|i| i.0)
+}
+
+/// Read a config file to a string, decompressing it if needed
+///
+/// Returns `ConfigError::InvalidUtf8` if it's not UTF-8, unless `decoding` says how to read it anyway.
+/// Then the lines (from 1) that had invalid UTF-8 are returned too.
+pub(crate) fn read(path: &Path, decoding: Decoding) -> Result<(String, Vec<usize>), ConfigError> {
+    decode_with(path, read_bytes(path)?, decoding)
+}
+
+thread_local! {
+    /// When the files read on this thread have to be read by, and the timeout it's from (see `with_deadline`)
+    static DEADLINE: Cell<Option<(Instant, Duration)>> = const { Cell::new(None) };
+}
+
+/// Puts the deadline from before `with_deadline` back, even if loading panics
+struct Reset(Option<(Instant, Duration)>);
+
+impl Drop for Reset {
+    fn drop(&mut self) {
+        DEADLINE.with(|i| i.set(self.0));
+    }
+}
+
+/// Run `load` with every config file it reads (including included and extended files) given up on after `timeout`
+///
+/// Each read is done on a background thread, if it's not done in time `ConfigError::Timeout` is returned.
+/// Threads can't be cancelled, so one that timed out keeps running until its read finishes on its own.
+pub(crate) fn with_deadline<T>(timeout: Duration, load: impl FnOnce() -> T) -> T {
+    let deadline = Instant::now() + timeout;
+    let _reset = Reset(DEADLINE.with(|i| i.replace(Some((deadline, timeout)))));
+    load()
+}
+
+/// Read the bytes of a file, on a background thread if there's a deadline (see `with_deadline`)
+fn read_bytes(path: &Path) -> Result<Vec<u8>, ConfigError> {
+    let (deadline, timeout) = match DEADLINE.with(Cell::get) {
+        Some(i) => i,
+        None => return fs::read(path).map_err(|e| ConfigError::file_read(path, e)),
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let thread_path = path.to_path_buf();
+    thread::spawn(move || {
+        // The receiver may be gone if we timed out
+        let _ = tx.send(fs::read(thread_path));
+    });
+
+    match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+        Ok(contents) => contents.map_err(|e| ConfigError::file_read(path, e)),
+        Err(_) => Err(ConfigError::Timeout(timeout)),
+    }
+}
+
+/// Decompress the contents of a config file if needed (based on its path) and convert it to a string, like `read`
+pub(crate) fn decode_with(
+    path: &Path,
+    contents: Vec<u8>,
+    decoding: Decoding,
+) -> Result<(String, Vec<usize>), ConfigError> {
+    let error = match String::from_utf8(decompress(path, contents)?) {
+        Ok(i) => return Ok((i, Vec::new())),
+        Err(e) => e,
+    };
+
+    let offset = error.utf8_error().valid_up_to();
+    let bytes = error.into_bytes();
+    let lines = bytes
+        .split(|i| *i == b'\n')
+        .enumerate()
+        .filter(|(_, i)| str::from_utf8(i).is_err())
+        .map(|(n, _)| n + 1)
+        .collect::<Vec<_>>();
+
+    match decoding {
+        Decoding::Strict => {
+            let line = bytes[..offset].iter().filter(|i| **i == b'\n').count() + 1;
+            Err(ConfigError::InvalidUtf8(line, offset))
+        }
+        Decoding::Replace => Ok((String::from_utf8_lossy(&bytes).into_owned(), lines)),
+        Decoding::Latin1 => Ok((bytes.iter().map(|i| *i as char).collect(), lines)),
+    }
+}
+
+/// Decompress the contents of a config file if needed, based on its path
+fn decompress(path: &Path, contents: Vec<u8>) -> io::Result<Vec<u8>> {
+    let ext = path.extension().and_then(|i| i.to_str());
+
+    match ext {
+        #[cfg(feature = "gzip")]
+        Some("gz") => {
+            let mut out = Vec::new();
+            flate2::read::MultiGzDecoder::new(contents.as_slice())
+                .read_to_end(&mut out)
+                .map(|_| out)
+        }
+        #[cfg(feature = "zstd")]
+        Some("zst") => {
+            let mut out = Vec::new();
+            zstd::stream::read::Decoder::new(contents.as_slice())?
+                .read_to_end(&mut out)
+                .map(|_| out)
+        }
+        #[cfg(not(feature = "gzip"))]
+        Some("gz") => Err(unsupported("gzip")),
+        #[cfg(not(feature = "zstd"))]
+        Some("zst") => Err(unsupported("zstd")),
+        _ => Ok(contents),
+    }
+}
+
+/// Error for compressed files when their feature is not enabled
+#[cfg(not(all(feature = "gzip", feature = "zstd")))]
+fn unsupported(feature: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!(
+            "Reading {} compressed configs needs the `{}` feature",
+            feature, feature
+        ),
+    )
+}
@@ -0,0 +1,139 @@
+//! Getters for values that have to be within limits
+use std::fmt::Display;
+use std::ops::{Bound, RangeBounds};
+
+use crate::convert;
+use crate::{Config, ConfigError, FromConfigValue};
+
+impl Config {
+    /// Get a value converted into any type that impls FromConfigValue, that has to be in a range
+    ///
+    /// Returns `ConfigError::InvalidValue` naming the key, value and range if it's not in the range.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new().text("port = 0\nworkers = 8").unwrap();
+    ///
+    /// assert_eq!(cfg.get_range::<u8, _>("workers", 1..=16).unwrap(), 8);
+    /// assert_eq!(
+    ///     cfg.get_range::<u16, _>("port", 1..=65535).unwrap_err().to_string(),
+    ///     "Value of `port` (`0`) must be from 1 to 65535"
+    /// );
+    /// ```
+    pub fn get_range<T, R>(&self, key: &str, range: R) -> Result<T, ConfigError>
+    where
+        T: FromConfigValue + PartialOrd + Display,
+        R: RangeBounds<T>,
+    {
+        self.located(self.entry(None, key), |i| {
+            let i = i?;
+            let value = convert::convert::<T>(&i.value, self.numbers, || i.name())?;
+            match range.contains(&value) {
+                true => Ok(value),
+                false => Err(invalid(
+                    &i.name(),
+                    &i.value,
+                    format!("must be {}", describe_range(&range)),
+                )),
+            }
+        })
+    }
+
+    /// Get a value that has to be one of some options
+    ///
+    /// Returns `ConfigError::InvalidValue` naming the key, value and options if it's not one of them.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new().text("mode = quick").unwrap();
+    ///
+    /// assert_eq!(
+    ///     cfg.get_one_of("mode", &["fast", "safe"]).unwrap_err().to_string(),
+    ///     "Value of `mode` (`quick`) must be one of `fast`, `safe`"
+    /// );
+    /// ```
+    pub fn get_one_of(&self, key: &str, options: &[&str]) -> Result<String, ConfigError> {
+        self.located(self.entry(None, key), |i| {
+            let i = i?;
+            if options.contains(&i.value.as_str()) {
+                return Ok(i.value.to_owned());
+            }
+
+            let options = options
+                .iter()
+                .map(|i| format!("`{}`", i))
+                .collect::<Vec<_>>();
+            Err(invalid(
+                &i.name(),
+                &i.value,
+                format!("must be one of {}", options.join(", ")),
+            ))
+        })
+    }
+
+    /// Get a value that has to fully match a regex
+    ///
+    /// Returns `ConfigError::InvalidValue` naming the key, value and regex if it does not match.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    /// use regex::Regex;
+    ///
+    /// let cfg = Config::new().text("host = db-01").unwrap();
+    ///
+    /// assert_eq!(cfg.get_matching("host", &Regex::new("[a-z]+-[0-9]+").unwrap()).unwrap(), "db-01");
+    /// assert_eq!(
+    ///     cfg.get_matching("host", &Regex::new("[a-z]+").unwrap()).unwrap_err().to_string(),
+    ///     "Value of `host` (`db-01`) must match `[a-z]+`"
+    /// );
+    /// ```
+    #[cfg(feature = "regex")]
+    pub fn get_matching(&self, key: &str, regex: &regex::Regex) -> Result<String, ConfigError> {
+        self.located(self.entry(None, key), |i| {
+            let i = i?;
+            let full = regex
+                .find(&i.value)
+                .is_some_and(|j| j.start() == 0 && j.end() == i.value.len());
+
+            match full {
+                true => Ok(i.value.to_owned()),
+                false => Err(invalid(
+                    &i.name(),
+                    &i.value,
+                    format!("must match `{}`", regex.as_str()),
+                )),
+            }
+        })
+    }
+}
+
+/// Make the error for a value that is not allowed
+pub(crate) fn invalid(key: &str, value: &str, constraint: String) -> ConfigError {
+    ConfigError::InvalidValue(format!("of `{}` (`{}`) {}", key, value, constraint))
+}
+
+/// Describe a range, like `from 1 to 10` or `at least 1`
+fn describe_range<T: Display>(range: &impl RangeBounds<T>) -> String {
+    match (range.start_bound(), range.end_bound()) {
+        (Bound::Included(start), Bound::Included(end)) => format!("from {} to {}", start, end),
+        (Bound::Included(start), Bound::Excluded(end)) => {
+            format!("from {} to less than {}", start, end)
+        }
+        (Bound::Excluded(start), Bound::Included(end)) => {
+            format!("more than {} and at most {}", start, end)
+        }
+        (Bound::Excluded(start), Bound::Excluded(end)) => {
+            format!("more than {} and less than {}", start, end)
+        }
+        (Bound::Included(start), Bound::Unbounded) => format!("at least {}", start),
+        (Bound::Excluded(start), Bound::Unbounded) => format!("more than {}", start),
+        (Bound::Unbounded, Bound::Included(end)) => format!("at most {}", end),
+        (Bound::Unbounded, Bound::Excluded(end)) => format!("less than {}", end),
+        (Bound::Unbounded, Bound::Unbounded) => "anything".to_owned(),
+    }
+}
@@ -0,0 +1,101 @@
+//! Converting config values into other types
+use std::str::FromStr;
+
+use crate::numbers::Numbers;
+use crate::ConfigError;
+
+/// A type that can be read from a config value, with `Config::get` and the other getters
+///
+/// Every type that impls `FromStr` impls this, with a value that doesn't parse being a `ConfigError::ParseError`
+/// (which getters turn into a `ConfigError::InvalidType` naming the key, value and type).
+/// Types that can't impl `FromStr` (or need another conversion for configs) can impl it themselves,
+/// returning a `ConfigError::InvalidValue` to say what is wrong with the value.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use simple_config_parser::{Config, ConfigError, FromConfigValue};
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Mode {
+///     Fast,
+///     Safe,
+/// }
+///
+/// impl FromConfigValue for Mode {
+///     fn from_config_value(value: &str) -> Result<Self, ConfigError> {
+///         match value.to_lowercase().as_str() {
+///             "fast" => Ok(Mode::Fast),
+///             "safe" => Ok(Mode::Safe),
+///             _ => Err(ConfigError::InvalidValue("is not `fast` or `safe`".to_owned())),
+///         }
+///     }
+/// }
+///
+/// let cfg = Config::new().text("mode = SAFE\nmodes = fast, safe\nother = slow").unwrap();
+/// assert_eq!(cfg.get::<Mode>("mode").unwrap(), Mode::Safe);
+/// assert_eq!(cfg.get_vec::<Mode>("modes").unwrap(), [Mode::Fast, Mode::Safe]);
+/// assert_eq!(
+///     cfg.get::<Mode>("other").unwrap_err().to_string(),
+///     "Value is not `fast` or `safe`"
+/// );
+/// ```
+pub trait FromConfigValue: Sized {
+    /// Convert a value (or an item of a list) into the type
+    fn from_config_value(value: &str) -> Result<Self, ConfigError>;
+}
+
+/// Convert a value, naming the key (from `key`) and type in the error if it's a `ParseError`
+///
+/// If the value can't be read but is a number in one of the forms of `numbers`, it's read again as a plain number.
+pub(crate) fn convert<T>(
+    value: &str,
+    numbers: Numbers,
+    key: impl FnOnce() -> String,
+) -> Result<T, ConfigError>
+where
+    T: FromConfigValue,
+{
+    let out = match T::from_config_value(value) {
+        Err(ConfigError::ParseError) => match numbers.normalize(value) {
+            Some(number) => T::from_config_value(&number),
+            None => Err(ConfigError::ParseError),
+        },
+        i => i,
+    };
+
+    out.map_err(|e| match e {
+        ConfigError::ParseError => {
+            ConfigError::InvalidType(key(), value.to_owned(), type_name::<T>())
+        }
+        e => e,
+    })
+}
+
+/// Get the name of a type without the paths of its modules, so `Vec<IpAddr>` not `alloc::vec::Vec<core::net::IpAddr>`
+pub(crate) fn type_name<T>() -> String {
+    let mut out = String::new();
+    let mut word = String::new();
+    for i in std::any::type_name::<T>().chars() {
+        match i {
+            ':' => word.clear(),
+            i if i.is_alphanumeric() || i == '_' => word.push(i),
+            i => {
+                out.push_str(&word);
+                word.clear();
+                out.push(i);
+            }
+        }
+    }
+
+    out.push_str(&word);
+    out
+}
+
+impl<T> FromConfigValue for T
+where
+    T: FromStr,
+{
+    fn from_config_value(value: &str) -> Result<Self, ConfigError> {
+        value.parse().map_err(|_| ConfigError::ParseError)
+    }
+}
@@ -0,0 +1,247 @@
+//! Deprecated keys and when they are removed
+//!
+//! Keys can be marked as deprecated with the version of the application they are removed in.
+//! Once the application's version (set with `Config::app_version`) reaches it,
+//! reading the key returns `ConfigError::RemovedKey` instead of its value.
+use std::cmp::Ordering;
+
+use crate::config::{normalize_key, Entry};
+use crate::{Config, ConfigError};
+
+/// Called with a deprecated key when it's read
+pub(crate) type Warn = Box<dyn Fn(&Deprecation) + Send + Sync>;
+
+/// A deprecated config key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deprecation {
+    /// The key (lowercase with spaces removed)
+    pub key: String,
+    /// Version of the application the key is removed in
+    pub removed_in: Option<String>,
+    /// What to use instead, or any other info for users
+    pub note: Option<String>,
+}
+
+impl Deprecation {
+    /// Deprecate a key, with no version it will be removed in
+    pub fn new(key: &str) -> Self {
+        Deprecation {
+            key: normalize_key(key),
+            removed_in: None,
+            note: None,
+        }
+    }
+
+    /// Set the version of the application the key is removed in (like `2.0.0`)
+    pub fn removed_in(self, version: &str) -> Self {
+        Self {
+            removed_in: Some(version.to_owned()),
+            ..self
+        }
+    }
+
+    /// Set a note for users of the key, like what to use instead
+    pub fn note(self, note: &str) -> Self {
+        Self {
+            note: Some(note.to_owned()),
+            ..self
+        }
+    }
+
+    /// Check if the key has been removed as of an application version
+    pub fn is_removed(&self, version: &str) -> bool {
+        match &self.removed_in {
+            Some(i) => compare_versions(version, i) != Ordering::Less,
+            None => false,
+        }
+    }
+}
+
+/// Compare two `major.minor.patch` versions
+///
+/// Missing parts count as `0` and anything after a `-` or `+` (pre-release / build info) is ignored.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parts = |i: &str| {
+        let i = i.trim().trim_start_matches('v');
+        i.split(['-', '+']).next().unwrap_or_default().to_owned()
+    };
+    let (a, b) = (parts(a), parts(b));
+    let (mut a, mut b) = (a.split('.'), b.split('.'));
+
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (i, j) => {
+                let num = |i: Option<&str>| i.and_then(|i| i.parse::<u64>().ok()).unwrap_or(0);
+                match num(i).cmp(&num(j)) {
+                    Ordering::Equal => continue,
+                    i => return i,
+                }
+            }
+        }
+    }
+}
+
+impl Config {
+    /// Mark a key as deprecated
+    ///
+    /// If it has a `removed_in` version and the version set with `Config::app_version` has reached it,
+    /// reading the key is an error while it's still defined.
+    /// Until then it can be read, and `Config::check_deprecations` lists it as a warning.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, ConfigError, Deprecation};
+    ///
+    /// let cfg = Config::new()
+    ///     .app_version("2.1.0")
+    ///     .deprecate(Deprecation::new("max_conns").removed_in("2.0.0").note("use `max_connections`"))
+    ///     .deprecate(Deprecation::new("log_file").removed_in("3.0.0"))
+    ///     .text("max_conns = 10\nlog_file = app.log")
+    ///     .unwrap();
+    ///
+    /// assert!(matches!(cfg.get_str("max_conns"), Err(ConfigError::RemovedKey(_))));
+    /// assert_eq!(cfg.get_str("log_file").unwrap(), "app.log");
+    /// ```
+    pub fn deprecate(mut self, deprecation: Deprecation) -> Self {
+        self.deprecations.push(deprecation);
+        self
+    }
+
+    /// Let a key keep working under its old name
+    ///
+    /// Reading `new` uses the value of `old` if only the old name is defined.
+    /// The old name is deprecated with a note to use the new one, so it is listed by `Config::check_deprecations`
+    /// and `Config::on_deprecated` is called when it is read.
+    /// Names are dotted (`section.key`) for keys in sections.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .alias("max_conns", "max_connections")
+    ///     .text("max_conns = 10")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get::<u32>("max_connections").unwrap(), 10);
+    /// assert_eq!(
+    ///     cfg.check_deprecations().unwrap()[0].note.as_deref(),
+    ///     Some("use `max_connections`")
+    /// );
+    /// ```
+    pub fn alias(mut self, old: &str, new: &str) -> Self {
+        let (old, new) = (self.key_style.key(old), self.key_style.key(new));
+        self.deprecations
+            .push(Deprecation::new(&old).note(&format!("use `{}`", new)));
+        self.aliases.push((old, new));
+        self
+    }
+
+    /// Call a function when a deprecated key is read, like to log a warning
+    ///
+    /// It's called every time the key is read, directly or through an alias (see `Config::alias`).
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, Deprecation};
+    ///
+    /// let cfg = Config::new()
+    ///     .deprecate(Deprecation::new("verbose").note("use `log.level`"))
+    ///     .on_deprecated(|i| eprintln!("[WARN] `{}` is deprecated", i.key))
+    ///     .text("verbose = true")
+    ///     .unwrap();
+    ///
+    /// assert!(cfg.get::<bool>("verbose").unwrap());
+    /// ```
+    pub fn on_deprecated<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&Deprecation) + Send + Sync + 'static,
+    {
+        self.on_deprecated = Some(Box::new(callback));
+        self
+    }
+
+    /// Find an entry by the old name of a key
+    pub(crate) fn aliased(&self, section: Option<&str>, key: &str) -> Option<&Entry> {
+        let name = match section {
+            Some(section) => format!("{}.{}", section, self.key_style.lookup(key)),
+            None => self.key_style.lookup(key).into_owned(),
+        };
+
+        self.aliases
+            .iter()
+            .rev()
+            .filter(|(_, new)| *new == name)
+            .find_map(|(old, _)| self.find_entry(None, old))
+    }
+
+    /// Call `on_deprecated` if an entry that was read is deprecated
+    pub(crate) fn warn_deprecated(&self, entry: &Entry) {
+        let callback = match &self.on_deprecated {
+            Some(i) => i,
+            None => return,
+        };
+
+        let name = entry.name();
+        if let Some(i) = self
+            .deprecations
+            .iter()
+            .find(|i| i.key == entry.key || i.key == name)
+        {
+            callback(i);
+        }
+    }
+
+    /// Set the version of the application, for checking if deprecated keys have been removed
+    pub fn app_version(mut self, version: &str) -> Self {
+        self.app_version = Some(version.to_owned());
+        self
+    }
+
+    /// Check the config for deprecated keys
+    ///
+    /// Returns the deprecations of keys that are still defined (warnings),
+    /// or `ConfigError::RemovedKey` if a key that has been removed is defined.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, Deprecation};
+    ///
+    /// let cfg = Config::new()
+    ///     .deprecate(Deprecation::new("max_conns").note("use `max_connections`"))
+    ///     .text("max_conns = 10")
+    ///     .unwrap();
+    ///
+    /// for i in cfg.check_deprecations().unwrap() {
+    ///     eprintln!("`{}` is deprecated, {}", i.key, i.note.as_deref().unwrap_or_default());
+    /// }
+    /// ```
+    pub fn check_deprecations(&self) -> Result<Vec<&Deprecation>, ConfigError> {
+        let mut warnings = Vec::new();
+        for i in self.deprecations.iter() {
+            if self.find_entry(None, &i.key).is_none() {
+                continue;
+            }
+
+            if self.is_removed(&i.key) {
+                return Err(ConfigError::RemovedKey(i.key.to_owned()));
+            }
+            warnings.push(i);
+        }
+
+        Ok(warnings)
+    }
+
+    /// Check if a key has been removed as of the application's version
+    pub(crate) fn is_removed(&self, key: &str) -> bool {
+        let version = match &self.app_version {
+            Some(i) => i,
+            None => return false,
+        };
+
+        self.deprecations
+            .iter()
+            .any(|i| i.key == key && i.is_removed(version))
+    }
+}
@@ -0,0 +1,390 @@
+//! Rendering parse and validation errors with the source lines they are on
+//!
+//! Output looks like rustc's, with the line, carets under the bad part and (optionally) colors:
+//! ```text
+//! error: section header is not closed
+//!  --> app.cfg:3:1
+//!   |
+//! 3 | [database
+//!   | ^^^^^^^^^ expected a `]` at the end
+//! ```
+use std::fmt;
+use std::ops::Range;
+
+use crate::config::{Comments, INCLUDE};
+use crate::encoding;
+use crate::unknown;
+use crate::{Config, ConfigError, KeyError, Schema, Severity, ValueType};
+
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// A problem with a config, and where in the text it is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// How bad the problem is
+    pub severity: Severity,
+    /// What the problem is
+    pub message: String,
+    /// The line it is on, starting at 1
+    /// `None` for problems that are not on a line, like a missing key.
+    pub line: Option<usize>,
+    /// The bytes of the line to underline
+    pub span: Option<Range<usize>>,
+    /// Text shown next to the underline
+    pub label: Option<String>,
+}
+
+/// The diagnostics for some config text, made by `diagnose`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostics {
+    /// Name of the source, shown before line numbers
+    pub source: String,
+    /// Every problem found
+    pub diagnostics: Vec<Diagnostic>,
+    /// The text checked, for showing lines
+    text: String,
+}
+
+/// Parse config text and validate it against a schema, finding problems with their lines
+///
+/// Syntax errors (that `Config::text` just returns `ConfigError::InvalidConfig` for) point at the bad line.
+/// If the text parses, every missing, invalid and unknown (see `Schema::deny_unknown`) key is found.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use simple_config_parser::{diagnose, Schema, SchemaKey, ValueType};
+///
+/// const SCHEMA: Schema = Schema::new(&[SchemaKey::required("port", ValueType::Int)]);
+///
+/// let found = diagnose("app.cfg", "port = eighty", &SCHEMA);
+/// assert!(!found.is_ok());
+/// assert_eq!(found.diagnostics[0].line, Some(1));
+///
+/// // Print it for the user (with colors)
+/// eprintln!("{}", found.render(true));
+/// ```
+pub fn diagnose(source: &str, text: &str, schema: &Schema) -> Diagnostics {
+    let mut out = Diagnostics {
+        source: source.to_owned(),
+        diagnostics: Vec::new(),
+        text: text.to_owned(),
+    };
+
+    if let Some(i) = syntax_error(text) {
+        out.diagnostics.push(i);
+        return out;
+    }
+
+    let cfg = match Config::new().text(text) {
+        Ok(i) => i,
+        Err(e) => {
+            out.diagnostics.push(Diagnostic::error(e.to_string()));
+            return out;
+        }
+    };
+
+    if let Err(e) = schema.validate(&cfg) {
+        for i in e.keys {
+            let name = match i.section {
+                Some(section) => format!("{}.{}", section, i.key),
+                None => i.key.to_owned(),
+            };
+
+            let line = match i.error {
+                ConfigError::NoItem => None,
+                _ => cfg
+                    .occurrences(&name)
+                    .into_iter()
+                    .rev()
+                    .filter(|j| j.source == "<text>")
+                    .find(|j| j.section.as_deref() == i.section.map(str::to_lowercase).as_deref())
+                    .and_then(|j| j.line),
+            };
+
+            let mut diagnostic = match &i.error {
+                ConfigError::NoItem => Diagnostic::error(format!("`{}` is missing", name)),
+                ConfigError::ParseError => Diagnostic::error(format!("`{}` is invalid", name))
+                    .label(&format!("expected {}", expected(schema, &i))),
+                e => Diagnostic::error(format!("`{}` is invalid", name)).label(&e.to_string()),
+            };
+            if let Some(line) = line {
+                diagnostic = diagnostic.at(line, value_span(&out.text, line));
+            }
+            out.diagnostics.push(diagnostic);
+        }
+
+        let known = schema
+            .keys()
+            .into_iter()
+            .map(|(section, i)| match section {
+                Some(section) => format!("{}.{}", section.to_lowercase(), i.key),
+                None => i.key.to_owned(),
+            })
+            .collect::<Vec<_>>();
+        for i in e.unknown {
+            let line = cfg
+                .occurrences(&i)
+                .into_iter()
+                .find(|j| j.source == "<text>")
+                .and_then(|j| j.line);
+
+            let mut diagnostic = Diagnostic::error(format!("`{}` is not a known key", i));
+            if let Some(line) = line {
+                diagnostic = diagnostic.at(line, key_span(&out.text, line));
+            }
+            if let Some(known) = unknown::suggest(&i, known.iter().map(String::as_str)) {
+                diagnostic = diagnostic.label(&format!("did you mean `{}`?", known));
+            }
+            out.diagnostics.push(diagnostic);
+        }
+    }
+
+    out
+}
+
+/// Describe the type a schema expects for a key that did not parse
+fn expected(schema: &Schema, error: &KeyError) -> &'static str {
+    let kind = schema
+        .keys()
+        .into_iter()
+        .find(|(section, i)| *section == error.section && i.key == error.key)
+        .map(|(_, i)| i.kind);
+
+    match kind {
+        Some(ValueType::Int) => "an integer",
+        Some(ValueType::Float) => "a number",
+        Some(ValueType::Bool) => "`true` or `false`",
+        _ => "text",
+    }
+}
+
+/// Find the first line config text can't be parsed at, and why
+///
+/// Follows the same rules as `Config::text`.
+fn syntax_error(text: &str) -> Option<Diagnostic> {
+    let comments = Comments::default();
+    let mut lines = text.lines().enumerate();
+    while let Some((n, raw)) = lines.next() {
+        let line = raw.trim();
+        let indent = raw.len() - raw.trim_start().len();
+        let whole = indent..indent + line.len();
+
+        if line.is_empty() || line.starts_with(INCLUDE) || comments.is_comment(line) {
+            continue;
+        }
+
+        if comments.remove(line).starts_with('[') {
+            if !comments.remove(line).trim_end().ends_with(']') {
+                return Some(
+                    Diagnostic::error("section header is not closed".to_owned())
+                        .at(n + 1, Some(whole))
+                        .label("expected a `]` at the end"),
+                );
+            }
+            continue;
+        }
+
+        let (key, rest) = match raw.split_once('=') {
+            Some(i) if comments.remove(i.0).len() == i.0.len() => i,
+            Some(_) => {
+                return Some(
+                    Diagnostic::error("comment before the `=`".to_owned())
+                        .at(n + 1, Some(whole))
+                        .label("keys can't have comment chars"),
+                )
+            }
+            None => {
+                return Some(
+                    Diagnostic::error("expected `key = value`".to_owned())
+                        .at(n + 1, Some(whole))
+                        .label("this line has no `=`"),
+                )
+            }
+        };
+
+        let start = key.len() + 1 + rest.len() - rest.trim_start().len();
+        if !raw[start..].starts_with(encoding::BLOCK_QUOTE) {
+            continue;
+        }
+
+        // Find the closing quotes of a multi-line value
+        let mut body = &raw[start + encoding::BLOCK_QUOTE.len()..];
+        let mut at = n;
+        let close = loop {
+            if let Some(i) = body.find(encoding::BLOCK_QUOTE) {
+                break &body[i + encoding::BLOCK_QUOTE.len()..];
+            }
+
+            match lines.next() {
+                Some((n, i)) => {
+                    body = i;
+                    at = n;
+                }
+                None => {
+                    return Some(
+                        Diagnostic::error("multi-line value is never closed".to_owned())
+                            .at(n + 1, Some(start..start + encoding::BLOCK_QUOTE.len()))
+                            .label("opened here"),
+                    )
+                }
+            }
+        };
+
+        if !comments.remove(close).trim().is_empty() {
+            let rest = close.trim_start();
+            let line = text.lines().nth(at).unwrap_or_default();
+            let start = line.len() - rest.len();
+            return Some(
+                Diagnostic::error("text after a multi-line value".to_owned())
+                    .at(at + 1, Some(start..line.trim_end().len()))
+                    .label("only a comment can come after the closing `\"\"\"`"),
+            );
+        }
+    }
+
+    None
+}
+
+/// Get the span of the value on a key value line
+fn value_span(text: &str, line: usize) -> Option<Range<usize>> {
+    let raw = text.lines().nth(line - 1)?;
+    let (key, rest) = raw.split_once('=')?;
+    let start = key.len() + 1 + rest.len() - rest.trim_start().len();
+    let (_, len) = Comments::default().value(&raw[start..]);
+    Some(start..start + len.max(1))
+}
+
+/// Get the span of the key on a key value line
+fn key_span(text: &str, line: usize) -> Option<Range<usize>> {
+    let raw = text.lines().nth(line - 1)?;
+    let (key, _) = raw.split_once('=')?;
+    let start = key.len() - key.trim_start().len();
+    Some(start..key.trim_end().len())
+}
+
+impl Diagnostic {
+    /// Create an error that is not at any line
+    fn error(message: String) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            message,
+            line: None,
+            span: None,
+            label: None,
+        }
+    }
+
+    /// Put the diagnostic at a line
+    fn at(self, line: usize, span: Option<Range<usize>>) -> Self {
+        Diagnostic {
+            line: Some(line),
+            span,
+            ..self
+        }
+    }
+
+    /// Set the text shown next to the underline
+    fn label(self, label: &str) -> Self {
+        Diagnostic {
+            label: Some(label.to_owned()),
+            ..self
+        }
+    }
+}
+
+impl Diagnostics {
+    /// Check if no errors were found
+    pub fn is_ok(&self) -> bool {
+        !self
+            .diagnostics
+            .iter()
+            .any(|i| i.severity == Severity::Error)
+    }
+
+    /// Render every diagnostic with the lines they are on
+    ///
+    /// With `color` they are colored using ANSI escapes, for printing to a terminal.
+    pub fn render(&self, color: bool) -> String {
+        let paint = |style: &'static str| if color { style } else { "" };
+        let reset = paint(RESET);
+        let blue = paint(BLUE);
+        let lines = self.text.lines().collect::<Vec<_>>();
+
+        let mut out = String::new();
+        for i in self.diagnostics.iter() {
+            let (name, style) = match i.severity {
+                Severity::Warning => ("warning", paint(YELLOW)),
+                Severity::Error => ("error", paint(RED)),
+            };
+            out.push_str(&format!(
+                "{}{}{}: {}{}{}\n",
+                style,
+                name,
+                reset,
+                paint(BOLD),
+                i.message,
+                reset
+            ));
+
+            let line = match i.line.and_then(|n| Some((n, *lines.get(n - 1)?))) {
+                Some(i) => i,
+                None => {
+                    out.push_str(&format!("{} --> {}{}\n", blue, reset, self.source));
+                    if let Some(label) = &i.label {
+                        out.push_str(&format!("{}  = {}note: {}\n", blue, reset, label));
+                    }
+                    out.push('\n');
+                    continue;
+                }
+            };
+
+            let (n, text) = line;
+            let span = i.span.clone().unwrap_or(0..text.len());
+            let column = text.get(..span.start).map_or(0, |i| i.chars().count());
+            let width = text
+                .get(span.clone())
+                .map_or(1, |i| i.chars().count().max(1));
+            let gutter = " ".repeat(n.to_string().len());
+
+            out.push_str(&format!(
+                "{}{}--> {}{}:{}:{}\n",
+                gutter,
+                blue,
+                reset,
+                self.source,
+                n,
+                column + 1
+            ));
+            out.push_str(&format!("{} {}|{}\n", gutter, blue, reset));
+            out.push_str(&format!("{}{} |{} {}\n", blue, n, reset, text));
+            out.push_str(&format!(
+                "{} {}|{} {}{}{}",
+                gutter,
+                blue,
+                reset,
+                " ".repeat(column),
+                style,
+                "^".repeat(width)
+            ));
+            if let Some(label) = &i.label {
+                out.push(' ');
+                out.push_str(label);
+            }
+            out.push_str(reset);
+            out.push_str("\n\n");
+        }
+
+        out
+    }
+}
+
+/// Renders the diagnostics without colors
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.render(false).trim_end())
+    }
+}
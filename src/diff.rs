@@ -0,0 +1,168 @@
+//! Finding the differences between two configs
+use std::fmt::{self, Write};
+
+use crate::{encoding, Change, Config};
+
+/// The keys that differ between two configs, made by `Config::diff`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diff {
+    /// Every key that was added, removed or changed
+    /// Keys of the old config come first (in the order they were defined), then the added keys.
+    pub changes: Vec<Change>,
+}
+
+impl Config {
+    /// Get the keys that differ from another config, with their old and new values
+    ///
+    /// This config is the old one, so keys only in `other` are added.
+    /// Values are compared as they would be read: the last definition of each key, with defaults.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let old = Config::new().text("port = 80\nhost = localhost").unwrap();
+    /// let new = Config::new().text("port = 8080\ntls = true").unwrap();
+    ///
+    /// let diff = old.diff(&new);
+    /// assert_eq!(diff.changed().next().unwrap().key, "port");
+    /// assert_eq!(diff.removed().next().unwrap().key, "host");
+    /// assert_eq!(diff.added().next().unwrap().key, "tls");
+    /// assert_eq!(diff.to_string(), "~ port = 80 -> 8080\n- host = localhost\n+ tls = true");
+    /// ```
+    pub fn diff(&self, other: &Config) -> Diff {
+        let old = self.values();
+        let new = other.values();
+
+        let mut changes = Vec::new();
+        for (key, value) in old.iter() {
+            let after = new.iter().find(|i| i.0 == *key).map(|i| i.1.to_owned());
+            if after.as_ref() != Some(value) {
+                changes.push(Change {
+                    key: key.to_owned(),
+                    old: Some(value.to_owned()),
+                    new: after,
+                });
+            }
+        }
+
+        for (key, value) in new {
+            if !old.iter().any(|i| i.0 == key) {
+                changes.push(Change {
+                    key,
+                    old: None,
+                    new: Some(value),
+                });
+            }
+        }
+
+        Diff { changes }
+    }
+
+    /// Write the keys that differ from a base config as a config file, to be loaded over the base
+    ///
+    /// Only keys that were added or changed are written, with the value in use,
+    /// as keys can't be removed by loading a file over another.
+    /// Keys are grouped by section in the order of `Config::iter_effective`.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let base = Config::new().text("[server]\nhost = 0.0.0.0\nport = 80").unwrap();
+    /// let prod = Config::new()
+    ///     .text("[server]\nhost = 0.0.0.0\nport = 443\n[tls]\ncert = /etc/cert.pem")
+    ///     .unwrap();
+    ///
+    /// let delta = prod.export_delta(&base);
+    /// assert_eq!(delta, "[server]\nport = 443\n[tls]\ncert = /etc/cert.pem\n");
+    ///
+    /// let cfg = Config::new().text("[server]\nhost = 0.0.0.0\nport = 80").unwrap().text(delta).unwrap();
+    /// assert!(cfg.diff(&prod).is_empty());
+    /// ```
+    pub fn export_delta(&self, base: &Config) -> String {
+        let diff = base.diff(self);
+        let changed = diff
+            .changes
+            .iter()
+            .filter(|i| i.new.is_some())
+            .map(|i| i.key.as_str())
+            .collect::<Vec<_>>();
+
+        let mut entries = self
+            .iter_effective()
+            .filter(|i| changed.contains(&i.name().as_str()))
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|i| i.section.is_some());
+
+        let mut out = String::new();
+        let mut section = None;
+        for i in entries {
+            if i.section.is_some() && i.section != section {
+                let _ = writeln!(out, "[{}]", i.section.as_deref().unwrap_or_default());
+                section = i.section.clone();
+            }
+            let _ = writeln!(out, "{} = {}", i.key, encoding::quote_value(&i.value));
+        }
+
+        out
+    }
+
+    /// Get the dotted name of every key with the value in use, in the order they were first defined
+    fn values(&self) -> Vec<(String, String)> {
+        let mut out: Vec<(String, String)> = Vec::new();
+        for i in self.entries() {
+            let name = i.name();
+            match out.iter_mut().find(|j| j.0 == name) {
+                Some(j) => j.1 = i.value.to_owned(),
+                None => out.push((name, i.value.to_owned())),
+            }
+        }
+
+        out
+    }
+}
+
+impl Diff {
+    /// Check if the configs are the same
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Get the keys only in the new config
+    pub fn added(&self) -> impl Iterator<Item = &Change> {
+        self.changes.iter().filter(|i| i.old.is_none())
+    }
+
+    /// Get the keys only in the old config
+    pub fn removed(&self) -> impl Iterator<Item = &Change> {
+        self.changes.iter().filter(|i| i.new.is_none())
+    }
+
+    /// Get the keys in both configs with different values
+    pub fn changed(&self) -> impl Iterator<Item = &Change> {
+        self.changes
+            .iter()
+            .filter(|i| i.old.is_some() && i.new.is_some())
+    }
+}
+
+/// One change per line, like `+ key = value`, `- key = value` or `~ key = old -> new`
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (n, i) in self.changes.iter().enumerate() {
+            if n > 0 {
+                f.write_str("\n")?;
+            }
+
+            match (&i.old, &i.new) {
+                (Some(old), Some(new)) => write!(f, "~ {} = {} -> {}", i.key, old, new)?,
+                (Some(old), None) => write!(f, "- {} = {}", i.key, old)?,
+                (None, Some(new)) => write!(f, "+ {} = {}", i.key, new)?,
+                (None, None) => {}
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+//! Tracking the keys changed since a config was loaded
+use crate::Config;
+#[cfg(feature = "file")]
+use crate::ConfigError;
+
+impl Config {
+    /// Check if any keys have been changed with `set` / `remove` / `clear` since the config was loaded
+    ///
+    /// The changes are forgotten by `Config::mark_clean` and `Config::save_if_dirty`, not `save` (as it does not change the config).
+    pub fn is_dirty(&self) -> bool {
+        !self.dirty.is_empty()
+    }
+
+    /// Get the dotted names of the keys changed since the config was loaded (see `Config::is_dirty`), in the order they were first changed
+    ///
+    /// Keys changed back to the value they had are still listed.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let mut cfg = Config::new().text("[window]\nwidth = 800\nheight = 600").unwrap();
+    /// assert!(!cfg.is_dirty());
+    ///
+    /// cfg.set("width", "1024");
+    /// cfg.set("height", "600");
+    /// cfg.set("theme", "dark");
+    /// assert_eq!(cfg.dirty_keys(), ["window.width", "theme"]);
+    /// ```
+    pub fn dirty_keys(&self) -> &[String] {
+        &self.dirty
+    }
+
+    /// Forget the changed keys, like after saving the config
+    pub fn mark_clean(&mut self) {
+        self.dirty.clear();
+    }
+
+    /// Save the config to the file it was loaded from if any keys have changed, see `Config::save`
+    ///
+    /// Returns if the file was written, the changed keys are forgotten once it is.
+    /// Meant for saving settings whenever they might have changed, without writing the file every time.
+    /// ## Example
+    /// ```rust,no_run
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let mut cfg = Config::new().file("settings.cfg").unwrap();
+    /// assert!(!cfg.save_if_dirty().unwrap());
+    ///
+    /// cfg.set("volume", "80");
+    /// assert!(cfg.save_if_dirty().unwrap());
+    /// assert!(!cfg.is_dirty());
+    /// ```
+    #[cfg(feature = "file")]
+    pub fn save_if_dirty(&mut self) -> Result<bool, ConfigError> {
+        if !self.is_dirty() {
+            return Ok(false);
+        }
+
+        self.save()?;
+        self.mark_clean();
+        Ok(true)
+    }
+
+    /// Note that a key has been changed
+    pub(crate) fn mark_dirty(&mut self, key: String) {
+        if !self.dirty.contains(&key) {
+            self.dirty.push(key);
+        }
+    }
+}
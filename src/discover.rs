@@ -0,0 +1,82 @@
+//! Finding config files in the usual places, for command line apps (with the `file` feature)
+use std::env;
+use std::path::{Path, PathBuf};
+
+use crate::paths;
+use crate::{Config, ConfigError};
+
+impl Config {
+    /// Get the paths a config file named `name` is looked for at by `Config::discover`, lowest precedence first
+    ///
+    /// The app name is the file name without its extension (`myapp` for `myapp.cfg`). The paths are:
+    /// - The directory of the executable (`<exe dir>/myapp.cfg`)
+    /// - `/etc/myapp/myapp.cfg` (not on windows)
+    /// - `~/.config/myapp/myapp.cfg`
+    /// - `$XDG_CONFIG_HOME/myapp/myapp.cfg`, if it's set to something else
+    /// - The working directory (`./myapp.cfg`)
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use std::path::Path;
+    /// use simple_config_parser::Config;
+    ///
+    /// let paths = Config::search_paths("myapp.cfg");
+    /// assert_eq!(paths.last().unwrap(), Path::new("myapp.cfg"));
+    /// ```
+    pub fn search_paths(name: &str) -> Vec<PathBuf> {
+        let app = Path::new(name)
+            .file_stem()
+            .map_or_else(|| name.into(), |i| i.to_os_string());
+        let mut out = Vec::new();
+
+        if let Some(dir) = env::current_exe().ok().as_deref().and_then(Path::parent) {
+            out.push(dir.join(name));
+        }
+        if cfg!(not(windows)) {
+            out.push(Path::new("/etc").join(&app).join(name));
+        }
+        if let Some(home) = paths::home_dir() {
+            out.push(home.join(".config").join(&app).join(name));
+        }
+        if let Some(xdg) = env::var_os("XDG_CONFIG_HOME").filter(|i| !i.is_empty()) {
+            out.push(Path::new(&xdg).join(&app).join(name));
+        }
+        out.push(PathBuf::from(name));
+
+        // `XDG_CONFIG_HOME` is usually `~/.config`
+        out.dedup();
+        out
+    }
+
+    /// Load every config file named `name` found in the usual places, see `Config::search_paths`
+    ///
+    /// Files are loaded lowest precedence first, so the working directory overrides the user's config,
+    /// which overrides the system one.
+    /// Missing files are skipped, but a file that can't be read or parsed fails the whole load.
+    /// The files that were used are the sources of `Config::load_report`, and `save` writes to the last one.
+    /// ## Example
+    /// ```rust,no_run
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new().discover("myapp.cfg").unwrap();
+    ///
+    /// for i in cfg.load_report().sources.iter() {
+    ///     println!("Using config from {}", i.source);
+    /// }
+    /// ```
+    pub fn discover(mut self, name: &str) -> Result<Self, ConfigError> {
+        for path in Config::search_paths(name) {
+            if !path.is_file() {
+                continue;
+            }
+
+            match self.load_file(&path) {
+                Err(ConfigError::FileNotFound(_)) => continue,
+                i => i?,
+            }
+        }
+
+        Ok(self)
+    }
+}
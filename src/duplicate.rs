@@ -0,0 +1,104 @@
+//! What to do with keys defined more than once
+use std::collections::HashSet;
+
+use crate::config::Entry;
+use crate::convert;
+use crate::layout::{self, Line};
+use crate::numbers::Numbers;
+use crate::{Config, ConfigError, FromConfigValue};
+
+/// What to do with a key defined more than once in a source (or the files it includes)
+///
+/// Keys defined again by a later source always replace the earlier value, unless the policy is `Collect`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicatePolicy {
+    /// The last definition is used
+    #[default]
+    LastWins,
+    /// The first definition is used, the others are ignored (but kept when saving)
+    FirstWins,
+    /// Loading the source fails with `ConfigError::DuplicateKey`
+    Error,
+    /// Every definition is kept and can be read with `Config::get_all`, `get` reads the last one
+    /// Definitions from every source are collected.
+    Collect,
+}
+
+impl Config {
+    /// Set what happens to keys defined more than once in sources loaded after this
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, ConfigError, DuplicatePolicy};
+    ///
+    /// let text = "listen = 80\nlisten = 443";
+    ///
+    /// let cfg = Config::new().duplicates(DuplicatePolicy::FirstWins).text(text).unwrap();
+    /// assert_eq!(cfg.get::<u16>("listen").unwrap(), 80);
+    ///
+    /// let cfg = Config::new().duplicates(DuplicatePolicy::Collect).text(text).unwrap();
+    /// assert_eq!(cfg.get_all::<u16>("listen").unwrap(), [80, 443]);
+    ///
+    /// let err = Config::new().duplicates(DuplicatePolicy::Error).text(text);
+    /// assert!(matches!(err, Err(ConfigError::DuplicateKey(_))));
+    /// ```
+    pub fn duplicates(mut self, policy: DuplicatePolicy) -> Self {
+        self.duplicates = policy;
+        self
+    }
+
+    /// Get every value of a key, converted into any type that impls FromConfigValue
+    ///
+    /// With `DuplicatePolicy::Collect` every definition of the key is returned in the order they were loaded,
+    /// otherwise just the one `get` reads.
+    /// Keys can be in any section, or a dotted `section.key` name.
+    pub fn get_all<T>(&self, key: &str) -> Result<Vec<T>, ConfigError>
+    where
+        T: FromConfigValue,
+    {
+        let entry = self.entry(None, key)?;
+        if self.duplicates != DuplicatePolicy::Collect {
+            return Ok(vec![parse(entry, self.numbers)?]);
+        }
+
+        let key = self.key_style.lookup(key);
+        self.entries()
+            .filter(|i| i.matches(&key))
+            .map(|i| parse(i, self.numbers))
+            .collect()
+    }
+}
+
+/// Convert the value of an entry
+fn parse<T: FromConfigValue>(entry: &Entry, numbers: Numbers) -> Result<T, ConfigError> {
+    convert::convert(&entry.value, numbers, || entry.name())
+}
+
+/// Apply a policy to the keys defined more than once in a source's entries
+pub(crate) fn apply(
+    policy: DuplicatePolicy,
+    data: &mut Vec<Entry>,
+    layout: &mut Vec<Line>,
+) -> Result<(), ConfigError> {
+    if matches!(policy, DuplicatePolicy::LastWins | DuplicatePolicy::Collect) {
+        return Ok(());
+    }
+
+    let mut seen = HashSet::new();
+    let repeated = data
+        .iter()
+        .map(|i| !seen.insert(i.name()))
+        .collect::<Vec<_>>();
+
+    if let Some(i) = repeated.iter().position(|i| *i) {
+        if policy == DuplicatePolicy::Error {
+            return Err(ConfigError::DuplicateKey(data[i].name()));
+        }
+
+        layout::ignore(layout, &repeated);
+        let mut repeated = repeated.into_iter();
+        data.retain(|_| !repeated.next().unwrap_or(false));
+    }
+
+    Ok(())
+}
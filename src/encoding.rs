@@ -0,0 +1,256 @@
+//! Helpers for storing binary data in config values
+//!
+//! Values can hold arbitrary bytes in one of two ways:
+//! - Prefixed with `base64:` and encoded with the standard base64 alphabet
+//! - As plain text where `\xNN` (hex) and `\\` escapes are decoded
+//!
+//! Along with percent encoding (`%20`) for values used in URLs, shell style word splitting,
+//! the double quoted values of config files and JSON strings.
+
+/// Prefix marking a value as base64 encoded.
+pub const BASE64_PREFIX: &str = "base64:";
+
+/// The standard base64 alphabet.
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Decode a config value into raw bytes.
+/// Returns `None` if the value is not valid base64 / contains a bad escape.
+pub fn decode_bytes(value: &str) -> Option<Vec<u8>> {
+    match value.strip_prefix(BASE64_PREFIX) {
+        Some(i) => decode_base64(i.trim()),
+        None => unescape_bytes(value),
+    }
+}
+
+/// Decode a base64 string (padding is optional).
+fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    let input = input.trim_end_matches('=');
+    let mut out = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buf = 0u32;
+    let mut bits = 0;
+
+    for i in input.bytes() {
+        let val = BASE64_CHARS.iter().position(|&c| c == i)? as u32;
+        buf = (buf << 6) | val;
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buf >> bits) as u8);
+            buf &= (1 << bits) - 1;
+        }
+    }
+
+    // A single leftover char can never make up a full byte
+    if bits >= 6 {
+        return None;
+    }
+
+    Some(out)
+}
+
+/// Decode `\xNN` and `\\` escapes into bytes.
+fn unescape_bytes(input: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut bytes = input.bytes();
+
+    while let Some(i) = bytes.next() {
+        if i != b'\\' {
+            out.push(i);
+            continue;
+        }
+
+        match bytes.next()? {
+            b'\\' => out.push(b'\\'),
+            b'x' => {
+                let high = (bytes.next()? as char).to_digit(16)?;
+                let low = (bytes.next()? as char).to_digit(16)?;
+                out.push((high << 4 | low) as u8);
+            }
+            _ => return None,
+        }
+    }
+
+    Some(out)
+}
+
+/// Start and end of a multi-line value
+pub const BLOCK_QUOTE: &str = "\"\"\"";
+
+/// Chars that can't be in an unquoted value
+const QUOTE_CHARS: [char; 6] = ['#', ';', '"', '\n', '\r', '\t'];
+
+/// Parse a double quoted value from the start of some text.
+///
+/// Decodes the `\"`, `\\`, `\n`, `\r` and `\t` escapes, other backslashes are kept as is.
+/// Returns the value and the length of the quoted text (including the quotes),
+/// or `None` if the text does not start with a closed quoted string.
+pub fn parse_quoted(text: &str) -> Option<(String, usize)> {
+    let mut chars = text.char_indices();
+    if chars.next()?.1 != '"' {
+        return None;
+    }
+
+    let mut out = String::new();
+    while let Some((n, i)) = chars.next() {
+        match i {
+            '"' => return Some((out, n + 1)),
+            '\\' => match chars.next()?.1 {
+                'n' => out.push('\n'),
+                'r' => out.push('\r'),
+                't' => out.push('\t'),
+                i @ ('"' | '\\') => out.push(i),
+                i => {
+                    out.push('\\');
+                    out.push(i);
+                }
+            },
+            i => out.push(i),
+        }
+    }
+
+    None
+}
+
+/// Encode a value to be written to a config file.
+///
+/// Values with comment chars, quotes, newlines / tabs or surrounding whitespace are double quoted.
+/// Multi-line values are written as `"""` blocks when they can be, everything else is written as is.
+pub fn quote_value(value: &str) -> String {
+    let needs_quotes = value.contains(&QUOTE_CHARS[..])
+        || value.starts_with(char::is_whitespace)
+        || value.ends_with(char::is_whitespace);
+
+    if !needs_quotes {
+        return value.to_owned();
+    }
+
+    if value.contains('\n')
+        && !value.contains(BLOCK_QUOTE)
+        && !value.contains('\r')
+        && !value.ends_with('"')
+    {
+        return format!("{}\n{}{}", BLOCK_QUOTE, value, BLOCK_QUOTE);
+    }
+
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for i in value.chars() {
+        match i {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            i => out.push(i),
+        }
+    }
+    out.push('"');
+
+    out
+}
+
+/// Decode `%XX` escapes (and `+` is left as is).
+/// Returns `None` if an escape is invalid or the result is not UTF-8.
+pub fn percent_decode(input: &str) -> Option<String> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut bytes = input.bytes();
+
+    while let Some(i) = bytes.next() {
+        if i != b'%' {
+            out.push(i);
+            continue;
+        }
+
+        let high = (bytes.next()? as char).to_digit(16)?;
+        let low = (bytes.next()? as char).to_digit(16)?;
+        out.push((high << 4 | low) as u8);
+    }
+
+    String::from_utf8(out).ok()
+}
+
+/// Percent encode everything but unreserved URL chars.
+pub fn percent_encode(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for i in input.bytes() {
+        match i {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                out.push(i as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", i)),
+        }
+    }
+
+    out
+}
+
+/// Split a value into words like a shell would.
+///
+/// Words are separated by whitespace, `'single'` quotes are literal and `"double"` quotes allow `\"` and `\\` escapes.
+/// Outside of quotes a backslash escapes the next char.
+/// Returns `None` if a quote is not closed or the value ends with a backslash.
+pub fn split_words(input: &str) -> Option<Vec<String>> {
+    let mut out = Vec::new();
+    let mut word = None;
+    let mut chars = input.chars();
+
+    while let Some(i) = chars.next() {
+        match i {
+            i if i.is_whitespace() => {
+                if let Some(i) = word.take() {
+                    out.push(i);
+                }
+            }
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next()? {
+                        '\'' => break,
+                        i => word.push(i),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => match chars.next()? {
+                            i @ ('"' | '\\' | '$' | '`') => word.push(i),
+                            i => {
+                                word.push('\\');
+                                word.push(i);
+                            }
+                        },
+                        i => word.push(i),
+                    }
+                }
+            }
+            '\\' => word.get_or_insert_with(String::new).push(chars.next()?),
+            i => word.get_or_insert_with(String::new).push(i),
+        }
+    }
+
+    out.extend(word);
+    Some(out)
+}
+
+/// Escape a string for a JSON string literal
+pub fn json_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for i in text.chars() {
+        match i {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            i if (i as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", i as u32)),
+            i => out.push(i),
+        }
+    }
+    out.push('"');
+    out
+}
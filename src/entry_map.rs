@@ -0,0 +1,316 @@
+//! The entries of a config, in order with an index for lookups
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{BuildHasher, BuildHasherDefault, Hasher};
+use std::iter::FromIterator;
+use std::ops::Deref;
+#[cfg(feature = "reload")]
+use std::ops::Range;
+use std::sync::OnceLock;
+
+use crate::config::Entry;
+use crate::value::Value;
+
+/// The entries of a config in the order they were loaded, like a `Vec<Entry>`
+///
+/// Keys can be defined more than once, every definition is kept.
+/// Entries are indexed by their key and dotted name (`section.key`) the first time a large map is searched,
+/// so lookups don't search every entry.
+/// The type of each value is inferred the first time it's asked for, see `Config::get_value`.
+/// It derefs to a slice of the entries for reading.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use simple_config_parser::Config;
+///
+/// let cfg = Config::new()
+///     .text("host = a\n[db]\nhost = b\n[cache]\nhost = c")
+///     .unwrap();
+///
+/// assert_eq!(cfg.data.len(), 3);
+/// assert_eq!(cfg.data[1].key, "host");
+/// assert_eq!(cfg.data.lookup("host").count(), 3);
+/// assert_eq!(cfg.data.lookup("db.host").next().unwrap().value, "b");
+/// ```
+#[derive(Clone, Default)]
+pub struct EntryMap {
+    /// The entries in order
+    list: Vec<Entry>,
+    /// The index of the entries, made the first time a large map is searched
+    index: OnceLock<Box<Index>>,
+    /// The inferred value of each entry, made the first time one is asked for
+    values: OnceLock<Vec<OnceLock<Value>>>,
+}
+
+/// Indexes of the entries with each key and dotted name, in order
+///
+/// Names are indexed by their hash so dotted names don't have to be built for every entry,
+/// entries whose name only has the same hash are skipped by lookups.
+#[derive(Clone, Default)]
+struct Index {
+    names: HashMap<u64, Vec<usize>, BuildHasherDefault<Hashed>>,
+    /// How names are hashed
+    hasher: RandomState,
+}
+
+/// Maps with fewer entries than this are searched in full, as that's faster than indexing them
+const SEARCHED: usize = 8;
+
+impl EntryMap {
+    /// Create an empty map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the entries found by a flat lookup of `key`, in the order they were loaded
+    ///
+    /// That is those with the key in any section, or the dotted name.
+    /// Keys are not normalized here, so they need to be as they are stored (lowercase by default).
+    pub fn lookup<'a: 'k, 'k>(
+        &'a self,
+        key: &'k str,
+    ) -> impl DoubleEndedIterator<Item = &'a Entry> + 'k {
+        self.indexes(key).map(move |i| &self.list[i])
+    }
+
+    /// Get the indexes of the entries found by a flat lookup of `key`, see `lookup`
+    pub(crate) fn indexes<'a: 'k, 'k>(
+        &'a self,
+        key: &'k str,
+    ) -> impl DoubleEndedIterator<Item = usize> + 'k {
+        let (all, found) = match self.list.len() < SEARCHED {
+            true => (0..self.list.len(), &[][..]),
+            false => (0..0, self.index().find(key)),
+        };
+        all.chain(found.iter().copied())
+            .filter(move |i| self.list[*i].matches(key))
+    }
+
+    /// Get the index, making it if this is the first time
+    fn index(&self) -> &Index {
+        self.index.get_or_init(|| {
+            let mut index = Index::default();
+            for (n, entry) in self.list.iter().enumerate() {
+                index.add(n, entry);
+            }
+            Box::new(index)
+        })
+    }
+
+    /// Get the inferred value of an entry in the map, `None` if it's not one of the entries of this map
+    ///
+    /// The entry is found by a lookup of its key, and its type is inferred the first time.
+    pub(crate) fn value_of(&self, entry: &Entry) -> Option<&Value> {
+        let n = self
+            .indexes(&entry.key)
+            .find(|i| std::ptr::eq(&self.list[*i], entry))?;
+        let values = self
+            .values
+            .get_or_init(|| self.list.iter().map(|_| OnceLock::new()).collect());
+        Some(values[n].get_or_init(|| Value::infer(&entry.value)))
+    }
+
+    /// Add an entry to the end
+    pub(crate) fn push(&mut self, entry: Entry) {
+        let n = self.list.len();
+        if let Some(index) = self.index.get_mut() {
+            index.add(n, &entry);
+        }
+        if let Some(values) = self.values.get_mut() {
+            values.push(OnceLock::new());
+        }
+        self.list.push(entry);
+    }
+
+    /// Move all the entries from `other` to the end
+    pub(crate) fn append(&mut self, other: &mut Vec<Entry>) {
+        if self.list.is_empty() && self.index.get().is_none() && self.values.get().is_none() {
+            self.list = std::mem::take(other);
+            return;
+        }
+
+        self.list.reserve(other.len());
+        other.drain(..).for_each(|i| self.push(i));
+    }
+
+    /// Replace the entries in `range` with `entries`, updating the index in place
+    ///
+    /// Only the indexes of the replaced entries and the ones after them are changed, for small edits to large configs.
+    #[cfg(feature = "reload")]
+    pub(crate) fn splice(&mut self, range: Range<usize>, entries: Vec<Entry>) {
+        if let Some(index) = self.index.get_mut() {
+            index.splice(&self.list, range.clone(), &entries);
+        }
+        if let Some(values) = self.values.get_mut() {
+            values.splice(range.clone(), entries.iter().map(|_| OnceLock::new()));
+        }
+        self.list.splice(range, entries);
+    }
+
+    /// Change the value of the entry at `index`
+    pub(crate) fn set_value(&mut self, index: usize, value: String) {
+        if let Some(values) = self.values.get_mut() {
+            values[index] = OnceLock::new();
+        }
+        self.list[index].value = value;
+    }
+
+    /// Keep only the entries `keep` is true for, in the same order
+    pub(crate) fn retain(&mut self, keep: impl FnMut(&Entry) -> bool) {
+        let mut list = std::mem::take(&mut self.list);
+        list.retain(keep);
+        *self = list.into();
+    }
+
+    /// Remove every entry
+    pub(crate) fn clear(&mut self) {
+        *self = EntryMap::new();
+    }
+
+    /// Take the entries out of the map
+    pub fn into_vec(self) -> Vec<Entry> {
+        self.list
+    }
+}
+
+impl Index {
+    /// Get the indexes of the entries with a name that has the same hash as `key`
+    fn find(&self, key: &str) -> &[usize] {
+        self.names
+            .get(&self.hash(None, key))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Add the entry at `n`, after the entries already indexed
+    fn add(&mut self, n: usize, entry: &Entry) {
+        for name in self.names(entry) {
+            self.names.entry(name).or_default().push(n);
+        }
+    }
+
+    /// Replace the entries of `list` in `range` with `entries`, see `EntryMap::splice`
+    #[cfg(feature = "reload")]
+    fn splice(&mut self, list: &[Entry], range: Range<usize>, entries: &[Entry]) {
+        for n in range.clone() {
+            for name in self.names(&list[n]) {
+                if let Some(i) = self.names.get_mut(&name) {
+                    i.retain(|i| *i != n);
+                    if i.is_empty() {
+                        self.names.remove(&name);
+                    }
+                }
+            }
+        }
+
+        // Indexes are in order, so only the end of each list needs moving
+        let (removed, added) = (range.len(), entries.len());
+        for i in self.names.values_mut() {
+            for i in i.iter_mut().rev().take_while(|i| **i >= range.end) {
+                *i = *i - removed + added;
+            }
+        }
+
+        for (n, entry) in (range.start..).zip(entries.iter()) {
+            for name in self.names(entry) {
+                let i = self.names.entry(name).or_default();
+                let at = i.partition_point(|i| *i < n);
+                i.insert(at, n);
+            }
+        }
+    }
+
+    /// Hash the name `section.key`, or just `key` with no section
+    fn hash(&self, section: Option<&str>, key: &str) -> u64 {
+        let mut hasher = self.hasher.build_hasher();
+        if let Some(section) = section {
+            hasher.write(section.as_bytes());
+            hasher.write_u8(b'.');
+        }
+        hasher.write(key.as_bytes());
+        hasher.finish()
+    }
+
+    /// Get the hashes of the names an entry is indexed by, its key and dotted name (if it's in a section)
+    fn names(&self, entry: &Entry) -> impl Iterator<Item = u64> {
+        let key = self.hash(None, &entry.key);
+        let name = entry
+            .section
+            .as_deref()
+            .map(|section| self.hash(Some(section), &entry.key))
+            .filter(|i| *i != key);
+        name.into_iter().chain(std::iter::once(key))
+    }
+}
+
+/// Uses the hashes of names as they are, as they are already hashed
+#[derive(Default)]
+struct Hashed(u64);
+
+impl Hasher for Hashed {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for i in bytes {
+            self.0 = self.0.rotate_left(8) ^ u64::from(*i);
+        }
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.0 = i;
+    }
+}
+
+impl Deref for EntryMap {
+    type Target = [Entry];
+
+    fn deref(&self) -> &[Entry] {
+        &self.list
+    }
+}
+
+impl From<Vec<Entry>> for EntryMap {
+    fn from(list: Vec<Entry>) -> Self {
+        let mut out = EntryMap::new();
+        list.into_iter().for_each(|i| out.push(i));
+        out
+    }
+}
+
+impl FromIterator<Entry> for EntryMap {
+    fn from_iter<T: IntoIterator<Item = Entry>>(iter: T) -> Self {
+        let mut out = EntryMap::new();
+        iter.into_iter().for_each(|i| out.push(i));
+        out
+    }
+}
+
+impl Extend<Entry> for EntryMap {
+    fn extend<T: IntoIterator<Item = Entry>>(&mut self, iter: T) {
+        iter.into_iter().for_each(|i| self.push(i));
+    }
+}
+
+impl<'a> IntoIterator for &'a EntryMap {
+    type Item = &'a Entry;
+    type IntoIter = std::slice::Iter<'a, Entry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.list.iter()
+    }
+}
+
+/// Written like the list of entries
+impl fmt::Debug for EntryMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.list.fmt(f)
+    }
+}
+
+impl PartialEq for EntryMap {
+    fn eq(&self, other: &Self) -> bool {
+        self.list == other.list
+    }
+}
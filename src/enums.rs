@@ -0,0 +1,83 @@
+//! Reading values as enums, by the names of their variants
+use crate::constrain;
+use crate::{Config, ConfigError};
+
+/// An enum that can be read from config values naming its variants (ignoring case)
+///
+/// Normally implemented with `#[derive(ConfigEnum)]` (`derive` feature), which also impls `FromConfigValue`
+/// so the enum can be read with `get`, `get_vec` and in `FromConfig` structs.
+/// Variants are named in `snake_case`, or with `#[config(rename = "name")]`.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use simple_config_parser::{Config, ConfigEnum};
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Level {
+///     Debug,
+///     Info,
+/// }
+///
+/// impl ConfigEnum for Level {
+///     const VARIANTS: &'static [&'static str] = &["debug", "info"];
+///
+///     fn from_variant(index: usize) -> Option<Self> {
+///         match index {
+///             0 => Some(Level::Debug),
+///             1 => Some(Level::Info),
+///             _ => None,
+///         }
+///     }
+/// }
+///
+/// let cfg = Config::new().text("level = DEBUG\nother = trace").unwrap();
+/// assert_eq!(cfg.get_enum::<Level>("level").unwrap(), Level::Debug);
+/// assert_eq!(
+///     cfg.get_enum::<Level>("other").unwrap_err().to_string(),
+///     "Value of `other` (`trace`) must be one of `debug`, `info`"
+/// );
+/// ```
+pub trait ConfigEnum: Sized {
+    /// Names of the variants as they are written in configs
+    const VARIANTS: &'static [&'static str];
+
+    /// Get the variant named by `VARIANTS[index]`
+    fn from_variant(index: usize) -> Option<Self>;
+
+    /// Get the variant a value names (ignoring case)
+    ///
+    /// Returns `ConfigError::InvalidValue` listing the variants if it does not name one.
+    fn from_name(value: &str) -> Result<Self, ConfigError> {
+        Self::VARIANTS
+            .iter()
+            .position(|i| i.eq_ignore_ascii_case(value.trim()))
+            .and_then(Self::from_variant)
+            .ok_or_else(|| ConfigError::InvalidValue(expected::<Self>()))
+    }
+}
+
+impl Config {
+    /// Get a value from config as an enum (That Impls ConfigEnum), matching the names of its variants without case
+    ///
+    /// Returns `ConfigError::InvalidValue` naming the key, value and every variant if it's not one of them.
+    /// See `ConfigEnum` for an example.
+    pub fn get_enum<T>(&self, key: &str) -> Result<T, ConfigError>
+    where
+        T: ConfigEnum,
+    {
+        self.located(self.entry(None, key), |i| {
+            let i = i?;
+            T::from_name(&i.value)
+                .map_err(|_| constrain::invalid(&i.name(), &i.value, expected::<T>()))
+        })
+    }
+}
+
+/// Describe the values an enum can be, like ``must be one of `a`, `b` ``
+fn expected<T: ConfigEnum>() -> String {
+    let variants = T::VARIANTS
+        .iter()
+        .map(|i| format!("`{}`", i))
+        .collect::<Vec<_>>();
+    format!("must be one of {}", variants.join(", "))
+}
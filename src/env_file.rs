@@ -0,0 +1,176 @@
+//! Loading `.env` / systemd `EnvironmentFile` style files (with the `env-file` feature)
+//!
+//! Each line is `NAME=value`, optionally starting with `export`.
+//! Values can be `"double quoted"` (with `\` escapes), `'single quoted'` (as is) or unquoted,
+//! and quoted values can span lines. Lines starting with `#` or `;` are comments,
+//! as is anything after a ` #` in an unquoted value. An unquoted value ending in `\` is continued on the next line.
+use std::path::Path;
+use std::time::Instant;
+
+use crate::compress;
+use crate::config::{Entry, KeyStyle};
+use crate::interpolate;
+use crate::utf8;
+use crate::{Config, ConfigError};
+
+impl Config {
+    /// Loads a `.env` style file into the config
+    ///
+    /// Names are mapped to keys like `Config::env` does: lowercased, with `__` separating nested keys,
+    /// so `SERVER__PORT=8080` is `server.port`. Like JSON keys, they are not in any section.
+    /// The file is not remembered for `Config::save`, as it would be written in this crates format.
+    /// Returns `ConfigError::InvalidConfig` if a line is not a variable or a quote is not closed.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// # let path = std::env::temp_dir().join("scp-env-file-doc.env");
+    /// std::fs::write(&path, "# Database\nexport DB__HOST=localhost\nDB__PASSWORD='p#ss'\nGREETING=\"Hello\\nWorld\"").unwrap();
+    ///
+    /// let cfg = Config::new()
+    ///     .text("[db]\nhost = 127.0.0.1\nport = 5432")
+    ///     .unwrap()
+    ///     .env_file(&path)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get_str("db.host").unwrap(), "localhost");
+    /// assert_eq!(cfg.get_str("db.password").unwrap(), "p#ss");
+    /// assert_eq!(cfg.get::<u16>("db.port").unwrap(), 5432);
+    /// assert_eq!(cfg.get_str("greeting").unwrap(), "Hello\nWorld");
+    /// # std::fs::remove_file(path).unwrap();
+    /// ```
+    pub fn env_file<T>(mut self, file: T) -> Result<Self, ConfigError>
+    where
+        T: AsRef<Path>,
+    {
+        let start = Instant::now();
+        let file = file.as_ref();
+        let (contents, invalid) = compress::read(file, self.decoding)?;
+        let mut data = parse(&utf8::normalize(&contents), self.key_style)?;
+
+        if let Some(unset) = self.env {
+            for i in data.iter_mut() {
+                i.value = interpolate::expand_env(&i.value, unset)?;
+            }
+        }
+        self.apply_transforms(&mut data, &mut Vec::new());
+        self.apply_processors(&mut data)?;
+
+        self.add_source(file.display().to_string(), start, (data, Vec::new()))?;
+        self.warn_invalid_utf8(invalid);
+        Ok(self)
+    }
+}
+
+/// Parse the variables of an env file into entries
+pub(crate) fn parse(text: &str, keys: KeyStyle) -> Result<Vec<Entry>, ConfigError> {
+    let mut out = Vec::new();
+    let mut lines = text.lines();
+    while let Some(line) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(['#', ';']) {
+            continue;
+        }
+
+        let line = match line.strip_prefix("export") {
+            Some(i) if i.starts_with([' ', '\t']) => i.trim_start(),
+            _ => line,
+        };
+        let (name, rest) = line.split_once('=').ok_or(ConfigError::InvalidConfig)?;
+        let name = name.trim();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(ConfigError::InvalidConfig);
+        }
+
+        out.push(Entry {
+            section: None,
+            key: keys.key(&name.to_lowercase().replace("__", ".")),
+            value: value(rest, &mut lines)?,
+        });
+    }
+
+    Ok(out)
+}
+
+/// Parse a value, taking more lines if it is continued
+fn value<'a>(
+    rest: &'a str,
+    lines: &mut impl Iterator<Item = &'a str>,
+) -> Result<String, ConfigError> {
+    let trimmed = rest.trim_start();
+    match trimmed.chars().next() {
+        Some(quote @ ('"' | '\'')) => quoted(&trimmed[1..], quote, lines),
+        _ => Ok(unquoted(rest, lines)),
+    }
+}
+
+/// Parse a quoted value, starting after the opening quote
+///
+/// Only a comment can come after the closing quote.
+fn quoted<'a>(
+    first: &'a str,
+    quote: char,
+    lines: &mut impl Iterator<Item = &'a str>,
+) -> Result<String, ConfigError> {
+    let mut out = String::new();
+    let mut line = first;
+    loop {
+        let mut chars = line.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                c if c == quote => {
+                    let after = line[i + 1..].trim_start();
+                    return match after.is_empty() || after.starts_with('#') {
+                        true => Ok(out),
+                        false => Err(ConfigError::InvalidConfig),
+                    };
+                }
+                '\\' if quote == '"' => match chars.next().map(|i| i.1) {
+                    Some('n') => out.push('\n'),
+                    Some('t') => out.push('\t'),
+                    Some('r') => out.push('\r'),
+                    Some(i @ ('"' | '\\' | '$' | '`')) => out.push(i),
+                    Some(i) => {
+                        out.push('\\');
+                        out.push(i);
+                    }
+                    // A `\` at the end of the line joins the next line on
+                    None => {
+                        line = lines.next().ok_or(ConfigError::InvalidConfig)?;
+                        chars = line.char_indices();
+                    }
+                },
+                c => out.push(c),
+            }
+        }
+
+        line = lines.next().ok_or(ConfigError::InvalidConfig)?;
+        out.push('\n');
+    }
+}
+
+/// Parse an unquoted value, without its comment and surrounding whitespace
+fn unquoted<'a>(rest: &'a str, lines: &mut impl Iterator<Item = &'a str>) -> String {
+    let mut out = String::new();
+    let mut line = rest;
+    loop {
+        let end = line
+            .char_indices()
+            .zip(line.chars().skip(1))
+            .find(|((_, a), b)| a.is_whitespace() && *b == '#')
+            .map_or(line.len(), |((i, _), _)| i);
+        let value = line[..end].trim();
+
+        if let Some(value) = value.strip_suffix('\\') {
+            if let Some(next) = lines.next() {
+                out.push_str(value);
+                line = next;
+                continue;
+            }
+        }
+
+        out.push_str(value);
+        return out;
+    }
+}
@@ -0,0 +1,348 @@
+//! Subscribing to changes of config values
+//!
+//! `Config::subscribe` gives a `Changes` receiver that gets the changes made with `set` / `remove`
+//! and by loading more sources.
+//! Subscriptions can be filtered to some keys, and rapid changes can be coalesced into one event.
+//!
+//! Events can also be sent to other channels with `Config::subscribe_with`,
+//! anything that impls `ChangeSink` works (std mpsc, crossbeam with the `crossbeam` feature
+//! and tokio broadcast / watch with the `tokio` feature).
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender};
+use std::time::Duration;
+
+use crate::{Config, Entry};
+
+/// A change to the value of a key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// The key, as `section.key` if it is in a section
+    pub key: String,
+    /// The value before the change (`None` if it was not defined)
+    pub old: Option<String>,
+    /// The value after the change (`None` if it was removed)
+    pub new: Option<String>,
+}
+
+/// A set of changes delivered to a subscriber at once
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangeEvent {
+    /// The changes, with at most one per key
+    pub changes: Vec<Change>,
+}
+
+/// Somewhere change events can be sent
+pub trait ChangeSink: Send + Sync {
+    /// Send an event, returning `false` if nothing can receive it anymore (the subscription is then dropped)
+    fn send(&self, event: ChangeEvent) -> bool;
+}
+
+impl ChangeSink for Sender<ChangeEvent> {
+    fn send(&self, event: ChangeEvent) -> bool {
+        Sender::send(self, event).is_ok()
+    }
+}
+
+/// Blocks while the channel is full
+impl ChangeSink for SyncSender<ChangeEvent> {
+    fn send(&self, event: ChangeEvent) -> bool {
+        SyncSender::send(self, event).is_ok()
+    }
+}
+
+/// Blocks while the channel is full
+#[cfg(feature = "crossbeam")]
+impl ChangeSink for crossbeam_channel::Sender<ChangeEvent> {
+    fn send(&self, event: ChangeEvent) -> bool {
+        crossbeam_channel::Sender::send(self, event).is_ok()
+    }
+}
+
+/// The subscription is dropped if there are no receivers when an event is sent
+#[cfg(feature = "tokio")]
+impl ChangeSink for tokio::sync::broadcast::Sender<ChangeEvent> {
+    fn send(&self, event: ChangeEvent) -> bool {
+        tokio::sync::broadcast::Sender::send(self, event).is_ok()
+    }
+}
+
+/// Receivers only see the latest event
+#[cfg(feature = "tokio")]
+impl ChangeSink for tokio::sync::watch::Sender<ChangeEvent> {
+    fn send(&self, event: ChangeEvent) -> bool {
+        tokio::sync::watch::Sender::send(self, event).is_ok()
+    }
+}
+
+/// Calls a function with each event, see `Config::on_change`
+struct Callback<F>(F);
+
+impl<F> ChangeSink for Callback<F>
+where
+    F: Fn(&ChangeEvent) + Send + Sync,
+{
+    fn send(&self, event: ChangeEvent) -> bool {
+        (self.0)(&event);
+        true
+    }
+}
+
+/// A subscriber to changes
+pub(crate) struct Subscriber {
+    /// Key patterns the subscriber is interested in (every key if empty)
+    filters: Vec<String>,
+    /// Where the changes are sent
+    sink: Box<dyn ChangeSink>,
+}
+
+impl Subscriber {
+    /// Create a subscriber to the keys matching `filters`, see `Config::subscribe`
+    pub(crate) fn new<T>(filters: &[&str], sink: T) -> Self
+    where
+        T: ChangeSink + 'static,
+    {
+        Subscriber {
+            filters: filters.iter().map(|i| i.to_lowercase()).collect(),
+            sink: Box::new(sink),
+        }
+    }
+
+    /// Check if the subscriber wants changes to a key
+    fn wants(&self, key: &str) -> bool {
+        self.filters.is_empty()
+            || self
+                .filters
+                .iter()
+                .any(|i| glob_match(i.as_bytes(), key.as_bytes()))
+    }
+}
+
+/// Receives the changes of a config, made with `Config::subscribe`
+pub struct Changes {
+    rx: Receiver<ChangeEvent>,
+    debounce: Option<Duration>,
+}
+
+/// Match a key against a pattern, where `*` is any number of chars and `?` is one char
+pub(crate) fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    match (pattern.first(), text.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            glob_match(&pattern[1..], text) || (!text.is_empty() && glob_match(pattern, &text[1..]))
+        }
+        (Some(b'?'), Some(_)) => glob_match(&pattern[1..], &text[1..]),
+        (Some(i), Some(j)) if i == j => glob_match(&pattern[1..], &text[1..]),
+        _ => false,
+    }
+}
+
+/// Merge changes into one per key, keeping the first old value and the last new value
+/// Keys that ended up back at their old value are left out.
+fn coalesce(changes: Vec<Change>) -> Vec<Change> {
+    let mut out: Vec<Change> = Vec::new();
+    for i in changes {
+        match out.iter_mut().find(|j| j.key == i.key) {
+            Some(j) => j.new = i.new,
+            None => out.push(i),
+        }
+    }
+
+    out.retain(|i| i.old != i.new);
+    out
+}
+
+impl Changes {
+    /// Receive the events sent to a channel
+    pub(crate) fn new(rx: Receiver<ChangeEvent>) -> Self {
+        Changes { rx, debounce: None }
+    }
+
+    /// Wait for changes to coalesce for `window` before delivering them
+    ///
+    /// After the first change `recv` keeps collecting changes until none have come in for `window`,
+    /// so bulk edits are delivered as one event.
+    pub fn debounce(self, window: Duration) -> Self {
+        Self {
+            debounce: Some(window),
+            ..self
+        }
+    }
+
+    /// Wait for the next event
+    ///
+    /// Returns `None` once the config has been dropped and every change was received.
+    pub fn recv(&self) -> Option<ChangeEvent> {
+        loop {
+            let mut changes = self.rx.recv().ok()?.changes;
+            match self.debounce {
+                Some(window) => {
+                    while let Ok(i) = self.rx.recv_timeout(window) {
+                        changes.extend(i.changes);
+                    }
+                }
+                None => changes.extend(self.rx.try_iter().flat_map(|i| i.changes)),
+            }
+
+            // Changes that cancelled out are not an event
+            let changes = coalesce(changes);
+            if !changes.is_empty() {
+                return Some(ChangeEvent { changes });
+            }
+        }
+    }
+
+    /// Get the changes that have already been made, without waiting
+    ///
+    /// Returns `None` if there are none (the debounce window is not used).
+    pub fn try_recv(&self) -> Option<ChangeEvent> {
+        let changes = coalesce(self.rx.try_iter().flat_map(|i| i.changes).collect());
+        match changes.is_empty() {
+            true => None,
+            false => Some(ChangeEvent { changes }),
+        }
+    }
+}
+
+impl Config {
+    /// Subscribe to changes of the config
+    ///
+    /// `filters` are key patterns (`*` matches any number of chars, `?` one char) matched against the key
+    /// (`section.key` for keys in a section), if it's empty every key is included.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let mut cfg = Config::new().text("[db]\nhost = localhost\nport = 5432").unwrap();
+    /// let changes = cfg.subscribe(&["db.*"]);
+    ///
+    /// cfg.set("host", "db1");
+    /// cfg.set("host", "db2");
+    /// cfg.set("other", "1");
+    ///
+    /// // Both changes to the host are in one event
+    /// let event = changes.try_recv().unwrap();
+    /// assert_eq!(event.changes.len(), 1);
+    /// assert_eq!(event.changes[0].key, "db.host");
+    /// assert_eq!(event.changes[0].old.as_deref(), Some("localhost"));
+    /// assert_eq!(event.changes[0].new.as_deref(), Some("db2"));
+    /// ```
+    pub fn subscribe(&mut self, filters: &[&str]) -> Changes {
+        let (tx, rx) = mpsc::channel();
+        self.subscribe_with(filters, tx);
+        Changes::new(rx)
+    }
+
+    /// Subscribe to changes of the config, sending them to a channel
+    ///
+    /// `filters` work like they do for `Config::subscribe`.
+    /// Each change to the config (a `set`, `remove` or loaded source) is sent as one event, they are not coalesced.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use std::sync::mpsc;
+    /// use simple_config_parser::Config;
+    ///
+    /// let mut cfg = Config::new().text("level = info").unwrap();
+    /// let (tx, rx) = mpsc::channel();
+    /// cfg.subscribe_with(&["level"], tx);
+    ///
+    /// cfg.set("level", "debug");
+    /// assert_eq!(rx.recv().unwrap().changes[0].new.as_deref(), Some("debug"));
+    /// ```
+    pub fn subscribe_with<T>(&mut self, filters: &[&str], sink: T)
+    where
+        T: ChangeSink + 'static,
+    {
+        self.subscribers.push(Subscriber::new(filters, sink));
+    }
+
+    /// Call a function with the changes to the config
+    ///
+    /// Works like `Config::subscribe_with`, for every key.
+    /// The function is called while the config is being changed, so it can't use the config.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use std::sync::{Arc, Mutex};
+    /// use simple_config_parser::Config;
+    ///
+    /// let changed = Arc::new(Mutex::new(Vec::new()));
+    /// let log = changed.clone();
+    ///
+    /// let mut cfg = Config::new().text("level = info").unwrap();
+    /// cfg.on_change(move |event| {
+    ///     log.lock().unwrap().extend(event.changes.iter().map(|i| i.key.to_owned()))
+    /// });
+    ///
+    /// cfg.set("level", "debug");
+    /// cfg.remove("level");
+    /// assert_eq!(*changed.lock().unwrap(), ["level", "level"]);
+    /// ```
+    pub fn on_change<F>(&mut self, callback: F)
+    where
+        F: Fn(&ChangeEvent) + Send + Sync + 'static,
+    {
+        self.subscribe_with(&[], Callback(callback));
+    }
+
+    /// Get the values of some keys before they are changed, if anything is subscribed
+    pub(crate) fn snapshot<'a>(
+        &self,
+        keys: impl IntoIterator<Item = &'a str>,
+    ) -> Option<Vec<(String, Option<Entry>)>> {
+        if self.subscribers.is_empty() {
+            return None;
+        }
+
+        let mut out = Vec::new();
+        for key in keys {
+            if !out.iter().any(|(i, _)| i == key) {
+                out.push((key.to_owned(), self.find_entry(None, key).cloned()));
+            }
+        }
+
+        Some(out)
+    }
+
+    /// Send the changes since a snapshot to the subscribers
+    pub(crate) fn notify(&mut self, snapshot: Option<Vec<(String, Option<Entry>)>>) {
+        let snapshot = match snapshot {
+            Some(i) => i,
+            None => return,
+        };
+
+        let mut changes = Vec::new();
+        for (key, before) in snapshot {
+            let after = self.find_entry(None, &key);
+            let old = before.as_ref().map(|i| i.value.to_owned());
+            let new = after.map(|i| i.value.to_owned());
+            if old == new {
+                continue;
+            }
+
+            // Removed keys are named by where they were
+            let section = after.or(before.as_ref()).and_then(|i| i.section.as_deref());
+            let key = match section {
+                Some(section) => format!("{}.{}", section, key),
+                None => key,
+            };
+            changes.push(Change { key, old, new });
+        }
+
+        send(&mut self.subscribers, &changes);
+    }
+}
+
+/// Send the changes each subscriber wants to it
+/// Subscribers that are gone are removed.
+pub(crate) fn send(subscribers: &mut Vec<Subscriber>, changes: &[Change]) {
+    subscribers.retain(|sub| {
+        let changes = changes
+            .iter()
+            .filter(|i| sub.wants(&i.key))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        changes.is_empty() || sub.sink.send(ChangeEvent { changes })
+    });
+}
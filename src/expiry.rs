@@ -0,0 +1,141 @@
+//! Values that stop being used after a time
+//!
+//! An entry with a comment like `; until 2024-07-01T00:00Z` expires at that time (UTC),
+//! so temporary settings can't be forgotten.
+use std::time::{Duration, SystemTime};
+
+use crate::config::Entry;
+use crate::Config;
+
+/// A definition of a key that has expired, see `Config::check_expired`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expired {
+    /// The dotted name of the key (`section.key`)
+    pub key: String,
+    /// When it expired
+    pub until: SystemTime,
+    /// The source it was loaded from
+    pub source: String,
+    /// The line in the source it was defined on (starting at 1)
+    pub line: Option<usize>,
+}
+
+impl Config {
+    /// Ignore entries that have expired when reading keys
+    ///
+    /// Entries expire when the comment after them is `until` and a time, like `; until 2024-07-01T00:00Z`.
+    /// Times are UTC, and can be a date (`2024-07-01`) or a date and time (with or without seconds).
+    /// Once an entry has expired, reading the key uses the definition before it (or the default).
+    /// Use `Config::check_expired` to warn about expired entries so they get removed.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .expiry(true)
+    ///     .text("read_only = false\nread_only = true ; until 2020-01-01T00:00Z")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get::<bool>("read_only").unwrap(), false);
+    /// assert_eq!(cfg.check_expired()[0].key, "read_only");
+    /// ```
+    pub fn expiry(mut self, expiry: bool) -> Self {
+        self.expiry = expiry;
+        self
+    }
+
+    /// Get the entries that have expired, to be warned about
+    ///
+    /// Entries are listed even if `Config::expiry` is off.
+    pub fn check_expired(&self) -> Vec<Expired> {
+        let now = SystemTime::now();
+        self.data
+            .iter()
+            .zip(self.origins.iter())
+            .filter_map(|(i, origin)| {
+                let until = origin.expires.filter(|i| *i <= now)?;
+                Some(Expired {
+                    key: i.name(),
+                    until,
+                    source: origin.source.to_string(),
+                    line: origin.line,
+                })
+            })
+            .collect()
+    }
+
+    /// Check if an entry should be ignored because it has expired
+    pub(crate) fn is_expired(&self, entry: &Entry) -> bool {
+        if !self.expiry {
+            return false;
+        }
+
+        self.data
+            .iter()
+            .position(|i| std::ptr::eq(i, entry))
+            .and_then(|i| self.origins.get(i)?.expires)
+            .is_some_and(|i| i <= SystemTime::now())
+    }
+}
+
+/// Get when an entry expires from the text after its value, like ` ; until 2024-07-01`
+pub(crate) fn until(suffix: &str) -> Option<SystemTime> {
+    let comment = suffix.trim_start();
+    let mut chars = comment.chars();
+    chars.next().filter(|i| !i.is_alphanumeric())?;
+
+    let time = chars.as_str().trim().strip_prefix("until")?;
+    match time.starts_with(char::is_whitespace) {
+        true => parse_time(time.trim()),
+        false => None,
+    }
+}
+
+/// Parse a UTC time like `2024-07-01`, `2024-07-01T12:30Z` or `2024-07-01 12:30:15`
+fn parse_time(time: &str) -> Option<SystemTime> {
+    let time = time.strip_suffix(['Z', 'z']).unwrap_or(time);
+    let (date, clock) = match time.split_once(['T', 't', ' ']) {
+        Some((date, clock)) => (date, Some(clock)),
+        None => (time, None),
+    };
+
+    let mut date = date.splitn(3, '-').map(|i| i.parse::<u64>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+    if !(1970..10000).contains(&year) || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let mut secs = 0;
+    if let Some(clock) = clock {
+        let parts = clock
+            .split(':')
+            .map(|i| i.parse::<u64>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        let (hour, minute, second) = match parts[..] {
+            [hour, minute] => (hour, minute, 0),
+            [hour, minute, second] => (hour, minute, second),
+            _ => return None,
+        };
+        if hour > 23 || minute > 59 || second > 59 {
+            return None;
+        }
+        secs = hour * 3600 + minute * 60 + second;
+    }
+
+    let days = days_since_epoch(year, month, day);
+    Some(SystemTime::UNIX_EPOCH + Duration::from_secs(days * 86400 + secs))
+}
+
+/// Get the number of days from 1970-01-01 to a date
+fn days_since_epoch(year: u64, month: u64, day: u64) -> u64 {
+    // Count years from March, so the leap day is at the end of the year
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let month = (month + 9) % 12;
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146097 + day_of_era - 719468
+}
@@ -0,0 +1,302 @@
+//! Writing configs as JSON or TOML (with the `json` / `toml` features), or in this crates format with a stable order
+//!
+//! For JSON and TOML dotted names are nested again, so `server.port` is `{"server": {"port": ...}}` / `[server]` `port = ...`.
+//! Values that are bools or numbers are written as them, everything else as a string.
+use std::fmt::Write;
+
+#[cfg(any(feature = "json", feature = "toml"))]
+use crate::encoding::json_string;
+use crate::encoding::quote_value;
+use crate::Config;
+#[cfg(feature = "file")]
+use crate::ConfigError;
+
+/// A key and its value, as written by `Config::to_normalized`
+type Pair<'a> = (&'a str, &'a str);
+
+/// A key of the nested config, with its value and the keys under it
+#[cfg(any(feature = "json", feature = "toml"))]
+#[derive(Default)]
+struct Node {
+    value: Option<String>,
+    children: Vec<(String, Node)>,
+}
+
+/// The dotted values and the tables in a node, see `Node::split`
+#[cfg(any(feature = "json", feature = "toml"))]
+type Split<'a> = (Vec<(String, String)>, Vec<(&'a str, &'a Node)>);
+
+#[cfg(any(feature = "json", feature = "toml"))]
+impl Node {
+    /// Set the value at a dotted path
+    fn insert(&mut self, path: &str, value: &str) {
+        let mut node = self;
+        for i in path.split('.') {
+            let n = match node.children.iter().position(|j| j.0 == i) {
+                Some(n) => n,
+                None => {
+                    node.children.push((i.to_owned(), Node::default()));
+                    node.children.len() - 1
+                }
+            };
+            node = &mut node.children[n].1;
+        }
+        node.value = Some(value.to_owned());
+    }
+
+    /// Get every value under this node, with their dotted path from it
+    fn flat(&self, prefix: &str, out: &mut Vec<(String, String)>) {
+        if let Some(i) = &self.value {
+            out.push((prefix.to_owned(), i.to_owned()));
+        }
+        for (key, i) in self.children.iter() {
+            i.flat(&format!("{}.{}", prefix, key), out);
+        }
+    }
+
+    /// Get the values and tables directly in this node
+    ///
+    /// A key with both a value and keys under it (`a = 1` and `a.b = 2`) can't be nested,
+    /// so the keys under it are written as dotted values next to it.
+    fn split(&self) -> Split<'_> {
+        let (mut values, mut tables) = (Vec::new(), Vec::new());
+        for (key, i) in self.children.iter() {
+            match i.value {
+                Some(_) => i.flat(key, &mut values),
+                None => tables.push((key.as_str(), i)),
+            }
+        }
+        (values, tables)
+    }
+}
+
+impl Config {
+    /// Write the values in use as a JSON object, with dotted names nested
+    ///
+    /// Keys are in the order of `Config::iter_effective`, so defaults are included.
+    /// If a key has both a value and keys under it, the keys under it are written with their dotted name.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .text("name = app\ndebug = true\n[server]\nport = 8080\nhost = \"a \\\"b\\\"\"")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     cfg.to_json(),
+    ///     r#"{"name": "app", "debug": true, "server": {"port": 8080, "host": "a \"b\""}}"#
+    /// );
+    /// assert_eq!(Config::new().json(cfg.to_json()).unwrap().get::<u16>("server.port").unwrap(), 8080);
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        write_json(&self.tree(), &mut out);
+        out
+    }
+
+    /// Write the values in use as TOML, with dotted names nested in tables
+    ///
+    /// Keys in no table come first, then a `[table]` for each nested key with values in it.
+    /// Keys are in the order of `Config::iter_effective`, so defaults are included.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, Format};
+    ///
+    /// let cfg = Config::new()
+    ///     .text("name = app\n[server]\nport = 8080\n[server.tls]\ncert = a.pem\n[log]\nlevel = info")
+    ///     .unwrap();
+    ///
+    /// let toml = cfg.to_toml();
+    /// assert_eq!(
+    ///     toml,
+    ///     "name = \"app\"\n\n[server]\nport = 8080\n\n[server.tls]\ncert = \"a.pem\"\n\n[log]\nlevel = \"info\"\n"
+    /// );
+    /// let cfg = Config::new().format(Format::Toml).text(toml).unwrap();
+    /// assert_eq!(cfg.get_str("server.tls.cert").unwrap(), "a.pem");
+    /// ```
+    #[cfg(feature = "toml")]
+    pub fn to_toml(&self) -> String {
+        let mut out = String::new();
+        write_toml(&self.tree(), "", &mut out);
+        out
+    }
+
+    /// Write the config with its keys sorted, for output that only changes when values do
+    ///
+    /// Keys are sorted in each section, with keys not in a section first.
+    /// Sections are sorted too if `sort_sections`, otherwise they are in the order they were first defined.
+    /// Every line is `key = value` (quoted if needed) with a blank line before each section,
+    /// keys defined more than once are written once with the value in use, and comments are not kept.
+    /// Only this config's keys are written, not the ones from defaults (like `save`).
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .text("# Comment\nname=app\n[server]\nport   = 80\nhost = a\n[db]\nurl = x\n[server]\nport = 8080")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     cfg.to_normalized(true),
+    ///     "name = app\n\n[db]\nurl = x\n\n[server]\nhost = a\nport = 8080\n"
+    /// );
+    /// assert!(cfg.to_normalized(false).ends_with("[server]\nhost = a\nport = 8080\n\n[db]\nurl = x\n"));
+    /// ```
+    pub fn to_normalized(&self, sort_sections: bool) -> String {
+        let mut sections: Vec<(Option<&str>, Vec<Pair>)> = Vec::new();
+        for i in self.data.iter().filter(|i| !self.is_expired(i)) {
+            let section = i.section.as_deref();
+            let n = match sections.iter().position(|j| j.0 == section) {
+                Some(n) => n,
+                None => {
+                    sections.push((section, Vec::new()));
+                    sections.len() - 1
+                }
+            };
+
+            let keys = &mut sections[n].1;
+            match keys.iter_mut().find(|j| j.0 == i.key) {
+                Some(j) => j.1 = &i.value,
+                None => keys.push((&i.key, &i.value)),
+            }
+        }
+
+        // Keys not in a section have to come before the first header
+        match sort_sections {
+            true => sections.sort_by(|a, b| a.0.cmp(&b.0)),
+            false => sections.sort_by_key(|i| i.0.is_some()),
+        }
+
+        let mut out = String::new();
+        for (section, mut keys) in sections {
+            if let Some(section) = section {
+                if !out.is_empty() {
+                    out.push('\n');
+                }
+                let _ = writeln!(out, "[{}]", section);
+            }
+
+            keys.sort_unstable_by_key(|i| i.0);
+            for (key, value) in keys {
+                let _ = writeln!(out, "{} = {}", key, quote_value(value));
+            }
+        }
+
+        out
+    }
+
+    /// Write the config to the file it was loaded from like `Config::to_normalized`
+    ///
+    /// Written the same way as `Config::save` (atomically, with backups if they are on).
+    /// Returns `ConfigError::NoFileDefined` if no file was loaded.
+    #[cfg(feature = "file")]
+    pub fn save_normalized(&self, sort_sections: bool) -> Result<(), ConfigError> {
+        let path = self.path.as_ref().ok_or(ConfigError::NoFileDefined)?;
+        self.write_file(path, self.to_normalized(sort_sections).as_bytes())
+    }
+
+    /// Nest the values in use by their dotted names
+    #[cfg(any(feature = "json", feature = "toml"))]
+    fn tree(&self) -> Node {
+        let mut root = Node::default();
+        for i in self.iter_effective() {
+            root.insert(&i.name(), &i.value);
+        }
+        root
+    }
+}
+
+/// Get a value as a JSON / TOML literal, a bool or number if it is one, otherwise a string
+#[cfg(any(feature = "json", feature = "toml"))]
+fn literal(value: &str) -> String {
+    match value {
+        "true" | "false" => value.to_owned(),
+        _ if is_number(value) => value.to_owned(),
+        _ => json_string(value),
+    }
+}
+
+/// Check if a value is a number as JSON writes them (which TOML can read too)
+///
+/// Integers also have to fit in an `i64`, the largest TOML allows.
+#[cfg(any(feature = "json", feature = "toml"))]
+fn is_number(value: &str) -> bool {
+    let digits = |i: &str| !i.is_empty() && i.bytes().all(|i| i.is_ascii_digit());
+    let text = value.strip_prefix('-').unwrap_or(value);
+    let (mantissa, exponent) = match text.find(['e', 'E']) {
+        Some(n) => (&text[..n], Some(&text[n + 1..])),
+        None => (text, None),
+    };
+    let (int, fraction) = match mantissa.split_once('.') {
+        Some((int, fraction)) => (int, Some(fraction)),
+        None => (mantissa, None),
+    };
+
+    digits(int)
+        && (int == "0" || !int.starts_with('0'))
+        && fraction.is_none_or(digits)
+        && exponent.is_none_or(|i| digits(i.strip_prefix(['+', '-']).unwrap_or(i)))
+        && (fraction.is_some() || exponent.is_some() || value.parse::<i64>().is_ok())
+}
+
+/// Write a node as a JSON object
+#[cfg(feature = "json")]
+fn write_json(node: &Node, out: &mut String) {
+    let (values, tables) = node.split();
+    out.push('{');
+    for (n, (key, value)) in values.iter().enumerate() {
+        if n > 0 {
+            out.push_str(", ");
+        }
+        let _ = write!(out, "{}: {}", json_string(key), literal(value));
+    }
+    for (n, (key, table)) in tables.into_iter().enumerate() {
+        if n > 0 || !values.is_empty() {
+            out.push_str(", ");
+        }
+        let _ = write!(out, "{}: ", json_string(key));
+        write_json(table, out);
+    }
+    out.push('}');
+}
+
+/// Write the values of a node and then its tables, `path` is the name of its table
+#[cfg(feature = "toml")]
+fn write_toml(node: &Node, path: &str, out: &mut String) {
+    let (values, tables) = node.split();
+    if !values.is_empty() && !path.is_empty() {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        let _ = writeln!(out, "[{}]", path);
+    }
+    for (key, value) in values {
+        let _ = writeln!(out, "{} = {}", toml_key(&key), literal(&value));
+    }
+
+    for (key, table) in tables {
+        let path = match path.is_empty() {
+            true => toml_key(key),
+            false => format!("{}.{}", path, toml_key(key)),
+        };
+        write_toml(table, &path, out);
+    }
+}
+
+/// Quote a TOML key if it is not a bare key
+#[cfg(feature = "toml")]
+fn toml_key(key: &str) -> String {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|i| i.is_ascii_alphanumeric() || i == '_' || i == '-');
+    match bare {
+        true => key.to_owned(),
+        false => json_string(key),
+    }
+}
@@ -0,0 +1,113 @@
+//! Config files that extend a base file with `extends = base.cfg` (with the `file` feature)
+//!
+//! The base file is loaded first, so the file extending it overrides its keys.
+//! A base can extend another file, and so on, as long as no file is in the chain twice.
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::compress;
+use crate::config::{self, Entry};
+use crate::layout::Line;
+use crate::{Config, ConfigError};
+
+/// Key naming the file a config file extends
+pub(crate) const EXTENDS: &str = "extends";
+
+/// A parsed source of a chain of extended files, with the lines that were not valid UTF-8
+pub(crate) struct Layer {
+    pub(crate) source: String,
+    pub(crate) parsed: (Vec<Entry>, Vec<Line>),
+    pub(crate) invalid_utf8: Vec<usize>,
+}
+
+impl Config {
+    /// Parse the contents of a file along with the files it extends, bases first
+    ///
+    /// The `extends` key has to be before any section, and is relative to the file it is in.
+    /// Files are only followed if includes are allowed.
+    /// Errors in a base file are wrapped in `ConfigError::Include` to name it.
+    pub(crate) fn parse_extended(
+        &self,
+        file: &Path,
+        contents: String,
+    ) -> Result<Vec<Layer>, ConfigError> {
+        let mut layers = vec![Layer {
+            source: file.display().to_string(),
+            parsed: self.parse_in(contents, config::parent_dir(file))?,
+            invalid_utf8: Vec::new(),
+        }];
+        let mut files = vec![file.to_path_buf()];
+        let mut chain = Vec::new();
+
+        while let Some(base) = self.extends(&layers[layers.len() - 1], &files[files.len() - 1]) {
+            // Only files that extend others are looked up on disk
+            if chain.is_empty() {
+                chain.push(canonical(file));
+            }
+            let id = canonical(&base);
+            if chain.contains(&id) {
+                files.push(base);
+                return Err(ConfigError::ExtendsCycle(files));
+            }
+
+            let error = |e| ConfigError::Include(base.to_owned(), Box::new(e));
+            let (contents, invalid_utf8) = compress::read(&base, self.decoding).map_err(error)?;
+            let parsed = self
+                .parse_in(contents, config::parent_dir(&base))
+                .map_err(error)?;
+            chain.push(id);
+            layers.push(Layer {
+                source: base.display().to_string(),
+                parsed,
+                invalid_utf8,
+            });
+            files.push(base);
+        }
+
+        layers.reverse();
+        Ok(layers)
+    }
+
+    /// Add the layers of a chain of extended files as sources, leaving the config as is if it fails
+    pub(crate) fn add_layers(
+        &mut self,
+        layers: Vec<Layer>,
+        start: Instant,
+    ) -> Result<(), ConfigError> {
+        for i in layers.iter() {
+            self.check_pins(&i.parsed.0, &i.source)?;
+        }
+
+        let keys = layers
+            .iter()
+            .flat_map(|i| i.parsed.0.iter().map(|i| i.key.as_str()));
+        let snapshot = self.snapshot(keys);
+        for i in layers {
+            self.push_source(i.source, start, i.parsed);
+            self.warn_invalid_utf8(i.invalid_utf8);
+        }
+        self.notify(snapshot);
+        Ok(())
+    }
+
+    /// Get the path of the file that a layer (loaded from `file`) extends, if it extends one
+    fn extends(&self, layer: &Layer, file: &Path) -> Option<PathBuf> {
+        if !self.includes {
+            return None;
+        }
+
+        let base = layer.parsed.0.iter().rev().find(|i| self.is_extends(i))?;
+        Some(config::parent_dir(file).join(base.value.trim()))
+    }
+
+    /// Check if an entry is the `extends` key
+    pub(crate) fn is_extends(&self, entry: &Entry) -> bool {
+        entry.section.is_none() && entry.key == self.key_style.key(EXTENDS)
+    }
+}
+
+/// Get a path that is the same for every way of naming a file, if it exists
+fn canonical(file: &Path) -> PathBuf {
+    fs::canonicalize(file).unwrap_or_else(|_| file.to_path_buf())
+}
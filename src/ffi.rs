@@ -0,0 +1,260 @@
+//! C bindings, so C / C++ code can read the same configs
+//!
+//! Enabled with the `ffi` feature.
+//! Build the crate as a `staticlib` or `cdylib` (like `cargo rustc --release --features ffi --crate-type staticlib`)
+//! and include `include/simple_config_parser.h`, which declares every function and error code
+//! (it's written from this module by a test, so it always matches):
+//! ```c
+//! #include "simple_config_parser.h"
+//!
+//! ScpConfig *cfg = scp_config_new();
+//! if (scp_config_load_file(cfg, "base.cfg") != SCP_OK || scp_config_load_file(cfg, "local.cfg") != SCP_OK) {
+//!     // ...
+//! }
+//!
+//! char *host = scp_config_get_str(cfg, "db.host");
+//! if (host) {
+//!     printf("%s\n", host);
+//!     scp_str_free(host);
+//! }
+//! scp_config_free(cfg);
+//! ```
+//! Functions never panic across the boundary, errors are returned as the codes in this module.
+//!
+//! There are no Python (PyO3) or Node (napi-rs) bindings, they would each need their own crate and build tooling.
+//! Scripting languages can instead load the library with their C FFI to read configs
+//! with the same rules (quoting, comments, precedence) as Rust code, like Python's `ctypes`:
+//! ```python
+//! import ctypes
+//!
+//! lib = ctypes.CDLL("libsimple_config_parser.so")
+//! lib.scp_config_file.restype = ctypes.c_void_p
+//! lib.scp_config_get.argtypes = [ctypes.c_void_p, ctypes.c_char_p, ctypes.c_char_p, ctypes.c_size_t]
+//! lib.scp_config_free.argtypes = [ctypes.c_void_p]
+//!
+//! cfg = lib.scp_config_file(b"app.cfg")
+//! buf = ctypes.create_string_buffer(256)
+//! if lib.scp_config_get(cfg, b"server.port", buf, len(buf)) == 0:
+//!     print(buf.value.decode())
+//! lib.scp_config_free(cfg)
+//! ```
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use crate::{Config, ConfigError};
+
+/// The value was read
+pub const SCP_OK: c_int = 0;
+/// The key is not defined
+pub const SCP_NO_ITEM: c_int = 1;
+/// The buffer is too small for the value and its nul terminator
+pub const SCP_BUFFER_TOO_SMALL: c_int = 2;
+/// A pointer was null or a string was not valid UTF-8
+pub const SCP_INVALID_ARGUMENT: c_int = 3;
+/// The value could not be read for another reason, like the key having been removed
+pub const SCP_ERROR: c_int = 4;
+/// The config file could not be read
+pub const SCP_IO_ERROR: c_int = 5;
+/// Something panicked, this is a bug
+pub const SCP_PANIC: c_int = -1;
+
+impl Config {
+    /// Write a value into a buffer as a nul terminated string, returning an error code
+    ///
+    /// Returns `SCP_OK`, `SCP_NO_ITEM`, `SCP_BUFFER_TOO_SMALL` (writing nothing)
+    /// or `SCP_ERROR`, and never panics so it can be used from any context.
+    /// Values with nul bytes are cut off at the first one when read from C.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{ffi, Config};
+    ///
+    /// let cfg = Config::new().text("host = localhost").unwrap();
+    ///
+    /// let mut buf = [0; 16];
+    /// assert_eq!(cfg.get_c_str("host", &mut buf), ffi::SCP_OK);
+    /// assert_eq!(&buf[..10], b"localhost\0");
+    /// assert_eq!(cfg.get_c_str("host", &mut [0; 4]), ffi::SCP_BUFFER_TOO_SMALL);
+    /// assert_eq!(cfg.get_c_str("port", &mut buf), ffi::SCP_NO_ITEM);
+    /// ```
+    pub fn get_c_str(&self, key: &str, buf: &mut [u8]) -> c_int {
+        let value = match panic::catch_unwind(AssertUnwindSafe(|| self.get_str(key))) {
+            Ok(Ok(i)) => i,
+            Ok(Err(ConfigError::NoItem)) => return SCP_NO_ITEM,
+            Ok(Err(_)) => return SCP_ERROR,
+            Err(_) => return SCP_PANIC,
+        };
+
+        if value.len() >= buf.len() {
+            return SCP_BUFFER_TOO_SMALL;
+        }
+
+        buf[..value.len()].copy_from_slice(value.as_bytes());
+        buf[value.len()] = 0;
+        SCP_OK
+    }
+}
+
+/// Opaque config handle for C
+pub struct ScpConfig(Config);
+
+/// Read a string from C
+///
+/// # Safety
+/// `text` must be null or a valid nul terminated string.
+unsafe fn c_str<'a>(text: *const c_char) -> Option<&'a str> {
+    if text.is_null() {
+        return None;
+    }
+
+    CStr::from_ptr(text).to_str().ok()
+}
+
+/// Make an empty config, to load files into with `scp_config_load_file`
+///
+/// The config has to be freed with `scp_config_free`.
+#[no_mangle]
+pub extern "C" fn scp_config_new() -> *mut ScpConfig {
+    Box::into_raw(Box::new(ScpConfig(Config::new())))
+}
+
+/// Load a config from a file, returning null if it fails
+///
+/// # Safety
+/// `path` must be null or a valid nul terminated string.
+/// The config has to be freed with `scp_config_free`.
+#[no_mangle]
+pub unsafe extern "C" fn scp_config_file(path: *const c_char) -> *mut ScpConfig {
+    let path = match c_str(path) {
+        Some(i) => i,
+        None => return ptr::null_mut(),
+    };
+
+    match panic::catch_unwind(|| Config::new().file(path)) {
+        Ok(Ok(i)) => Box::into_raw(Box::new(ScpConfig(i))),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// Parse a config from text, returning null if it fails
+///
+/// # Safety
+/// `text` must be null or a valid nul terminated string.
+/// The config has to be freed with `scp_config_free`.
+#[no_mangle]
+pub unsafe extern "C" fn scp_config_text(text: *const c_char) -> *mut ScpConfig {
+    let text = match c_str(text) {
+        Some(i) => i,
+        None => return ptr::null_mut(),
+    };
+
+    match panic::catch_unwind(|| Config::new().text(text)) {
+        Ok(Ok(i)) => Box::into_raw(Box::new(ScpConfig(i))),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// Load a file into a config like `Config::file`, so its keys take priority over ones loaded before, returning an error code
+///
+/// Returns `SCP_IO_ERROR` if the file (or a file it includes or extends) could not be read
+/// and `SCP_ERROR` if it could not be parsed, leaving the config as it was.
+///
+/// # Safety
+/// `config` must be null or from `scp_config_new`, `scp_config_file` or `scp_config_text` (and not freed),
+/// `path` must be null or a valid nul terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn scp_config_load_file(
+    config: *mut ScpConfig,
+    path: *const c_char,
+) -> c_int {
+    let (config, path) = match (config.as_mut(), c_str(path)) {
+        (Some(config), Some(path)) => (config, path),
+        _ => return SCP_INVALID_ARGUMENT,
+    };
+
+    let loaded = panic::catch_unwind(AssertUnwindSafe(|| {
+        config.0.load_file(std::path::Path::new(path))
+    }));
+    match loaded {
+        Ok(Ok(())) => SCP_OK,
+        Ok(Err(e)) if e.io_kind().is_some() => SCP_IO_ERROR,
+        Ok(Err(_)) => SCP_ERROR,
+        Err(_) => SCP_PANIC,
+    }
+}
+
+/// Get a value as a new nul terminated string, returning null if it can't be read
+///
+/// Values with nul bytes are cut off at the first one, like `scp_config_get`.
+///
+/// # Safety
+/// `config` must be null or from `scp_config_new`, `scp_config_file` or `scp_config_text` (and not freed),
+/// `key` must be null or a valid nul terminated string.
+/// The string has to be freed with `scp_str_free`.
+#[no_mangle]
+pub unsafe extern "C" fn scp_config_get_str(
+    config: *const ScpConfig,
+    key: *const c_char,
+) -> *mut c_char {
+    let (config, key) = match (config.as_ref(), c_str(key)) {
+        (Some(config), Some(key)) => (config, key),
+        _ => return ptr::null_mut(),
+    };
+
+    let value = match panic::catch_unwind(AssertUnwindSafe(|| config.0.get_str(key))) {
+        Ok(Ok(i)) => i,
+        _ => return ptr::null_mut(),
+    };
+
+    let value = value.split('\0').next().unwrap_or_default();
+    match CString::new(value) {
+        Ok(i) => i.into_raw(),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Free a string from `scp_config_get_str`
+///
+/// # Safety
+/// `text` must be null or from `scp_config_get_str`, and not already freed.
+#[no_mangle]
+pub unsafe extern "C" fn scp_str_free(text: *mut c_char) {
+    if !text.is_null() {
+        drop(CString::from_raw(text));
+    }
+}
+
+/// Write a value into `buf` (of `len` bytes) as a nul terminated string, returning an error code
+///
+/// # Safety
+/// `config` must be null or from `scp_config_new`, `scp_config_file` or `scp_config_text` (and not freed),
+/// `key` must be null or a valid nul terminated string
+/// and `buf` must be null or valid for writing `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn scp_config_get(
+    config: *const ScpConfig,
+    key: *const c_char,
+    buf: *mut c_char,
+    len: usize,
+) -> c_int {
+    let (config, key) = match (config.as_ref(), c_str(key)) {
+        (Some(config), Some(key)) if !buf.is_null() => (config, key),
+        _ => return SCP_INVALID_ARGUMENT,
+    };
+
+    let buf = std::slice::from_raw_parts_mut(buf as *mut u8, len);
+    config.0.get_c_str(key, buf)
+}
+
+/// Free a config
+///
+/// # Safety
+/// `config` must be null or from `scp_config_new`, `scp_config_file` or `scp_config_text`, and not already freed.
+#[no_mangle]
+pub unsafe extern "C" fn scp_config_free(config: *mut ScpConfig) {
+    if !config.is_null() {
+        drop(Box::from_raw(config));
+    }
+}
@@ -0,0 +1,203 @@
+//! Loading and saving config files (with the `file` feature)
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::compress;
+use crate::fingerprint;
+use crate::layout::Line;
+use crate::report::SourceReport;
+use crate::{Config, ConfigError};
+
+impl Config {
+    /// Reads and parses config from a file
+    ///
+    /// If called more than one time it will append the current values.
+    /// So the recently appended valued will take priority
+    ///
+    /// Files ending in `.gz` or `.zst` are decompressed (with the `gzip` / `zstd` features).
+    /// Configs are always saved uncompressed.
+    ///
+    /// A file with an `extends = base.cfg` key (before any section) loads the base file first, relative to the file,
+    /// so its keys can be overridden. Bases can extend other files, `ConfigError::ExtendsCycle` is returned if they loop.
+    /// Each file is its own source in the load report.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// // Create a new config with a file
+    /// let mut cfg = Config::new().file("config.cfg").unwrap();
+    ///
+    /// // Read a value
+    /// assert_eq!(cfg.get_str("hello").unwrap(), "World");
+    /// ```
+    pub fn file<T>(mut self, file: T) -> Result<Self, ConfigError>
+    where
+        T: AsRef<Path>,
+    {
+        self.load_file(file.as_ref())?;
+        Ok(self)
+    }
+
+    /// Reads and parses config from a file, if it can
+    ///
+    /// Unlike `file` this never fails, if the file (or a file it extends) can't be read or parsed the config is left as is.
+    /// The failure is recorded and can be seen with `cfg.load_report()`.
+    /// Use this for sources that are preferred but not required.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// // Load defaults and an optional override file
+    /// let cfg = Config::new()
+    ///     .file("config.cfg")
+    ///     .unwrap()
+    ///     .file_optional("nonexistent.cfg");
+    ///
+    /// // Values from the first file are still there
+    /// assert_eq!(cfg.get_str("hello").unwrap(), "World");
+    ///
+    /// // And the failure was recorded
+    /// assert_eq!(cfg.load_report().failures().count(), 1);
+    /// ```
+    pub fn file_optional<T>(mut self, file: T) -> Self
+    where
+        T: AsRef<Path>,
+    {
+        let start = Instant::now();
+        let source = file.as_ref().display().to_string();
+        let (loaded, invalid) = match compress::read(file.as_ref(), self.decoding) {
+            Ok((contents, invalid)) => (
+                self.load_file_contents(file.as_ref(), contents, start),
+                invalid,
+            ),
+            Err(e) => (Err(e), Vec::new()),
+        };
+
+        match loaded {
+            Ok(()) => self.warn_invalid_utf8(invalid),
+            Err(error) => self.report.sources.push(SourceReport {
+                source,
+                error: Some(error),
+                entries: 0,
+                overridden: Vec::new(),
+                duration: start.elapsed(),
+                invalid_utf8: invalid,
+                invalid_lines: Vec::new(),
+            }),
+        }
+
+        self
+    }
+
+    /// Reads and parses config from a file, giving up after `timeout`
+    ///
+    /// Useful for files on network mounts that can hang forever.
+    /// The file and the files it includes or extends all have to be read before the timeout,
+    /// otherwise `ConfigError::Timeout` is returned (in `ConfigError::Include` for an included / extended file).
+    /// Each read is done on a background thread. Threads can't be cancelled, so after a timeout
+    /// the thread of the read that hung keeps running (and holds the file open) until the read finishes on its own.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use std::time::Duration;
+    /// use simple_config_parser::Config;
+    ///
+    /// // Create a new config with a file
+    /// let cfg = Config::new()
+    ///     .file_timeout("config.cfg", Duration::from_secs(5))
+    ///     .unwrap();
+    ///
+    /// // Read a value
+    /// assert_eq!(cfg.get_str("hello").unwrap(), "World");
+    /// ```
+    pub fn file_timeout<T>(mut self, file: T, timeout: Duration) -> Result<Self, ConfigError>
+    where
+        T: AsRef<Path>,
+    {
+        compress::with_deadline(timeout, || self.load_file(file.as_ref()))?;
+        Ok(self)
+    }
+
+    /// Write the config back to the file it was last loaded from
+    ///
+    /// Returns `ConfigError::NoFileDefined` if no file was loaded, use `save_to` instead.
+    /// ## Example
+    /// ```rust,no_run
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let mut cfg = Config::new().file("config.cfg").unwrap();
+    /// cfg.set("hello", "people");
+    ///
+    /// // Write the change to `config.cfg`
+    /// cfg.save().unwrap();
+    /// ```
+    pub fn save(&self) -> Result<(), ConfigError> {
+        match &self.path {
+            Some(i) => self.save_to(i),
+            None => Err(ConfigError::NoFileDefined),
+        }
+    }
+
+    /// Write the config to a file
+    ///
+    /// The file is written in the same format `Config::file` reads.
+    /// Comments, blank lines and formatting of loaded sources are kept, only changed values are rewritten.
+    ///
+    /// The file is replaced at once: the config is written to a temporary file in the same directory,
+    /// synced to disk and renamed over the file, so a crash or power loss leaves either the old or new config.
+    /// The permissions of the old file are kept, see `Config::preserve_permissions`,
+    /// and backups of it are kept if `Config::backups` is set.
+    /// ## Example
+    /// ```rust,no_run
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let mut cfg = Config::new();
+    /// cfg.set("hello", "world");
+    ///
+    /// cfg.save_to("new_config.cfg").unwrap();
+    /// ```
+    pub fn save_to<T>(&self, file: T) -> Result<(), ConfigError>
+    where
+        T: AsRef<Path>,
+    {
+        self.write_file(file.as_ref(), self.to_string().as_bytes())
+    }
+
+    /// Load a file, leaving the config as is if it fails
+    pub(crate) fn load_file(&mut self, file: &Path) -> Result<(), ConfigError> {
+        let start = Instant::now();
+        let (contents, invalid) = compress::read(file, self.decoding)?;
+        self.load_file_contents(file, contents, start)?;
+        self.warn_invalid_utf8(invalid);
+        Ok(())
+    }
+
+    /// Parse the contents of a file and add it as a source, leaving the config as is if it fails
+    ///
+    /// Any files it extends (see `Config::file`) are added first.
+    pub(crate) fn load_file_contents(
+        &mut self,
+        file: &Path,
+        contents: String,
+        start: Instant,
+    ) -> Result<(), ConfigError> {
+        let hash = fingerprint::contents_hash(&contents);
+        let layers = self.parse_extended(file, contents)?;
+        // Only a file that reads no others can be told to be unchanged by its contents
+        let alone = layers.len() == 1
+            && !layers[0]
+                .parsed
+                .1
+                .iter()
+                .any(|i| matches!(i, Line::Include { .. }));
+        self.add_layers(layers, start)?;
+        self.path = Some(file.to_path_buf());
+        self.validated = Some(SystemTime::now());
+        self.file_hash = alone.then_some(hash);
+        Ok(())
+    }
+}
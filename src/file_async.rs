@@ -0,0 +1,39 @@
+//! Loading files without blocking an async runtime
+use std::path::Path;
+use std::time::Instant;
+
+use crate::compress;
+use crate::{Config, ConfigError};
+
+impl Config {
+    /// Reads and parses config from a file with `tokio::fs`, like `Config::file`
+    ///
+    /// The file is read without blocking the runtime, so it can be used by async services at startup or to reload.
+    /// Files it `!include`s are still read the normal way.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let cfg = Config::new().file_async("config.cfg").await.unwrap();
+    ///
+    /// assert_eq!(cfg.get_str("hello").unwrap(), "World");
+    /// # });
+    /// ```
+    pub async fn file_async<T>(mut self, file: T) -> Result<Self, ConfigError>
+    where
+        T: AsRef<Path>,
+    {
+        let start = Instant::now();
+        let file = file.as_ref();
+        let contents = tokio::fs::read(file)
+            .await
+            .map_err(|e| ConfigError::file_read(file, e))?;
+        let (contents, invalid) = compress::decode_with(file, contents, self.decoding)?;
+
+        self.load_file_contents(file, contents, start)?;
+        self.warn_invalid_utf8(invalid);
+        Ok(self)
+    }
+}
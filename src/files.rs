@@ -0,0 +1,101 @@
+//! Loading many files at once, like a directory of config fragments (with the `file` feature)
+//!
+//! Every file is read and parsed before any are added (each on its own thread with the `parallel` feature),
+//! so one broken file does not stop the rest from being loaded or checked.
+use std::path::Path;
+use std::time::{Instant, SystemTime};
+
+use crate::compress;
+use crate::extends::Layer;
+use crate::{Config, ConfigError};
+
+/// A file read and parsed along with the files it extends, and the lines that were not valid UTF-8
+type Parsed = Result<(Vec<Layer>, Vec<usize>), ConfigError>;
+
+impl Config {
+    /// Reads and parses config from many files, adding them in order like calling `Config::file` for each
+    ///
+    /// So keys in later files take priority.
+    /// A file that fails to load is skipped and the rest are still added,
+    /// then every failure is returned at once in a `ConfigError::Sources` naming the file.
+    /// With the `parallel` feature the files are read and parsed on their own threads.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, ConfigError};
+    ///
+    /// let cfg = Config::new().files(&["config.cfg"]).unwrap();
+    /// assert_eq!(cfg.get_str("hello").unwrap(), "World");
+    ///
+    /// match Config::new().files(&["config.cfg", "nonexistent.cfg"]) {
+    ///     Err(ConfigError::Sources(i)) => {
+    ///         assert_eq!(i.len(), 1);
+    ///         assert_eq!(i[0].0, "nonexistent.cfg");
+    ///     }
+    ///     _ => panic!("The missing file should fail"),
+    /// }
+    /// ```
+    pub fn files<T>(mut self, files: &[T]) -> Result<Self, ConfigError>
+    where
+        T: AsRef<Path>,
+    {
+        let files = files.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+        let parsed = self.read_all(&files);
+
+        let mut errors = Vec::new();
+        for (file, (start, parsed)) in files.into_iter().zip(parsed) {
+            let result = parsed.and_then(|(layers, invalid)| {
+                self.add_layers(layers, start)?;
+                self.warn_invalid_utf8(invalid);
+                Ok(())
+            });
+
+            match result {
+                Ok(()) => {
+                    self.path = Some(file.to_path_buf());
+                    self.validated = Some(SystemTime::now());
+                }
+                Err(e) => errors.push((file.display().to_string(), e)),
+            }
+        }
+
+        match errors.is_empty() {
+            true => Ok(self),
+            false => Err(ConfigError::Sources(errors)),
+        }
+    }
+
+    /// Read and parse every file one after another, with when each was started
+    #[cfg(not(feature = "parallel"))]
+    fn read_all(&self, files: &[&Path]) -> Vec<(Instant, Parsed)> {
+        files
+            .iter()
+            .map(|i| (Instant::now(), self.read_parsed(i)))
+            .collect()
+    }
+
+    /// Read and parse every file on its own thread, with when each was started
+    #[cfg(feature = "parallel")]
+    fn read_all(&self, files: &[&Path]) -> Vec<(Instant, Parsed)> {
+        std::thread::scope(|s| {
+            let threads = files
+                .iter()
+                .map(|i| (Instant::now(), s.spawn(move || self.read_parsed(i))))
+                .collect::<Vec<_>>();
+
+            threads
+                .into_iter()
+                .map(|(start, i)| {
+                    let parsed = i.join().unwrap_or_else(|e| std::panic::resume_unwind(e));
+                    (start, parsed)
+                })
+                .collect()
+        })
+    }
+
+    /// Read and parse a file and the files it extends, without adding them
+    fn read_parsed(&self, file: &Path) -> Parsed {
+        let (contents, invalid) = compress::read(file, self.decoding)?;
+        Ok((self.parse_extended(file, contents)?, invalid))
+    }
+}
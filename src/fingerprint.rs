@@ -0,0 +1,109 @@
+//! Fingerprints of the values of a config, for telling if a new version of it changed anything
+#[cfg(feature = "file")]
+use std::collections::hash_map::DefaultHasher;
+#[cfg(feature = "file")]
+use std::hash::Hasher;
+#[cfg(feature = "file")]
+use std::path::Path;
+#[cfg(feature = "file")]
+use std::time::Instant;
+
+#[cfg(feature = "file")]
+use crate::compress;
+use crate::Config;
+#[cfg(feature = "file")]
+use crate::ConfigError;
+
+/// Hash bytes with 64 bit FNV-1a, which (unlike `DefaultHasher`) is the same in every version
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, i| {
+        (hash ^ *i as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+impl Config {
+    /// Get a hash of the values in use, that only changes if a value read from the config would
+    ///
+    /// Keys are hashed by their dotted name in sorted order, so formatting, comments and the order of keys don't change it.
+    /// The hash is the same across versions and platforms, so it can be stored.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let a = Config::new().text("port = 80\nhost = localhost").unwrap();
+    /// let b = Config::new().text("# Comment\nHost = localhost\nport   =   80").unwrap();
+    /// let c = Config::new().text("port = 8080\nhost = localhost").unwrap();
+    ///
+    /// assert_eq!(a.fingerprint(), b.fingerprint());
+    /// assert_ne!(a.fingerprint(), c.fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> u64 {
+        let mut values = self
+            .iter_effective()
+            .map(|i| (i.name(), i.value.as_str()))
+            .collect::<Vec<_>>();
+        values.sort_unstable();
+
+        values
+            .iter()
+            .fold(0xcbf29ce484222325, |hash, (name, value)| {
+                let hash = fnv1a(hash, name.as_bytes());
+                let hash = fnv1a(hash, b"=");
+                let hash = fnv1a(hash, value.as_bytes());
+                fnv1a(hash, b"\0")
+            })
+    }
+
+    /// Reads and parses config from a file, if its values changed since they were loaded into `previous`
+    ///
+    /// Returns `None` if the file has the same contents as when `previous` loaded it, without parsing it,
+    /// or if it parses to the same values (see `Config::fingerprint`).
+    /// So a poller can skip reloading when the file was only touched or reformatted.
+    /// Contents are only compared if the file was the last source of `previous` and did not include or extend other files.
+    /// If they are the same, variables named in `env:NAME` comments are not read again.
+    /// ## Example
+    /// ```rust,no_run
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let mut cfg = Config::new().file("config.cfg").unwrap();
+    ///
+    /// // When the file is modified
+    /// if let Some(new) = Config::new().file_if_changed("config.cfg", &cfg).unwrap() {
+    ///     cfg = new;
+    /// }
+    /// ```
+    #[cfg(feature = "file")]
+    pub fn file_if_changed<T>(
+        mut self,
+        file: T,
+        previous: &Config,
+    ) -> Result<Option<Self>, ConfigError>
+    where
+        T: AsRef<Path>,
+    {
+        let start = Instant::now();
+        let file = file.as_ref();
+        let (contents, invalid) = compress::read(file, self.decoding)?;
+        let unchanged = previous.path.as_deref() == Some(file)
+            && previous.file_hash == Some(contents_hash(&contents));
+        if unchanged {
+            return Ok(None);
+        }
+
+        self.load_file_contents(file, contents, start)?;
+        self.warn_invalid_utf8(invalid);
+        Ok((self.fingerprint() != previous.fingerprint()).then_some(self))
+    }
+}
+
+/// Hash the contents of a file, to tell if it changed without parsing it
+///
+/// Only compared within a process, so unlike `Config::fingerprint` it can change between versions.
+#[cfg(feature = "file")]
+pub(crate) fn contents_hash(contents: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hasher.write(contents.as_bytes());
+    hasher.finish()
+}
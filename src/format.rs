@@ -0,0 +1,92 @@
+//! Config formats and picking them from a content type
+//!
+//! There is no built in fetching of remote configs,
+//! but anything that does the fetching can pass along the response's `Content-Type`
+//! to load it with the right parser.
+use crate::{Config, ConfigError};
+
+/// A format config text can be written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// The simplified ini format of this crate
+    Ini,
+    /// A subset of TOML, see `Config::format` (with the `toml` feature)
+    #[cfg(feature = "toml")]
+    Toml,
+}
+
+impl Format {
+    /// Get the format for a MIME content type (like `text/plain; charset=utf-8`)
+    ///
+    /// Parameters other than a UTF-8 (or ASCII) `charset` are ignored.
+    /// Returns `None` for unknown types or other charsets.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Format;
+    ///
+    /// assert_eq!(Format::from_content_type("text/x-ini"), Some(Format::Ini));
+    /// assert_eq!(Format::from_content_type("Text/Plain; charset=UTF-8"), Some(Format::Ini));
+    /// #[cfg(feature = "toml")]
+    /// assert_eq!(Format::from_content_type("application/toml"), Some(Format::Toml));
+    /// assert_eq!(Format::from_content_type("image/png"), None);
+    /// ```
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let mut parts = content_type.split(';');
+        let mime = parts.next()?.trim().to_lowercase();
+
+        for i in parts {
+            let (name, value) = i.split_once('=')?;
+            let value = value.trim().trim_matches('"').to_lowercase();
+            if name.trim().eq_ignore_ascii_case("charset")
+                && !matches!(value.as_str(), "utf-8" | "utf8" | "us-ascii")
+            {
+                return None;
+            }
+        }
+
+        match mime.as_str() {
+            "text/plain" | "text/x-ini" | "application/x-ini" | "text/x-cfg" => Some(Format::Ini),
+            #[cfg(feature = "toml")]
+            "application/toml" | "text/x-toml" => Some(Format::Toml),
+            _ => None,
+        }
+    }
+}
+
+impl Config {
+    /// Parses config text in the format its `Content-Type` says it is in
+    ///
+    /// Returns `ConfigError::UnsupportedFormat` if there is no parser for the content type.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, ConfigError};
+    ///
+    /// // Body and headers of a response from a config service
+    /// let cfg = Config::new()
+    ///     .text_with_content_type("hello = World", "text/plain; charset=utf-8")
+    ///     .unwrap();
+    /// assert_eq!(cfg.get_str("hello").unwrap(), "World");
+    ///
+    /// let err = Config::new().text_with_content_type("<html>", "text/html");
+    /// assert!(matches!(err, Err(ConfigError::UnsupportedFormat(_))));
+    /// ```
+    pub fn text_with_content_type<T>(self, text: T, content_type: &str) -> Result<Self, ConfigError>
+    where
+        T: std::fmt::Display,
+    {
+        let format = match Format::from_content_type(content_type) {
+            Some(i) => i,
+            None => return Err(ConfigError::UnsupportedFormat(content_type.to_owned())),
+        };
+
+        // Only this text is parsed in the content type's format
+        let mut cfg = self;
+        let previous = cfg.format;
+        cfg.format = format;
+        let mut cfg = cfg.text(text)?;
+        cfg.format = previous;
+        Ok(cfg)
+    }
+}
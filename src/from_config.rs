@@ -0,0 +1,151 @@
+//! Loading typed structs from configs
+//!
+//! With the `derive` feature `#[derive(FromConfig)]` can be used to implement `FromConfig` for structs.
+use std::fmt;
+
+use crate::config;
+use crate::{Config, ConfigError, FromConfigValue};
+
+/// Types that can be loaded from a config
+///
+/// Normally implemented with `#[derive(FromConfig)]` (`derive` feature).
+/// Each field is read from the key with the same name, this can be changed with some attributes:
+/// - `#[config(rename = "key")]` Read the field from a different key
+/// - `#[config(section = "name")]` Read the field from a section (can also be put on the struct)
+/// - `#[config(default)]` / `#[config(default = expr)]` Value to use if the key is not defined
+///
+/// Fields with an `Option` type are `None` if the key is not defined.
+/// ## Example
+/// ```rust
+/// # #[cfg(feature = "derive")] {
+/// // Import Lib
+/// use simple_config_parser::{Config, FromConfig};
+///
+/// #[derive(FromConfig)]
+/// struct Server {
+///     host: String,
+///     #[config(default = 8080)]
+///     port: u16,
+///     #[config(rename = "db_url", section = "database")]
+///     database: String,
+///     workers: Option<u8>,
+/// }
+///
+/// let cfg = Config::new()
+///     .text("host = localhost\n[database]\ndb_url = postgres://db")
+///     .unwrap();
+///
+/// let server = Server::from_config(&cfg).unwrap();
+/// assert_eq!(server.port, 8080);
+/// assert_eq!(server.database, "postgres://db");
+/// assert_eq!(server.workers, None);
+/// # }
+/// ```
+pub trait FromConfig: Sized {
+    /// Load the type from a config
+    fn from_config(cfg: &Config) -> Result<Self, FromConfigError>;
+}
+
+/// Error loading a type from a config
+///
+/// Lists every field that was missing or invalid, not just the first one.
+#[derive(Debug)]
+pub struct FromConfigError {
+    /// The fields that could not be loaded
+    pub fields: Vec<FieldError>,
+}
+
+/// A single field that could not be loaded
+#[derive(Debug)]
+pub struct FieldError {
+    /// Name of the struct field
+    pub field: &'static str,
+    /// Section the key was looked for in
+    pub section: Option<&'static str>,
+    /// The key that was read
+    pub key: &'static str,
+    /// Why the field could not be loaded (`NoItem` or `InvalidType`)
+    pub error: ConfigError,
+}
+
+impl fmt::Display for FromConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Invalid config:")?;
+        for i in self.fields.iter() {
+            let key = match i.section {
+                Some(section) => format!("[{}] {}", section, i.key),
+                None => i.key.to_owned(),
+            };
+
+            match i.error {
+                ConfigError::NoItem => writeln!(f, " - `{}` is missing", key)?,
+                _ => writeln!(f, " - `{}` is invalid ({})", key, i.error)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Helpers used by the code `#[derive(FromConfig)]` generates
+#[doc(hidden)]
+pub mod __private {
+    use super::*;
+
+    /// A field being loaded
+    pub struct Field<'a> {
+        pub cfg: &'a Config,
+        pub field: &'static str,
+        pub section: Option<&'static str>,
+        pub key: &'static str,
+    }
+
+    impl Field<'_> {
+        /// Read the field, recording any error
+        pub fn get<T: FromConfigValue>(&self, errors: &mut Vec<FieldError>) -> Option<T> {
+            match self.read() {
+                Ok(i) => Some(i),
+                Err(e) => self.error(errors, e),
+            }
+        }
+
+        /// Read the field, using a default if the key is not defined
+        pub fn get_or<T: FromConfigValue>(
+            &self,
+            errors: &mut Vec<FieldError>,
+            default: impl FnOnce() -> T,
+        ) -> Option<T> {
+            match self.read() {
+                Ok(i) => Some(i),
+                Err(ConfigError::NoItem) => Some(default()),
+                Err(e) => self.error(errors, e),
+            }
+        }
+
+        /// Read an optional field
+        pub fn get_opt<T: FromConfigValue>(
+            &self,
+            errors: &mut Vec<FieldError>,
+        ) -> Option<Option<T>> {
+            match self.read() {
+                Ok(i) => Some(Some(i)),
+                Err(ConfigError::NoItem) => Some(None),
+                Err(e) => self.error(errors, e),
+            }
+        }
+
+        fn read<T: FromConfigValue>(&self) -> Result<T, ConfigError> {
+            config::parse_value(self.cfg.entry(self.section, self.key), self.cfg.numbers)
+        }
+
+        fn error<T>(&self, errors: &mut Vec<FieldError>, error: ConfigError) -> Option<T> {
+            errors.push(FieldError {
+                field: self.field,
+                section: self.section,
+                key: self.key,
+                error,
+            });
+            None
+        }
+    }
+}
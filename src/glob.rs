@@ -0,0 +1,33 @@
+//! File pattern values (with the `glob` feature)
+use globset::{GlobBuilder, GlobMatcher};
+
+use crate::{Config, ConfigError};
+
+impl Config {
+    /// Get a value from config as a glob pattern matcher
+    ///
+    /// `*` and `?` don't match path separators, use `**` to match any number of directories.
+    /// Returns `ConfigError::InvalidType` naming the key if the pattern is not valid.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new().text("include = /var/log/**/*.log").unwrap();
+    /// let include = cfg.get_glob("include").unwrap();
+    ///
+    /// assert!(include.is_match("/var/log/nginx/access.log"));
+    /// assert!(!include.is_match("/var/log/nginx/access.log.1"));
+    /// ```
+    pub fn get_glob(&self, key: &str) -> Result<GlobMatcher, ConfigError> {
+        self.located(self.entry(None, key), |i| {
+            let entry = i?;
+            let glob = GlobBuilder::new(&entry.value)
+                .literal_separator(true)
+                .build()
+                .map_err(|_| entry.invalid_type("glob pattern"))?;
+
+            Ok(glob.compile_matcher())
+        })
+    }
+}
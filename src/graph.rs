@@ -0,0 +1,178 @@
+//! Graph of what a config depends on, for visualizing and auditing layered configs
+//!
+//! Covers the files that include other files (with `!include`) and the keys that reference
+//! something else with `${name:reference}` (environment variables or resolvers, see `Config::resolver`).
+use std::fmt::Write;
+
+use crate::encoding;
+use crate::layout::Line;
+use crate::Config;
+
+/// What the files and keys of a config depend on
+///
+/// Get one with `cfg.dependency_graph()` and write it out with `to_dot` or `to_json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DependencyGraph {
+    /// Files that include other files, as `(file, included file)`
+    pub includes: Vec<(String, String)>,
+    /// Keys that reference something, as `(dotted key, reference)` like `("db.password", "vault:db")`
+    pub references: Vec<(String, String)>,
+}
+
+impl DependencyGraph {
+    /// Write the graph in the Graphviz DOT format
+    ///
+    /// Files are boxes, keys are ellipses and references are diamonds.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph config {\n");
+        for (file, included) in self.includes.iter() {
+            let _ = writeln!(
+                out,
+                "    {} [shape=box];\n    {} [shape=box];\n    {} -> {} [label=\"includes\"];",
+                dot_id(file),
+                dot_id(included),
+                dot_id(file),
+                dot_id(included)
+            );
+        }
+
+        for (key, reference) in self.references.iter() {
+            let _ = writeln!(
+                out,
+                "    {} [shape=diamond];\n    {} -> {} [label=\"references\"];",
+                dot_id(reference),
+                dot_id(key),
+                dot_id(reference)
+            );
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    /// Write the graph as JSON (`{"includes":[{"from":..,"to":..}],"references":[..]}`)
+    pub fn to_json(&self) -> String {
+        let edges = |edges: &[(String, String)]| {
+            edges
+                .iter()
+                .map(|(from, to)| {
+                    format!(
+                        "{{\"from\":{},\"to\":{}}}",
+                        encoding::json_string(from),
+                        encoding::json_string(to)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        format!(
+            "{{\"includes\":[{}],\"references\":[{}]}}",
+            edges(&self.includes),
+            edges(&self.references)
+        )
+    }
+}
+
+/// Quote a node name for DOT
+fn dot_id(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Get the include edges of a source from its layout, as `(file, included file)`
+pub(crate) fn include_edges(layout: &[Line], source: &str) -> Vec<(String, String)> {
+    let mut out = Vec::new();
+    for i in layout {
+        if let Line::Include { file, includes, .. } = i {
+            out.push((source.to_owned(), file.to_owned()));
+            out.extend(includes.iter().cloned());
+        }
+    }
+
+    out
+}
+
+/// Get the `name:reference` parts of the `${name:reference}` references in some text
+fn references(text: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("${") {
+        let escaped = rest[..start].ends_with('$');
+        rest = &rest[start + 2..];
+        let end = match rest.find('}') {
+            Some(i) => i,
+            None => break,
+        };
+
+        if !escaped && rest[..end].contains(':') {
+            out.push(&rest[..end]);
+        }
+        rest = &rest[end + 1..];
+    }
+
+    out
+}
+
+impl Config {
+    /// Get a graph of the includes and references of the config
+    ///
+    /// References are found in values as they were written, so environment variables that
+    /// were expanded with `Config::expand_env` (in values that have not been changed since) are included.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .text("[db]\npassword = ${vault:db/password}\nhost = ${env:DB_HOST}")
+    ///     .unwrap();
+    ///
+    /// let graph = cfg.dependency_graph();
+    /// assert_eq!(graph.references[0], ("db.password".to_owned(), "vault:db/password".to_owned()));
+    /// assert!(graph.to_dot().contains("\"db.host\" -> \"env:DB_HOST\""));
+    /// ```
+    pub fn dependency_graph(&self) -> DependencyGraph {
+        let mut graph = DependencyGraph::default();
+        for i in self.include_edges.iter() {
+            if !graph.includes.contains(i) {
+                graph.includes.push(i.to_owned());
+            }
+        }
+
+        // Values that have not changed since loading can have expanded references in their raw text
+        let mut raw = vec![None; self.data.len()];
+        for i in self.layout.iter() {
+            if let Line::Entry {
+                text,
+                value_at,
+                index,
+                ..
+            } = i
+            {
+                if let Some(raw) = raw
+                    .get_mut(*index)
+                    .filter(|_| i.loaded() == Some(&self.data[*index].value))
+                {
+                    *raw = Some(&text[value_at.clone()]);
+                }
+            }
+        }
+
+        for (i, raw) in self.data.iter().zip(raw) {
+            let name = i.name();
+            let found = references(&i.value)
+                .into_iter()
+                .chain(raw.map(references).unwrap_or_default());
+
+            for reference in found {
+                let edge = (name.to_owned(), reference.to_owned());
+                if !graph.references.contains(&edge) {
+                    graph.references.push(edge);
+                }
+            }
+        }
+
+        graph
+    }
+}
@@ -0,0 +1,110 @@
+//! Write protection with `managed-by` markers
+//!
+//! Files written by tools can have a `# managed-by: <tool>` comment in their header.
+//! `Config::save_guarded` uses it to keep tools from overwriting hand maintained files, and people from overwriting tool managed ones.
+#[cfg(feature = "file")]
+use std::fs;
+
+use crate::layout::Line;
+use crate::Config;
+#[cfg(feature = "file")]
+use crate::ConfigError;
+
+/// Comment prefix of a managed-by marker
+const MARKER: &str = "managed-by:";
+
+/// Which files `Config::save_guarded` is allowed to overwrite
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveGuard<'a> {
+    /// Only overwrite files managed by this tool (or files that don't exist yet)
+    ManagedBy(&'a str),
+    /// Only overwrite files with no managed-by marker
+    Unmanaged,
+}
+
+/// Find the managed-by marker in the header comments of some lines
+pub(crate) fn find_marker<'a>(mut lines: impl Iterator<Item = &'a str>) -> Option<String> {
+    lines
+        .by_ref()
+        .map(|i| i.trim())
+        .take_while(|i| i.is_empty() || i.starts_with('#') || i.starts_with(';'))
+        .filter_map(|i| i.get(1..))
+        .find_map(|i| i.trim().strip_prefix(MARKER))
+        .map(|i| i.trim().to_owned())
+}
+
+impl Config {
+    /// Get the tool named in the config's `# managed-by: <tool>` header
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .text("# managed-by: deploy-bot\nhello = world")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.managed_by().unwrap(), "deploy-bot");
+    /// ```
+    pub fn managed_by(&self) -> Option<String> {
+        find_marker(self.layout.iter().map(|i| match i {
+            Line::Text(i) => i.as_str(),
+            _ => "=",
+        }))
+    }
+
+    /// Mark the config as managed by a tool
+    ///
+    /// Adds a `# managed-by: <tool>` line to the top of the config (replacing any existing marker).
+    pub fn set_managed_by(&mut self, tool: &str) {
+        let marker = Line::Text(format!("# {} {}", MARKER, tool));
+        let existing = self.layout.iter().position(|i| match i {
+            Line::Text(i) => find_marker(std::iter::once(i.as_str())).is_some(),
+            _ => false,
+        });
+
+        match existing {
+            Some(i) if self.layout[..i].iter().all(|i| matches!(i, Line::Text(_))) => {
+                self.layout[i] = marker
+            }
+            _ => self.layout.insert(0, marker),
+        }
+    }
+
+    /// Save the config (like `save`) only if the file on disk is allowed to be overwritten
+    ///
+    /// The markers of the file currently on disk are checked, returning `ConfigError::WriteProtected` if the guard does not allow it.
+    /// ## Example
+    /// ```rust,no_run
+    /// // Import Lib
+    /// use simple_config_parser::{Config, SaveGuard};
+    ///
+    /// let mut cfg = Config::new().file("generated.cfg").unwrap();
+    /// cfg.set("version", "2");
+    /// cfg.set_managed_by("deploy-bot");
+    ///
+    /// // Will not overwrite the file if someone took it over by hand
+    /// cfg.save_guarded(SaveGuard::ManagedBy("deploy-bot")).unwrap();
+    /// ```
+    #[cfg(feature = "file")]
+    pub fn save_guarded(&self, guard: SaveGuard) -> Result<(), ConfigError> {
+        let path = self.path.as_ref().ok_or(ConfigError::NoFileDefined)?;
+        let existing = match fs::read_to_string(path) {
+            Ok(i) => Some(find_marker(i.lines())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(ConfigError::Io(e)),
+        };
+
+        let allowed = match (guard, existing) {
+            (_, None) => true,
+            (SaveGuard::ManagedBy(tool), Some(marker)) => marker.as_deref() == Some(tool),
+            (SaveGuard::Unmanaged, Some(marker)) => marker.is_none(),
+        };
+
+        if !allowed {
+            return Err(ConfigError::WriteProtected);
+        }
+
+        self.save_to(path)
+    }
+}
@@ -0,0 +1,118 @@
+//! Log events for how a config is loaded (with the `log` feature)
+//!
+//! Events are logged to the `simple_config_parser` target with the values they are about as key-values,
+//! so operators can see which source supplied each value. Without the feature these do nothing.
+#![cfg_attr(not(feature = "log"), allow(unused_variables))]
+use std::time::Duration;
+
+#[cfg(feature = "reload")]
+use crate::Staleness;
+use crate::{Config, LineError};
+
+/// Target of every event
+#[cfg(feature = "log")]
+const TARGET: &str = "simple_config_parser";
+
+/// A source was loaded
+pub(crate) fn loaded(source: &str, entries: usize, duration: Duration) {
+    #[cfg(feature = "log")]
+    log::info!(
+        target: TARGET,
+        source = source,
+        entries = entries,
+        duration_ms = duration.as_secs_f64() * 1000.0;
+        "Loaded {} entries from {} in {:?}",
+        entries,
+        source,
+        duration
+    );
+}
+
+/// A key defined by an earlier source was overridden
+pub(crate) fn overridden(key: &str, source: &str) {
+    #[cfg(feature = "log")]
+    log::debug!(
+        target: TARGET,
+        key = key,
+        source = source;
+        "`{}` is overridden by {}",
+        key,
+        source
+    );
+}
+
+/// The value of a key was replaced by an overlay, like an `env:NAME` comment
+pub(crate) fn overlay(key: &str, from: &str, source: &str) {
+    #[cfg(feature = "log")]
+    log::debug!(
+        target: TARGET,
+        key = key,
+        from = from,
+        source = source;
+        "`{}` in {} is set from {}",
+        key,
+        source,
+        from
+    );
+}
+
+/// A line that could not be parsed was skipped
+pub(crate) fn invalid_line(source: &str, error: &LineError) {
+    #[cfg(feature = "log")]
+    log::warn!(
+        target: TARGET,
+        source = source,
+        line = error.line;
+        "Skipped line {} of {}: {}",
+        error.line,
+        source,
+        error.error
+    );
+}
+
+/// A config was reloaded in place, only parsing the lines that changed if `partial`
+#[cfg(feature = "reload")]
+pub(crate) fn reloaded(source: &str, partial: bool) {
+    #[cfg(feature = "log")]
+    log::info!(
+        target: TARGET,
+        source = source,
+        partial = partial;
+        "Reloaded {}",
+        source
+    );
+}
+
+/// Reloading a watched config failed, it's tried again after `retry`
+#[cfg(feature = "reload")]
+pub(crate) fn reload_failed(error: &str, retry: Duration) {
+    #[cfg(feature = "log")]
+    log::warn!(
+        target: TARGET,
+        retry_ms = retry.as_secs_f64() * 1000.0;
+        "Reloading failed ({}), trying again in {:?}",
+        error,
+        retry
+    );
+}
+
+/// A watched config looks out of date, see `Watcher::warn_stale`
+#[cfg(feature = "reload")]
+pub(crate) fn stale(reason: &Staleness) {
+    #[cfg(feature = "log")]
+    log::warn!(target: TARGET, "Config may be stale: {:?}", reason);
+}
+
+/// A shared config was replaced with a new one
+pub(crate) fn replaced(old: &Config, new: &Config) {
+    #[cfg(feature = "log")]
+    if log::log_enabled!(target: TARGET, log::Level::Info) {
+        let changes = old.diff(new).changes.len();
+        log::info!(
+            target: TARGET,
+            changes = changes;
+            "Replaced shared config, {} values changed",
+            changes
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! Expanding `${env:NAME}` references to environment variables in values
+//!
+//! Turned on with `Config::expand_env`, values are expanded as they are loaded.
+//! Write `$${env:NAME}` for a literal `${env:NAME}`.
+//!
+//! Keys can also be overridden by a variable with an `env:NAME` word in their inline comment (`port = 80 ; env:PORT`),
+//! or every variable with a prefix can be loaded as a source with `Config::env`.
+use std::env;
+use std::time::Instant;
+
+use crate::config::{Comments, Entry};
+use crate::{Config, ConfigError};
+
+/// Start of an environment variable reference
+const ENV_START: &str = "${env:";
+
+/// What to do with references to environment variables that are not set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnsetEnv {
+    /// Fail loading with `ConfigError::MissingEnv`
+    Error,
+    /// Leave the `${env:NAME}` placeholder in the value
+    Keep,
+}
+
+/// Prefix of the word in an inline comment naming a variable to override the value with
+const OVERRIDE_PREFIX: &str = "env:";
+
+/// Get the name and value of the variable named in the inline comment of a key, if it is set
+///
+/// `rest` is the text of the line after the value.
+pub(crate) fn env_override<'a>(rest: &'a str, comments: &Comments) -> Option<(&'a str, String)> {
+    memchr::memmem::find(rest.as_bytes(), OVERRIDE_PREFIX.as_bytes())?;
+    let comment = rest.trim_start();
+    let start = comment
+        .chars()
+        .next()
+        .filter(|_| comments.is_comment(comment))?;
+    let name = comment[start.len_utf8()..]
+        .split_whitespace()
+        .find_map(|i| i.strip_prefix(OVERRIDE_PREFIX))
+        .filter(|i| !i.is_empty())?;
+    Some((name, env::var(name).ok()?))
+}
+
+/// Expand the environment variable references in a value
+pub(crate) fn expand_env(value: &str, unset: UnsetEnv) -> Result<String, ConfigError> {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find(ENV_START) {
+        // `$${env:` is an escaped reference
+        if rest[..start].ends_with('$') {
+            out.push_str(&rest[..start - 1]);
+            out.push_str(ENV_START);
+            rest = &rest[start + ENV_START.len()..];
+            continue;
+        }
+
+        out.push_str(&rest[..start]);
+        let name_start = start + ENV_START.len();
+        let end = match rest[name_start..].find('}') {
+            Some(i) => name_start + i,
+            None => {
+                rest = &rest[start..];
+                break;
+            }
+        };
+
+        let name = &rest[name_start..end];
+        match (env::var(name), unset) {
+            (Ok(i), _) => out.push_str(&i),
+            (Err(_), UnsetEnv::Keep) => out.push_str(&rest[start..=end]),
+            (Err(_), UnsetEnv::Error) => return Err(ConfigError::MissingEnv(name.to_owned())),
+        }
+        rest = &rest[end + 1..];
+    }
+
+    out.push_str(rest);
+    Ok(out)
+}
+
+impl Config {
+    /// Expand `${env:NAME}` references to environment variables in the values of sources loaded after this
+    ///
+    /// `unset` picks what happens if a variable is not set.
+    /// Saving the config keeps the references in values that have not been changed.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, ConfigError, UnsetEnv};
+    ///
+    /// std::env::set_var("APP_HOME", "/home/app");
+    /// let cfg = Config::new()
+    ///     .expand_env(UnsetEnv::Keep)
+    ///     .text("logs = ${env:APP_HOME}/logs\nother = ${env:APP_UNSET}")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get_str("logs").unwrap(), "/home/app/logs");
+    /// assert_eq!(cfg.get_str("other").unwrap(), "${env:APP_UNSET}");
+    ///
+    /// let err = Config::new()
+    ///     .expand_env(UnsetEnv::Error)
+    ///     .text("other = ${env:APP_UNSET}");
+    /// assert!(matches!(err, Err(ConfigError::MissingEnv(_))));
+    /// ```
+    pub fn expand_env(mut self, unset: UnsetEnv) -> Self {
+        self.env = Some(unset);
+        self
+    }
+
+    /// Load every environment variable starting with `prefix` as a source
+    ///
+    /// The prefix is removed and the rest lowercased, with `__` separating nested keys.
+    /// So with the prefix `APP_`, `APP_LOG_LEVEL` is `log_level` and `APP_SERVER__PORT` is `server.port`.
+    /// Like JSON keys, they are not in any section but are found by their dotted name.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// std::env::set_var("MYAPP_SERVER__PORT", "8080");
+    /// let cfg = Config::new()
+    ///     .text("[server]\nport = 80\nhost = localhost")
+    ///     .unwrap()
+    ///     .env("MYAPP_")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get::<u16>("server.port").unwrap(), 8080);
+    /// assert_eq!(cfg.get_str("server.host").unwrap(), "localhost");
+    /// ```
+    pub fn env(mut self, prefix: &str) -> Result<Self, ConfigError> {
+        self.load_env(prefix)?;
+        Ok(self)
+    }
+
+    /// Load the variables starting with a prefix, leaving the config as is if it fails
+    pub(crate) fn load_env(&mut self, prefix: &str) -> Result<(), ConfigError> {
+        let start = Instant::now();
+        // Variables that are not valid unicode are skipped
+        let mut vars = env::vars_os()
+            .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+            .filter_map(|(name, value)| Some((name.strip_prefix(prefix)?.to_owned(), value)))
+            .filter(|(name, _)| !name.is_empty())
+            .collect::<Vec<_>>();
+        vars.sort();
+
+        let mut data = vars
+            .into_iter()
+            .map(|(name, value)| Entry {
+                section: None,
+                key: self.key_style.key(&name.to_lowercase().replace("__", ".")),
+                value,
+            })
+            .collect::<Vec<_>>();
+
+        self.apply_transforms(&mut data, &mut Vec::new());
+        self.apply_processors(&mut data)?;
+        self.add_source(format!("<env:{}>", prefix), start, (data, Vec::new()))
+    }
+}
@@ -0,0 +1,240 @@
+//! Loading JSON objects into the flat key space (with the `json` feature)
+//!
+//! Nested objects are flattened into dotted keys (`{"server": {"port": 8080}}` is `server.port`).
+//! Arrays of plain values are stored as their items separated by `, `, so they can be read with `get_vec`,
+//! while arrays with objects or arrays in them use the index as a key (`servers.0.host`).
+//! `null` values are skipped.
+use std::iter::Peekable;
+use std::str::Chars;
+use std::time::Instant;
+
+use crate::config::{Entry, KeyStyle};
+use crate::interpolate;
+use crate::utf8;
+use crate::{Config, ConfigError};
+
+impl Config {
+    /// Loads a JSON object into the config, with nested keys joined by `.`
+    ///
+    /// Keys are not in any section, so they are read with the normal `get` functions.
+    /// As the JSON layout is not kept, saving the config writes the keys in this crates format.
+    /// Returns `ConfigError::InvalidConfig` if the text is not a JSON object.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .json(r#"{"server": {"port": 8080, "hosts": ["a", "b"]}, "debug": true}"#)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get::<u16>("server.port").unwrap(), 8080);
+    /// assert_eq!(cfg.get_vec::<String>("server.hosts").unwrap(), ["a", "b"]);
+    /// assert!(cfg.get::<bool>("debug").unwrap());
+    /// ```
+    pub fn json<T>(mut self, text: T) -> Result<Self, ConfigError>
+    where
+        T: std::fmt::Display,
+    {
+        self.load_json(&text.to_string())?;
+        Ok(self)
+    }
+
+    /// Load a JSON object, leaving the config as is if it fails
+    pub(crate) fn load_json(&mut self, text: &str) -> Result<(), ConfigError> {
+        let start = Instant::now();
+        let mut data = parse(&utf8::normalize(text), self.key_style)?;
+
+        if let Some(unset) = self.env {
+            for i in data.iter_mut() {
+                i.value = interpolate::expand_env(&i.value, unset)?;
+            }
+        }
+        self.apply_transforms(&mut data, &mut Vec::new());
+        self.apply_processors(&mut data)?;
+
+        self.add_source("<json>".to_string(), start, (data, Vec::new()))
+    }
+}
+
+/// A parsed JSON value
+pub(crate) enum Value {
+    Null,
+    /// Strings, numbers and bools, as they would be written in a config
+    Plain(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+/// Parse a JSON object into flattened entries
+pub(crate) fn parse(input: &str, keys: KeyStyle) -> Result<Vec<Entry>, ConfigError> {
+    let mut chars = input.chars().peekable();
+    let value = parse_value(&mut chars)?;
+    skip_space(&mut chars);
+    if chars.next().is_some() || !matches!(value, Value::Object(_)) {
+        return Err(ConfigError::InvalidConfig);
+    }
+
+    let mut out = Vec::new();
+    flatten(String::new(), value, keys, &mut out);
+    Ok(out)
+}
+
+/// Add a value and everything in it to `out`, under the key `prefix`
+pub(crate) fn flatten(prefix: String, value: Value, keys: KeyStyle, out: &mut Vec<Entry>) {
+    let join = |key: &str| match prefix.is_empty() {
+        true => key.to_owned(),
+        false => format!("{}.{}", prefix, key),
+    };
+
+    match value {
+        Value::Null => {}
+        Value::Plain(value) => out.push(Entry {
+            section: None,
+            key: keys.key(&prefix),
+            value,
+        }),
+        Value::Object(items) => {
+            for (key, value) in items {
+                flatten(join(&key), value, keys, out);
+            }
+        }
+        Value::Array(items) if items.iter().all(|i| matches!(i, Value::Plain(_))) => {
+            let value = items
+                .into_iter()
+                .filter_map(|i| match i {
+                    Value::Plain(i) => Some(i),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            flatten(prefix, Value::Plain(value), keys, out);
+        }
+        Value::Array(items) => {
+            for (n, value) in items.into_iter().enumerate() {
+                flatten(join(&n.to_string()), value, keys, out);
+            }
+        }
+    }
+}
+
+/// Skip whitespace between tokens
+fn skip_space(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|i| i.is_whitespace()).is_some() {}
+}
+
+/// Parse any JSON value
+fn parse_value(chars: &mut Peekable<Chars>) -> Result<Value, ConfigError> {
+    skip_space(chars);
+    match chars.peek().ok_or(ConfigError::InvalidConfig)? {
+        '{' => {
+            chars.next();
+            let mut items = Vec::new();
+            skip_space(chars);
+            if chars.next_if_eq(&'}').is_some() {
+                return Ok(Value::Object(items));
+            }
+
+            loop {
+                skip_space(chars);
+                let key = parse_string(chars)?;
+                skip_space(chars);
+                if chars.next() != Some(':') {
+                    return Err(ConfigError::InvalidConfig);
+                }
+                items.push((key, parse_value(chars)?));
+
+                skip_space(chars);
+                match chars.next() {
+                    Some(',') => {}
+                    Some('}') => return Ok(Value::Object(items)),
+                    _ => return Err(ConfigError::InvalidConfig),
+                }
+            }
+        }
+        '[' => {
+            chars.next();
+            let mut items = Vec::new();
+            skip_space(chars);
+            if chars.next_if_eq(&']').is_some() {
+                return Ok(Value::Array(items));
+            }
+
+            loop {
+                items.push(parse_value(chars)?);
+                skip_space(chars);
+                match chars.next() {
+                    Some(',') => {}
+                    Some(']') => return Ok(Value::Array(items)),
+                    _ => return Err(ConfigError::InvalidConfig),
+                }
+            }
+        }
+        '"' => Ok(Value::Plain(parse_string(chars)?)),
+        _ => {
+            let mut word = String::new();
+            while let Some(i) = chars.next_if(|i| i.is_ascii_alphanumeric() || "+-.".contains(*i)) {
+                word.push(i);
+            }
+
+            match word.as_str() {
+                "null" => Ok(Value::Null),
+                "true" | "false" => Ok(Value::Plain(word)),
+                _ if word.starts_with(|i: char| i == '-' || i.is_ascii_digit())
+                    && word.parse::<f64>().is_ok() =>
+                {
+                    Ok(Value::Plain(word))
+                }
+                _ => Err(ConfigError::InvalidConfig),
+            }
+        }
+    }
+}
+
+/// Parse a JSON string, decoding its escapes
+fn parse_string(chars: &mut Peekable<Chars>) -> Result<String, ConfigError> {
+    if chars.next() != Some('"') {
+        return Err(ConfigError::InvalidConfig);
+    }
+
+    let mut out = String::new();
+    loop {
+        match chars.next().ok_or(ConfigError::InvalidConfig)? {
+            '"' => return Ok(out),
+            '\\' => match chars.next().ok_or(ConfigError::InvalidConfig)? {
+                'n' => out.push('\n'),
+                't' => out.push('\t'),
+                'r' => out.push('\r'),
+                'b' => out.push('\u{8}'),
+                'f' => out.push('\u{c}'),
+                i @ ('"' | '\\' | '/') => out.push(i),
+                'u' => {
+                    let mut code = parse_hex(chars)?;
+                    // Characters outside of the BMP are written as surrogate pairs
+                    if (0xD800..0xDC00).contains(&code) {
+                        if chars.next() != Some('\\') || chars.next() != Some('u') {
+                            return Err(ConfigError::InvalidConfig);
+                        }
+                        let low = parse_hex(chars)?;
+                        if !(0xDC00..0xE000).contains(&low) {
+                            return Err(ConfigError::InvalidConfig);
+                        }
+                        code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                    }
+                    out.push(char::from_u32(code).ok_or(ConfigError::InvalidConfig)?);
+                }
+                _ => return Err(ConfigError::InvalidConfig),
+            },
+            i => out.push(i),
+        }
+    }
+}
+
+/// Parse the four hex digits of a `\u` escape
+fn parse_hex(chars: &mut Peekable<Chars>) -> Result<u32, ConfigError> {
+    let hex = chars.take(4).collect::<String>();
+    match hex.len() {
+        4 => u32::from_str_radix(&hex, 16).map_err(|_| ConfigError::InvalidConfig),
+        _ => Err(ConfigError::InvalidConfig),
+    }
+}
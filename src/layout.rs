@@ -0,0 +1,377 @@
+//! Lossless line layout of loaded configs
+//!
+//! Keeps every line of the source text (comments, blank lines and formatting) so saving a config only changes the lines that were edited.
+use std::fmt;
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::Entry;
+use crate::encoding;
+use crate::occurrence::Origin;
+use crate::LineError;
+
+/// A line of a config file
+#[derive(Debug, Clone)]
+pub(crate) enum Line {
+    /// Blank lines and comments, kept as is
+    Text(String),
+    /// A section header, kept as is
+    Section(String),
+    /// A key value pair
+    /// The line is everything before the value (`  key = `), the value and everything after it (` ; comment`).
+    Entry {
+        /// The line as it was written
+        text: String,
+        /// Where the value as it was written (with any quotes) is in `text`
+        value_at: Range<usize>,
+        /// The value when it was loaded, if it still matches the entry `text` is written as is
+        /// `None` if it's the value as written, see `Line::loaded`.
+        value: Option<String>,
+        /// Index of the entry in the config data
+        index: usize,
+        /// Where the value was overridden from (like `env:PORT`), if it's not the value written
+        overridden: Option<String>,
+    },
+    /// An `!include` directive, kept as is
+    /// The entries of the included file are not written out.
+    Include {
+        /// The line of the directive
+        raw: String,
+        /// Indexes of the included entries in the config data
+        entries: Range<usize>,
+        /// Where the included entries came from, taken when the source is added to a config
+        origins: Vec<Origin>,
+        /// The included file
+        file: String,
+        /// Files included by the included file (and so on), as `(file, included)`
+        includes: Vec<(String, String)>,
+    },
+    /// A line that could not be parsed, only in layouts that are parsed with `Config::skip_invalid_lines`
+    /// It's turned into a `Text` line when the source is added to a config.
+    /// Shared so lines can be cloned, as errors can't be.
+    Invalid(Arc<LineError>),
+}
+
+impl Line {
+    /// Split a key value line around its value, found at `start..end`
+    pub(crate) fn entry(line: &str, start: usize, end: usize, value: &str, index: usize) -> Self {
+        Line::Entry {
+            text: line.to_owned(),
+            value_at: start..end,
+            value: (line[start..end] != *value).then(|| value.to_owned()),
+            index,
+            overridden: None,
+        }
+    }
+
+    /// Create a new key value line in the default format
+    pub(crate) fn new_entry(entry: &Entry, index: usize) -> Self {
+        let text = format!("{} = {}", entry.key, encoding::quote_value(&entry.value));
+        Line::Entry {
+            value_at: entry.key.len() + 3..text.len(),
+            value: (text[entry.key.len() + 3..] != entry.value).then(|| entry.value.to_owned()),
+            text,
+            index,
+            overridden: None,
+        }
+    }
+
+    /// Get the value of an entry line when it was loaded
+    pub(crate) fn loaded(&self) -> Option<&str> {
+        match self {
+            Line::Entry {
+                text,
+                value_at,
+                value,
+                ..
+            } => Some(value.as_deref().unwrap_or(&text[value_at.clone()])),
+            _ => None,
+        }
+    }
+
+    /// Note where the value of an entry line was overridden from
+    pub(crate) fn overridden(mut self, from: &str) -> Self {
+        if let Line::Entry { overridden, .. } = &mut self {
+            *overridden = Some(from.to_owned());
+        }
+        self
+    }
+}
+
+/// Start of the comments noting overridden values, see `Config::annotate_overrides`
+const OVERRIDE_NOTE: &str = "# effective:";
+
+/// What to do with the lines of removed keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RemovePolicy {
+    /// Delete the line
+    #[default]
+    Delete,
+    /// Comment out the line, noting when it was removed
+    /// Ex: `# removed 2024-05-01: key = value`
+    CommentOut,
+}
+
+/// Remove the lines of entries from the layout
+///
+/// `removed` has an item for every entry and is `true` for the ones being removed.
+/// The indexes of the remaining entries are updated to match the data once the removed entries are taken out.
+pub(crate) fn remove(
+    layout: &mut Vec<Line>,
+    data: &[Entry],
+    removed: &[bool],
+    policy: RemovePolicy,
+) {
+    let date = today();
+    retain(layout, removed, |line, index, out| {
+        if policy == RemovePolicy::CommentOut {
+            // Multi-line values are commented out line by line
+            let line = entry_line(line, &data[index]);
+            let mut lines = line.lines();
+            let first = lines.next().unwrap_or_default().trim();
+            out.push(Line::Text(format!("# removed {}: {}", date, first)));
+            out.extend(lines.map(|i| Line::Text(format!("# {}", i))));
+        }
+    });
+}
+
+/// Keep the lines of entries that are no longer read as they are, like `remove` otherwise
+pub(crate) fn ignore(layout: &mut Vec<Line>, removed: &[bool]) {
+    retain(layout, removed, |line, _, out| {
+        if let Line::Entry { text, .. } = line {
+            out.push(Line::Text(text.to_owned()));
+        }
+    });
+}
+
+/// Take the lines of removed entries out of the layout, adding what `replace` gives in their place
+fn retain(
+    layout: &mut Vec<Line>,
+    removed: &[bool],
+    mut replace: impl FnMut(&Line, usize, &mut Vec<Line>),
+) {
+    let mut new_index = Vec::with_capacity(removed.len());
+    let mut next = 0;
+    for i in removed.iter() {
+        new_index.push(next);
+        next += !*i as usize;
+    }
+
+    let mut out = Vec::with_capacity(layout.len());
+    for i in layout.drain(..) {
+        match i {
+            Line::Entry { index, .. } if removed.get(index) == Some(&true) => {
+                replace(&i, index, &mut out);
+            }
+            Line::Entry {
+                text,
+                value_at,
+                value,
+                index,
+                overridden,
+            } => out.push(Line::Entry {
+                text,
+                value_at,
+                value,
+                index: new_index.get(index).copied().unwrap_or(index),
+                overridden,
+            }),
+            Line::Include {
+                raw,
+                entries,
+                mut origins,
+                file,
+                includes,
+            } => {
+                let mut n = entries.start;
+                origins.retain(|_| {
+                    n += 1;
+                    removed.get(n - 1) != Some(&true)
+                });
+
+                let remap = |i| new_index.get(i).copied().unwrap_or(next);
+                out.push(Line::Include {
+                    raw,
+                    entries: remap(entries.start)..remap(entries.end),
+                    origins,
+                    file,
+                    includes,
+                })
+            }
+            i => out.push(i),
+        }
+    }
+
+    *layout = out;
+}
+
+/// Get the current (UTC) date as `YYYY-MM-DD`
+fn today() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|i| i.as_secs())
+        .unwrap_or_default();
+
+    // Days since the epoch to a civil date (Howard Hinnant's algorithm)
+    let days = (secs / 86400) as i64 + 719468;
+    let era = days / 146097;
+    let doe = days - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Find where a new un-sectioned key should go
+///
+/// That is before the first section, and before any comments or blank lines leading up to it.
+pub(crate) fn unsectioned_insert_point(layout: &[Line]) -> usize {
+    let mut point = match layout.iter().position(|i| matches!(i, Line::Section(_))) {
+        Some(i) => i,
+        None => return layout.len(),
+    };
+
+    while point > 0 && matches!(layout[point - 1], Line::Text(_)) {
+        point -= 1;
+    }
+
+    point
+}
+
+/// Find where a new key of `section` should go
+///
+/// That is after the last entry line of the section, or after its (last) header if it has none.
+/// `header` gets the name of the section of a header line. Returns `None` if the section is not in the layout.
+pub(crate) fn section_insert_point(
+    layout: &[Line],
+    data: &[Entry],
+    section: &str,
+    header: impl Fn(&str) -> Option<String>,
+) -> Option<usize> {
+    let entry = layout.iter().rposition(|i| match i {
+        Line::Entry { index, .. } => {
+            data.get(*index).and_then(|i| i.section.as_deref()) == Some(section)
+        }
+        _ => false,
+    });
+
+    entry
+        .or_else(|| {
+            layout.iter().rposition(|i| match i {
+                Line::Section(raw) => header(raw).as_deref() == Some(section),
+                _ => false,
+            })
+        })
+        .map(|i| i + 1)
+}
+
+/// Write out the layout with the current values of the entries
+///
+/// Lines are written as they were read with only the values replaced, so comments, spacing and order are kept.
+/// Un-sectioned entries missing from the layout (ex. pushed onto `data` directly) are written first, followed by any sectioned ones.
+/// With `annotate` overridden values are noted in a comment above their line (replacing the old notes).
+pub(crate) fn render(
+    f: &mut fmt::Formatter<'_>,
+    layout: &[Line],
+    data: &[Entry],
+    annotate: bool,
+) -> fmt::Result {
+    let mut used = vec![false; data.len()];
+    for i in layout.iter() {
+        let entries = match i {
+            Line::Entry { index, .. } => *index..*index + 1,
+            Line::Include { entries, .. } => entries.to_owned(),
+            _ => continue,
+        };
+
+        for i in entries {
+            if let Some(i) = used.get_mut(i) {
+                *i = true;
+            }
+        }
+    }
+
+    let missing = |section: bool| {
+        data.iter()
+            .zip(used.iter())
+            .filter(move |(i, used)| !**used && i.section.is_some() == section)
+            .map(|(i, _)| i)
+    };
+
+    for i in missing(false) {
+        writeln!(f, "{} = {}", i.key, encoding::quote_value(&i.value))?;
+    }
+
+    for i in layout.iter() {
+        match i {
+            Line::Text(raw) if annotate && raw.trim_start().starts_with(OVERRIDE_NOTE) => {}
+            Line::Text(raw) | Line::Section(raw) | Line::Include { raw, .. } => {
+                writeln!(f, "{}", raw)?
+            }
+            Line::Invalid(i) => writeln!(f, "{}", i.text)?,
+            Line::Entry {
+                index, overridden, ..
+            } => {
+                let entry = match data.get(*index) {
+                    Some(i) => i,
+                    None => continue,
+                };
+
+                let unchanged = i.loaded() == Some(&entry.value);
+                if let (true, Some(from), true) = (annotate, overridden, unchanged) {
+                    let value = encoding::quote_value(&entry.value);
+                    writeln!(
+                        f,
+                        "{} {} = {} (from {})",
+                        OVERRIDE_NOTE, entry.key, value, from
+                    )?;
+                }
+                writeln!(f, "{}", entry_line(i, entry))?;
+            }
+        }
+    }
+
+    let mut section = None;
+    for i in missing(true) {
+        if section != i.section.as_ref() {
+            section = i.section.as_ref();
+            writeln!(f, "[{}]", section.unwrap())?;
+        }
+        writeln!(f, "{} = {}", i.key, encoding::quote_value(&i.value))?;
+    }
+
+    Ok(())
+}
+
+/// Get the text of a key value line with the current value of its entry
+fn entry_line(line: &Line, entry: &Entry) -> String {
+    let (text, value_at) = match line {
+        Line::Entry { text, value_at, .. } => (text, value_at),
+        _ => unreachable!(),
+    };
+    if line.loaded() == Some(&entry.value) {
+        return text.to_owned();
+    }
+
+    let (prefix, suffix) = (&text[..value_at.start], &text[value_at.end..]);
+    match value_at.is_empty() {
+        // Flags (lines with just a key) need a separator for the new value
+        true if !prefix.trim_end().ends_with(['=', ':']) => format!(
+            "{} = {}{}",
+            prefix,
+            encoding::quote_value(&entry.value),
+            suffix
+        ),
+        _ => format!(
+            "{}{}{}",
+            prefix,
+            encoding::quote_value(&entry.value),
+            suffix
+        ),
+    }
+}
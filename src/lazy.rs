@@ -0,0 +1,83 @@
+//! Configs that are only loaded when they are first used
+use std::fmt;
+#[cfg(feature = "file")]
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use crate::{Config, ConfigError};
+
+/// How a `LazyConfig` is loaded
+type Loader = Box<dyn Fn() -> Result<Config, ConfigError> + Send + Sync>;
+
+/// A config that is loaded the first time it's used, made with `LazyConfig::new`
+///
+/// It can be shared between threads, the config is only loaded once even if they use it at the same time.
+/// Useful for the configs of optional parts of a program, which may never be needed.
+pub struct LazyConfig {
+    load: Loader,
+    config: OnceLock<Result<Config, ConfigError>>,
+}
+
+impl LazyConfig {
+    /// Create a config that loads a file (with `Config::file`) when it's first used
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::LazyConfig;
+    ///
+    /// let cfg = LazyConfig::new("config.cfg");
+    /// assert!(!cfg.is_loaded());
+    ///
+    /// assert_eq!(cfg.force().unwrap().get_str("hello").unwrap(), "World");
+    /// assert!(cfg.is_loaded());
+    /// ```
+    #[cfg(feature = "file")]
+    pub fn new<T>(file: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        let file = file.into();
+        LazyConfig::with(move || Config::new().file(&file))
+    }
+
+    /// Create a config that is loaded by a function when it's first used
+    ///
+    /// For configs with options or more than one source.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, LazyConfig, UnsetEnv};
+    ///
+    /// let cfg = LazyConfig::with(|| Config::new().expand_env(UnsetEnv::Keep).text("hello = World"));
+    /// assert_eq!(cfg.force().unwrap().get_str("hello").unwrap(), "World");
+    /// ```
+    pub fn with<F>(load: F) -> Self
+    where
+        F: Fn() -> Result<Config, ConfigError> + Send + Sync + 'static,
+    {
+        LazyConfig {
+            load: Box::new(load),
+            config: OnceLock::new(),
+        }
+    }
+
+    /// Get the config, loading it if this is the first use
+    ///
+    /// If loading failed the error is returned, every time (it's not loaded again).
+    pub fn force(&self) -> Result<&Config, &ConfigError> {
+        self.config.get_or_init(|| (self.load)()).as_ref()
+    }
+
+    /// Check if the config has been loaded (or failed to), without loading it
+    pub fn is_loaded(&self) -> bool {
+        self.config.get().is_some()
+    }
+}
+
+impl fmt::Debug for LazyConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyConfig")
+            .field("loaded", &self.is_loaded())
+            .finish()
+    }
+}
@@ -12,17 +12,57 @@ Just add the following to your `Cargo.toml`:
 simple_config_parser = "1.0.0"
 ```
 
+Loading config files (the `file` feature) is on by default.
+To only parse config text, with nothing that reads the filesystem, turn off the default features:
+```toml
+[dependencies]
+simple_config_parser = { version = "1.0.0", default-features = false }
+```
+
 ## 📀 Quick Start
 
-This config parser is made for use with a simplified version of an ini file. There are no sections and currently no Escape character support.
+This config parser is made for use with a simplified version of an ini file. Values can be double quoted (`key = "a # b"`) to keep comment chars and surrounding spaces, with `\"`, `\\`, `\n`, `\r` and `\t` escapes.
+Values that span multiple lines go between triple quotes (`"""`), the text between them is used as is.
+With the `file` feature other files can be pulled in with `!include other.cfg` (relative to the including file),
+and a file can extend a base file with `extends = base.cfg` (before any section), the base is loaded first so its keys can be overridden.
+A key can be overridden by an environment variable named in its comment, like `port = 8080 ; env:PORT`.
+The crate needs `std` and does not support `no_std` targets, as `Config` and `ConfigError` are built on `std` types (locks, channels, `io::Error` and paths).
+Without the `file` feature nothing reads the filesystem. `ConfigRef::parse` reads config text without copying it, but it needs `std` like the rest of the crate (it returns `ConfigError`).
 ```ini
 ; This is a comment
 # This is also a comment
 hello = World
 rust = Is great
 test = "TEST"
+
+[database]
+host = localhost
 ```
 
+## 🧩 Features
+
+Parsing and reading values has no dependencies. Everything that needs one, or that reads and writes files, is behind a cargo feature, only `file` is on by default:
+- `file`: `Config::file`, `Config::save` and the rest of loading and saving config files
+- `reload`: `Config::reload` / `Config::reload_text` for reloading a config in place, and `SharedConfig::watch`
+- `json` / `toml`: `Config::json` / `Format::Toml` for loading them, and `Config::to_json` / `Config::to_toml`
+- `env-file`: `Config::env_file` for loading `.env` files
+- `derive`: `#[derive(FromConfig)]` for loading structs and `#[derive(ConfigEnum)]` for enums
+- `glob`: `Config::get_glob` for file pattern values
+- `regex`: `SchemaKey::pattern` for checking values against regexes
+- `gzip` / `zstd`: loading compressed config files
+- `archive`: `Config::from_archive` for configs in tar / zip files
+- `crossbeam` / `tokio`: sending change events to their channels
+- `async`: `Config::file_async` for loading files with `tokio::fs`
+- `log`: `Config::set_log_level` / `Config::log_settings` for reading the `log.level`, `log.file` and `log.format` keys to set up a logger (like `env_logger` or `tracing-subscriber`) with, and logging which source each value is loaded from
+- `url`: `Config::get_url` for URL values
+- `parallel`: reading and parsing the files of `Config::files` on their own threads
+- `yaml`: `Config::yaml` for loading simple YAML mappings (like Kubernetes ConfigMaps)
+- `ffi`: C bindings (see the `ffi` module and `include/simple_config_parser.h`) and `Config::get_c_str`. There are no PyO3 / napi-rs bindings, scripts use the C bindings through their FFI (like Python's `ctypes`)
+- `diagnostics`: `diagnose` for showing errors with the lines they are on
+- `testing`: helpers for testing code that uses configs
+
+`reload`, `env-file`, `gzip`, `zstd`, `archive`, `async`, `parallel`, `ffi` and `testing` also turn on `file`, and `yaml` turns on `json`.
+
 ## 🐳 Why
 
 There are already a few config parsers out there for rust so why use this one?
@@ -45,7 +85,8 @@ let cfg = Config::new()
     .text("hello = world")
     .unwrap();
 
-// Create a new config from a file
+// Create a new config from a file (with the `file` feature)
+# #[cfg(feature = "file")]
 let cfg2 = Config::new()
     .file("config.cfg")
     .unwrap();
@@ -65,7 +106,22 @@ let cfg = Config::new()
 println!("Hello, {}", cfg.get_str("hello").unwrap());
 ```
 
-Get value from a config as any type that implements FromStr.
+Get a value from a section.
+```rust
+// Import Lib
+use simple_config_parser::Config;
+
+// Create a new config with a section
+let cfg = Config::new()
+    .text("[database]\nhost = localhost\nport = 5432")
+    .unwrap();
+
+// Get a value from the section
+let db = cfg.section("database").unwrap();
+assert_eq!(db.get::<u16>("port").unwrap(), 5432);
+```
+
+Get value from a config as any type that implements FromStr (or `FromConfigValue` for other conversions).
 ```rust
 // Import Lib
 use simple_config_parser::Config;
@@ -88,5 +144,160 @@ assert_eq!(cfg.get::<f32>("pi").unwrap(), 3.1415926535);
 
 #![warn(missing_docs)]
 
+mod access;
+mod anchor;
+#[cfg(feature = "archive")]
+mod archive;
+mod args;
+#[cfg(feature = "file")]
+mod atomic;
+#[cfg(feature = "file")]
+mod backup;
+mod borrowed;
+mod builder;
+mod bundle;
+mod cache;
+mod catalog;
+mod check;
+mod comment;
+#[cfg(feature = "file")]
+mod compress;
 mod config;
-pub use config::{Config, ConfigError};
+mod constrain;
+mod convert;
+mod deprecation;
+#[cfg(feature = "diagnostics")]
+mod diagnostics;
+mod diff;
+mod dirty;
+#[cfg(feature = "file")]
+mod discover;
+mod duplicate;
+mod encoding;
+mod entry_map;
+mod enums;
+#[cfg(feature = "env-file")]
+mod env_file;
+mod events;
+mod expiry;
+mod export;
+#[cfg(feature = "file")]
+mod extends;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "file")]
+mod file;
+#[cfg(feature = "async")]
+mod file_async;
+#[cfg(feature = "file")]
+mod files;
+mod fingerprint;
+mod format;
+mod from_config;
+#[cfg(feature = "glob")]
+mod glob;
+mod graph;
+mod guard;
+mod instrument;
+mod interpolate;
+#[cfg(feature = "json")]
+mod json;
+mod layout;
+mod lazy;
+#[cfg(feature = "log")]
+mod logging;
+mod merge;
+mod namespace;
+mod net;
+mod numbers;
+mod objects;
+mod occurrence;
+mod paths;
+mod pin;
+mod processor;
+mod profile;
+mod query;
+mod recover;
+#[cfg(feature = "reload")]
+mod reload;
+mod report;
+mod required;
+mod resolve;
+mod sandbox;
+mod schema;
+mod schema_diff;
+mod secret;
+mod section;
+mod shared;
+mod stale;
+mod subset;
+mod subtree;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "toml")]
+mod toml;
+mod transform;
+mod units;
+mod unknown;
+#[cfg(feature = "url")]
+mod url;
+mod utf8;
+mod value;
+#[cfg(feature = "reload")]
+mod watch;
+#[cfg(feature = "yaml")]
+mod yaml;
+pub use borrowed::{ConfigRef, EntryRef};
+pub use builder::ConfigBuilder;
+pub use cache::{CacheStats, ConfigCache};
+pub use catalog::Catalog;
+#[cfg(feature = "file")]
+pub use check::check;
+pub use check::{CheckReport, Issue, Severity};
+pub use config::{Config, ConfigError, Entry};
+pub use convert::FromConfigValue;
+pub use deprecation::Deprecation;
+#[cfg(feature = "diagnostics")]
+pub use diagnostics::{diagnose, Diagnostic, Diagnostics};
+pub use diff::Diff;
+pub use duplicate::DuplicatePolicy;
+pub use entry_map::EntryMap;
+pub use enums::ConfigEnum;
+pub use events::{Change, ChangeEvent, ChangeSink, Changes};
+pub use expiry::Expired;
+pub use format::Format;
+#[doc(hidden)]
+pub use from_config::__private;
+pub use from_config::{FieldError, FromConfig, FromConfigError};
+#[cfg(feature = "glob")]
+pub use globset::GlobMatcher;
+pub use graph::DependencyGraph;
+pub use guard::SaveGuard;
+pub use interpolate::UnsetEnv;
+pub use layout::RemovePolicy;
+pub use lazy::LazyConfig;
+#[cfg(feature = "log")]
+pub use logging::{LogFormat, LogSettings};
+pub use merge::{Conflict, MergeStrategy};
+pub use namespace::Namespace;
+pub use occurrence::Occurrence;
+pub use pin::PinViolation;
+pub use processor::Processor;
+pub use recover::LineError;
+pub use report::{LoadReport, SourceReport};
+pub use resolve::Resolver;
+pub use sandbox::Sandbox;
+pub use schema::{KeyError, Schema, SchemaError, SchemaKey, ValueType};
+pub use schema_diff::{SchemaChange, SchemaDiff};
+pub use section::Section;
+pub use shared::SharedConfig;
+#[cfg(feature = "derive")]
+pub use simple_config_parser_derive::{ConfigEnum, FromConfig};
+pub use stale::{StalePolicy, Staleness};
+pub use subtree::Subtree;
+pub use unknown::UnknownKey;
+#[cfg(feature = "url")]
+pub use url::Url;
+pub use value::Value;
+#[cfg(feature = "reload")]
+pub use watch::Watcher;
@@ -0,0 +1,134 @@
+//! Setting up logging from a config
+//!
+//! Log lines are written by the logger the application uses (like `env_logger` or `tracing-subscriber`),
+//! this only reads the settings for it and sets the level of the `log` crate.
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::PathBuf;
+
+use log::LevelFilter;
+
+use crate::{Config, ConfigError};
+
+/// How log lines should be written, from `log.format`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// Plain lines, like `[INFO app::db] message`
+    #[default]
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+/// Logging settings read from a config, see `Config::log_settings`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogSettings {
+    /// Most verbose level to log, from `log.level`
+    pub level: LevelFilter,
+    /// File to append logs to, from `log.file` (stderr if `None`)
+    pub file: Option<PathBuf>,
+    /// How lines should be written, from `log.format`
+    pub format: LogFormat,
+}
+
+impl LogSettings {
+    /// Open the log file for appending, to use as the output of the logger
+    ///
+    /// Returns `None` if logs go to stderr.
+    pub fn open_file(&self) -> io::Result<Option<File>> {
+        self.file
+            .as_ref()
+            .map(|i| OpenOptions::new().create(true).append(true).open(i))
+            .transpose()
+    }
+}
+
+impl Config {
+    /// Read the logging settings of the config
+    ///
+    /// Uses these keys, all of which can be left out:
+    /// - `log.level`: `off`, `error`, `warn`, `info` (the default), `debug` or `trace`
+    /// - `log.file`: file to append logs to, they go to stderr if not set
+    /// - `log.format`: `text` (the default) or `json`
+    ///
+    /// Returns `ConfigError::InvalidType` / `ConfigError::InvalidValue` for values that are not valid.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, LogFormat};
+    ///
+    /// let cfg = Config::new()
+    ///     .text("[log]\nlevel = debug\nformat = json")
+    ///     .unwrap();
+    ///
+    /// let log = cfg.log_settings().unwrap();
+    /// assert_eq!(log.level, log::LevelFilter::Debug);
+    /// assert_eq!(log.format, LogFormat::Json);
+    /// assert_eq!(log.file, None);
+    /// ```
+    pub fn log_settings(&self) -> Result<LogSettings, ConfigError> {
+        let level = match self.get::<LevelFilter>("log.level") {
+            Err(ConfigError::NoItem) => LevelFilter::Info,
+            i => i?,
+        };
+
+        let format = match self.get_str("log.format") {
+            Err(ConfigError::NoItem) => LogFormat::Text,
+            Ok(i) if i.eq_ignore_ascii_case("text") => LogFormat::Text,
+            Ok(i) if i.eq_ignore_ascii_case("json") => LogFormat::Json,
+            Ok(_) => {
+                return Err(ConfigError::InvalidValue(
+                    "`log.format` must be `text` or `json`".to_owned(),
+                ))
+            }
+            Err(e) => return Err(e),
+        };
+
+        let file = match self.get_path("log.file") {
+            Err(ConfigError::NoItem) => None,
+            i => Some(i?),
+        };
+
+        Ok(LogSettings {
+            level,
+            file,
+            format,
+        })
+    }
+
+    /// Set the level of the `log` crate from the config, returning the settings to set up the logger with
+    ///
+    /// The logger itself is up to the application, this saves reading and checking the `log` keys
+    /// (see `Config::log_settings`) and passing them on.
+    /// Records above the level are then skipped by the `log` macros, whichever logger is set.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new().text("[log]\nlevel = warn").unwrap();
+    ///
+    /// let log = cfg.set_log_level().unwrap();
+    /// assert_eq!(log::max_level(), log::LevelFilter::Warn);
+    /// ```
+    /// With `env_logger` the rest of the settings are used like this:
+    /// ```rust,ignore
+    /// let settings = cfg.set_log_level()?;
+    /// let mut logger = env_logger::Builder::new();
+    /// logger.filter_level(settings.level);
+    /// if let Some(file) = settings.open_file()? {
+    ///     logger.target(env_logger::Target::Pipe(Box::new(file)));
+    /// }
+    /// logger.init();
+    /// ```
+    pub fn set_log_level(&self) -> Result<LogSettings, ConfigError> {
+        let settings = self.log_settings()?;
+        log::set_max_level(settings.level);
+        log::debug!(
+            target: "simple_config_parser",
+            "Logging at {} from the config",
+            settings.level
+        );
+        Ok(settings)
+    }
+}
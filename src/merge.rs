@@ -0,0 +1,107 @@
+//! Merging one config into another
+use std::time::Instant;
+
+use crate::layout::{self, RemovePolicy};
+use crate::{Config, ConfigError};
+
+/// How `Config::merge` picks the value of a key both configs define
+pub enum MergeStrategy {
+    /// Use the value of the config being merged in
+    OverrideWith,
+    /// Keep the value the config already has
+    KeepExisting,
+    /// Let a function pick the value
+    Resolve(Box<dyn FnMut(&Conflict) -> String>),
+}
+
+/// A key defined by both configs being merged
+#[derive(Debug)]
+pub struct Conflict<'a> {
+    /// The dotted name of the key (`section.key`)
+    pub key: &'a str,
+    /// The value in the config being merged into
+    pub existing: &'a str,
+    /// The value in the config being merged in
+    pub incoming: &'a str,
+}
+
+impl Config {
+    /// Merge the entries of another config into this one
+    ///
+    /// Keys only in `other` are added, and `strategy` picks the value of keys both define.
+    /// Saving the merged config writes the lines of `other` after this config's, like loading another source.
+    /// Returns `ConfigError::Pinned` (merging nothing) if a pinned key would be changed.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, MergeStrategy};
+    ///
+    /// let system = Config::new().text("theme = dark\nfont = mono").unwrap();
+    /// let user = Config::new().text("theme = light\nsize = 12").unwrap();
+    ///
+    /// let cfg = system.merge(user, MergeStrategy::KeepExisting).unwrap();
+    /// assert_eq!(cfg.get_str("theme").unwrap(), "dark");
+    /// assert_eq!(cfg.get::<u8>("size").unwrap(), 12);
+    ///
+    /// let resolve = MergeStrategy::Resolve(Box::new(|i| format!("{}+{}", i.existing, i.incoming)));
+    /// let cfg = Config::new()
+    ///     .text("path = /usr/bin")
+    ///     .unwrap()
+    ///     .merge(Config::new().text("path = ~/bin").unwrap(), resolve)
+    ///     .unwrap();
+    /// assert_eq!(cfg.get_str("path").unwrap(), "/usr/bin+~/bin");
+    /// ```
+    pub fn merge(mut self, other: Config, strategy: MergeStrategy) -> Result<Self, ConfigError> {
+        let start = Instant::now();
+        let mut strategy = strategy;
+        let (mut data, mut layout, origins) = (other.data.into_vec(), other.layout, other.origins);
+
+        let mut removed = Vec::with_capacity(data.len());
+        for i in data.iter_mut() {
+            let existing = self
+                .data
+                .iter()
+                .rev()
+                .find(|j| j.section == i.section && j.key == i.key);
+
+            let existing = match existing {
+                Some(j) => j.value.to_owned(),
+                None => {
+                    removed.push(false);
+                    continue;
+                }
+            };
+
+            removed.push(matches!(strategy, MergeStrategy::KeepExisting));
+            if let MergeStrategy::Resolve(resolve) = &mut strategy {
+                i.value = resolve(&Conflict {
+                    key: &i.name(),
+                    existing: &existing,
+                    incoming: &i.value,
+                });
+            }
+        }
+
+        layout::remove(&mut layout, &data, &removed, RemovePolicy::Delete);
+        let mut origins = origins.into_iter();
+        let origins = removed
+            .iter()
+            .map(|_| origins.next())
+            .zip(removed.iter())
+            .filter(|(_, removed)| !**removed)
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        let mut removed = removed.into_iter();
+        data.retain(|_| !removed.next().unwrap_or(false));
+
+        let offset = self.data.len();
+        self.add_source("<merge>".to_owned(), start, (data, layout))?;
+        for (n, i) in origins.into_iter().enumerate() {
+            if let (Some(i), Some(origin)) = (i, self.origins.get_mut(offset + n)) {
+                *origin = i;
+            }
+        }
+
+        Ok(self)
+    }
+}
@@ -0,0 +1,113 @@
+//! Config namespaces registered by libraries
+//!
+//! A library can register a namespace (a section of the host application's config) with its own defaults and schema,
+//! then read its values through `Config::namespace`, so many components can share one config file.
+use crate::config::{normalize_key, normalize_section};
+use crate::{Config, Entry, Schema, SchemaError, SchemaKey, Section};
+
+/// A namespace for the config of a library
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use simple_config_parser::{Config, Namespace, SchemaKey, ValueType};
+///
+/// // In the library
+/// const SCHEMA: &[SchemaKey] = &[SchemaKey::required("timeout", ValueType::Int)];
+///
+/// fn register(cfg: &mut Config) {
+///     let ns = Namespace::new("http_client")
+///         .default("timeout", "30")
+///         .schema(SCHEMA);
+///     cfg.register(ns).unwrap();
+/// }
+///
+/// // In the application
+/// let mut cfg = Config::new().text("[http_client]\nretries = 3").unwrap();
+/// register(&mut cfg);
+///
+/// let http = cfg.namespace("http_client");
+/// assert_eq!(http.get::<u32>("timeout").unwrap(), 30);
+/// assert_eq!(http.get::<u32>("retries").unwrap(), 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Namespace {
+    /// Name of the namespace (the section it is in)
+    name: &'static str,
+    /// Default values of keys
+    defaults: Vec<(String, String)>,
+    /// Keys of the namespace's schema
+    schema: &'static [SchemaKey],
+}
+
+impl Namespace {
+    /// Make a namespace for a section, with no defaults or schema
+    pub fn new(name: &'static str) -> Self {
+        Namespace {
+            name,
+            defaults: Vec::new(),
+            schema: &[],
+        }
+    }
+
+    /// Add a default value for a key, used if the config does not define it
+    pub fn default(mut self, key: &str, value: &str) -> Self {
+        self.defaults.push((normalize_key(key), value.to_owned()));
+        self
+    }
+
+    /// Set the keys the namespace should have
+    pub fn schema(self, keys: &'static [SchemaKey]) -> Self {
+        Self {
+            schema: keys,
+            ..self
+        }
+    }
+}
+
+impl Config {
+    /// Register a namespace
+    ///
+    /// Its defaults are used for reads (like `Config::with_defaults`, they are not saved)
+    /// and the namespace's section is checked against its schema.
+    /// The schema is remembered and checked again by `Config::validate_namespaces`.
+    pub fn register(&mut self, namespace: Namespace) -> Result<(), SchemaError> {
+        let section = normalize_section(namespace.name);
+        self.registered
+            .extend(namespace.defaults.into_iter().map(|(key, value)| Entry {
+                section: Some(section.to_owned()),
+                key,
+                value,
+            }));
+
+        let schema = Schema::section(namespace.name, namespace.schema);
+        let result = self.validate(&schema);
+        self.namespaces.push(schema);
+        result
+    }
+
+    /// Check every registered namespace against its schema
+    pub fn validate_namespaces(&self) -> Result<(), SchemaError> {
+        let mut keys = Vec::new();
+        for i in self.namespaces.iter() {
+            if let Err(e) = self.validate(i) {
+                keys.extend(e.keys);
+            }
+        }
+
+        match keys.is_empty() {
+            true => Ok(()),
+            false => Err(SchemaError {
+                keys,
+                unknown: Vec::new(),
+            }),
+        }
+    }
+
+    /// Get a view of a namespace's section
+    ///
+    /// Unlike `Config::section` this works even if the section is not in the config,
+    /// so namespaces with defaults can always be read.
+    pub fn namespace(&self, name: &str) -> Section<'_> {
+        Section::new(self, normalize_section(name))
+    }
+}
@@ -0,0 +1,70 @@
+//! Reading values as IP and socket addresses
+//!
+//! Addresses are parsed as they are written, hostnames are never looked up.
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use crate::config::Entry;
+use crate::{Config, ConfigError};
+
+impl Config {
+    /// Get a value from config as an IP address (v4 or v6)
+    ///
+    /// Returns `ConfigError::InvalidType` naming the key if the value is not an IP address.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    /// use std::net::{IpAddr, Ipv6Addr};
+    ///
+    /// let cfg = Config::new().text("bind = 127.0.0.1\nbind6 = ::1").unwrap();
+    ///
+    /// assert_eq!(cfg.get_ip("bind").unwrap(), IpAddr::from([127, 0, 0, 1]));
+    /// assert_eq!(cfg.get_ip("bind6").unwrap(), IpAddr::V6(Ipv6Addr::LOCALHOST));
+    /// ```
+    pub fn get_ip(&self, key: &str) -> Result<IpAddr, ConfigError> {
+        self.located(self.entry(None, key), |i| {
+            let entry = i?;
+            entry
+                .value
+                .parse()
+                .map_err(|_| entry.invalid_type("IP address"))
+        })
+    }
+
+    /// Get a value from config as a socket address, like `10.0.0.1:8080` or `[::1]:8080`
+    ///
+    /// The host has to be an IP address, no DNS lookups are done.
+    /// A value with only a port (`:8080`) listens on every interface (`0.0.0.0`).
+    /// Returns `ConfigError::InvalidType` naming the key if the value is not a socket address.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    /// use std::net::SocketAddr;
+    ///
+    /// let cfg = Config::new()
+    ///     .text("listen = :8080\nupstream = [::1]:9000\ndb = db.local:5432")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get_socket_addr("listen").unwrap(), SocketAddr::from(([0, 0, 0, 0], 8080)));
+    /// assert_eq!(cfg.get_socket_addr("upstream").unwrap().port(), 9000);
+    /// assert!(cfg.get_socket_addr("db").is_err());
+    /// ```
+    pub fn get_socket_addr(&self, key: &str) -> Result<SocketAddr, ConfigError> {
+        self.located(self.entry(None, key), socket_addr_value)
+    }
+}
+
+/// Parse an entry's value into a socket address, with an empty host being every interface
+fn socket_addr_value(entry: Result<&Entry, ConfigError>) -> Result<SocketAddr, ConfigError> {
+    let entry = entry?;
+    let addr = match entry.value.strip_prefix(':') {
+        Some(port) => port
+            .parse()
+            .ok()
+            .map(|port| SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), port)),
+        None => entry.value.parse().ok(),
+    };
+
+    addr.ok_or_else(|| entry.invalid_type("socket address"))
+}
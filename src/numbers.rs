@@ -0,0 +1,133 @@
+//! Other forms of numbers accepted by the typed getters, like `1_000_000` or `0xFF`
+//!
+//! Values are first read as they are, only values that fail are rewritten (if they are a number in one of the enabled forms)
+//! and read again, so strings and other types are never changed.
+use crate::Config;
+
+/// Forms of numbers that are accepted, see `Config::number_underscores`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Numbers {
+    /// `_` between digits (`1_000_000`)
+    underscores: bool,
+    /// `0x`, `0o` and `0b` integer prefixes
+    prefixes: bool,
+    /// `,` as the decimal separator (`3,14`)
+    decimal_comma: bool,
+}
+
+impl Numbers {
+    /// Rewrite a number in one of the enabled forms as a plain one, `None` if it's not one
+    pub(crate) fn normalize(&self, value: &str) -> Option<String> {
+        if *self == Numbers::default() {
+            return None;
+        }
+
+        let (sign, digits) = match value.strip_prefix(['-', '+']) {
+            Some(rest) => (&value[..1], rest),
+            None => ("", value),
+        };
+
+        let radix = match digits.get(..2) {
+            Some("0x" | "0X") => 16,
+            Some("0o" | "0O") => 8,
+            Some("0b" | "0B") => 2,
+            _ => 10,
+        };
+        if radix != 10 {
+            if !self.prefixes {
+                return None;
+            }
+
+            let digits = self.digits(&digits[2..], radix)?;
+            let number = u128::from_str_radix(&digits, radix).ok()?;
+            return Some(format!("{}{}", sign, number));
+        }
+
+        let digits = self.digits(digits, 10)?;
+        let out = match (self.decimal_comma, digits.matches(',').count()) {
+            (true, 1) if !digits.contains('.') => digits.replacen(',', ".", 1),
+            (_, 0) => digits,
+            _ => return None,
+        };
+
+        let out = format!("{}{}", sign, out);
+        (out != value).then_some(out)
+    }
+
+    /// Remove the underscores between digits (if they are allowed), `None` if one is anywhere else
+    fn digits(&self, text: &str, radix: u32) -> Option<String> {
+        let bytes = text.as_bytes();
+        let digit = |i: Option<&u8>| i.is_some_and(|i| (*i as char).is_digit(radix));
+        for (n, _) in text.match_indices('_') {
+            if !self.underscores || !digit(bytes.get(n.wrapping_sub(1))) || !digit(bytes.get(n + 1))
+            {
+                return None;
+            }
+        }
+
+        Some(text.replace('_', ""))
+    }
+}
+
+impl Config {
+    /// Allow `_` between the digits of numbers read with the typed getters, like `1_000_000`
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .number_underscores(true)
+    ///     .text("max_size = 1_000_000\nratio = 0.000_5")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get::<u32>("max_size").unwrap(), 1_000_000);
+    /// assert_eq!(cfg.get::<f64>("ratio").unwrap(), 0.0005);
+    /// ```
+    pub fn number_underscores(mut self, underscores: bool) -> Self {
+        self.numbers.underscores = underscores;
+        self
+    }
+
+    /// Allow integers read with the typed getters to be hex (`0xFF`), octal (`0o755`) or binary (`0b1010`)
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .number_prefixes(true)
+    ///     .text("color = 0xFF8800\nmode = 0o755\nflags = 0b1010")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get::<u32>("color").unwrap(), 0xFF8800);
+    /// assert_eq!(cfg.get::<u32>("mode").unwrap(), 0o755);
+    /// assert_eq!(cfg.get::<u8>("flags").unwrap(), 10);
+    /// ```
+    pub fn number_prefixes(mut self, prefixes: bool) -> Self {
+        self.numbers.prefixes = prefixes;
+        self
+    }
+
+    /// Allow `,` as the decimal separator of numbers read with the typed getters, like `3,14`
+    ///
+    /// Items of lists are split before they are read, so use another `Config::list_delimiter` for lists of these numbers.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .decimal_comma(true)
+    ///     .list_delimiter('|')
+    ///     .text("price = 4,99\nweights = 0,5 | 1,25")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get::<f32>("price").unwrap(), 4.99);
+    /// assert_eq!(cfg.get_vec::<f32>("weights").unwrap(), [0.5, 1.25]);
+    /// ```
+    pub fn decimal_comma(mut self, comma: bool) -> Self {
+        self.numbers.decimal_comma = comma;
+        self
+    }
+}
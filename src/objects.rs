@@ -0,0 +1,148 @@
+//! Reading lists of objects, like a config for each plugin
+use std::collections::BTreeMap;
+
+use crate::config::Entry;
+use crate::layout::Line;
+use crate::{Config, ConfigError, FromConfigValue};
+
+impl Config {
+    /// Get a list of objects, each as its own config
+    ///
+    /// Objects are indexed keys under the prefix (`plugin.0.name` or `plugin[0].name`, `plugin.1.name`... or `[plugin.0]` sections),
+    /// in the order of their index.
+    /// If there are none, each `[plugin]` section header starts a new object instead.
+    /// The keys of an object have the prefix (and index) removed, so `plugin.0.name` is `name`.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .text("[job]\nname = backup\nevery = 1d\n[job]\nname = cleanup\nevery = 1h")
+    ///     .unwrap();
+    ///
+    /// let jobs = cfg.objects("job");
+    /// assert_eq!(jobs.len(), 2);
+    /// assert_eq!(jobs[1].get_str("name").unwrap(), "cleanup");
+    /// assert_eq!(jobs[1].get_duration("every").unwrap().as_secs(), 3600);
+    /// ```
+    pub fn objects(&self, prefix: &str) -> Vec<Config> {
+        let prefix = self.key_style.key(prefix);
+        let prefix = prefix.trim_matches('.');
+
+        let mut indexed = BTreeMap::<usize, Vec<Entry>>::new();
+        for i in self.data.iter() {
+            let name = i.name();
+            let rest = match name.strip_prefix(prefix).and_then(|i| i.strip_prefix('.')) {
+                Some(i) => i,
+                None => continue,
+            };
+
+            if let Some((index, key)) = rest.split_once('.') {
+                if let Ok(index) = index.parse() {
+                    indexed
+                        .entry(index)
+                        .or_default()
+                        .push(self.entry_as(key, i));
+                }
+            }
+        }
+
+        if !indexed.is_empty() {
+            return indexed.into_values().map(|i| self.object(i)).collect();
+        }
+
+        // Each header of the section starts an object
+        let section = self.key_style.section(prefix);
+        let mut objects = Vec::new();
+        let mut current = None;
+        for i in self.layout.iter() {
+            match i {
+                Line::Section(raw) => {
+                    let name = self.comments.remove(raw.trim()).trim_end();
+                    let name = name.strip_prefix('[').and_then(|i| i.strip_suffix(']'));
+                    if let Some(i) = current.take() {
+                        objects.push(i);
+                    }
+                    if name.map(|i| self.key_style.section(i)).as_ref() == Some(&section) {
+                        current = Some(Vec::new());
+                    }
+                }
+                Line::Entry { index, .. } => {
+                    if let (Some(object), Some(entry)) = (&mut current, self.data.get(*index)) {
+                        object.push(self.entry_as(&entry.key, entry));
+                    }
+                }
+                _ => {}
+            }
+        }
+        objects.extend(current);
+
+        objects.into_iter().map(|i| self.object(i)).collect()
+    }
+
+    /// Get a value of an item of an indexed list, converted into any type that impls FromConfigValue
+    ///
+    /// Reads `prefix.index.key`, which can also be written `prefix[index].key`.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .text("servers[0].host = a\nservers[0].port = 80\nservers[1].host = b")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.index_len("servers"), 2);
+    /// assert_eq!(cfg.get_indexed::<String>("servers", 1, "host").unwrap(), "b");
+    /// assert_eq!(cfg.get_indexed::<u16>("servers", 0, "port").unwrap(), 80);
+    /// assert_eq!(cfg.get_str("servers[1].host").unwrap(), "b");
+    /// ```
+    pub fn get_indexed<T>(&self, prefix: &str, index: usize, key: &str) -> Result<T, ConfigError>
+    where
+        T: FromConfigValue,
+    {
+        self.get(&format!("{}.{}.{}", prefix.trim_matches('.'), index, key))
+    }
+
+    /// Get the length of an indexed list, one more than the largest index under `prefix`
+    ///
+    /// Returns 0 if there are no indexed keys under the prefix.
+    pub fn index_len(&self, prefix: &str) -> usize {
+        let prefix = self.key_style.key(prefix);
+        let prefix = prefix.trim_matches('.');
+
+        self.entries()
+            .filter_map(|i| {
+                let name = i.name();
+                let rest = name.strip_prefix(prefix)?.strip_prefix('.')?;
+                rest.split('.').next()?.parse::<usize>().ok()
+            })
+            .max()
+            .map_or(0, |i| i + 1)
+    }
+
+    /// Copy an entry to be in an object under a new key
+    fn entry_as(&self, key: &str, entry: &Entry) -> Entry {
+        Entry {
+            section: None,
+            key: key.to_owned(),
+            value: entry.value.to_owned(),
+        }
+    }
+
+    /// Create the config of an object, with the same options as this one
+    fn object(&self, data: Vec<Entry>) -> Config {
+        let mut out = Config::new();
+        out.key_style = self.key_style;
+        out.list_delimiter = self.list_delimiter;
+        out.numbers = self.numbers;
+        out.layout = data
+            .iter()
+            .enumerate()
+            .map(|(n, i)| Line::new_entry(i, n))
+            .collect();
+        out.data = data.into();
+        out
+    }
+}
@@ -0,0 +1,264 @@
+//! Where each definition of a key came from
+//!
+//! Lookups use the last definition of a key, but every definition is kept
+//! so diagnostics can point users at all the places a key was set.
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use crate::config::Entry;
+use crate::expiry;
+use crate::layout::Line;
+use crate::{Config, ConfigError};
+
+/// One definition of a key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Occurrence {
+    /// The source it was loaded from (a file path, `<text>` or `<set>` for values added with `Config::set`)
+    pub source: String,
+    /// The line in the source it was defined on (starting at 1)
+    /// `None` if the source has no lines, like values added with `Config::set`.
+    pub line: Option<usize>,
+    /// The section it was defined in
+    pub section: Option<String>,
+    /// The value it was set to
+    pub value: String,
+    /// When it was loaded or last changed with `Config::set`
+    /// Only recorded with `Config::timestamps`.
+    pub changed: Option<SystemTime>,
+}
+
+/// Where an entry of the config data came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Origin {
+    /// The source (as in `SourceReport::source`), shared by the entries of a source
+    pub(crate) source: Arc<str>,
+    /// The line in the source, starting at 1
+    pub(crate) line: Option<usize>,
+    /// When the entry was loaded or changed
+    pub(crate) changed: Option<SystemTime>,
+    /// When the entry expires, see `Config::expiry`
+    pub(crate) expires: Option<SystemTime>,
+}
+
+impl Origin {
+    /// Create an origin with no time
+    pub(crate) fn new(source: &str, line: Option<usize>) -> Self {
+        Origin {
+            source: Arc::from(source),
+            line,
+            changed: None,
+            expires: None,
+        }
+    }
+}
+
+/// Get the origin of each of the `len` entries of a source from its layout
+///
+/// The origins of included entries are taken from their `!include` lines.
+pub(crate) fn origins(layout: &mut [Line], source: Arc<str>, len: usize) -> Vec<Origin> {
+    let origin = Origin {
+        source,
+        line: None,
+        changed: None,
+        expires: None,
+    };
+    let mut out = vec![origin; len];
+
+    let mut line = 1;
+    for i in layout.iter_mut() {
+        let text = match i {
+            Line::Text(raw) | Line::Section(raw) => raw.as_str(),
+            Line::Invalid(i) => i.text.as_str(),
+            Line::Entry {
+                text,
+                value_at,
+                index,
+                ..
+            } => {
+                if let Some(i) = out.get_mut(*index) {
+                    i.line = Some(line);
+                    i.expires = expiry::until(&text[value_at.end..]);
+                }
+                // Only multi-line values span lines
+                text.as_str()
+            }
+            Line::Include {
+                raw,
+                entries,
+                origins,
+                ..
+            } => {
+                for (i, origin) in entries.clone().zip(origins.drain(..)) {
+                    if let Some(i) = out.get_mut(i) {
+                        *i = origin;
+                    }
+                }
+                raw.as_str()
+            }
+        };
+        line += lines(text) + 1;
+    }
+
+    out
+}
+
+/// Count the line breaks in some text
+fn lines(text: &str) -> usize {
+    text.bytes().filter(|i| *i == b'\n').count()
+}
+
+impl Config {
+    /// Get every definition of a key, in the order they were loaded
+    ///
+    /// The last one is the value lookups use.
+    /// Like `get`, a key defined in any section (or a dotted `section.key` name) is included.
+    /// Defaults from `Config::with_defaults` and registered namespaces are not.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .text("port = 80\n# Again\nport = 8080")
+    ///     .unwrap()
+    ///     .text("[server]\nport = 9000")
+    ///     .unwrap();
+    ///
+    /// let found = cfg.occurrences("port");
+    /// assert_eq!(found.len(), 3);
+    /// assert_eq!(found[1].line, Some(3));
+    /// assert_eq!(found[2].section.as_deref(), Some("server"));
+    /// assert_eq!(found[2].value, cfg.get_str("port").unwrap());
+    /// ```
+    pub fn occurrences(&self, key: &str) -> Vec<Occurrence> {
+        let key = self.key_style.key(key);
+        self.data
+            .indexes(&key)
+            .map(|n| self.occurrence(n, &self.data[n]))
+            .collect()
+    }
+
+    /// Get the occurrence of the entry at `n` in the data
+    fn occurrence(&self, n: usize, entry: &Entry) -> Occurrence {
+        let origin = self.origins.get(n);
+        Occurrence {
+            source: origin.map_or("<set>".to_owned(), |i| i.source.to_string()),
+            line: origin.and_then(|i| i.line),
+            section: entry.section.to_owned(),
+            value: entry.value.to_owned(),
+            changed: origin.and_then(|i| i.changed),
+        }
+    }
+
+    /// Find where an entry of this config (or its defaults) came from
+    ///
+    /// Entries from registered namespaces have the source `<default>`.
+    pub(crate) fn occurrence_of(&self, entry: &Entry) -> Occurrence {
+        if let Some(n) = self.data.iter().position(|i| std::ptr::eq(i, entry)) {
+            return self.occurrence(n, entry);
+        }
+
+        match &self.defaults {
+            Some(defaults) => defaults.occurrence_of(entry),
+            None => Occurrence {
+                source: "<default>".to_owned(),
+                line: None,
+                section: entry.section.to_owned(),
+                value: entry.value.to_owned(),
+                changed: None,
+            },
+        }
+    }
+
+    /// Say where values came from in the errors of getters that can't read them
+    ///
+    /// Errors (other than the key not being defined) are then `ConfigError::Value`,
+    /// with the key and the source and line of its value. So it's clear if the file, an env var or an argument is wrong.
+    /// Use `ConfigError::inner` to get the error it wraps.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, ConfigError};
+    ///
+    /// let cfg = Config::new()
+    ///     .error_provenance(true)
+    ///     .text("name = app\nport = eighty")
+    ///     .unwrap();
+    ///
+    /// let err = cfg.get::<u16>("port").unwrap_err();
+    /// assert_eq!(err.to_string(), "`port` from <text> line 2: Value of `port` (`eighty`) can't be read as u16");
+    /// assert!(matches!(err.inner(), ConfigError::InvalidType(..)));
+    /// assert!(matches!(cfg.get::<u16>("host"), Err(ConfigError::NoItem)));
+    /// ```
+    pub fn error_provenance(mut self, provenance: bool) -> Self {
+        self.provenance = provenance;
+        self
+    }
+
+    /// Read an entry with `read`, adding where it came from to the error if `Config::error_provenance` is on
+    pub(crate) fn located<'a, T>(
+        &self,
+        entry: Result<&'a Entry, ConfigError>,
+        read: impl FnOnce(Result<&'a Entry, ConfigError>) -> Result<T, ConfigError>,
+    ) -> Result<T, ConfigError> {
+        let found = entry.as_ref().ok().copied();
+        match (read(entry), found) {
+            (Err(e), Some(entry)) if self.provenance => Err(ConfigError::Value(
+                entry.name(),
+                Box::new(self.occurrence_of(entry)),
+                Box::new(e),
+            )),
+            (i, _) => i,
+        }
+    }
+
+    /// Get where the value in use for a key came from, and when if `Config::timestamps` is on
+    ///
+    /// This is the last of `Config::occurrences`.
+    /// Returns `None` if the key is not defined, or only by defaults.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .timestamps(true)
+    ///     .text("port = 80")
+    ///     .unwrap()
+    ///     .text("\nport = 8080")
+    ///     .unwrap();
+    ///
+    /// let meta = cfg.metadata("port").unwrap();
+    /// assert_eq!((meta.source.as_str(), meta.line), ("<text>", Some(2)));
+    /// assert!(meta.changed.is_some());
+    /// ```
+    pub fn metadata(&self, key: &str) -> Option<Occurrence> {
+        self.occurrences(key).pop()
+    }
+
+    /// Get the file (or other source) and line the value `get` reads for a key was defined on
+    ///
+    /// Unlike `Config::metadata` this finds the value the way getters do, so it follows
+    /// profiles and aliases, skips expired entries and includes defaults (with the source of the defaults config).
+    /// Returns `None` if the key is not defined.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let defaults = Config::new().text("port = 80\nhost = localhost").unwrap();
+    /// let cfg = Config::new()
+    ///     .with_defaults(defaults)
+    ///     .text("\n\nport = 8080")
+    ///     .unwrap();
+    ///
+    /// let source = cfg.source_of("port").unwrap();
+    /// assert_eq!((source.source.as_str(), source.line), ("<text>", Some(3)));
+    /// assert_eq!(cfg.source_of("host").unwrap().line, Some(2));
+    /// assert!(cfg.source_of("missing").is_none());
+    /// ```
+    pub fn source_of(&self, key: &str) -> Option<Occurrence> {
+        let entry = self.entry(None, key).ok()?;
+        Some(self.occurrence_of(entry))
+    }
+}
@@ -0,0 +1,101 @@
+//! Reading values as paths, with `~` expanded and relative paths resolved
+//!
+//! Relative paths are relative to the directory of the file the value was loaded from,
+//! so a config works the same no matter what directory the process is run from.
+use std::env;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::ptr;
+
+use crate::config::Entry;
+use crate::{Config, ConfigError};
+
+impl Config {
+    /// Get a value from config as a path
+    ///
+    /// A leading `~` is expanded to the home directory (`HOME`, or `USERPROFILE` on windows).
+    /// Relative paths are resolved from the directory of the file the value was loaded from.
+    /// Values from text or other sources that are not files are left relative to the current directory.
+    /// Returns `ConfigError::MissingEnv` if the home directory is needed but not set.
+    /// ## Example
+    /// ```rust,no_run
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    /// use std::path::Path;
+    ///
+    /// # #[cfg(feature = "file")] {
+    /// // With `/etc/app/app.cfg` containing `data = data` and `cache = ~/.cache/app`
+    /// let cfg = Config::new().file("/etc/app/app.cfg").unwrap();
+    ///
+    /// assert_eq!(cfg.get_path("data").unwrap(), Path::new("/etc/app/data"));
+    /// assert!(cfg.get_path("cache").unwrap().ends_with(".cache/app"));
+    /// # }
+    /// ```
+    pub fn get_path(&self, key: &str) -> Result<PathBuf, ConfigError> {
+        self.located(self.entry(None, key), |i| self.path_value(i))
+    }
+
+    /// Get a value from config as a path (see `Config::get_path`) that must exist
+    ///
+    /// Returns `ConfigError::Io` with `io::ErrorKind::NotFound` if nothing is at the path.
+    pub fn get_existing_path(&self, key: &str) -> Result<PathBuf, ConfigError> {
+        let path = self.get_path(key)?;
+        if !path.exists() {
+            return Err(ConfigError::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("`{}` does not exist", path.display()),
+            )));
+        }
+
+        Ok(path)
+    }
+
+    /// Resolve an entry's value into a path
+    pub(crate) fn path_value(
+        &self,
+        entry: Result<&Entry, ConfigError>,
+    ) -> Result<PathBuf, ConfigError> {
+        let entry = entry?;
+        let path = expand_home(&entry.value)?;
+        if path.is_absolute() {
+            return Ok(path);
+        }
+
+        Ok(match self.source_dir(entry) {
+            Some(dir) => dir.join(path),
+            None => path,
+        })
+    }
+
+    /// Get the directory of the file an entry was loaded from, if it was from a file
+    fn source_dir(&self, entry: &Entry) -> Option<&Path> {
+        match self.data.iter().position(|i| ptr::eq(i, entry)) {
+            Some(i) => {
+                let source = Path::new(&*self.origins.get(i)?.source);
+                source.is_file().then(|| source.parent()).flatten()
+            }
+            None => self.defaults.as_ref()?.source_dir(entry),
+        }
+    }
+}
+
+/// Expand a leading `~` to the home directory
+fn expand_home(value: &str) -> Result<PathBuf, ConfigError> {
+    let rest = match value.strip_prefix('~') {
+        Some(i) if i.is_empty() || i.starts_with(['/', '\\']) => i.trim_start_matches(['/', '\\']),
+        _ => return Ok(PathBuf::from(value)),
+    };
+
+    let home = home_dir().ok_or_else(|| ConfigError::MissingEnv(HOME.to_owned()))?;
+    Ok(home.join(rest))
+}
+
+/// Variable with the home directory
+const HOME: &str = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+
+/// Get the home directory, if it is set
+pub(crate) fn home_dir() -> Option<PathBuf> {
+    env::var_os(HOME)
+        .filter(|i| !i.is_empty())
+        .map(PathBuf::from)
+}
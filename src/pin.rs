@@ -0,0 +1,141 @@
+//! Pinning the keys of a layer so later layers and `set` can't change them
+//!
+//! Lets operators enforce settings (like a security section loaded from `/etc`)
+//! that application or user config should not be able to override.
+use std::fmt;
+use std::sync::Arc;
+
+use crate::config::Entry;
+use crate::{Config, ConfigError};
+
+/// A key that is pinned, along with the source that pinned it
+#[derive(Debug, Clone)]
+pub(crate) struct Pin {
+    /// The dotted name of the key (`section.key`)
+    pub(crate) key: String,
+    /// The source the key was loaded from, shared by the keys of a source
+    pub(crate) source: Arc<str>,
+}
+
+/// An attempt to change a pinned key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PinViolation {
+    /// The dotted name of the key (`section.key`)
+    pub key: String,
+    /// The source that pinned the key
+    pub pinned_by: String,
+    /// The source that tried to change it (`<set>` for `Config::set`)
+    pub source: String,
+}
+
+impl fmt::Display for PinViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` is pinned by `{}` and can't be changed by `{}`",
+            self.key, self.pinned_by, self.source
+        )
+    }
+}
+
+impl Config {
+    /// Pin every key of the source loaded last, so later sources and `set` can't change them
+    ///
+    /// Keys are pinned by their dotted name, so `port` in `[server]` is `server.port`.
+    /// Loading a source that defines a pinned key fails with `ConfigError::Pinned`,
+    /// `set` leaves pinned keys as they are and `try_set` returns the error.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, ConfigError};
+    ///
+    /// let mut cfg = Config::new()
+    ///     .text("[security]\ntls = required")
+    ///     .unwrap()
+    ///     .pin();
+    ///
+    /// // `set` can't change it
+    /// cfg.set("tls", "off");
+    /// assert_eq!(cfg.get_str("security.tls").unwrap(), "required");
+    /// match cfg.try_set("tls", "off") {
+    ///     Err(ConfigError::Pinned(i)) => assert_eq!(i.key, "security.tls"),
+    ///     _ => unreachable!(),
+    /// }
+    ///
+    /// // And neither can later layers
+    /// let err = cfg.text("[security]\ntls = off");
+    /// assert!(matches!(err, Err(ConfigError::Pinned(_))));
+    /// ```
+    pub fn pin(mut self) -> Self {
+        if let Some((source, layer)) = self.last_layer.take() {
+            let layer = layer.start.min(self.data.len())..layer.end.min(self.data.len());
+            let pins = self.data[layer].iter().map(|i| Pin {
+                key: i.name(),
+                source: source.clone(),
+            });
+            self.pins.extend(pins);
+        }
+        self
+    }
+
+    /// Set a value in the config, returning an error if the key is pinned
+    ///
+    /// Works like `Config::set` otherwise, see `Config::pin`.
+    pub fn try_set(&mut self, key: &str, value: &str) -> Result<(), ConfigError> {
+        if let Some(i) = self.set_violation(key) {
+            return Err(ConfigError::Pinned(i));
+        }
+
+        self.set(key, value);
+        Ok(())
+    }
+
+    /// Check if `set` would change a pinned key
+    pub(crate) fn set_violation(&self, key: &str) -> Option<PinViolation> {
+        let key = self.key_style.key(key);
+        let name = self
+            .data
+            .iter()
+            .rev()
+            .find(|i| i.key == key)
+            .map_or(key, |i| i.name());
+        self.pin_violation(&name, "<set>")
+    }
+
+    /// Move the last layer to where its entries are after removing the entries that are `true` in `removed`
+    pub(crate) fn shift_last_layer(&mut self, removed: &[bool]) {
+        if let Some((_, layer)) = &mut self.last_layer {
+            let before = removed.iter().take(layer.start).filter(|i| **i).count();
+            let inside = removed[layer.start.min(removed.len())..layer.end.min(removed.len())]
+                .iter()
+                .filter(|i| **i)
+                .count();
+            *layer = layer.start - before..layer.end - before - inside;
+        }
+    }
+
+    /// Check if entries from a source change any pinned keys
+    pub(crate) fn check_pins(&self, data: &[Entry], source: &str) -> Result<(), ConfigError> {
+        if self.pins.is_empty() {
+            return Ok(());
+        }
+
+        match data
+            .iter()
+            .find_map(|i| self.pin_violation(&i.name(), source))
+        {
+            Some(i) => Err(ConfigError::Pinned(i)),
+            None => Ok(()),
+        }
+    }
+
+    /// Get the violation of changing the key `name` from `source`, if it is pinned
+    fn pin_violation(&self, name: &str, source: &str) -> Option<PinViolation> {
+        let pin = self.pins.iter().find(|i| i.key == name)?;
+        Some(PinViolation {
+            key: pin.key.to_owned(),
+            pinned_by: pin.source.to_string(),
+            source: source.to_owned(),
+        })
+    }
+}
@@ -0,0 +1,75 @@
+//! Processing values as they are loaded, like trimming or decrypting them
+use crate::config::Entry;
+use crate::events::glob_match;
+use crate::{Config, ConfigError};
+
+/// Something that changes values as they are loaded, added with `Config::processor`
+pub trait Processor: Send + Sync {
+    /// Get the processed value
+    fn process(&self, value: &str) -> Result<String, ConfigError>;
+}
+
+impl<F> Processor for F
+where
+    F: Fn(&str) -> Result<String, ConfigError> + Send + Sync,
+{
+    fn process(&self, value: &str) -> Result<String, ConfigError> {
+        self(value)
+    }
+}
+
+impl Config {
+    /// Process the values of keys matching `pattern` in sources loaded after this
+    ///
+    /// Patterns are matched against the dotted name of keys, `*` matches any number of chars and `?` one char.
+    /// Processors run in the order they were added, after `${env:NAME}` references are expanded.
+    /// So the pipeline values go through (trim, decrypt, fill in a template...) is up to the application.
+    /// If a processor fails, loading the source fails with its error.
+    /// Saving the config keeps the original text of values that have not been changed.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, ConfigError};
+    ///
+    /// let cfg = Config::new()
+    ///     .processor("*", |i: &str| Ok(i.trim_matches('\'').to_owned()))
+    ///     .processor("secret.*", |i: &str| Ok(i.chars().rev().collect()))
+    ///     .text("name = 'app'\n[secret]\ntoken = '321cba'")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get_str("name").unwrap(), "app");
+    /// assert_eq!(cfg.get_str("secret.token").unwrap(), "abc123");
+    ///
+    /// let err = Config::new()
+    ///     .processor("port", |_: &str| Err(ConfigError::InvalidValue("is not allowed".to_owned())))
+    ///     .text("port = 80");
+    /// assert!(matches!(err, Err(ConfigError::InvalidValue(_))));
+    /// ```
+    pub fn processor<P>(mut self, pattern: &str, processor: P) -> Self
+    where
+        P: Processor + 'static,
+    {
+        let pattern = self.key_style.lookup(pattern);
+        self.processors
+            .push((pattern.into_owned(), Box::new(processor)));
+        self
+    }
+
+    /// Run the processors on the values of a source's entries
+    pub(crate) fn apply_processors(&self, data: &mut [Entry]) -> Result<(), ConfigError> {
+        if self.processors.is_empty() {
+            return Ok(());
+        }
+
+        for i in data.iter_mut() {
+            let name = i.name();
+            for (pattern, processor) in self.processors.iter() {
+                if glob_match(pattern.as_bytes(), name.as_bytes()) {
+                    i.value = processor.process(&i.value)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
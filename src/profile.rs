@@ -0,0 +1,60 @@
+//! Profiles, for keeping the variants of a config (like dev / staging / prod) in one file
+use crate::config::Entry;
+use crate::Config;
+
+/// What the sections of profiles start with
+pub(crate) const PREFIX: &str = "profile.";
+
+impl Config {
+    /// Use the keys of a profile over the others
+    ///
+    /// The keys of `[profile.name]` are used over the un-sectioned keys,
+    /// and the keys of `[profile.name.section]` over the ones in `[section]`.
+    /// Keys the profile does not define are read as usual, but never from another profile.
+    /// The profile is only used when reading keys, the config is saved as it was loaded.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let text = "\
+    /// debug = true
+    /// [server]
+    /// host = localhost
+    /// port = 8080
+    /// [profile.production]
+    /// debug = false
+    /// [profile.production.server]
+    /// host = example.com";
+    ///
+    /// let cfg = Config::new().text(text).unwrap().with_profile("production");
+    /// assert_eq!(cfg.get::<bool>("debug").unwrap(), false);
+    /// assert_eq!(cfg.get_str("server.host").unwrap(), "example.com");
+    /// assert_eq!(cfg.get::<u16>("server.port").unwrap(), 8080);
+    /// ```
+    pub fn with_profile(mut self, name: &str) -> Self {
+        self.profile = Some(self.key_style.section(&format!("{}{}", PREFIX, name)));
+        self
+    }
+
+    /// Get the selected profile, see `Config::with_profile`
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref().and_then(|i| i.strip_prefix(PREFIX))
+    }
+}
+
+/// Check if an entry is in a profile
+pub(crate) fn is_profile(entry: &Entry) -> bool {
+    match &entry.section {
+        Some(i) => i.starts_with(PREFIX),
+        None => entry.key.starts_with(PREFIX),
+    }
+}
+
+/// Check if an entry is in the sections of a profile (`profile.name` or `profile.name.section`)
+pub(crate) fn is_in(entry: &Entry, profile: &str) -> bool {
+    entry.section.as_deref().is_some_and(|i| {
+        i.strip_prefix(profile)
+            .is_some_and(|i| i.is_empty() || i.starts_with('.'))
+    })
+}
@@ -0,0 +1,57 @@
+//! Finding keys by prefix or pattern, for configs with keys named at runtime
+use crate::events::glob_match;
+use crate::Config;
+
+impl Config {
+    /// Get the keys starting with `prefix` and their values in use
+    ///
+    /// Keys are dotted names (`section.key` for keys in a section), in the order of `Config::iter_effective`.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .text("[plugin]\nfoo.enabled = true\nbar.enabled = false\n[server]\nport = 80")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     cfg.get_prefixed("plugin."),
+    ///     [
+    ///         ("plugin.foo.enabled".to_owned(), "true"),
+    ///         ("plugin.bar.enabled".to_owned(), "false")
+    ///     ]
+    /// );
+    /// ```
+    pub fn get_prefixed(&self, prefix: &str) -> Vec<(String, &str)> {
+        let prefix = self.key_style.lookup(prefix);
+        self.iter_effective()
+            .map(|i| (i.name(), i.value.as_str()))
+            .filter(|i| i.0.starts_with(&*prefix))
+            .collect()
+    }
+
+    /// Get the keys matching a pattern and their values in use
+    ///
+    /// Patterns are matched against dotted names like `Config::subscribe` filters,
+    /// `*` matches any number of chars (including `.`) and `?` one char.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .text("[db]\nmain.host = a\nmain.port = 1\nreplica.host = b")
+    ///     .unwrap();
+    ///
+    /// let hosts = cfg.keys_matching("db.*.host");
+    /// assert_eq!(hosts, [("db.main.host".to_owned(), "a"), ("db.replica.host".to_owned(), "b")]);
+    /// ```
+    pub fn keys_matching(&self, pattern: &str) -> Vec<(String, &str)> {
+        let pattern = self.key_style.lookup(pattern);
+        self.iter_effective()
+            .map(|i| (i.name(), i.value.as_str()))
+            .filter(|i| glob_match(pattern.as_bytes(), i.0.as_bytes()))
+            .collect()
+    }
+}
@@ -0,0 +1,98 @@
+//! Loading configs with invalid lines, reporting every one instead of failing on the first
+use std::fmt;
+use std::sync::Arc;
+
+use crate::layout::Line;
+use crate::{Config, ConfigError};
+
+/// A line that could not be parsed, see `Config::skip_invalid_lines`
+#[derive(Debug)]
+pub struct LineError {
+    /// The line number (from 1) in its source
+    pub line: usize,
+    /// The text of the line
+    /// For a `"""` value that is never closed this is every line after it too.
+    pub text: String,
+    /// Why the line could not be parsed
+    pub error: ConfigError,
+}
+
+impl Config {
+    /// Skip lines that can't be parsed in sources loaded after this, instead of failing
+    ///
+    /// Every invalid line (an unclosed section header, a line without a `=`, a bad `!include`...)
+    /// is listed in the `Config::load_report` with its line number and error, and the rest of the source is loaded.
+    /// The skipped lines are kept as they are when the config is saved.
+    /// Errors that aren't about a line (like I/O errors) still fail the load, and included files are parsed strictly.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .skip_invalid_lines(true)
+    ///     .text("name = app\n[server\nport\nhost = localhost")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get_str("host").unwrap(), "localhost");
+    /// let lines = cfg.line_errors().map(|i| i.line).collect::<Vec<_>>();
+    /// assert_eq!(lines, [2, 3]);
+    /// assert_eq!(cfg.to_string(), "name = app\n[server\nport\nhost = localhost\n");
+    /// ```
+    pub fn skip_invalid_lines(mut self, skip: bool) -> Self {
+        self.syntax.skip_invalid = skip;
+        self
+    }
+
+    /// Get every line that was skipped by `Config::skip_invalid_lines`, in load order
+    pub fn line_errors(&self) -> impl Iterator<Item = &LineError> {
+        self.report
+            .sources
+            .iter()
+            .flat_map(|i| i.invalid_lines.iter())
+    }
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {} (`{}`)", self.line, self.error, self.text)
+    }
+}
+
+/// Keep a line that failed to parse if `skip` is set, otherwise fail with its error
+///
+/// I/O errors (reading the lines of a `"""` value) always fail.
+pub(crate) fn skip_line(
+    skip: bool,
+    layout: &mut Vec<Line>,
+    line: usize,
+    text: &str,
+    error: ConfigError,
+) -> Result<(), ConfigError> {
+    if !skip || matches!(error, ConfigError::Io(_)) {
+        return Err(error);
+    }
+
+    layout.push(Line::Invalid(Arc::new(LineError {
+        line,
+        text: text.to_owned(),
+        error,
+    })));
+    Ok(())
+}
+
+/// Take the skipped lines out of a parsed layout, leaving their text to be written as is
+pub(crate) fn take_invalid(layout: &mut [Line]) -> Vec<LineError> {
+    let mut out = Vec::new();
+    for i in layout.iter_mut() {
+        if let Line::Invalid(error) = i {
+            let text = error.text.to_owned();
+            if let Line::Invalid(error) = std::mem::replace(i, Line::Text(text)) {
+                // Parsed layouts are not cloned, so this is the only reference
+                out.extend(Arc::try_unwrap(error).ok());
+            }
+        }
+    }
+
+    out
+}
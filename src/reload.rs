@@ -0,0 +1,346 @@
+//! Reloading a config in place, only parsing the lines that changed (with the `reload` feature)
+//!
+//! The layout of a config loaded from a single source has every line, so a new version of the text is compared to it
+//! and only the lines from the first to the last change are parsed again.
+//! Anything a line depends on other than the section it's in (includes, lists of sections, anchors, ...)
+//! makes the whole text be parsed instead.
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Instant, SystemTime};
+
+use crate::compress;
+use crate::duplicate::DuplicatePolicy;
+use crate::extends::Layer;
+use crate::format::Format;
+use crate::instrument;
+use crate::layout::Line;
+use crate::occurrence;
+use crate::utf8;
+use crate::{Config, ConfigError};
+
+impl Config {
+    /// Read the config file again, only parsing the lines that changed
+    ///
+    /// Works like `Config::reload_text` with the contents of the file last loaded,
+    /// along with the files it extends (see `Config::file`).
+    /// Returns `ConfigError::NoFileDefined` if no file was loaded.
+    /// ## Example
+    /// ```rust,no_run
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let mut cfg = Config::new().file("config.cfg").unwrap();
+    ///
+    /// // When the file changes
+    /// cfg.reload().unwrap();
+    /// ```
+    pub fn reload(&mut self) -> Result<(), ConfigError> {
+        let start = Instant::now();
+        let path = self.path.clone().ok_or(ConfigError::NoFileDefined)?;
+        let (contents, invalid) = compress::read(&path, self.decoding)?;
+        let source = path.display().to_string();
+        self.reparse(source, &contents, Some(&path), start)?;
+        self.validated = Some(SystemTime::now());
+        self.warn_invalid_utf8(invalid);
+        Ok(())
+    }
+
+    /// Replace the config with a new version of its text, only parsing the lines that changed
+    ///
+    /// For configs loaded from a single ini source the text is compared to the lines that were loaded,
+    /// and only the lines from the first to the last change are parsed (replacing their entries in place).
+    /// Otherwise, or if the change moves keys into another section, the whole text is parsed.
+    /// Either way the config ends up as if the text was loaded in place of its sources:
+    /// values changed with `set` are replaced and subscribers are sent the changes.
+    /// If the text can't be parsed the config is left as is.
+    /// An `extends` key in the text is not followed, as it has no file to be relative to.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let mut cfg = Config::new()
+    ///     .text("[server]\nhost = localhost\nport = 80")
+    ///     .unwrap();
+    ///
+    /// cfg.reload_text("[server]\nhost = localhost\nport = 8080")
+    ///     .unwrap();
+    /// assert_eq!(cfg.get::<u16>("server.port").unwrap(), 8080);
+    /// ```
+    pub fn reload_text(&mut self, text: &str) -> Result<(), ConfigError> {
+        let start = Instant::now();
+        let source = match self.report.sources.as_slice() {
+            [i] => i.source.to_owned(),
+            _ => "<text>".to_owned(),
+        };
+        self.reparse(source, text, None, start)
+    }
+
+    /// Replace the sources of the config with `text` (the contents of `file`, if it's from one),
+    /// parsing only the changed lines if it can
+    fn reparse(
+        &mut self,
+        source: String,
+        text: &str,
+        file: Option<&Path>,
+        start: Instant,
+    ) -> Result<(), ConfigError> {
+        if self.patch(&source, text, start)? {
+            instrument::reloaded(&source, true);
+            return Ok(());
+        }
+
+        let layers = match file {
+            Some(file) => self.parse_extended(file, text.to_owned())?,
+            None => vec![Layer {
+                source: source.clone(),
+                parsed: self.parse_in(text.to_owned(), Path::new(""))?,
+                invalid_utf8: Vec::new(),
+            }],
+        };
+        let old = self
+            .data
+            .iter()
+            .map(|i| (i.section.as_deref(), i.key.as_str(), i.value.as_str()))
+            .collect::<HashSet<_>>();
+        for layer in layers.iter() {
+            let changed = layer
+                .parsed
+                .0
+                .iter()
+                .filter(|i| {
+                    !old.contains(&(i.section.as_deref(), i.key.as_str(), i.value.as_str()))
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            self.check_pins(&changed, &layer.source)?;
+        }
+
+        let keys = self
+            .data
+            .iter()
+            .chain(layers.iter().flat_map(|i| i.parsed.0.iter()))
+            .map(|i| i.key.to_owned())
+            .collect::<Vec<_>>();
+        let snapshot = self.snapshot(keys.iter().map(String::as_str));
+        self.data.clear();
+        self.layout.clear();
+        self.origins.clear();
+        self.include_edges.clear();
+        self.report.sources.clear();
+        for i in layers {
+            self.push_source(i.source, start, i.parsed);
+            self.warn_invalid_utf8(i.invalid_utf8);
+        }
+        instrument::reloaded(&source, false);
+        self.notify(snapshot);
+        Ok(())
+    }
+
+    /// Reload by parsing only the changed lines, returns `false` if the whole text has to be parsed
+    fn patch(&mut self, source: &str, text: &str, start: Instant) -> Result<bool, ConfigError> {
+        if !self.can_patch(source) {
+            return Ok(false);
+        }
+
+        let text = utf8::normalize(text);
+        let new = text.lines().collect::<Vec<_>>();
+
+        // Skip the lines at the start and end that did not change
+        let (mut a, mut ca) = (0, 0);
+        while let Some(n) = self.layout.get(a).and_then(|i| starts(i, &new[ca..])) {
+            a += 1;
+            ca += n;
+        }
+        let (mut b, mut cb) = (self.layout.len(), new.len());
+        while let Some(n) = (b > a)
+            .then(|| ends(&self.layout[b - 1], &new[ca..cb]))
+            .flatten()
+        {
+            b -= 1;
+            cb -= n;
+        }
+
+        // The lines after the change need to still be in the same section
+        let before = self.last_header(&self.layout[..a]).unwrap_or_default();
+        let lines = new[ca..cb].iter().map(Ok);
+        let (keys, syntax) = (self.key_style, self.syntax);
+        let parsed =
+            Config::parse_lines(lines, None, 0, keys, syntax, &self.comments, before.clone());
+        let (data, mut layout) = match parsed {
+            Ok(i) => i,
+            Err(_) => return Ok(false),
+        };
+        let after = self.last_header(&layout).unwrap_or(before);
+        if after != self.last_header(&self.layout[..b]).unwrap_or_default()
+            || layout.iter().any(is_repeated)
+            || (self.includes && data.iter().any(|i| self.is_extends(i)))
+        {
+            return Ok(false);
+        }
+
+        let ia = entry_count(&self.layout[..a]);
+        let ib = ia + entry_count(&self.layout[a..b]);
+        let old = &self.data[ia..ib];
+        let changed = data
+            .iter()
+            .filter(|i| !old.contains(i))
+            .cloned()
+            .collect::<Vec<_>>();
+        self.check_pins(&changed, source)?;
+
+        // Values changed with `set` go back to the ones loaded
+        let reset = self.layout[..a]
+            .iter()
+            .chain(&self.layout[b..])
+            .filter_map(|i| match i {
+                Line::Entry { index, .. } if i.loaded() != Some(&self.data[*index].value) => {
+                    Some((*index, i.loaded()?.to_owned()))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let keys = old
+            .iter()
+            .chain(data.iter())
+            .chain(reset.iter().map(|(i, _)| &self.data[*i]))
+            .map(|i| i.key.to_owned())
+            .collect::<Vec<_>>();
+        let snapshot = self.snapshot(keys.iter().map(String::as_str));
+        for (i, value) in reset {
+            self.data.set_value(i, value);
+        }
+
+        let (removed, added) = (ib - ia, data.len());
+        let old_lines = self.layout[a..b].iter().map(line_count).sum::<usize>();
+        let changed = self.timestamps.then(SystemTime::now);
+        let mut origins = occurrence::origins(&mut layout, Arc::from(source), added);
+        for i in origins.iter_mut() {
+            i.line = i.line.map(|i| i + ca);
+            i.changed = changed;
+        }
+        for i in self.origins[ib..].iter_mut() {
+            i.line = i.line.map(|i| i + (cb - ca) - old_lines);
+        }
+        for i in layout.iter_mut() {
+            if let Line::Entry { index, .. } = i {
+                *index += ia;
+            }
+        }
+        for i in self.layout[b..].iter_mut() {
+            if let Line::Entry { index, .. } = i {
+                *index = *index - removed + added;
+            }
+        }
+
+        self.layout.splice(a..b, layout);
+        self.origins.splice(ia..ib, origins);
+        self.data.splice(ia..ib, data);
+        if let Some(report) = self.report.sources.first_mut() {
+            report.entries = self.data.len();
+            report.duration = start.elapsed();
+            report.invalid_utf8.clear();
+        }
+
+        self.notify(snapshot);
+        Ok(true)
+    }
+
+    /// Check if the config can be reloaded by parsing only the changed lines
+    ///
+    /// It has to be from one ini source that was parsed a line at a time, with every entry still on its line.
+    fn can_patch(&self, source: &str) -> bool {
+        let loaded = matches!(
+            self.report.sources.as_slice(),
+            [i] if i.source == source && i.error.is_none()
+        );
+        let processed = self.anchors
+            || self.env.is_some()
+            || !self.processors.is_empty()
+            || !self.transforms.is_empty()
+            || !matches!(
+                self.duplicates,
+                DuplicatePolicy::LastWins | DuplicatePolicy::Collect
+            );
+        if !loaded
+            || processed
+            || self.format != Format::Ini
+            || self.syntax.skip_invalid
+            || self.origins.len() != self.data.len()
+        {
+            return false;
+        }
+
+        let mut next = 0;
+        for i in self.layout.iter() {
+            match i {
+                Line::Entry { index, .. } if *index == next => next += 1,
+                Line::Entry { .. } | Line::Include { .. } | Line::Invalid(_) => return false,
+                i if is_repeated(i) => return false,
+                _ => {}
+            }
+        }
+        next == self.data.len()
+    }
+
+    /// Get the section started by the last header of some lines, `None` if there is no header
+    fn last_header(&self, layout: &[Line]) -> Option<Option<String>> {
+        layout.iter().rev().find_map(|i| match i {
+            Line::Section(raw) => {
+                let name = self.comments.remove(raw.trim()).trim_end();
+                let name = name.strip_prefix('[').and_then(|i| i.strip_suffix(']'));
+                Some(name.map(|i| self.key_style.section(i)))
+            }
+            _ => None,
+        })
+    }
+}
+
+/// Check if a line is the header of a list of sections (`[[name]]`)
+fn is_repeated(line: &Line) -> bool {
+    matches!(line, Line::Section(raw) if raw.trim_start().starts_with("[["))
+}
+
+/// Count the entry lines of some layout
+fn entry_count(layout: &[Line]) -> usize {
+    layout
+        .iter()
+        .filter(|i| matches!(i, Line::Entry { .. }))
+        .count()
+}
+
+/// Get the text of a line as it was loaded
+fn text(line: &Line) -> Cow<'_, str> {
+    match line {
+        Line::Text(raw) | Line::Section(raw) | Line::Include { raw, .. } => Cow::Borrowed(raw),
+        Line::Invalid(i) => Cow::Borrowed(&i.text),
+        Line::Entry { text, .. } => Cow::Borrowed(text),
+    }
+}
+
+/// Count the lines of text a line is (more than one for multi-line values)
+fn line_count(line: &Line) -> usize {
+    match line {
+        Line::Entry { text, .. } => text.matches('\n').count() + 1,
+        _ => 1,
+    }
+}
+
+/// Check if a line is the same as the first lines of `lines`, returning how many lines it is
+fn starts(line: &Line, lines: &[&str]) -> Option<usize> {
+    let n = line_count(line);
+    (lines.len() >= n && same(line, &lines[..n])).then_some(n)
+}
+
+/// Check if a line is the same as the last lines of `lines`, returning how many lines it is
+fn ends(line: &Line, lines: &[&str]) -> Option<usize> {
+    let n = line_count(line);
+    (lines.len() >= n && same(line, &lines[lines.len() - n..])).then_some(n)
+}
+
+/// Check if a line is the same as some lines of text
+fn same(line: &Line, lines: &[&str]) -> bool {
+    text(line).split('\n').eq(lines.iter().copied())
+}
@@ -0,0 +1,74 @@
+//! Info on how a config was assembled from its sources
+use std::fmt;
+use std::time::Duration;
+
+use crate::{ConfigError, LineError};
+
+/// Report of how a config was loaded
+#[derive(Debug, Default)]
+pub struct LoadReport {
+    /// Every source attempted, in load order
+    pub sources: Vec<SourceReport>,
+}
+
+/// Info on one source of a config
+#[derive(Debug)]
+pub struct SourceReport {
+    /// The source (a file path or `<text>`)
+    pub source: String,
+    /// Why the source failed to load
+    /// Only optional sources can fail without failing the whole load.
+    pub error: Option<ConfigError>,
+    /// Number of entries loaded from the source
+    pub entries: usize,
+    /// Keys from earlier sources that this source overrode
+    pub overridden: Vec<String>,
+    /// How long it took to load the source
+    pub duration: Duration,
+    /// Lines (from 1) with invalid UTF-8 that was replaced, see `Config::lossy_utf8` and `Config::latin1`
+    pub invalid_utf8: Vec<usize>,
+    /// Lines that could not be parsed and were skipped, see `Config::skip_invalid_lines`
+    pub invalid_lines: Vec<LineError>,
+}
+
+impl LoadReport {
+    /// Get the sources that failed to load
+    pub fn failures(&self) -> impl Iterator<Item = &SourceReport> {
+        self.sources.iter().filter(|i| i.error.is_some())
+    }
+}
+
+impl fmt::Display for LoadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for i in self.sources.iter() {
+            match &i.error {
+                Some(e) => write!(f, "{}: failed ({})", i.source, e)?,
+                None => write!(f, "{}: {} entries", i.source, i.entries)?,
+            }
+            write!(f, " in {:?}", i.duration)?;
+
+            if !i.overridden.is_empty() {
+                write!(f, ", overrode [{}]", i.overridden.join(", "))?;
+            }
+            if !i.invalid_utf8.is_empty() {
+                let lines = i.invalid_utf8.iter().map(|i| i.to_string());
+                write!(
+                    f,
+                    ", invalid UTF-8 on lines [{}]",
+                    lines.collect::<Vec<_>>().join(", ")
+                )?;
+            }
+            if !i.invalid_lines.is_empty() {
+                let lines = i.invalid_lines.iter().map(|i| i.line.to_string());
+                write!(
+                    f,
+                    ", skipped invalid lines [{}]",
+                    lines.collect::<Vec<_>>().join(", ")
+                )?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
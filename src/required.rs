@@ -0,0 +1,58 @@
+//! Placeholder values for keys that template configs leave for the user to fill in
+//!
+//! A key like `token = <REQUIRED>` parses as usual, but reading it returns `ConfigError::Unset`.
+use std::borrow::Cow;
+
+use crate::{Config, Entry};
+
+/// The default placeholder, see `Config::required_placeholder`
+pub(crate) const PLACEHOLDER: &str = "<REQUIRED>";
+
+impl Config {
+    /// Set the value that marks a key as required but not set yet (`<REQUIRED>` by default)
+    ///
+    /// Reading a key with this value returns `ConfigError::Unset` naming it, instead of the placeholder (or an error parsing it).
+    /// An empty placeholder turns this off.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, ConfigError};
+    ///
+    /// let cfg = Config::new()
+    ///     .required_placeholder("CHANGE_ME")
+    ///     .text("port = CHANGE_ME")
+    ///     .unwrap();
+    ///
+    /// assert!(matches!(cfg.get::<u16>("port"), Err(ConfigError::Unset(_))));
+    /// ```
+    pub fn required_placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = Cow::Owned(placeholder.to_owned());
+        self
+    }
+
+    /// Get the dotted names of the keys that still have the required placeholder as their value
+    ///
+    /// Useful for reporting every key left to fill in at once, when starting up.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .text("host = localhost\n[auth]\ntoken = <REQUIRED>")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.unset_keys(), ["auth.token"]);
+    /// ```
+    pub fn unset_keys(&self) -> Vec<String> {
+        self.iter_effective()
+            .filter(|i| self.is_unset(i))
+            .map(|i| i.name())
+            .collect()
+    }
+
+    /// Check if an entry has the required placeholder as its value
+    pub(crate) fn is_unset(&self, entry: &Entry) -> bool {
+        !self.placeholder.is_empty() && entry.value == self.placeholder
+    }
+}
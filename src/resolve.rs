@@ -0,0 +1,203 @@
+//! Resolving `${name:reference}` references with resolvers, like secret stores
+//!
+//! Unlike environment variables these are resolved when read with `Config::get_resolved`, not when loaded.
+//! Resolved values are cached per key, so reading them often does not call the resolver every time.
+//! Write `$${name:reference}` for a literal `${name:reference}`.
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::secret;
+use crate::{Config, ConfigError};
+
+/// Something that can resolve references to values, like a secret store
+pub trait Resolver: Send + Sync {
+    /// Get the value of a reference (the part after `name:`)
+    fn resolve(&self, reference: &str) -> Result<String, ConfigError>;
+}
+
+impl<F> Resolver for F
+where
+    F: Fn(&str) -> Result<String, ConfigError> + Send + Sync,
+{
+    fn resolve(&self, reference: &str) -> Result<String, ConfigError> {
+        self(reference)
+    }
+}
+
+/// The resolvers of a config along with the cache of values they resolved
+#[derive(Default)]
+pub(crate) struct Resolvers {
+    /// Resolvers and the names they are used for
+    resolvers: Vec<(String, Box<dyn Resolver>)>,
+    /// Resolver for `!secret:name` values, see `Config::secrets`
+    pub(crate) secrets: Option<Box<dyn Resolver>>,
+    /// How long resolved values are cached for (until invalidated if `None`)
+    ttl: Option<Duration>,
+    /// Resolved values by key
+    cache: Mutex<BTreeMap<String, Cached>>,
+}
+
+/// A resolved value
+struct Cached {
+    /// The value before it was resolved, if the key is changed it is resolved again
+    raw: String,
+    /// The resolved value
+    value: String,
+    /// When the value was resolved
+    time: Instant,
+}
+
+impl Resolvers {
+    /// Get the cache, even if a thread panicked while using it
+    fn cache(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, Cached>> {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Resolve every reference with a known resolver in a value
+    fn expand(&self, value: &str) -> Result<String, ConfigError> {
+        if let (Some(secrets), Some(name)) = (&self.secrets, value.strip_prefix(secret::PREFIX)) {
+            return secrets.resolve(name);
+        }
+
+        let mut out = String::with_capacity(value.len());
+        let mut rest = value;
+
+        while let Some(start) = rest.find("${") {
+            // `$${` is an escaped reference
+            if rest[..start].ends_with('$') {
+                out.push_str(&rest[..start - 1]);
+                out.push_str("${");
+                rest = &rest[start + 2..];
+                continue;
+            }
+
+            out.push_str(&rest[..start]);
+            let end = match rest[start..].find('}') {
+                Some(i) => start + i,
+                None => {
+                    rest = &rest[start..];
+                    break;
+                }
+            };
+
+            let inner = &rest[start + 2..end];
+            let resolver = inner.split_once(':').and_then(|(name, reference)| {
+                let resolver = self.resolvers.iter().find(|i| i.0 == name)?;
+                Some((&resolver.1, reference))
+            });
+            match resolver {
+                Some((resolver, reference)) => out.push_str(&resolver.resolve(reference)?),
+                None => out.push_str(&rest[start..=end]),
+            }
+            rest = &rest[end + 1..];
+        }
+
+        out.push_str(rest);
+        Ok(out)
+    }
+}
+
+impl Config {
+    /// Add a resolver for `${name:reference}` references, read with `Config::get_resolved`
+    ///
+    /// References with names that have no resolver are left as they are.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, ConfigError};
+    ///
+    /// let cfg = Config::new()
+    ///     .resolver("vault", |reference: &str| match reference {
+    ///         "db/password" => Ok("hunter2".to_owned()),
+    ///         _ => Err(ConfigError::NoItem),
+    ///     })
+    ///     .text("[db]\npassword = ${vault:db/password}")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get_resolved("db.password").unwrap(), "hunter2");
+    /// assert_eq!(cfg.get_str("db.password").unwrap(), "${vault:db/password}");
+    /// ```
+    pub fn resolver<R>(mut self, name: &str, resolver: R) -> Self
+    where
+        R: Resolver + 'static,
+    {
+        self.resolvers
+            .resolvers
+            .push((name.to_owned(), Box::new(resolver)));
+        self
+    }
+
+    /// Set how long values resolved by `Config::get_resolved` are cached for
+    ///
+    /// By default they are cached until the key changes or is invalidated with `Config::invalidate`.
+    pub fn resolver_ttl(mut self, ttl: Duration) -> Self {
+        self.resolvers.ttl = Some(ttl);
+        self
+    }
+
+    /// Get a value from config with its references resolved (see `Config::resolver`)
+    ///
+    /// Values that are `!secret:name` are resolved by the backend set with `Config::secrets`.
+    ///
+    /// The resolved value is cached, so resolvers are only called when it is first read,
+    /// after it expires (see `Config::resolver_ttl`), is invalidated or the key changes.
+    /// Errors from resolvers are returned as they are and nothing is cached.
+    pub fn get_resolved(&self, key: &str) -> Result<String, ConfigError> {
+        let key = self.key_style.key(key);
+        let raw = self.get_str(&key)?;
+
+        if let Some(i) = self.resolvers.cache().get(&key) {
+            let fresh = self.resolvers.ttl.is_none_or(|ttl| i.time.elapsed() < ttl);
+            if fresh && i.raw == raw {
+                return Ok(i.value.to_owned());
+            }
+        }
+
+        // Resolved without holding the cache, as resolvers can be slow
+        let value = self.resolvers.expand(&raw)?;
+        self.resolvers.cache().insert(
+            key,
+            Cached {
+                raw,
+                value: value.to_owned(),
+                time: Instant::now(),
+            },
+        );
+        Ok(value)
+    }
+
+    /// Drop the cached resolved value of a key, so it is resolved again when next read
+    ///
+    /// Used when a secret is rotated.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let calls = Arc::new(AtomicUsize::new(0));
+    /// let counter = calls.clone();
+    /// let cfg = Config::new()
+    ///     .resolver("secret", move |_: &str| {
+    ///         Ok(counter.fetch_add(1, Ordering::SeqCst).to_string())
+    ///     })
+    ///     .text("token = ${secret:token}")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get_resolved("token").unwrap(), "0");
+    /// assert_eq!(cfg.get_resolved("token").unwrap(), "0");
+    ///
+    /// cfg.invalidate("token");
+    /// assert_eq!(cfg.get_resolved("token").unwrap(), "1");
+    /// ```
+    pub fn invalidate(&self, key: &str) {
+        self.resolvers.cache().remove(&self.key_style.key(key));
+    }
+
+    /// Drop every cached resolved value
+    pub fn invalidate_all(&self) {
+        self.resolvers.cache().clear();
+    }
+}
@@ -0,0 +1,118 @@
+//! Restricted parsing of untrusted config text
+//!
+//! Meant for config fragments from users (like uploaded plugin settings).
+//! Text parsed in a sandbox can't include files or reference environment variables,
+//! can only set the keys it is allowed to and is limited in size.
+use crate::config::normalize_key;
+use crate::{Config, ConfigError};
+
+/// Parses untrusted config text with strict limits
+///
+/// Only keys in the allowlist can be set, by their dotted name (`section.key` for keys in sections).
+/// `!include` directives and environment variables are never used, so loading text can't read anything.
+/// Any text that breaks a rule is rejected with `ConfigError::Restricted`.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use simple_config_parser::{ConfigError, Sandbox};
+///
+/// let sandbox = Sandbox::new(&["color", "limits.rate"]).max_entries(4);
+///
+/// let cfg = sandbox.parse("color = blue\n[limits]\nrate = 10").unwrap();
+/// assert_eq!(cfg.get::<u32>("limits.rate").unwrap(), 10);
+///
+/// // Keys outside the allowlist and includes are rejected
+/// assert!(matches!(sandbox.parse("admin = true"), Err(ConfigError::Restricted(_))));
+/// assert!(matches!(sandbox.parse("!include /etc/passwd"), Err(ConfigError::Restricted(_))));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    allowed: Vec<String>,
+    max_size: usize,
+    max_entries: usize,
+    max_value_len: usize,
+}
+
+impl Sandbox {
+    /// Create a sandbox that allows setting only the keys in `allowed`
+    ///
+    /// By default text can be up to 64 KiB, with up to 256 entries of up to 4 KiB each.
+    pub fn new(allowed: &[&str]) -> Self {
+        Sandbox {
+            allowed: allowed.iter().map(|i| normalize_key(i)).collect(),
+            max_size: 64 * 1024,
+            max_entries: 256,
+            max_value_len: 4 * 1024,
+        }
+    }
+
+    /// Set the most bytes of text that can be parsed
+    pub fn max_size(self, bytes: usize) -> Self {
+        Self {
+            max_size: bytes,
+            ..self
+        }
+    }
+
+    /// Set the most entries the text can define (counting each definition of a key)
+    pub fn max_entries(self, entries: usize) -> Self {
+        Self {
+            max_entries: entries,
+            ..self
+        }
+    }
+
+    /// Set the most bytes a single value can be
+    pub fn max_value_len(self, bytes: usize) -> Self {
+        Self {
+            max_value_len: bytes,
+            ..self
+        }
+    }
+
+    /// Parse untrusted text into a new config
+    ///
+    /// The text is checked against the size limit before it is parsed.
+    pub fn parse<T>(&self, text: T) -> Result<Config, ConfigError>
+    where
+        T: std::fmt::Display,
+    {
+        let text = text.to_string();
+        if text.len() > self.max_size {
+            return Err(ConfigError::Restricted(format!(
+                "Text is over {} bytes",
+                self.max_size
+            )));
+        }
+
+        let mut cfg = Config::new();
+        cfg.includes = false;
+        let cfg = cfg.text(text)?;
+
+        if cfg.data.len() > self.max_entries {
+            return Err(ConfigError::Restricted(format!(
+                "Over {} entries are defined",
+                self.max_entries
+            )));
+        }
+
+        for i in cfg.data.iter() {
+            let name = i.name();
+            if !self.allowed.contains(&name) {
+                return Err(ConfigError::Restricted(format!(
+                    "Key `{}` is not allowed",
+                    name
+                )));
+            }
+
+            if i.value.len() > self.max_value_len {
+                return Err(ConfigError::Restricted(format!(
+                    "Value of `{}` is over {} bytes",
+                    name, self.max_value_len
+                )));
+            }
+        }
+
+        Ok(cfg)
+    }
+}
@@ -0,0 +1,501 @@
+//! Checking configs against a schema
+//!
+//! Schemas list the keys a config should have, the type of their values,
+//! the values they allow and their defaults.
+//! They can be made for a single section and added together,
+//! so each module of an application can own the schema for its part of the config.
+use std::fmt;
+use std::ops::Add;
+
+use crate::config::{normalize_key, normalize_section, Entry};
+use crate::{Config, ConfigError};
+
+/// The type a value should be parsable as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    /// Any text
+    Str,
+    /// An integer (`i64`)
+    Int,
+    /// A floating point number (`f64`)
+    Float,
+    /// `true` or `false`
+    Bool,
+}
+
+impl ValueType {
+    /// Name of the type, used in templates
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            ValueType::Str => "text",
+            ValueType::Int => "integer",
+            ValueType::Float => "number",
+            ValueType::Bool => "true or false",
+        }
+    }
+
+    /// Check if a value can be parsed as the type
+    fn check(&self, value: &str) -> bool {
+        match self {
+            ValueType::Str => true,
+            ValueType::Int => value.parse::<i64>().is_ok(),
+            ValueType::Float => value.parse::<f64>().is_ok(),
+            ValueType::Bool => value.parse::<bool>().is_ok(),
+        }
+    }
+}
+
+/// A key in a schema
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SchemaKey {
+    /// The key
+    pub key: &'static str,
+    /// Type of the value
+    pub kind: ValueType,
+    /// If the key has to be defined
+    pub required: bool,
+    /// Smallest and largest values allowed (inclusive), for `Int` and `Float` keys
+    pub range: Option<(f64, f64)>,
+    /// Regex the whole value has to match
+    #[cfg(feature = "regex")]
+    pub pattern: Option<&'static str>,
+    /// Value used when the key is not defined
+    /// A required key with a default can be left out.
+    pub default: Option<&'static str>,
+    /// What the key is for, written above it in templates
+    pub doc: Option<&'static str>,
+}
+
+impl SchemaKey {
+    /// A key that has to be defined
+    pub const fn required(key: &'static str, kind: ValueType) -> Self {
+        SchemaKey {
+            key,
+            kind,
+            required: true,
+            range: None,
+            #[cfg(feature = "regex")]
+            pattern: None,
+            default: None,
+            doc: None,
+        }
+    }
+
+    /// A key that can be left out
+    pub const fn optional(key: &'static str, kind: ValueType) -> Self {
+        SchemaKey {
+            required: false,
+            ..SchemaKey::required(key, kind)
+        }
+    }
+
+    /// Only allow values from `min` to `max` (inclusive)
+    ///
+    /// Only used for `Int` and `Float` keys.
+    pub const fn range(self, min: f64, max: f64) -> Self {
+        SchemaKey {
+            range: Some((min, max)),
+            ..self
+        }
+    }
+
+    /// Only allow values that fully match a regex
+    #[cfg(feature = "regex")]
+    pub const fn pattern(self, pattern: &'static str) -> Self {
+        SchemaKey {
+            pattern: Some(pattern),
+            ..self
+        }
+    }
+
+    /// Set the value used when the key is not defined, see `Schema::defaults`
+    pub const fn default(self, value: &'static str) -> Self {
+        SchemaKey {
+            default: Some(value),
+            ..self
+        }
+    }
+
+    /// Describe what the key is for, see `Schema::generate_template`
+    pub const fn doc(self, doc: &'static str) -> Self {
+        SchemaKey {
+            doc: Some(doc),
+            ..self
+        }
+    }
+
+    /// Describe the values the key allows, like `integer, required, from 1 to 10`
+    pub(crate) fn describe(&self) -> String {
+        let mut out = format!(
+            "{}, {}",
+            self.kind.name(),
+            if self.required {
+                "required"
+            } else {
+                "optional"
+            }
+        );
+
+        if let (Some((min, max)), ValueType::Int | ValueType::Float) = (self.range, self.kind) {
+            out.push_str(&format!(", from {} to {}", min, max));
+        }
+
+        #[cfg(feature = "regex")]
+        if let Some(pattern) = self.pattern {
+            out.push_str(&format!(", matching `{}`", pattern));
+        }
+
+        out
+    }
+
+    /// Check a defined value against the key, returning why it is not valid
+    fn check(&self, value: &str) -> Option<ConfigError> {
+        if !self.kind.check(value) {
+            return Some(ConfigError::ParseError);
+        }
+
+        if let (Some((min, max)), ValueType::Int | ValueType::Float) = (self.range, self.kind) {
+            match value.parse::<f64>() {
+                Ok(i) if i >= min && i <= max => {}
+                _ => {
+                    return Some(ConfigError::InvalidValue(format!(
+                        "must be from {} to {}",
+                        min, max
+                    )))
+                }
+            }
+        }
+
+        #[cfg(feature = "regex")]
+        if let Some(pattern) = self.pattern {
+            let regex = match regex::Regex::new(&format!("^(?:{})$", pattern)) {
+                Ok(i) => i,
+                Err(_) => {
+                    return Some(ConfigError::InvalidValue(format!(
+                        "can't be checked, `{}` is not a valid regex",
+                        pattern
+                    )))
+                }
+            };
+
+            if !regex.is_match(value) {
+                return Some(ConfigError::InvalidValue(format!(
+                    "must match `{}`",
+                    pattern
+                )));
+            }
+        }
+
+        None
+    }
+}
+
+/// The keys a config (or a section of it) should have
+///
+/// Schemas can be `const` and combined with `+`.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use simple_config_parser::{Config, Schema, SchemaKey, ValueType};
+///
+/// mod db {
+///     use simple_config_parser::{Schema, SchemaKey, ValueType};
+///
+///     pub const SCHEMA: Schema = Schema::section("database", &[
+///         SchemaKey::required("host", ValueType::Str),
+///         SchemaKey::optional("port", ValueType::Int),
+///     ]);
+/// }
+///
+/// const SERVER: Schema = Schema::new(&[SchemaKey::required("workers", ValueType::Int)]);
+///
+/// let schema = db::SCHEMA + SERVER;
+/// let cfg = Config::new()
+///     .text("workers = many\n[database]\nport = 5432")
+///     .unwrap();
+///
+/// let errors = cfg.validate(&schema).unwrap_err();
+/// assert_eq!(errors.keys.len(), 2);
+/// ```
+///
+/// Keys can also limit their values and have defaults, and schemas can reject keys they don't list.
+/// ```rust
+/// // Import Lib
+/// use simple_config_parser::{Config, Schema, SchemaKey, ValueType};
+///
+/// const KEYS: &[SchemaKey] = &[
+///     SchemaKey::required("port", ValueType::Int).range(1.0, 65535.0),
+///     SchemaKey::optional("workers", ValueType::Int).default("4"),
+/// ];
+/// let schema = Schema::section("server", KEYS).deny_unknown();
+///
+/// let cfg = Config::new().text("[server]\nport = 0\nprot = 80").unwrap();
+/// let errors = schema.validate(&cfg).unwrap_err();
+/// assert_eq!(
+///     errors.to_string(),
+///     "Invalid config:\n - `[server] port` is invalid (Value must be from 1 to 65535)\n - `server.prot` is not a known key\n"
+/// );
+///
+/// let cfg = Config::new()
+///     .text("[server]\nport = 80")
+///     .unwrap()
+///     .with_defaults(schema.defaults());
+/// assert_eq!(cfg.get::<u32>("server.workers").unwrap(), 4);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schema {
+    /// Section the keys are in (`None` for un-sectioned keys)
+    section: Option<&'static str>,
+    /// Keys of this schema
+    keys: &'static [SchemaKey],
+    /// Schemas added to this one
+    parts: Vec<Schema>,
+    /// If keys that are not in the schema are errors
+    deny_unknown: bool,
+}
+
+impl Schema {
+    /// A schema for un-sectioned keys
+    pub const fn new(keys: &'static [SchemaKey]) -> Self {
+        Schema {
+            section: None,
+            keys,
+            parts: Vec::new(),
+            deny_unknown: false,
+        }
+    }
+
+    /// A schema for the keys of a section
+    pub const fn section(name: &'static str, keys: &'static [SchemaKey]) -> Self {
+        Schema {
+            section: Some(name),
+            keys,
+            parts: Vec::new(),
+            deny_unknown: false,
+        }
+    }
+
+    /// Make keys in the config that are not in the schema errors
+    ///
+    /// Schemas added to one that denies unknown keys (or added to one) deny them as well.
+    pub fn deny_unknown(mut self) -> Self {
+        self.deny_unknown = true;
+        self
+    }
+
+    /// Check a config against the schema, same as `Config::validate`
+    pub fn validate(&self, config: &Config) -> Result<(), SchemaError> {
+        config.validate(self)
+    }
+
+    /// Get a config with the defaults of the schema's keys, to be used with `Config::with_defaults`
+    pub fn defaults(&self) -> Config {
+        let mut out = Config::new();
+        out.data = self
+            .keys()
+            .into_iter()
+            .filter_map(|(section, i)| {
+                Some(Entry {
+                    section: section.map(normalize_section),
+                    key: normalize_key(i.key),
+                    value: i.default?.to_owned(),
+                })
+            })
+            .collect();
+        out
+    }
+
+    /// Generate an example config file with every key of the schema
+    ///
+    /// Each key has a comment with its doc (if set) and the values it allows.
+    /// Keys with a default are commented out with the default value, as they don't have to be set.
+    /// Required keys without a default are left empty to be filled in, optional ones are commented out.
+    /// Un-sectioned keys come first, then each section in the order it is first used.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, Schema, SchemaKey, ValueType};
+    ///
+    /// const KEYS: &[SchemaKey] = &[
+    ///     SchemaKey::required("host", ValueType::Str).doc("Address to listen on"),
+    ///     SchemaKey::optional("port", ValueType::Int).range(1.0, 65535.0).default("80"),
+    /// ];
+    /// let schema = Schema::section("server", KEYS);
+    ///
+    /// let template = schema.generate_template();
+    /// assert_eq!(
+    ///     template,
+    ///     "[server]\n# Address to listen on\n# text, required\nhost =\n\n# integer, optional, from 1 to 65535\n# port = 80\n"
+    /// );
+    /// assert!(Config::new().text(template).unwrap().get_str("server.host").is_ok());
+    /// ```
+    pub fn generate_template(&self) -> String {
+        let mut keys = self.keys();
+        keys.sort_by_key(|(section, _)| section.is_some());
+
+        let mut out = String::new();
+        let mut sections = Vec::new();
+        for (section, _) in keys.iter() {
+            if !sections.contains(section) {
+                sections.push(*section);
+            }
+        }
+
+        for section in sections {
+            if let Some(i) = section {
+                if !out.is_empty() {
+                    out.push('\n');
+                }
+                out.push_str(&format!("[{}]\n", i));
+            }
+
+            for (n, (_, i)) in keys.iter().filter(|i| i.0 == section).enumerate() {
+                if n > 0 {
+                    out.push('\n');
+                }
+                if let Some(doc) = i.doc {
+                    doc.lines()
+                        .for_each(|j| out.push_str(&format!("# {}\n", j)));
+                }
+
+                out.push_str(&format!("# {}\n", i.describe()));
+                match (i.default, i.required) {
+                    (Some(value), _) => out.push_str(&format!("# {} = {}\n", i.key, value)),
+                    (None, true) => out.push_str(&format!("{} =\n", i.key)),
+                    (None, false) => out.push_str(&format!("# {} =\n", i.key)),
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Check if the schema or any of its parts deny unknown keys
+    fn denies_unknown(&self) -> bool {
+        self.deny_unknown || self.parts.iter().any(|i| i.denies_unknown())
+    }
+
+    /// Check if a config entry is one of the schema's keys
+    ///
+    /// Un-sectioned keys of the schema match the key in any section, like `get`.
+    pub(crate) fn knows(&self, entry: &Entry) -> bool {
+        self.keys().iter().any(|(section, i)| {
+            normalize_key(i.key) == entry.key
+                && (section.is_none() || section.map(normalize_section) == entry.section)
+        })
+    }
+
+    /// Get every key in the schema with the section it is in
+    pub fn keys(&self) -> Vec<(Option<&'static str>, &SchemaKey)> {
+        let mut out = self
+            .keys
+            .iter()
+            .map(|i| (self.section, i))
+            .collect::<Vec<_>>();
+        for i in self.parts.iter() {
+            out.extend(i.keys());
+        }
+
+        out
+    }
+}
+
+impl Add for Schema {
+    type Output = Schema;
+
+    fn add(self, other: Schema) -> Schema {
+        Schema {
+            section: None,
+            keys: &[],
+            parts: vec![self, other],
+            deny_unknown: false,
+        }
+    }
+}
+
+/// Error checking a config against a schema
+///
+/// Lists every key that was missing or invalid, not just the first one.
+#[derive(Debug)]
+pub struct SchemaError {
+    /// The keys that did not match the schema
+    pub keys: Vec<KeyError>,
+    /// Dotted names of keys that are not in the schema, if it denies unknown keys
+    pub unknown: Vec<String>,
+}
+
+/// A single key that did not match a schema
+#[derive(Debug)]
+pub struct KeyError {
+    /// Section the key was looked for in
+    pub section: Option<&'static str>,
+    /// The key
+    pub key: &'static str,
+    /// Why the key did not match (`NoItem`, `ParseError` or `InvalidValue`)
+    pub error: ConfigError,
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Invalid config:")?;
+        for i in self.keys.iter() {
+            let key = match i.section {
+                Some(section) => format!("[{}] {}", section, i.key),
+                None => i.key.to_owned(),
+            };
+
+            match i.error {
+                ConfigError::NoItem => writeln!(f, " - `{}` is missing", key)?,
+                _ => writeln!(f, " - `{}` is invalid ({})", key, i.error)?,
+            }
+        }
+
+        for i in self.unknown.iter() {
+            writeln!(f, " - `{}` is not a known key", i)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Check the config against a schema
+    ///
+    /// Un-sectioned keys are looked for in the whole config (like `get`),
+    /// keys of section schemas only in their section.
+    /// Missing keys with a default are not errors.
+    pub fn validate(&self, schema: &Schema) -> Result<(), SchemaError> {
+        let mut keys = Vec::new();
+        for (section, i) in schema.keys() {
+            let name = section.map(normalize_section);
+            let error = match self.entry(name.as_deref(), i.key) {
+                Ok(entry) => match i.check(&entry.value) {
+                    Some(e) => e,
+                    None => continue,
+                },
+                Err(ConfigError::NoItem) if !i.required || i.default.is_some() => continue,
+                Err(e) => e,
+            };
+
+            keys.push(KeyError {
+                section,
+                key: i.key,
+                error,
+            });
+        }
+
+        let mut unknown = Vec::new();
+        if schema.denies_unknown() {
+            for i in self.data.iter().filter(|i| !schema.knows(i)) {
+                if !unknown.contains(&i.name()) {
+                    unknown.push(i.name());
+                }
+            }
+        }
+
+        match keys.is_empty() && unknown.is_empty() {
+            true => Ok(()),
+            false => Err(SchemaError { keys, unknown }),
+        }
+    }
+}
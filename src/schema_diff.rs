@@ -0,0 +1,198 @@
+//! Comparing the schemas of two versions of an application
+use std::fmt::{self, Write};
+
+use crate::{Schema, SchemaKey, ValueType};
+
+/// How a key changed between two schemas
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaChange {
+    /// The key is only in the new schema
+    Added(String, SchemaKey),
+    /// The key is only in the old schema
+    Removed(String, SchemaKey),
+    /// The key's value has a different type, the old then new type
+    Retyped(String, ValueType, ValueType),
+}
+
+/// The keys that differ between two schemas, made by `Schema::diff`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaDiff {
+    /// Every key that was added, removed or retyped
+    /// Keys of the old schema come first (in the order they are in it), then the added keys.
+    pub changes: Vec<SchemaChange>,
+}
+
+impl Schema {
+    /// Get the keys that differ from the schema of another version, like the next release
+    ///
+    /// This schema is the old one, so keys only in `new` are added.
+    /// Keys are named by their dotted name (`section.key`).
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Schema, SchemaKey, ValueType};
+    ///
+    /// const OLD: Schema = Schema::section("server", &[
+    ///     SchemaKey::required("port", ValueType::Str),
+    ///     SchemaKey::optional("threads", ValueType::Int),
+    /// ]);
+    /// const NEW: Schema = Schema::section("server", &[
+    ///     SchemaKey::required("port", ValueType::Int),
+    ///     SchemaKey::optional("workers", ValueType::Int).default("4"),
+    /// ]);
+    ///
+    /// let diff = OLD.diff(&NEW);
+    /// assert_eq!(
+    ///     diff.upgrade_note(),
+    ///     "- `server.port` is now integer (was text)\n\
+    ///      - `server.threads` was removed\n\
+    ///      - `server.workers` was added (integer, optional, default `4`)\n"
+    /// );
+    /// ```
+    pub fn diff(&self, new: &Schema) -> SchemaDiff {
+        let old = named_keys(self);
+        let new = named_keys(new);
+
+        let mut changes = Vec::new();
+        for (name, key) in old.iter() {
+            match new.iter().find(|i| i.0 == *name) {
+                Some((_, i)) if i.kind != key.kind => {
+                    changes.push(SchemaChange::Retyped(name.to_owned(), key.kind, i.kind))
+                }
+                Some(_) => {}
+                None => changes.push(SchemaChange::Removed(name.to_owned(), **key)),
+            }
+        }
+
+        for (name, key) in new {
+            if !old.iter().any(|i| i.0 == name) {
+                changes.push(SchemaChange::Added(name, *key));
+            }
+        }
+
+        SchemaDiff { changes }
+    }
+}
+
+impl SchemaDiff {
+    /// Check if the schemas have the same keys
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Describe the changes for release notes, one change per line
+    pub fn upgrade_note(&self) -> String {
+        let mut out = String::new();
+        for i in self.changes.iter() {
+            let _ = match i {
+                SchemaChange::Added(name, key) => match key.default {
+                    Some(default) => writeln!(
+                        out,
+                        "- `{}` was added ({}, default `{}`)",
+                        name,
+                        key.describe(),
+                        default
+                    ),
+                    None => writeln!(out, "- `{}` was added ({})", name, key.describe()),
+                },
+                SchemaChange::Removed(name, _) => writeln!(out, "- `{}` was removed", name),
+                SchemaChange::Retyped(name, old, new) => writeln!(
+                    out,
+                    "- `{}` is now {} (was {})",
+                    name,
+                    new.name(),
+                    old.name()
+                ),
+            };
+        }
+
+        out
+    }
+
+    /// Write the start of a function to update configs from the old schema, to be filled in
+    ///
+    /// Removed un-sectioned keys are removed,
+    /// and there are `TODO` comments for other removed keys, retyped keys and required keys without a default.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Schema, SchemaKey, ValueType};
+    ///
+    /// const OLD: Schema = Schema::new(&[SchemaKey::optional("threads", ValueType::Int)]);
+    /// const NEW: Schema = Schema::new(&[SchemaKey::required("workers", ValueType::Int)]);
+    ///
+    /// assert_eq!(
+    ///     OLD.diff(&NEW).migration_stub(),
+    ///     "/// Update a config from the old schema to the new one
+    /// fn migrate(cfg: &mut Config) {
+    ///     // `threads` was removed
+    ///     cfg.remove(\"threads\");
+    ///
+    ///     // `workers` was added
+    ///     // TODO: cfg.set(\"workers\", ...);
+    /// }
+    /// "
+    /// );
+    /// ```
+    pub fn migration_stub(&self) -> String {
+        let mut out = "/// Update a config from the old schema to the new one\n".to_owned();
+        out.push_str("fn migrate(cfg: &mut Config) {\n");
+
+        let mut first = true;
+        for i in self.changes.iter() {
+            let lines = match i {
+                SchemaChange::Added(name, key) if key.required && key.default.is_none() => {
+                    format!(
+                        "// `{}` was added\n// TODO: cfg.set(\"{}\", ...);",
+                        name, name
+                    )
+                }
+                SchemaChange::Added(..) => continue,
+                // `remove` takes un-sectioned keys
+                SchemaChange::Removed(name, key) if *name == key.key => {
+                    format!("// `{}` was removed\ncfg.remove(\"{}\");", name, name)
+                }
+                SchemaChange::Removed(name, _) => {
+                    format!("// `{}` was removed\n// TODO: remove `{}`", name, name)
+                }
+                SchemaChange::Retyped(name, old, new) => format!(
+                    "// `{}` is now {} (was {})\n// TODO: convert the value of `{}`",
+                    name,
+                    new.name(),
+                    old.name(),
+                    name
+                ),
+            };
+
+            if !first {
+                out.push('\n');
+            }
+            first = false;
+            for line in lines.lines() {
+                let _ = writeln!(out, "    {}", line);
+            }
+        }
+
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Lines like `upgrade_note`
+impl fmt::Display for SchemaDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.upgrade_note())
+    }
+}
+
+/// Get every key of a schema with its dotted name
+fn named_keys(schema: &Schema) -> Vec<(String, &SchemaKey)> {
+    schema
+        .keys()
+        .into_iter()
+        .map(|(section, i)| match section {
+            Some(section) => (format!("{}.{}", section, i.key), i),
+            None => (i.key.to_owned(), i),
+        })
+        .collect()
+}
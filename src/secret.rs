@@ -0,0 +1,160 @@
+//! Secret values, resolved from a backend when read
+//!
+//! A value of `!secret:name` is replaced with the secret `name` by `Config::get_resolved`,
+//! and keys holding secrets are redacted when the config is summarized.
+use std::fmt;
+
+use crate::bundle::REDACTED;
+use crate::config::Entry;
+use crate::events::glob_match;
+use crate::layout;
+use crate::{Config, Resolver};
+
+/// What values that are secrets start with
+pub(crate) const PREFIX: &str = "!secret:";
+
+impl Config {
+    /// Set the backend for `!secret:name` values, read with `Config::get_resolved`
+    ///
+    /// The resolver gets the name after `!secret:` and can read it from anywhere (the environment, a file, a KMS...).
+    /// Secrets are cached like other resolved values (see `Config::resolver_ttl`), and `get_str` still returns `!secret:name`.
+    /// Keys with secret values are always sensitive, see `Config::sensitive`.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, ConfigError};
+    ///
+    /// let cfg = Config::new()
+    ///     .secrets(|name: &str| match name {
+    ///         "db_pass" => Ok("hunter2".to_owned()),
+    ///         _ => Err(ConfigError::NoItem),
+    ///     })
+    ///     .text("[db]\npassword = !secret:db_pass\nuser = admin")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get_resolved("db.password").unwrap(), "hunter2");
+    /// assert_eq!(cfg.get_resolved("db.user").unwrap(), "admin");
+    /// assert!(cfg.is_sensitive("db.password"));
+    /// ```
+    pub fn secrets<R>(mut self, resolver: R) -> Self
+    where
+        R: Resolver + 'static,
+    {
+        self.resolvers.secrets = Some(Box::new(resolver));
+        self
+    }
+
+    /// Mark the keys matching `pattern` as sensitive, so their values are redacted in summaries
+    ///
+    /// Patterns are matched against the dotted name of keys, `*` matches any number of chars and `?` one char.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .sensitive("*.api_key")
+    ///     .text("[mail]\napi_key = abc\nhost = smtp")
+    ///     .unwrap();
+    ///
+    /// assert!(cfg.is_sensitive("mail.api_key"));
+    /// assert!(!cfg.is_sensitive("mail.host"));
+    /// ```
+    pub fn sensitive(mut self, pattern: &str) -> Self {
+        self.sensitive
+            .push(self.key_style.lookup(pattern).into_owned());
+        self
+    }
+
+    /// Get the config written like `Display` (the format it's saved in), with the values of secrets and sensitive keys redacted
+    ///
+    /// Keys are redacted like they are for `{:?}`, so this can be logged when the layout of the file matters.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .sensitive("*.dsn")
+    ///     .text("# Database\n[db]\npassword = hunter2 ; Rotated monthly\ndsn = pg://a:b@db\nhost = db")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     cfg.redacted().to_string(),
+    ///     "# Database\n[db]\npassword = <redacted> ; Rotated monthly\ndsn = <redacted>\nhost = db\n"
+    /// );
+    /// ```
+    pub fn redacted(&self) -> impl fmt::Display + '_ {
+        Redacted(self)
+    }
+
+    /// Check if a value matches the secret in a key, in constant time
+    ///
+    /// The time taken only depends on the length of `provided`, not on how much of it matches,
+    /// so it can be used to check passwords and tokens.
+    /// The secret is read with `Config::get_resolved`, so it can be a `!secret:` or `${name:reference}`.
+    /// Returns `false` if the key is not defined or can't be resolved.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new().text("api_key = hunter2").unwrap();
+    ///
+    /// assert!(cfg.secret_eq("api_key", "hunter2"));
+    /// assert!(!cfg.secret_eq("api_key", "hunter3"));
+    /// assert!(!cfg.secret_eq("missing", ""));
+    /// ```
+    pub fn secret_eq(&self, key: &str, provided: &str) -> bool {
+        match self.get_resolved(key) {
+            Ok(secret) => constant_time_eq(secret.as_bytes(), provided.as_bytes()),
+            Err(_) => false,
+        }
+    }
+
+    /// Check if a key has been marked as sensitive with `Config::sensitive`, or its value is a `!secret:`
+    pub fn is_sensitive(&self, key: &str) -> bool {
+        let entry = self.find_entry(None, key);
+        if entry.is_some_and(|i| i.value.starts_with(PREFIX)) {
+            return true;
+        }
+
+        let name = entry.map_or_else(|| self.key_style.lookup(key).into_owned(), |i| i.name());
+        self.sensitive
+            .iter()
+            .any(|i| glob_match(i.as_bytes(), name.as_bytes()))
+    }
+}
+
+/// Compare a secret to a value, taking the same time for any value of the same length
+fn constant_time_eq(secret: &[u8], provided: &[u8]) -> bool {
+    let mut diff = (secret.len() != provided.len()) as u8;
+    for (n, i) in provided.iter().enumerate() {
+        let expected = secret.get(n).copied().unwrap_or(0);
+        diff |= std::hint::black_box(expected ^ i);
+    }
+
+    std::hint::black_box(diff) == 0
+}
+
+/// A config written like `Display` with sensitive values redacted, see `Config::redacted`
+struct Redacted<'a>(&'a Config);
+
+impl fmt::Display for Redacted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cfg = self.0;
+        let data = cfg
+            .data
+            .iter()
+            .map(|i| match cfg.is_redacted(i) {
+                true => Entry {
+                    value: REDACTED.to_owned(),
+                    ..i.clone()
+                },
+                false => i.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        layout::render(f, &cfg.layout, &data, cfg.annotate_overrides)
+    }
+}
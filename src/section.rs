@@ -0,0 +1,165 @@
+//! A view into a single section of a config
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::config::{self, Entry};
+use crate::{Config, ConfigError, FromConfigValue};
+
+/// A view of the keys in one section
+///
+/// Get one with `cfg.section("name")`.
+pub struct Section<'a> {
+    config: &'a Config,
+    name: String,
+}
+
+impl<'a> Section<'a> {
+    /// Create a new section view
+    pub(crate) fn new(config: &'a Config, name: String) -> Self {
+        Section { config, name }
+    }
+
+    /// Get the name of the section
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get a value from the section as any type (That Impls FromConfigValue)
+    pub fn get<T>(&self, key: &str) -> Result<T, ConfigError>
+    where
+        T: FromConfigValue,
+    {
+        self.config.located(self.entry(key), |i| {
+            config::parse_value(i, self.config.numbers)
+        })
+    }
+
+    /// Get a value from the section as a String
+    pub fn get_str(&self, key: &str) -> Result<String, ConfigError> {
+        self.entry(key).map(|i| i.value.to_owned())
+    }
+
+    /// Get a value from the section as raw bytes
+    /// See `Config::get_bytes` for the accepted formats.
+    pub fn get_bytes(&self, key: &str) -> Result<Vec<u8>, ConfigError> {
+        self.config.located(self.entry(key), config::bytes_value)
+    }
+
+    /// Get a value from the section as a list
+    /// See `Config::get_vec` for how it is split.
+    pub fn get_vec<T>(&self, key: &str) -> Result<Vec<T>, ConfigError>
+    where
+        T: FromConfigValue,
+    {
+        self.config.located(self.entry(key), |i| {
+            config::vec_value(i, self.config.list_delimiter, self.config.numbers)
+        })
+    }
+
+    /// Get a value from the section as a bool
+    /// See `Config::get_bool` for the accepted values.
+    pub fn get_bool(&self, key: &str) -> Result<bool, ConfigError> {
+        self.config.located(self.entry(key), config::bool_value)
+    }
+
+    /// Get a value from the section as a duration
+    /// See `Config::get_duration` for the accepted units.
+    pub fn get_duration(&self, key: &str) -> Result<Duration, ConfigError> {
+        self.config.located(self.entry(key), config::duration_value)
+    }
+
+    /// Get a value from the section as a size in bytes
+    /// See `Config::get_byte_size` for the accepted units.
+    pub fn get_byte_size(&self, key: &str) -> Result<u64, ConfigError> {
+        self.config
+            .located(self.entry(key), config::byte_size_value)
+    }
+
+    /// Get a value from the section as a path
+    /// See `Config::get_path` for how it is resolved.
+    pub fn get_path(&self, key: &str) -> Result<PathBuf, ConfigError> {
+        self.config
+            .located(self.entry(key), |i| self.config.path_value(i))
+    }
+
+    /// Get the keys defined in the section (in definition order, without duplicates)
+    pub fn keys(&self) -> Vec<&'a str> {
+        let mut keys = Vec::new();
+        for i in self.config.entries() {
+            if i.section.as_deref() == Some(&self.name) && !keys.contains(&i.key.as_str()) {
+                keys.push(i.key.as_str());
+            }
+        }
+
+        keys
+    }
+
+    /// Get the keys of the section with their values in use, for sections used as dictionaries
+    pub fn to_map(&self) -> BTreeMap<String, String> {
+        self.keys()
+            .into_iter()
+            .filter_map(|i| Some((i.to_owned(), self.entry(i).ok()?.value.to_owned())))
+            .collect()
+    }
+
+    /// Get the keys of the section with their values converted into any type (That Impls FromConfigValue)
+    ///
+    /// Fails with the error of the first value that can't be converted.
+    pub fn to_map_of<T>(&self) -> Result<BTreeMap<String, T>, ConfigError>
+    where
+        T: FromConfigValue,
+    {
+        self.keys()
+            .into_iter()
+            .filter_map(|i| match self.get(i) {
+                Err(ConfigError::NoItem) => None,
+                value => Some(value.map(|value| (i.to_owned(), value))),
+            })
+            .collect()
+    }
+
+    /// Find the most recent entry for a key in this section
+    fn entry(&self, key: &str) -> Result<&'a Entry, ConfigError> {
+        self.config.entry(Some(&self.name), key)
+    }
+}
+
+impl Config {
+    /// Get the keys of a section with their values in use, for sections used as dictionaries (like HTTP headers)
+    ///
+    /// Returns an empty map if the section is not defined.
+    /// Keys are normalized like every other key, use `ConfigBuilder::case_sensitive` to keep their case.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .text("[headers]\nx-request-source = app\naccept = text/html\n[limits]\nfiles = 10\nusers = 5")
+    ///     .unwrap();
+    ///
+    /// let headers = cfg.section_as_map("headers");
+    /// assert_eq!(headers["accept"], "text/html");
+    /// assert_eq!(headers.len(), 2);
+    ///
+    /// let limits = cfg.section_as_map_of::<u32>("limits").unwrap();
+    /// assert_eq!(limits.values().sum::<u32>(), 15);
+    /// ```
+    pub fn section_as_map(&self, name: &str) -> BTreeMap<String, String> {
+        self.section(name).map(|i| i.to_map()).unwrap_or_default()
+    }
+
+    /// Get the keys of a section with their values converted into any type (That Impls FromConfigValue)
+    ///
+    /// Returns an empty map if the section is not defined, see `Config::section_as_map`.
+    pub fn section_as_map_of<T>(&self, name: &str) -> Result<BTreeMap<String, T>, ConfigError>
+    where
+        T: FromConfigValue,
+    {
+        match self.section(name) {
+            Some(i) => i.to_map_of(),
+            None => Ok(BTreeMap::new()),
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! A config shared between threads, replaced as a whole when it is reloaded
+use std::sync::{mpsc, Arc, Mutex, RwLock, RwLockReadGuard};
+
+use crate::events::{self, Subscriber};
+use crate::instrument;
+use crate::{ChangeSink, Changes, Config, ConfigError};
+
+/// A handle to a config shared between threads
+///
+/// Cloning the handle is cheap and every clone sees the same config.
+/// Readers get the config in use with `SharedConfig::load` and keep it (as an `Arc`) for as long as they need,
+/// while `SharedConfig::replace` / `SharedConfig::reload` swap in a new config at once for the readers after them.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use simple_config_parser::{Config, SharedConfig};
+///
+/// let shared = SharedConfig::new(Config::new().text("workers = 4").unwrap());
+///
+/// let handle = shared.clone();
+/// std::thread::spawn(move || {
+///     assert_eq!(handle.load().get::<u8>("workers").unwrap(), 4);
+/// })
+/// .join()
+/// .unwrap();
+///
+/// shared.reload(|| Config::new().text("workers = 8")).unwrap();
+/// assert_eq!(shared.load().get::<u8>("workers").unwrap(), 8);
+/// ```
+#[derive(Clone)]
+pub struct SharedConfig {
+    inner: Arc<Inner>,
+}
+
+/// The config in use and the subscribers to its replacements
+struct Inner {
+    config: RwLock<Arc<Config>>,
+    subscribers: Mutex<Vec<Subscriber>>,
+}
+
+impl SharedConfig {
+    /// Share a config
+    pub fn new(config: Config) -> Self {
+        SharedConfig {
+            inner: Arc::new(Inner {
+                config: RwLock::new(Arc::new(config)),
+                subscribers: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Get the config in use
+    ///
+    /// It's kept for as long as the `Arc` is, even if the config is replaced,
+    /// so a request can be handled with one config from start to end.
+    pub fn load(&self) -> Arc<Config> {
+        self.read().clone()
+    }
+
+    /// Lock the config in use for reading
+    ///
+    /// Replacing the config waits until the guard is dropped, use `SharedConfig::load` to not hold it up.
+    pub fn read(&self) -> RwLockReadGuard<'_, Arc<Config>> {
+        self.inner.config.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Replace the config in use with a new one, returning the old one
+    ///
+    /// Subscribers (see `SharedConfig::subscribe`) get the values that differ between them,
+    /// in the order the configs were replaced even if that is from more than one thread.
+    pub fn replace(&self, config: Config) -> Arc<Config> {
+        // Held until the changes are sent, so replacements one after another send them in the same order
+        let mut subscribers = self
+            .inner
+            .subscribers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        let config = Arc::new(config);
+        let old = {
+            let mut current = self.inner.config.write().unwrap_or_else(|e| e.into_inner());
+            std::mem::replace(&mut *current, config.clone())
+        };
+
+        if !subscribers.is_empty() {
+            events::send(&mut subscribers, &old.diff(&config).changes);
+        }
+        instrument::replaced(&old, &config);
+
+        old
+    }
+
+    /// Load a new config and replace the one in use with it
+    ///
+    /// If loading fails the config in use is kept and the error is returned,
+    /// so a broken edit to a file doesn't take down a running service.
+    /// Meant to be called from a file watcher or a signal handler to hot reload the config.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, SharedConfig};
+    ///
+    /// let shared = SharedConfig::new(Config::new().text("port = 80").unwrap());
+    /// let changes = shared.subscribe(&["port"]);
+    ///
+    /// assert!(shared.reload(|| Config::new().text("[broken")).is_err());
+    /// assert_eq!(shared.load().get::<u16>("port").unwrap(), 80);
+    ///
+    /// shared.reload(|| Config::new().text("port = 8080")).unwrap();
+    /// let event = changes.try_recv().unwrap();
+    /// assert_eq!(event.changes[0].new.as_deref(), Some("8080"));
+    /// ```
+    pub fn reload<F>(&self, load: F) -> Result<(), ConfigError>
+    where
+        F: FnOnce() -> Result<Config, ConfigError>,
+    {
+        self.replace(load()?);
+        Ok(())
+    }
+
+    /// Subscribe to the changes made by replacing the config
+    ///
+    /// `filters` work like they do for `Config::subscribe`.
+    /// Changes made to a config with `set` before it is shared are not included.
+    pub fn subscribe(&self, filters: &[&str]) -> Changes {
+        let (tx, rx) = mpsc::channel();
+        self.subscribe_with(filters, tx);
+        Changes::new(rx)
+    }
+
+    /// Subscribe to the changes made by replacing the config, sending them to a channel
+    ///
+    /// Works like `Config::subscribe_with`, with an event for each replacement that changed a value.
+    pub fn subscribe_with<T>(&self, filters: &[&str], sink: T)
+    where
+        T: ChangeSink + 'static,
+    {
+        self.inner
+            .subscribers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Subscriber::new(filters, sink));
+    }
+}
+
+impl From<Config> for SharedConfig {
+    fn from(config: Config) -> Self {
+        SharedConfig::new(config)
+    }
+}
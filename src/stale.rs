@@ -0,0 +1,85 @@
+//! Checks for configs that may be out of date
+#[cfg(feature = "file")]
+use std::fs;
+use std::time::{Duration, SystemTime};
+
+use crate::Config;
+
+/// Limits used by `Config::check_stale`
+///
+/// Limits that are `None` are not checked.
+#[derive(Debug, Clone, Default)]
+pub struct StalePolicy {
+    /// Warn if the config file was last modified longer ago than this
+    pub max_file_age: Option<Duration>,
+    /// Warn if the config file was loaded (or `revalidate`d) longer ago than this
+    pub max_unvalidated: Option<Duration>,
+}
+
+/// Why a config is stale
+#[derive(Debug, Clone, PartialEq)]
+pub enum Staleness {
+    /// The config file was last modified this long ago
+    OldFile(Duration),
+    /// The config file was last loaded / validated this long ago
+    NotRevalidated(Duration),
+}
+
+impl Config {
+    /// Check if the config file looks out of date
+    ///
+    /// Returns a warning for every limit of the policy that was passed.
+    /// Meant to be called at startup (and then periodically) to catch machines running forgotten old configs,
+    /// `Watcher::warn_stale` checks a watched config on every poll.
+    /// Configs that were not loaded from a file never count as stale.
+    /// File ages are only checked with the `file` feature.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use std::time::Duration;
+    /// use simple_config_parser::{Config, StalePolicy};
+    ///
+    /// let cfg = Config::new().text("hello = World").unwrap();
+    /// let policy = StalePolicy {
+    ///     max_file_age: Some(Duration::from_secs(60 * 60 * 24 * 365 * 100)),
+    ///     max_unvalidated: Some(Duration::from_secs(60 * 60)),
+    /// };
+    ///
+    /// for i in cfg.check_stale(&policy) {
+    ///     eprintln!("[WARN] Config may be stale: {:?}", i);
+    /// }
+    /// # assert!(cfg.check_stale(&policy).is_empty());
+    /// ```
+    pub fn check_stale(&self, policy: &StalePolicy) -> Vec<Staleness> {
+        let mut out = Vec::new();
+        let now = SystemTime::now();
+
+        #[cfg(feature = "file")]
+        if let (Some(max), Some(path)) = (policy.max_file_age, &self.path) {
+            let modified = fs::metadata(path).and_then(|i| i.modified());
+            if let Ok(age) = modified.map(|i| now.duration_since(i).unwrap_or_default()) {
+                if age > max {
+                    out.push(Staleness::OldFile(age));
+                }
+            }
+        }
+
+        if let (Some(max), Some(validated)) = (policy.max_unvalidated, self.validated) {
+            let age = now.duration_since(validated).unwrap_or_default();
+            if age > max {
+                out.push(Staleness::NotRevalidated(age));
+            }
+        }
+
+        out
+    }
+
+    /// Mark the config as validated now
+    ///
+    /// Use this after checking the config is still current some other way, it resets the `max_unvalidated` timer.
+    pub fn revalidate(&mut self) {
+        if self.path.is_some() {
+            self.validated = Some(SystemTime::now());
+        }
+    }
+}
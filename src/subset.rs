@@ -0,0 +1,72 @@
+//! Copying part of a config into a new one, to pass to a component without the rest of its keys
+use crate::config::Entry;
+use crate::Config;
+
+impl Config {
+    /// Make a new config with only some of the keys of this one
+    ///
+    /// Keys are dotted names (`section.key` for keys in a section) and get the values in use, including ones from defaults.
+    /// Keys that are not defined are left out.
+    /// The new config has the same key and list options, but no file, defaults or subscribers,
+    /// so nothing else of this config can be read or changed from it.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .text("[db]\nhost = localhost\nport = 5432\npassword = hunter2")
+    ///     .unwrap();
+    ///
+    /// let db = cfg.subset(&["db.host", "db.port"]);
+    /// assert_eq!(db.get::<u16>("db.port").unwrap(), 5432);
+    /// assert!(db.get_str("db.password").is_err());
+    /// ```
+    pub fn subset(&self, keys: &[&str]) -> Config {
+        let keys = keys
+            .iter()
+            .map(|i| self.key_style.lookup(i))
+            .collect::<Vec<_>>();
+        self.project(|name| keys.iter().any(|i| *i == name))
+    }
+
+    /// Make a new config with every key of this one except the ones starting with `prefix`
+    ///
+    /// Like `Config::subset`, keys are matched by their dotted name and have the values in use.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .text("name = app\n[internal]\ntoken = abc\n[server]\nport = 80")
+    ///     .unwrap();
+    ///
+    /// let public = cfg.without_prefix("internal.");
+    /// assert_eq!(public.to_string(), "name = app\n[server]\nport = 80\n");
+    /// ```
+    pub fn without_prefix(&self, prefix: &str) -> Config {
+        let prefix = self.key_style.lookup(prefix);
+        self.project(|name| !name.starts_with(&*prefix))
+    }
+
+    /// Copy the entries in use with a dotted name that `keep` accepts into a new config
+    fn project(&self, keep: impl Fn(&str) -> bool) -> Config {
+        let data = self
+            .iter_effective()
+            .filter(|i| !self.is_removed(&i.key) && keep(&i.name()))
+            .cloned()
+            .collect::<Vec<Entry>>();
+
+        let mut out = Config::new();
+        out.key_style = self.key_style;
+        out.list_delimiter = self.list_delimiter;
+        out.numbers = self.numbers;
+        out.comments = self.comments.clone();
+        out.syntax = self.syntax;
+        out.sensitive = self.sensitive.clone();
+        out.placeholder = self.placeholder.clone();
+        out.data = data.into();
+        out
+    }
+}
@@ -0,0 +1,163 @@
+//! A view into the keys under a dotted prefix
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::config::{self, Entry};
+use crate::{Config, ConfigError, FromConfigValue};
+
+/// A view of the keys under a prefix, like `server` for `server.port`
+///
+/// Sections are part of a keys dotted name, so `port` in `[server]` is under `server` too.
+/// Get one with `cfg.subtree("prefix")`.
+pub struct Subtree<'a> {
+    config: &'a Config,
+    prefix: String,
+}
+
+impl<'a> Subtree<'a> {
+    /// Get the prefix of the subtree
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Get a value from the subtree as any type (That Impls FromConfigValue)
+    pub fn get<T>(&self, key: &str) -> Result<T, ConfigError>
+    where
+        T: FromConfigValue,
+    {
+        self.config.located(self.entry(key), |i| {
+            config::parse_value(i, self.config.numbers)
+        })
+    }
+
+    /// Get a value from the subtree as a String
+    pub fn get_str(&self, key: &str) -> Result<String, ConfigError> {
+        self.entry(key).map(|i| i.value.to_owned())
+    }
+
+    /// Get a value from the subtree as raw bytes
+    /// See `Config::get_bytes` for the accepted formats.
+    pub fn get_bytes(&self, key: &str) -> Result<Vec<u8>, ConfigError> {
+        self.config.located(self.entry(key), config::bytes_value)
+    }
+
+    /// Get a value from the subtree as a list
+    /// See `Config::get_vec` for how it is split.
+    pub fn get_vec<T>(&self, key: &str) -> Result<Vec<T>, ConfigError>
+    where
+        T: FromConfigValue,
+    {
+        self.config.located(self.entry(key), |i| {
+            config::vec_value(i, self.config.list_delimiter, self.config.numbers)
+        })
+    }
+
+    /// Get a value from the subtree as a bool
+    /// See `Config::get_bool` for the accepted values.
+    pub fn get_bool(&self, key: &str) -> Result<bool, ConfigError> {
+        self.config.located(self.entry(key), config::bool_value)
+    }
+
+    /// Get a value from the subtree as a duration
+    /// See `Config::get_duration` for the accepted units.
+    pub fn get_duration(&self, key: &str) -> Result<Duration, ConfigError> {
+        self.config.located(self.entry(key), config::duration_value)
+    }
+
+    /// Get a value from the subtree as a size in bytes
+    /// See `Config::get_byte_size` for the accepted units.
+    pub fn get_byte_size(&self, key: &str) -> Result<u64, ConfigError> {
+        self.config
+            .located(self.entry(key), config::byte_size_value)
+    }
+
+    /// Get a value from the subtree as a path
+    /// See `Config::get_path` for how it is resolved.
+    pub fn get_path(&self, key: &str) -> Result<PathBuf, ConfigError> {
+        self.config
+            .located(self.entry(key), |i| self.config.path_value(i))
+    }
+
+    /// Check if a key is defined in the subtree
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.config.find_entry(None, &self.path(key)).is_some()
+    }
+
+    /// Get the keys under the prefix, relative to it (in definition order, without duplicates)
+    pub fn keys(&self) -> Vec<String> {
+        keys(self.config, &self.prefix)
+    }
+
+    /// Get a view of the keys under a prefix of this subtree
+    ///
+    /// Returns `None` if no keys are under it.
+    pub fn subtree(&self, prefix: &str) -> Option<Subtree<'a>> {
+        self.config.subtree(&self.path(prefix))
+    }
+
+    /// Get the full dotted name of a key in the subtree
+    fn path(&self, key: &str) -> String {
+        format!("{}.{}", self.prefix, key)
+    }
+
+    /// Find the most recent entry for a key in this subtree
+    fn entry(&self, key: &str) -> Result<&'a Entry, ConfigError> {
+        self.config.entry(None, &self.path(key))
+    }
+}
+
+/// Get the keys under a prefix, relative to it
+fn keys(config: &Config, prefix: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    for i in config.entries() {
+        let name = i.name();
+        let key = match name.strip_prefix(prefix).and_then(|i| i.strip_prefix('.')) {
+            Some(key) => key.to_owned(),
+            None => continue,
+        };
+
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+
+    keys
+}
+
+impl Config {
+    /// Get a view of the keys under a dotted prefix
+    ///
+    /// Keys are split on `.`, with the section as the first part of a keys name.
+    /// So both `server.port = 80` and `port = 80` in `[server]` are `port` in `cfg.subtree("server")`.
+    /// Returns `None` if no keys are under the prefix.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .text("server.host = localhost\n[server]\nport = 80\ntls.enabled = true")
+    ///     .unwrap();
+    ///
+    /// let server = cfg.subtree("server").unwrap();
+    /// assert_eq!(server.get_str("host").unwrap(), "localhost");
+    /// assert_eq!(server.get::<u16>("port").unwrap(), 80);
+    /// assert_eq!(server.keys(), ["host", "port", "tls.enabled"]);
+    ///
+    /// // Subtrees can be nested, and keys are reachable by their full name
+    /// assert!(server.subtree("tls").unwrap().get::<bool>("enabled").unwrap());
+    /// assert_eq!(cfg.get::<u16>("server.port").unwrap(), 80);
+    /// ```
+    pub fn subtree(&self, prefix: &str) -> Option<Subtree<'_>> {
+        let prefix = self.key_style.key(prefix);
+        let prefix = prefix.trim_matches('.');
+        if keys(self, prefix).is_empty() {
+            return None;
+        }
+
+        Some(Subtree {
+            config: self,
+            prefix: prefix.to_owned(),
+        })
+    }
+}
@@ -0,0 +1,200 @@
+//! Helpers for testing code that uses configs
+//!
+//! Enabled with the `testing` feature, mostly useful as a dev-dependency:
+//! ```toml
+//! [dev-dependencies]
+//! simple_config_parser = { version = "1.0.0", features = ["testing"] }
+//! ```
+use std::fmt::{Debug, Display};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::encoding;
+use crate::{Config, ConfigError, FromConfigValue};
+
+mod generate;
+pub use generate::{ConfigGen, Mutation, ValueKind};
+
+/// Counter used to give each temp file a unique name
+static TEMP_FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// Builder for configs made of typed values
+///
+/// Get one with `Config::fake()`.
+#[derive(Debug, Default)]
+pub struct FakeConfig {
+    /// Un-sectioned key value pairs
+    values: Vec<(String, String)>,
+    /// Sections with their key value pairs
+    sections: Vec<(String, Vec<(String, String)>)>,
+}
+
+/// A file in the temp directory that is deleted when dropped
+#[derive(Debug)]
+pub struct TempFile {
+    path: PathBuf,
+}
+
+impl Config {
+    /// Start building a fake config from typed values
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{assert_key_eq, Config};
+    ///
+    /// let cfg = Config::fake()
+    ///     .with("port", 8080)
+    ///     .with("debug", true)
+    ///     .with_in("database", "host", "localhost")
+    ///     .build();
+    ///
+    /// assert_key_eq!(cfg, "port", 8080);
+    /// assert_key_eq!(cfg, "debug", true);
+    /// assert_eq!(cfg.section("database").unwrap().get_str("host").unwrap(), "localhost");
+    /// ```
+    pub fn fake() -> FakeConfig {
+        FakeConfig::default()
+    }
+}
+
+impl FakeConfig {
+    /// Add a key value pair
+    pub fn with<T: Display>(mut self, key: &str, value: T) -> Self {
+        self.values
+            .push((key.to_owned(), encoding::quote_value(&value.to_string())));
+        self
+    }
+
+    /// Add a key value pair to a section
+    pub fn with_in<T: Display>(mut self, section: &str, key: &str, value: T) -> Self {
+        let pair = (key.to_owned(), encoding::quote_value(&value.to_string()));
+        match self.sections.iter_mut().find(|i| i.0 == section) {
+            Some(i) => i.1.push(pair),
+            None => self.sections.push((section.to_owned(), vec![pair])),
+        }
+        self
+    }
+
+    /// Get the config as the text of a config file
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for (key, value) in self.values.iter() {
+            out.push_str(&format!("{} = {}\n", key, value));
+        }
+
+        for (section, values) in self.sections.iter() {
+            out.push_str(&format!("[{}]\n", section));
+            for (key, value) in values.iter() {
+                out.push_str(&format!("{} = {}\n", key, value));
+            }
+        }
+
+        out
+    }
+
+    /// Parse the fake values into a real config
+    ///
+    /// Panics if the values don't make a valid config.
+    pub fn build(&self) -> Config {
+        Config::new()
+            .text(self.to_text())
+            .expect("Fake config values are not valid")
+    }
+
+    /// Write the config to a temp file
+    pub fn write_temp(&self) -> io::Result<TempFile> {
+        TempFile::new(self.to_text())
+    }
+}
+
+impl TempFile {
+    /// Write some text to a new temp file
+    pub fn new<T: AsRef<[u8]>>(contents: T) -> io::Result<Self> {
+        let id = TEMP_FILES.fetch_add(1, Ordering::Relaxed);
+        let path =
+            std::env::temp_dir().join(format!("simple_config_parser-{}-{}.cfg", process::id(), id));
+
+        fs::write(&path, contents)?;
+        Ok(TempFile { path })
+    }
+
+    /// Get the path of the temp file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Load the temp file as a config
+    pub fn load(&self) -> Result<Config, ConfigError> {
+        Config::new().file(&self.path)
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Write some config text to a temp file and load it back
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use simple_config_parser::testing;
+///
+/// let cfg = testing::roundtrip("hello = world").unwrap();
+/// assert_eq!(cfg.get_str("hello").unwrap(), "world");
+/// ```
+pub fn roundtrip<T: AsRef<[u8]>>(contents: T) -> Result<Config, ConfigError> {
+    let file = TempFile::new(contents)?;
+    file.load()
+}
+
+/// Check a key parses to the expected value, panicking with a useful message if not
+///
+/// Used by `assert_key_eq!`.
+/// For strings pass a `String` as `&str` can't be parsed into.
+#[track_caller]
+pub fn check_key<T>(cfg: &Config, key: &str, expected: T)
+where
+    T: FromConfigValue + PartialEq + Debug,
+{
+    let raw = match cfg.get_str(key) {
+        Ok(i) => i,
+        Err(_) => panic!("Key `{}` not found in config", key),
+    };
+
+    match T::from_config_value(&raw) {
+        Ok(i) if i == expected => {}
+        Ok(i) => panic!(
+            "Key `{}` is {:?} but expected {:?} (raw value `{}`)",
+            key, i, expected, raw
+        ),
+        Err(_) => panic!(
+            "Key `{}` has value `{}` that can't be parsed like {:?}",
+            key, raw, expected
+        ),
+    }
+}
+
+/// Assert that a config key parses to a value
+///
+/// The key is parsed into the type of the expected value.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use simple_config_parser::{assert_key_eq, Config};
+///
+/// let cfg = Config::new().text("port = 8080\nhost = localhost").unwrap();
+///
+/// assert_key_eq!(cfg, "port", 8080);
+/// assert_key_eq!(cfg, "host", String::from("localhost"));
+/// ```
+#[macro_export]
+macro_rules! assert_key_eq {
+    ($cfg:expr, $key:expr, $value:expr) => {
+        $crate::testing::check_key(&$cfg, $key, $value)
+    };
+}
@@ -0,0 +1,308 @@
+//! Deterministic random config generation
+use std::fmt::Write;
+
+/// The kind of value to generate for a key
+#[derive(Debug, Clone)]
+pub enum ValueKind {
+    /// An integer in the (inclusive) range
+    Int(i64, i64),
+    /// A float in the range
+    Float(f64, f64),
+    /// `true` or `false`
+    Bool,
+    /// An alphanumeric string with a length in the (inclusive) range
+    Str(usize, usize),
+    /// One of the given values
+    OneOf(Vec<String>),
+}
+
+/// How an adversarial config was broken
+#[derive(Debug, Clone, PartialEq)]
+pub enum Mutation {
+    /// A key value line is missing its `=`
+    MissingEquals(String),
+    /// A section header is missing its closing `]`
+    UnclosedSection(String),
+    /// A value that is not valid for its kind
+    BadValue(String),
+    /// A required key was left out
+    MissingKey(String),
+}
+
+/// A key the generator knows about
+#[derive(Debug, Clone)]
+struct GenKey {
+    section: Option<String>,
+    key: String,
+    kind: ValueKind,
+    required: bool,
+}
+
+/// Generates random valid (or almost valid) configs from a schema of keys
+///
+/// The same seed always generates the same configs.
+/// ## Example
+/// ```rust
+/// // Import Lib
+/// use simple_config_parser::testing::{ConfigGen, ValueKind};
+/// use simple_config_parser::Config;
+///
+/// let mut gen = ConfigGen::new(42)
+///     .key("port", ValueKind::Int(1, 65535))
+///     .optional_key("debug", ValueKind::Bool)
+///     .key_in("database", "host", ValueKind::Str(1, 10));
+///
+/// for _ in 0..10 {
+///     let cfg = Config::new().text(gen.generate()).unwrap();
+///     let port = cfg.get::<i64>("port").unwrap();
+///     assert!((1..=65535).contains(&port));
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConfigGen {
+    state: u64,
+    keys: Vec<GenKey>,
+}
+
+impl ConfigGen {
+    /// Create a new generator from a seed
+    pub fn new(seed: u64) -> Self {
+        ConfigGen {
+            // Xorshift can't have a zero state
+            state: seed ^ 0x9E37_79B9_7F4A_7C15,
+            keys: Vec::new(),
+        }
+    }
+
+    /// Add a required key
+    pub fn key(self, key: &str, kind: ValueKind) -> Self {
+        self.add(None, key, kind, true)
+    }
+
+    /// Add a key that may be left out
+    pub fn optional_key(self, key: &str, kind: ValueKind) -> Self {
+        self.add(None, key, kind, false)
+    }
+
+    /// Add a required key in a section
+    pub fn key_in(self, section: &str, key: &str, kind: ValueKind) -> Self {
+        self.add(Some(section), key, kind, true)
+    }
+
+    /// Generate the text of a valid config
+    ///
+    /// Has every required key, some of the optional ones, and random comments, blank lines and spacing.
+    pub fn generate(&mut self) -> String {
+        let lines = self.lines();
+        lines.into_iter().map(|i| i + "\n").collect()
+    }
+
+    /// Generate the text of a config with one thing wrong with it
+    ///
+    /// Returns the text and what was broken.
+    /// The config may still parse (ex. with a `BadValue` or `MissingKey`) but should fail the app's validation.
+    pub fn generate_adversarial(&mut self) -> (String, Mutation) {
+        let mut lines = self.lines();
+        let entries = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| is_entry(i))
+            .map(|(n, _)| n)
+            .collect::<Vec<_>>();
+
+        // Without any keys the only thing to break is a section
+        let kind = match self.keys.is_empty() {
+            true => 1,
+            false => self.below(4),
+        };
+
+        let mutation = match kind {
+            0 if !entries.is_empty() => {
+                let line = entries[self.below(entries.len() as u64) as usize];
+                let broken = lines[line].replacen('=', " ", 1);
+                lines[line] = broken;
+                Mutation::MissingEquals(lines[line].to_owned())
+            }
+            0 | 1 => {
+                let name = self.string(1, 8);
+                lines.push(format!("[{}", name));
+                Mutation::UnclosedSection(name)
+            }
+            2 if self.keys.iter().any(|i| i.required) => {
+                let required = self.keys.iter().filter(|i| i.required).count() as u64;
+                let pick = self.below(required) as usize;
+                let key = self
+                    .keys
+                    .iter()
+                    .filter(|i| i.required)
+                    .nth(pick)
+                    .unwrap()
+                    .clone();
+                let mut section = None;
+                lines.retain(|i| {
+                    if i.starts_with('[') {
+                        section = Some(i[1..i.len() - 1].to_owned());
+                    }
+                    !(section == key.section && is_entry(i) && entry_key(i) == key.key)
+                });
+                Mutation::MissingKey(key.key)
+            }
+            _ => {
+                let pick = self.below(self.keys.len() as u64) as usize;
+                let key = self.keys[pick].clone();
+                let value = self.bad_value(&key.kind);
+                let mut section = None;
+                for i in lines.iter_mut() {
+                    if i.starts_with('[') {
+                        section = Some(i[1..i.len() - 1].to_owned());
+                    }
+                    if section == key.section && is_entry(i) && entry_key(i) == key.key {
+                        *i = format!("{} = {}", key.key, value);
+                    }
+                }
+
+                // Optional keys may not have been generated
+                if !lines.iter().any(|i| is_entry(i) && entry_key(i) == key.key) {
+                    let line = match &key.section {
+                        Some(s) => format!("[{}]\n{} = {}", s, key.key, value),
+                        None => format!("{} = {}", key.key, value),
+                    };
+                    match key.section {
+                        Some(_) => lines.push(line),
+                        None => lines.insert(0, line),
+                    }
+                }
+                Mutation::BadValue(key.key)
+            }
+        };
+
+        let text = lines.into_iter().map(|i| i + "\n").collect();
+        (text, mutation)
+    }
+
+    fn add(mut self, section: Option<&str>, key: &str, kind: ValueKind, required: bool) -> Self {
+        self.keys.push(GenKey {
+            section: section.map(|i| i.to_owned()),
+            key: key.to_owned(),
+            kind,
+            required,
+        });
+        self
+    }
+
+    /// Generate the lines of a valid config
+    fn lines(&mut self) -> Vec<String> {
+        let keys = self.keys.clone();
+        let mut sections: Vec<Option<String>> = vec![None];
+        for i in keys.iter() {
+            if !sections.contains(&i.section) {
+                sections.push(i.section.clone());
+            }
+        }
+
+        let mut lines = Vec::new();
+        for section in sections {
+            if let Some(name) = &section {
+                lines.push(format!("[{}]", name));
+            }
+
+            for i in keys.iter().filter(|i| i.section == section) {
+                if !i.required && self.below(2) == 0 {
+                    continue;
+                }
+
+                if self.below(4) == 0 {
+                    lines.push(format!("# {}", self.string(0, 20)));
+                }
+
+                if self.below(6) == 0 {
+                    lines.push(String::new());
+                }
+
+                let value = self.value(&i.kind);
+                let mut line = String::new();
+                let spaces = |n| " ".repeat(n as usize);
+                let _ = write!(
+                    line,
+                    "{}{}{}={}{}",
+                    spaces(self.below(3)),
+                    i.key,
+                    spaces(self.below(3)),
+                    spaces(self.below(3)),
+                    value
+                );
+
+                if self.below(4) == 0 {
+                    let _ = write!(line, " ; {}", self.string(0, 10));
+                }
+                lines.push(line);
+            }
+        }
+
+        lines
+    }
+
+    /// Generate a valid value
+    fn value(&mut self, kind: &ValueKind) -> String {
+        match kind {
+            ValueKind::Int(min, max) => {
+                let range = (*max as i128 - *min as i128 + 1) as u128;
+                let n = (self.next() as u128 % range) as i128 + *min as i128;
+                n.to_string()
+            }
+            ValueKind::Float(min, max) => {
+                let unit = (self.next() >> 11) as f64 / (1u64 << 53) as f64;
+                (min + (max - min) * unit).to_string()
+            }
+            ValueKind::Bool => (self.below(2) == 0).to_string(),
+            ValueKind::Str(min, max) => self.string(*min, *max),
+            ValueKind::OneOf(values) => values[self.below(values.len() as u64) as usize].clone(),
+        }
+    }
+
+    /// Generate a value that is not valid for its kind
+    fn bad_value(&mut self, kind: &ValueKind) -> String {
+        match kind {
+            ValueKind::Int(_, max) if self.below(2) == 0 && *max < i64::MAX => {
+                (*max as i128 + 1 + self.below(100) as i128).to_string()
+            }
+            ValueKind::Int(..) | ValueKind::Float(..) => format!("{}x", self.below(1000)),
+            ValueKind::Bool => "maybe".to_owned(),
+            ValueKind::Str(_, max) => self.string(max + 1, max + 10),
+            ValueKind::OneOf(values) => format!("not_{}", values.join("_")),
+        }
+    }
+
+    /// Generate an alphanumeric string
+    fn string(&mut self, min: usize, max: usize) -> String {
+        const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+        let len = min + self.below((max - min + 1) as u64) as usize;
+        (0..len)
+            .map(|_| CHARS[self.below(CHARS.len() as u64) as usize] as char)
+            .collect()
+    }
+
+    /// Get a random number below `max`
+    fn below(&mut self, max: u64) -> u64 {
+        self.next() % max
+    }
+
+    /// Xorshift64*
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+}
+
+/// Check if a generated line is a key value pair
+fn is_entry(line: &str) -> bool {
+    let line = line.trim_start();
+    !line.is_empty() && !line.starts_with('#') && !line.starts_with('[')
+}
+
+/// Get the key of a generated key value line
+fn entry_key(line: &str) -> &str {
+    line.split('=').next().unwrap_or_default().trim()
+}
@@ -0,0 +1,269 @@
+//! Parser for a subset of TOML (with the `toml` feature)
+//!
+//! Supports tables, arrays of tables, basic and literal strings, integers, floats, bools and arrays of them.
+//! Tables become sections (`[a.b]` is the section `a.b`) and dotted keys are kept as is.
+//! Each table of an array is numbered like the sections of `[[name]]` in ini files (`[[a]]` is `a.0`, then `a.1`...).
+//! Arrays are stored as their items separated by `, `, so they can be read with `get_vec`.
+use std::collections::BTreeMap;
+
+use crate::config::{self, Entry, KeyStyle};
+use crate::layout::Line;
+use crate::ConfigError;
+
+/// Parse TOML text into entries and lines
+///
+/// Lines are kept in the layout, so unchanged values are written out as they were.
+pub(crate) fn parse(input: &str, keys: KeyStyle) -> Result<(Vec<Entry>, Vec<Line>), ConfigError> {
+    let mut data = Vec::new();
+    let mut layout = Vec::new();
+    let mut section = None;
+    let mut repeated = BTreeMap::new();
+
+    let mut lines = input.lines();
+    while let Some(raw) = lines.next() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            layout.push(Line::Text(raw.to_owned()));
+            continue;
+        }
+
+        // Tables
+        if let Some(rest) = line.strip_prefix('[') {
+            let array = rest.strip_prefix('[');
+            let (name, rest) = parse_key(array.unwrap_or(rest), ']')?;
+            let rest = match array {
+                Some(_) => rest.strip_prefix(']').ok_or(ConfigError::InvalidConfig)?,
+                None => rest,
+            };
+            if !is_comment(rest) {
+                return Err(ConfigError::InvalidConfig);
+            }
+
+            let name = keys.section(&name);
+            section = Some(match array {
+                Some(_) => config::repeated_section(&mut repeated, name),
+                None => name,
+            });
+            layout.push(Line::Section(raw.to_owned()));
+            continue;
+        }
+
+        // Arrays can go over more than one line
+        let (key, rest) = parse_key(line, '=')?;
+        let start = raw.len() - raw.trim_start().len() + (line.len() - rest.trim_start().len());
+        let mut full = raw.to_owned();
+        while full[start..].starts_with('[') && !array_closed(&full[start..]) {
+            full.push('\n');
+            full.push_str(lines.next().ok_or(ConfigError::InvalidConfig)?);
+        }
+
+        let (value, rest) = parse_value(&full[start..], true)?;
+        if !is_comment(rest) {
+            return Err(ConfigError::InvalidConfig);
+        }
+
+        let end = full.len() - rest.len();
+        layout.push(Line::entry(&full, start, end, &value, data.len()));
+        data.push(Entry {
+            section: section.clone(),
+            key: keys.key(&key),
+            value,
+        });
+    }
+
+    Ok((data, layout))
+}
+
+/// Check if the rest of a line is empty or a comment
+fn is_comment(rest: &str) -> bool {
+    let rest = rest.trim();
+    rest.is_empty() || rest.starts_with('#')
+}
+
+/// Parse a (possibly dotted / quoted) key up to `end`, returning the key and the text after `end`
+fn parse_key(text: &str, end: char) -> Result<(String, &str), ConfigError> {
+    let mut parts = Vec::new();
+    let mut rest = text.trim_start();
+
+    loop {
+        let (part, after) = match rest.chars().next() {
+            Some('"') | Some('\'') => parse_string(rest)?,
+            _ => {
+                let len = rest
+                    .find(|i: char| !(i.is_ascii_alphanumeric() || i == '_' || i == '-'))
+                    .unwrap_or(rest.len());
+                if len == 0 {
+                    return Err(ConfigError::InvalidConfig);
+                }
+                (rest[..len].to_owned(), &rest[len..])
+            }
+        };
+        parts.push(part);
+
+        rest = after.trim_start();
+        match rest.chars().next() {
+            Some('.') => rest = rest[1..].trim_start(),
+            Some(i) if i == end => return Ok((parts.join("."), &rest[1..])),
+            _ => return Err(ConfigError::InvalidConfig),
+        }
+    }
+}
+
+/// Check if an array's brackets are balanced (ignoring any in strings or comments)
+fn array_closed(text: &str) -> bool {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut chars = text.chars();
+
+    while let Some(i) = chars.next() {
+        match (quote, i) {
+            (Some('"'), '\\') => {
+                chars.next();
+            }
+            (Some(q), i) if q == i => quote = None,
+            (Some(_), _) => {}
+            (None, '"') | (None, '\'') => quote = Some(i),
+            (None, '#') => {
+                // Skip to the end of the line
+                for i in chars.by_ref() {
+                    if i == '\n' {
+                        break;
+                    }
+                }
+            }
+            (None, '[') => depth += 1,
+            (None, ']') => {
+                depth -= 1;
+                if depth == 0 {
+                    return true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    false
+}
+
+/// Parse a value, returning it as text along with the text after it
+fn parse_value(text: &str, arrays: bool) -> Result<(String, &str), ConfigError> {
+    match text.chars().next() {
+        Some('"') | Some('\'') => parse_string(text),
+        Some('[') if arrays => parse_array(text),
+        _ => {
+            let len = text
+                .find(|i: char| i.is_whitespace() || i == ',' || i == ']' || i == '#')
+                .unwrap_or(text.len());
+            let value = parse_scalar(&text[..len]).ok_or(ConfigError::InvalidConfig)?;
+            Ok((value, &text[len..]))
+        }
+    }
+}
+
+/// Parse an array, with its items joined by `, `
+fn parse_array(text: &str) -> Result<(String, &str), ConfigError> {
+    let mut items = Vec::new();
+    let mut rest = skip_space(&text[1..]);
+
+    loop {
+        if let Some(after) = rest.strip_prefix(']') {
+            return Ok((items.join(", "), after));
+        }
+
+        let (item, after) = parse_value(rest, false)?;
+        items.push(item);
+
+        rest = skip_space(after);
+        match rest.chars().next() {
+            Some(',') => rest = skip_space(&rest[1..]),
+            Some(']') => {}
+            _ => return Err(ConfigError::InvalidConfig),
+        }
+    }
+}
+
+/// Skip whitespace, newlines and comments in arrays
+fn skip_space(mut text: &str) -> &str {
+    loop {
+        text = text.trim_start();
+        match text.strip_prefix('#') {
+            Some(i) => text = i.find('\n').map(|j| &i[j..]).unwrap_or(""),
+            None => return text,
+        }
+    }
+}
+
+/// Parse a basic (`"..."`) or literal (`'...'`) string, returning it and the text after it
+fn parse_string(text: &str) -> Result<(String, &str), ConfigError> {
+    let quote = text.chars().next().ok_or(ConfigError::InvalidConfig)?;
+    let mut out = String::new();
+    let mut chars = text.char_indices().skip(1);
+
+    while let Some((n, i)) = chars.next() {
+        match i {
+            '\n' => break,
+            i if i == quote => return Ok((out, &text[n + 1..])),
+            '\\' if quote == '"' => {
+                let (_, escape) = chars.next().ok_or(ConfigError::InvalidConfig)?;
+                match escape {
+                    'n' => out.push('\n'),
+                    't' => out.push('\t'),
+                    'r' => out.push('\r'),
+                    '"' => out.push('"'),
+                    '\\' => out.push('\\'),
+                    'u' | 'U' => {
+                        let len = if escape == 'u' { 4 } else { 8 };
+                        let hex = (0..len)
+                            .filter_map(|_| chars.next().map(|i| i.1))
+                            .collect::<String>();
+                        let code = u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or(ConfigError::InvalidConfig)?;
+                        out.push(code);
+                    }
+                    _ => return Err(ConfigError::InvalidConfig),
+                }
+            }
+            i => out.push(i),
+        }
+    }
+
+    Err(ConfigError::InvalidConfig)
+}
+
+/// Parse a bool or number, with numbers normalized to how Rust would parse them
+fn parse_scalar(text: &str) -> Option<String> {
+    if text == "true" || text == "false" {
+        return Some(text.to_owned());
+    }
+
+    let number = text.replace('_', "");
+    let (sign, digits) = match number.strip_prefix('-') {
+        Some(i) => ("-", i),
+        None => ("", number.strip_prefix('+').unwrap_or(&number)),
+    };
+
+    for (prefix, radix) in [("0x", 16), ("0o", 8), ("0b", 2)] {
+        if let Some(i) = digits.strip_prefix(prefix) {
+            let value = i64::from_str_radix(i, radix).ok()?;
+            return Some(format!("{}{}", sign, value));
+        }
+    }
+
+    match digits {
+        "inf" | "nan" => return Some(format!("{}{}", sign, digits)),
+        _ if digits.starts_with(|i: char| i.is_ascii_digit()) => {}
+        _ => return None,
+    }
+
+    if number.parse::<i64>().is_ok() || number.parse::<f64>().is_ok() {
+        return Some(
+            digits
+                .strip_prefix('+')
+                .map_or(number.to_owned(), |i| i.to_owned()),
+        );
+    }
+
+    None
+}
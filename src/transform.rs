@@ -0,0 +1,73 @@
+//! Changing or dropping keys as they are parsed, before they are added to the config
+use std::sync::Mutex;
+
+use crate::config::Entry;
+use crate::layout::{self, Line};
+use crate::Config;
+
+/// A callback added with `Config::transform`
+pub(crate) type Transform = Mutex<Box<dyn FnMut(&str, &str) -> Option<String> + Send>>;
+
+impl Config {
+    /// Call `transform` with the dotted name and value of every key in sources loaded after this
+    ///
+    /// It returns the value to load, or `None` to leave the key out (so it is as if it was not defined).
+    /// Transforms run in the order they were added, before any `Config::processor`, and a key left out is not passed to later ones.
+    /// Like processors, saving the config keeps the original text of values that have not been changed,
+    /// and the lines of keys that were left out are kept as they are.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .transform(|_, value| Some(value.trim_matches('\'').to_owned()))
+    ///     .transform(|_, value| match value {
+    ///         "yes" | "on" => Some("true".to_owned()),
+    ///         "no" | "off" => Some("false".to_owned()),
+    ///         _ => Some(value.to_owned()),
+    ///     })
+    ///     .transform(|key, value| (!key.starts_with("internal.")).then(|| value.to_owned()))
+    ///     .text("name = 'app'\ndebug = on\n[internal]\ntoken = abc")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get_str("name").unwrap(), "app");
+    /// assert!(cfg.get::<bool>("debug").unwrap());
+    /// assert!(cfg.get_str("internal.token").is_err());
+    /// ```
+    pub fn transform<F>(mut self, transform: F) -> Self
+    where
+        F: FnMut(&str, &str) -> Option<String> + Send + 'static,
+    {
+        self.transforms.push(Mutex::new(Box::new(transform)));
+        self
+    }
+
+    /// Run the transforms on a source's entries, removing the ones they leave out
+    pub(crate) fn apply_transforms(&self, data: &mut Vec<Entry>, layout: &mut Vec<Line>) {
+        if self.transforms.is_empty() {
+            return;
+        }
+
+        let mut dropped = vec![false; data.len()];
+        for (i, entry) in data.iter_mut().enumerate() {
+            let name = entry.name();
+            for transform in self.transforms.iter() {
+                let mut transform = transform.lock().unwrap_or_else(|e| e.into_inner());
+                match transform(&name, &entry.value) {
+                    Some(value) => entry.value = value,
+                    None => {
+                        dropped[i] = true;
+                        break;
+                    }
+                }
+            }
+        }
+
+        if dropped.contains(&true) {
+            layout::ignore(layout, &dropped);
+            let mut dropped = dropped.into_iter();
+            data.retain(|_| !dropped.next().unwrap_or(false));
+        }
+    }
+}
@@ -0,0 +1,102 @@
+//! Parsing human friendly durations (`2h30m`) and byte sizes (`10MiB`)
+use std::convert::TryFrom;
+use std::time::Duration;
+
+/// Units of a duration and how many nanoseconds they are
+const DURATION_UNITS: [(&str, u128); 8] = [
+    ("ns", 1),
+    ("us", 1_000),
+    ("µs", 1_000),
+    ("ms", 1_000_000),
+    ("s", 1_000_000_000),
+    ("m", 60_000_000_000),
+    ("h", 3_600_000_000_000),
+    ("d", 86_400_000_000_000),
+];
+
+/// Units of a byte size and how many bytes they are (case insensitive)
+/// Single letters and `iB` suffixes are powers of 1024, `B` suffixes are powers of 1000.
+const SIZE_UNITS: [(&str, u64); 14] = [
+    ("", 1),
+    ("b", 1),
+    ("k", 1 << 10),
+    ("kib", 1 << 10),
+    ("kb", 1_000),
+    ("m", 1 << 20),
+    ("mib", 1 << 20),
+    ("mb", 1_000_000),
+    ("g", 1 << 30),
+    ("gib", 1 << 30),
+    ("gb", 1_000_000_000),
+    ("t", 1 << 40),
+    ("tib", 1 << 40),
+    ("tb", 1_000_000_000_000),
+];
+
+/// Parse a duration made of numbers with units, like `30s`, `1.5h` or `2h30m`
+///
+/// Units are `ns`, `us` (or `µs`), `ms`, `s`, `m`, `h` and `d`.
+/// A bare `0` is allowed, other numbers need a unit.
+pub(crate) fn parse_duration(text: &str) -> Option<Duration> {
+    let text = text.trim();
+    if text == "0" {
+        return Some(Duration::ZERO);
+    }
+
+    let mut rest = text;
+    let mut nanos = 0u128;
+    while !rest.is_empty() {
+        let (number, after) = split_number(rest)?;
+        let len = after
+            .find(|i: char| i.is_ascii_digit() || i == '.')
+            .unwrap_or(after.len());
+        let scale = DURATION_UNITS.iter().find(|i| i.0 == &after[..len])?.1;
+
+        nanos = nanos.checked_add(scale_number(number, scale)?)?;
+        rest = &after[len..];
+    }
+
+    let secs = u64::try_from(nanos / 1_000_000_000).ok()?;
+    Some(Duration::new(secs, (nanos % 1_000_000_000) as u32))
+}
+
+/// Parse a byte size like `512`, `512K`, `10MiB` or `1.5GB`
+///
+/// Units are case insensitive, see `SIZE_UNITS`.
+pub(crate) fn parse_byte_size(text: &str) -> Option<u64> {
+    let (number, unit) = split_number(text.trim())?;
+    let unit = unit.trim_start().to_lowercase();
+    let scale = SIZE_UNITS.iter().find(|i| i.0 == unit)?.1;
+
+    u64::try_from(scale_number(number, scale as u128)?).ok()
+}
+
+/// Split a number (digits with an optional fraction) off the start of some text
+fn split_number(text: &str) -> Option<(&str, &str)> {
+    let len = text
+        .find(|i: char| !(i.is_ascii_digit() || i == '.'))
+        .unwrap_or(text.len());
+    match &text[..len] {
+        "" | "." => None,
+        number => Some((number, &text[len..])),
+    }
+}
+
+/// Multiply a number by a scale, rounding any fraction down
+fn scale_number(number: &str, scale: u128) -> Option<u128> {
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    let whole = match whole {
+        "" => 0,
+        i => i.parse::<u128>().ok()?.checked_mul(scale)?,
+    };
+
+    // Each digit of the fraction is a tenth of the last
+    let mut out = whole;
+    let mut place = scale;
+    for i in fraction.chars() {
+        place /= 10;
+        out = out.checked_add(i.to_digit(10)? as u128 * place)?;
+    }
+
+    Some(out)
+}
@@ -0,0 +1,105 @@
+//! Finding keys that are not expected, with suggestions for typos
+use std::fmt;
+
+use crate::Config;
+
+/// A key that is defined but not expected, made by `Config::unknown_keys`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownKey {
+    /// The key, as `section.key` if it is in a section
+    pub key: String,
+    /// The closest expected key, if one is close enough to be a typo
+    pub suggestion: Option<String>,
+}
+
+impl Config {
+    /// Get the keys that are defined but not in `expected`
+    ///
+    /// Expected keys are given as `section.key` for keys in sections.
+    /// Each unknown key is listed once (in the order they were defined), with the expected key it is most likely a typo of.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .text("host = localhost\nprot = 8080\ncolour = red")
+    ///     .unwrap();
+    ///
+    /// let unknown = cfg.unknown_keys(&["host", "port"]);
+    /// assert_eq!(unknown.len(), 2);
+    /// assert_eq!(unknown[0].suggestion.as_deref(), Some("port"));
+    /// assert_eq!(unknown[0].to_string(), "Unknown key `prot`, did you mean `port`?");
+    /// assert_eq!(unknown[1].suggestion, None);
+    /// ```
+    pub fn unknown_keys(&self, expected: &[&str]) -> Vec<UnknownKey> {
+        let expected = expected
+            .iter()
+            .map(|i| self.key_style.key(i))
+            .collect::<Vec<_>>();
+
+        let mut out: Vec<UnknownKey> = Vec::new();
+        for i in self.data.iter() {
+            let key = i.name();
+            if expected.contains(&key) || out.iter().any(|i| i.key == key) {
+                continue;
+            }
+
+            out.push(UnknownKey {
+                suggestion: suggest(&key, expected.iter().map(String::as_str)),
+                key,
+            });
+        }
+
+        out
+    }
+}
+
+/// Find the candidate a name is most likely a typo of
+///
+/// Candidates up to a third of the name's length (at least one) edits away are close enough.
+pub(crate) fn suggest<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Option<String> {
+    let max = (name.chars().count() / 3).max(1);
+    candidates
+        .map(|i| (edit_distance(name, i), i))
+        .filter(|(distance, _)| *distance <= max)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, i)| i.to_owned())
+}
+
+/// Get how many single char insertions, deletions, substitutions or swaps turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+
+    // Rows for the previous two chars of `a` and the current one
+    let mut before = vec![0; b.len() + 1];
+    let mut last = (0..=b.len()).collect::<Vec<_>>();
+    for i in 0..a.len() {
+        let mut row = vec![i + 1; b.len() + 1];
+        for j in 0..b.len() {
+            let cost = usize::from(a[i] != b[j]);
+            row[j + 1] = (last[j] + cost).min(last[j + 1] + 1).min(row[j] + 1);
+
+            // Two chars swapped counts as one edit
+            if i > 0 && j > 0 && a[i] == b[j - 1] && a[i - 1] == b[j] {
+                row[j + 1] = row[j + 1].min(before[j - 1] + 1);
+            }
+        }
+        before = std::mem::replace(&mut last, row);
+    }
+
+    last[b.len()]
+}
+
+/// Like `Unknown key `prot`, did you mean `port`?`
+impl fmt::Display for UnknownKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown key `{}`", self.key)?;
+        if let Some(i) = &self.suggestion {
+            write!(f, ", did you mean `{}`?", i)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+//! Reading config text that is not plain UTF-8
+//!
+//! A byte order mark at the start of text is removed and `\r\n` / `\r` line endings are read as `\n`,
+//! so files edited on Windows have the same keys. Files that are not UTF-8 fail to load,
+//! unless `Config::lossy_utf8` or `Config::latin1` say how to read them.
+use std::borrow::Cow;
+
+use crate::Config;
+
+/// UTF-8 byte order mark, which some editors put at the start of files
+const BOM: char = '\u{feff}';
+
+/// How files that are not valid UTF-8 are read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Decoding {
+    /// Fail with `ConfigError::InvalidUtf8`
+    Strict,
+    /// Replace invalid UTF-8 with `U+FFFD`
+    Replace,
+    /// Read the whole file as Latin-1 (ISO 8859-1)
+    Latin1,
+}
+
+impl Config {
+    /// Replace invalid UTF-8 in files loaded after this with `U+FFFD` instead of failing
+    ///
+    /// By default a file that is not valid UTF-8 fails to load with `ConfigError::InvalidUtf8`,
+    /// so a stray byte in a comment can keep an application from starting.
+    /// With this the file is loaded and the lines with invalid UTF-8 are listed in the `Config::load_report`
+    /// (and logged as a warning with the `log` feature).
+    /// Files that are `!include`d are still checked strictly.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, ConfigError};
+    ///
+    /// # #[cfg(feature = "file")] {
+    /// # let path = std::env::temp_dir().join("scp-lossy-doc.cfg");
+    /// std::fs::write(&path, b"; caf\xe9\nname = app").unwrap();
+    /// assert!(matches!(Config::new().file(&path), Err(ConfigError::InvalidUtf8(1, 5))));
+    ///
+    /// let cfg = Config::new().lossy_utf8(true).file(&path).unwrap();
+    /// assert_eq!(cfg.get_str("name").unwrap(), "app");
+    /// assert_eq!(cfg.load_report().sources[0].invalid_utf8, [1]);
+    /// # std::fs::remove_file(path).unwrap();
+    /// # }
+    /// ```
+    pub fn lossy_utf8(mut self, lossy: bool) -> Self {
+        self.decoding = match lossy {
+            true => Decoding::Replace,
+            false => Decoding::Strict,
+        };
+        self
+    }
+
+    /// Read files loaded after this that are not valid UTF-8 as Latin-1 (ISO 8859-1) instead of failing
+    ///
+    /// Files that are valid UTF-8 are read as normal. Like `Config::lossy_utf8`
+    /// the lines that were not UTF-8 are listed in the `Config::load_report`, and it replaces that option.
+    /// Saving the config writes it as UTF-8.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// # #[cfg(feature = "file")] {
+    /// # let path = std::env::temp_dir().join("scp-latin1-doc.cfg");
+    /// std::fs::write(&path, b"city = M\xfcnchen").unwrap();
+    ///
+    /// let cfg = Config::new().latin1(true).file(&path).unwrap();
+    /// assert_eq!(cfg.get_str("city").unwrap(), "München");
+    /// # std::fs::remove_file(path).unwrap();
+    /// # }
+    /// ```
+    pub fn latin1(mut self, latin1: bool) -> Self {
+        self.decoding = match latin1 {
+            true => Decoding::Latin1,
+            false => Decoding::Strict,
+        };
+        self
+    }
+
+    /// Record the lines with invalid UTF-8 of the source just loaded
+    #[cfg(feature = "file")]
+    pub(crate) fn warn_invalid_utf8(&mut self, lines: Vec<usize>) {
+        if lines.is_empty() {
+            return;
+        }
+
+        if let Some(source) = self.report.sources.last_mut() {
+            #[cfg(feature = "log")]
+            log::warn!(
+                "{} is not valid UTF-8 on lines {:?}, it was read anyway",
+                source.source,
+                lines
+            );
+            source.invalid_utf8 = lines;
+        }
+    }
+}
+
+/// Remove a byte order mark from the start of text and make every line ending `\n`
+pub(crate) fn normalize(text: &str) -> Cow<'_, str> {
+    let text = text.strip_prefix(BOM).unwrap_or(text);
+    match text.contains('\r') {
+        true => Cow::Owned(text.replace("\r\n", "\n").replace('\r', "\n")),
+        false => Cow::Borrowed(text),
+    }
+}
+
+/// Remove a byte order mark from the first of some lines and split lines with `\r` line endings
+pub(crate) fn normalize_lines<E>(
+    lines: impl Iterator<Item = Result<String, E>>,
+) -> impl Iterator<Item = Result<String, E>> {
+    lines.enumerate().flat_map(|(n, i)| {
+        let lines = match i {
+            Ok(i) => {
+                let i = match n {
+                    0 => i.strip_prefix(BOM).unwrap_or(&i).to_owned(),
+                    _ => i,
+                };
+                i.split('\r').map(|i| Ok(i.to_owned())).collect()
+            }
+            Err(e) => vec![Err(e)],
+        };
+        lines.into_iter()
+    })
+}
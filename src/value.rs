@@ -0,0 +1,203 @@
+//! Values with their type inferred from the text
+use std::fmt;
+
+use crate::config::Entry;
+use crate::{Config, ConfigError};
+
+/// A value parsed into the type it looks like, see `Config::get_value` and `Value::infer`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// Text that is not any of the other types
+    Str(String),
+    /// An integer
+    Int(i64),
+    /// A number with a fraction or exponent
+    Float(f64),
+    /// `true` or `false` (in any case)
+    Bool(bool),
+    /// Items in `[..]` separated by `,`, each inferred on its own
+    List(Vec<Value>),
+}
+
+impl Value {
+    /// Infer the type of a value
+    ///
+    /// Only values in `[..]` are lists, so text with commas in it stays text.
+    /// Items of a list can be quoted (with `"` or `'`) to have commas or brackets in them, and are then always text.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Value;
+    ///
+    /// assert_eq!(Value::infer("8080"), Value::Int(8080));
+    /// assert_eq!(Value::infer("0.5"), Value::Float(0.5));
+    /// assert_eq!(Value::infer("True"), Value::Bool(true));
+    /// assert_eq!(Value::infer("nan"), Value::Str("nan".to_owned()));
+    /// assert_eq!(Value::infer("a, b"), Value::Str("a, b".to_owned()));
+    /// assert_eq!(
+    ///     Value::infer("[1, 'a, b']"),
+    ///     Value::List(vec![Value::Int(1), Value::Str("a, b".to_owned())])
+    /// );
+    /// ```
+    pub fn infer(value: &str) -> Value {
+        if let Some(items) = value.strip_prefix('[').and_then(|i| i.strip_suffix(']')) {
+            return Value::List(split_items(items).into_iter().map(item).collect());
+        }
+
+        if let Ok(i) = value.parse() {
+            return Value::Int(i);
+        }
+
+        // So words like `inf` and `nan` stay text
+        if value.chars().any(|i| i.is_ascii_digit()) {
+            if let Ok(i) = value.parse() {
+                return Value::Float(i);
+            }
+        }
+
+        match value {
+            i if i.eq_ignore_ascii_case("true") => Value::Bool(true),
+            i if i.eq_ignore_ascii_case("false") => Value::Bool(false),
+            _ => Value::Str(value.to_owned()),
+        }
+    }
+
+    /// Get the text of a `Str`
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(i) => Some(i),
+            _ => None,
+        }
+    }
+
+    /// Get an `Int`
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            Value::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// Get a `Float`, or an `Int` as a float
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Value::Float(i) => Some(*i),
+            Value::Int(i) => Some(*i as f64),
+            _ => None,
+        }
+    }
+
+    /// Get a `Bool`
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// Get the items of a `List`
+    pub fn as_list(&self) -> Option<&[Value]> {
+        match self {
+            Value::List(i) => Some(i),
+            _ => None,
+        }
+    }
+}
+
+/// Writes the value as it would be in a config, with lists in `[..]` and their items separated by `, `
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Str(i) => f.write_str(i),
+            Value::Int(i) => write!(f, "{}", i),
+            Value::Float(i) => write!(f, "{}", i),
+            Value::Bool(i) => write!(f, "{}", i),
+            Value::List(i) => {
+                f.write_str("[")?;
+                for (n, i) in i.iter().enumerate() {
+                    if n > 0 {
+                        f.write_str(", ")?;
+                    }
+                    match i {
+                        // Quoted so it's read back as one item of text
+                        Value::Str(i) if i.contains([',', '[', ']']) => {
+                            let quote = if i.contains('"') { '\'' } else { '"' };
+                            write!(f, "{}{}{}", quote, i, quote)?
+                        }
+                        i => write!(f, "{}", i)?,
+                    }
+                }
+                f.write_str("]")
+            }
+        }
+    }
+}
+
+impl Config {
+    /// Get a value from config with its type inferred
+    ///
+    /// The type is inferred with `Value::infer` the first time the value is read (and again after it's set),
+    /// so only values in `[..]` are a `List`.
+    /// Use this to see what type a value is.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::{Config, Value};
+    ///
+    /// let cfg = Config::new()
+    ///     .text("port = 8080\nratio = 0.75\nhosts = [a, b]\nname = app, v2")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get_value("port").unwrap().as_int(), Some(8080));
+    /// assert_eq!(cfg.get_value("ratio").unwrap(), &Value::Float(0.75));
+    /// assert_eq!(cfg.get_value("hosts").unwrap().as_list().unwrap().len(), 2);
+    /// assert_eq!(cfg.get_value("name").unwrap().as_str(), Some("app, v2"));
+    /// ```
+    pub fn get_value(&self, key: &str) -> Result<&Value, ConfigError> {
+        let entry = self.entry(None, key)?;
+        self.value_of(entry).ok_or(ConfigError::NoItem)
+    }
+
+    /// Get the inferred value of an entry of the config, its defaults or its registered namespaces
+    fn value_of(&self, entry: &Entry) -> Option<&Value> {
+        self.data
+            .value_of(entry)
+            .or_else(|| self.registered.value_of(entry))
+            .or_else(|| self.defaults.as_ref()?.value_of(entry))
+    }
+}
+
+/// Infer the type of an item of a list, text if it's quoted
+fn item(item: &str) -> Value {
+    for quote in ['"', '\''] {
+        if let Some(i) = item.strip_prefix(quote).and_then(|i| i.strip_suffix(quote)) {
+            return Value::Str(i.to_owned());
+        }
+    }
+    Value::infer(item)
+}
+
+/// Split the items of a list on the commas that are not in quotes or a list in it
+fn split_items(items: &str) -> Vec<&str> {
+    if items.trim().is_empty() {
+        return Vec::new();
+    }
+
+    let (mut out, mut start, mut depth, mut quote) = (Vec::new(), 0, 0usize, None);
+    for (n, i) in items.char_indices() {
+        match (quote, i) {
+            (Some(q), i) if i == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') if items[start..n].trim().is_empty() => quote = Some(i),
+            (None, '[') => depth += 1,
+            (None, ']') => depth = depth.saturating_sub(1),
+            (None, ',') if depth == 0 => {
+                out.push(items[start..n].trim());
+                start = n + 1;
+            }
+            _ => {}
+        }
+    }
+    out.push(items[start..].trim());
+    out
+}
@@ -0,0 +1,229 @@
+//! Watching the files of a shared config and reloading it when they change (with the `reload` feature)
+//!
+//! Files are polled for changes to their modified time and size, so this works the same on every platform.
+//! A reload that fails, or one where an optional source failed (see `Config::file_optional`),
+//! is tried again on a timer that backs off, as well as whenever a file changes.
+//! The config in use can be checked for being out of date on every poll too, see `Watcher::warn_stale`.
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::instrument;
+use crate::{Config, ConfigError, SharedConfig, StalePolicy, Staleness};
+
+/// Longest time between retries, unless the polling interval is longer
+const MAX_RETRY: Duration = Duration::from_secs(60);
+
+/// A thread watching the files of a shared config, see `SharedConfig::watch`
+///
+/// The thread is stopped when this is dropped.
+pub struct Watcher {
+    stop: Option<mpsc::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+    error: Arc<Mutex<Option<String>>>,
+    shared: SharedConfig,
+    stale: Arc<Mutex<Stale>>,
+}
+
+/// The stale policy of a watcher (if it has one) and why the config was stale when it was last checked
+type Stale = Option<(StalePolicy, Vec<Staleness>)>;
+
+/// What the thread knows about a file: when it was modified and its size (`None` if it can't be read)
+type Stamp = Option<(SystemTime, u64)>;
+
+impl SharedConfig {
+    /// Watch some files on a thread, replacing the config with the one from `load` when any of them change
+    ///
+    /// The files are checked every `interval`.
+    /// If `load` fails the config in use is kept, and loading is tried again after `interval`,
+    /// then twice as long after every failure (up to a minute), or as soon as a file changes.
+    /// It's tried again the same way while a source of the config in use failed to load (see `LoadReport::failures`),
+    /// so a config loaded without an optional source picks it up once that source can be read.
+    /// ## Example
+    /// ```rust,no_run
+    /// // Import Lib
+    /// use std::time::Duration;
+    /// use simple_config_parser::{Config, SharedConfig};
+    ///
+    /// let load = || Ok(Config::new().file("config.cfg")?.file_optional("local.cfg"));
+    /// let shared = SharedConfig::new(load().unwrap());
+    ///
+    /// // Reloads until it's dropped
+    /// let watcher = shared.watch(&["config.cfg", "local.cfg"], Duration::from_secs(1), load);
+    /// ```
+    pub fn watch<P, F>(&self, files: &[P], interval: Duration, mut load: F) -> Watcher
+    where
+        P: AsRef<Path>,
+        F: FnMut() -> Result<Config, ConfigError> + Send + 'static,
+    {
+        let files = files
+            .iter()
+            .map(|i| i.as_ref().to_path_buf())
+            .collect::<Vec<_>>();
+        let (stop, stopped) = mpsc::channel();
+        let error = Arc::new(Mutex::new(None));
+        let stale = Arc::new(Mutex::new(None));
+
+        let shared = self.clone();
+        let last_error = error.clone();
+        let last_stale = stale.clone();
+        let thread = thread::spawn(move || {
+            let mut seen = stamps(&files);
+            let mut failures = 0;
+            let mut retry = failure(&shared.load()).map(|_| Instant::now() + interval);
+
+            while let Err(mpsc::RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                check_stale(
+                    &shared,
+                    &mut last_stale.lock().unwrap_or_else(|e| e.into_inner()),
+                );
+                let current = stamps(&files);
+                let due = retry.is_some_and(|i| Instant::now() >= i);
+                if current == seen && !due {
+                    continue;
+                }
+                seen = current;
+
+                // The config in use is only replaced by one that loaded
+                let failed = match load() {
+                    Ok(config) => {
+                        let failed = failure(&config);
+                        shared.replace(config);
+                        failed
+                    }
+                    Err(e) => Some(e.to_string()),
+                };
+
+                retry = match &failed {
+                    Some(error) => {
+                        failures += 1;
+                        let wait = backoff(interval, failures);
+                        instrument::reload_failed(error, wait);
+                        Some(Instant::now() + wait)
+                    }
+                    None => {
+                        failures = 0;
+                        None
+                    }
+                };
+                *last_error.lock().unwrap_or_else(|e| e.into_inner()) = failed;
+            }
+        });
+
+        Watcher {
+            stop: Some(stop),
+            thread: Some(thread),
+            error,
+            shared: self.clone(),
+            stale,
+        }
+    }
+}
+
+impl Watcher {
+    /// Get why the last reload failed (or the first source that failed to load in it), `None` if it worked
+    ///
+    /// Also `None` before the first reload.
+    pub fn last_error(&self) -> Option<String> {
+        self.error.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Warn when the config in use looks out of date (see `Config::check_stale`), checking it now and then on every poll
+    ///
+    /// Each reason is logged (with the `log` feature) when the config first becomes stale for it,
+    /// and `Watcher::stale` gives the reasons found by the last check.
+    /// Only reloads reset the `max_unvalidated` timer, so it warns about files that have not changed in that long.
+    /// ## Example
+    /// ```rust,no_run
+    /// // Import Lib
+    /// use std::time::Duration;
+    /// use simple_config_parser::{Config, SharedConfig, StalePolicy};
+    ///
+    /// let load = || Config::new().file("config.cfg");
+    /// let shared = SharedConfig::new(load().unwrap());
+    ///
+    /// let watcher = shared.watch(&["config.cfg"], Duration::from_secs(1), load);
+    /// watcher.warn_stale(StalePolicy {
+    ///     max_file_age: Some(Duration::from_secs(60 * 60 * 24 * 365)),
+    ///     max_unvalidated: None,
+    /// });
+    /// for i in watcher.stale() {
+    ///     eprintln!("[WARN] Config may be stale: {:?}", i);
+    /// }
+    /// ```
+    pub fn warn_stale(&self, policy: StalePolicy) {
+        let mut stale = self.stale.lock().unwrap_or_else(|e| e.into_inner());
+        *stale = Some((policy, Vec::new()));
+        check_stale(&self.shared, &mut stale);
+    }
+
+    /// Get why the config in use looked out of date when it was last checked, see `Watcher::warn_stale`
+    pub fn stale(&self) -> Vec<Staleness> {
+        let stale = self.stale.lock().unwrap_or_else(|e| e.into_inner());
+        stale.as_ref().map(|i| i.1.clone()).unwrap_or_default()
+    }
+
+    /// Stop watching the files, waiting for a reload in progress to finish
+    pub fn stop(mut self) {
+        self.join();
+    }
+
+    /// Tell the thread to stop and wait for it
+    fn join(&mut self) {
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        self.join();
+    }
+}
+
+/// Get the first source of a config that failed to load, with why
+fn failure(config: &Config) -> Option<String> {
+    let source = config.load_report().failures().next()?;
+    let error = source.error.as_ref()?;
+    Some(format!("{}: {}", source.source, error))
+}
+
+/// Check if the config in use is stale, warning about reasons it was not stale for at the last check
+fn check_stale(shared: &SharedConfig, stale: &mut Stale) {
+    let (policy, last) = match stale {
+        Some(i) => i,
+        None => return,
+    };
+
+    let found = shared.load().check_stale(policy);
+    for i in found.iter() {
+        if !last
+            .iter()
+            .any(|j| mem::discriminant(i) == mem::discriminant(j))
+        {
+            instrument::stale(i);
+        }
+    }
+    *last = found;
+}
+
+/// Get the stamp of every file
+fn stamps(files: &[PathBuf]) -> Vec<Stamp> {
+    files
+        .iter()
+        .map(|i| {
+            let meta = std::fs::metadata(i).ok()?;
+            Some((meta.modified().ok()?, meta.len()))
+        })
+        .collect()
+}
+
+/// Get how long to wait before trying again after some failures in a row
+fn backoff(interval: Duration, failures: u32) -> Duration {
+    let wait = interval.saturating_mul(1 << (failures - 1).min(16));
+    wait.min(MAX_RETRY.max(interval))
+}
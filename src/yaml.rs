@@ -0,0 +1,369 @@
+//! Loading simple YAML mappings into the flat key space
+//!
+//! Supports nested block mappings, lists (block `- item` or flow `[a, b]`), plain and quoted scalars,
+//! `|` / `>` block scalars and `#` comments.
+//! Values are flattened like JSON (see `Config::json`): nested keys are joined by `.`,
+//! lists of scalars are their items separated by `, ` and other lists use the index as a key.
+//! Anchors, tags, flow mappings and multiple documents are not supported.
+use std::time::Instant;
+
+use crate::interpolate;
+use crate::json::{self, Value};
+use crate::utf8;
+use crate::{Config, ConfigError};
+
+impl Config {
+    /// Loads a YAML mapping into the config, with nested keys joined by `.`
+    ///
+    /// Like `Config::json` keys are not in any section and saving writes them in this crates format.
+    /// `null` (and `~` or empty) values are skipped.
+    /// Returns `ConfigError::InvalidConfig` if the text is not a mapping or uses YAML that is not supported.
+    /// ## Example
+    /// ```rust
+    /// // Import Lib
+    /// use simple_config_parser::Config;
+    ///
+    /// let cfg = Config::new()
+    ///     .yaml("server:\n  port: 8080 # Comment\n  hosts:\n    - a\n    - 'b'\ndebug: true\nmotd: |\n  Hello\n  World\n")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cfg.get::<u16>("server.port").unwrap(), 8080);
+    /// assert_eq!(cfg.get_vec::<String>("server.hosts").unwrap(), ["a", "b"]);
+    /// assert!(cfg.get::<bool>("debug").unwrap());
+    /// assert_eq!(cfg.get_str("motd").unwrap(), "Hello\nWorld\n");
+    /// ```
+    pub fn yaml<T>(mut self, text: T) -> Result<Self, ConfigError>
+    where
+        T: std::fmt::Display,
+    {
+        let start = Instant::now();
+        let mut data = Vec::new();
+        json::flatten(
+            String::new(),
+            parse(&utf8::normalize(&text.to_string()))?,
+            self.key_style,
+            &mut data,
+        );
+
+        if let Some(unset) = self.env {
+            for i in data.iter_mut() {
+                i.value = interpolate::expand_env(&i.value, unset)?;
+            }
+        }
+        self.apply_transforms(&mut data, &mut Vec::new());
+        self.apply_processors(&mut data)?;
+
+        self.add_source("<yaml>".to_string(), start, (data, Vec::new()))?;
+        Ok(self)
+    }
+}
+
+/// A line of YAML, with its indent
+struct Line<'a> {
+    indent: usize,
+    /// The line without its indent
+    text: &'a str,
+}
+
+/// Parse a YAML document, which has to be a mapping (or empty)
+pub(crate) fn parse(input: &str) -> Result<Value, ConfigError> {
+    let mut lines = input
+        .lines()
+        .map(|i| Line {
+            indent: i.len() - i.trim_start_matches(' ').len(),
+            text: i.trim_start_matches(' ').trim_end(),
+        })
+        .collect::<Vec<_>>();
+
+    // One document at most
+    let start = lines
+        .iter()
+        .position(|i| i.text == "---")
+        .map_or(0, |i| i + 1);
+    if let Some(end) = lines[start..]
+        .iter()
+        .position(|i| i.text == "---" || i.text == "...")
+    {
+        lines.truncate(start + end);
+    }
+    let mut lines = lines.split_off(start.min(lines.len()));
+
+    let mut pos = 0;
+    let value = match next(&lines, pos) {
+        Some(i) => {
+            pos = i;
+            if lines[i].text.starts_with('-') {
+                return Err(ConfigError::InvalidConfig);
+            }
+            let indent = lines[i].indent;
+            parse_mapping(&mut lines, &mut pos, indent)?
+        }
+        None => Value::Object(Vec::new()),
+    };
+
+    match next(&lines, pos) {
+        Some(_) => Err(ConfigError::InvalidConfig),
+        None => Ok(value),
+    }
+}
+
+/// Find the next line with something on it, from `pos`
+fn next(lines: &[Line], pos: usize) -> Option<usize> {
+    (pos..lines.len()).find(|i| !is_blank(lines[*i].text))
+}
+
+/// Check if a line is empty or just a comment
+fn is_blank(text: &str) -> bool {
+    text.is_empty() || text.starts_with('#')
+}
+
+/// Parse the block value (mapping or list) after a key at `indent`, or `Value::Null` if there is none
+fn parse_block(lines: &mut [Line], pos: &mut usize, indent: usize) -> Result<Value, ConfigError> {
+    let i = match next(lines, *pos) {
+        Some(i) => i,
+        None => return Ok(Value::Null),
+    };
+
+    // Lists can be at the same indent as their key
+    let (at, item) = (lines[i].indent, is_item(lines[i].text));
+    if at < indent || (at == indent && !item) {
+        return Ok(Value::Null);
+    }
+
+    *pos = i;
+    match item {
+        true => parse_list(lines, pos, at),
+        false => parse_mapping(lines, pos, at),
+    }
+}
+
+/// Check if a line is an item of a list
+fn is_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+/// Parse the `key: value` lines at `indent`, starting at `pos`
+fn parse_mapping(lines: &mut [Line], pos: &mut usize, indent: usize) -> Result<Value, ConfigError> {
+    let mut items = Vec::new();
+
+    while let Some(i) = next(lines, *pos) {
+        let line = &lines[i];
+        if line.indent < indent || (line.indent == indent && is_item(line.text)) {
+            break;
+        }
+        if line.indent > indent {
+            return Err(ConfigError::InvalidConfig);
+        }
+
+        let (key, rest) = split_key(line.text)?;
+        *pos = i + 1;
+        let value = match rest {
+            "" => parse_block(lines, pos, indent)?,
+            i if i.starts_with('|') || i.starts_with('>') => {
+                parse_block_scalar(lines, pos, indent, i)?
+            }
+            i => parse_scalar(i)?,
+        };
+
+        items.retain(|i: &(String, Value)| i.0 != key);
+        items.push((key, value));
+    }
+
+    Ok(Value::Object(items))
+}
+
+/// Parse the `- item` lines at `indent`, starting at `pos`
+fn parse_list(lines: &mut [Line], pos: &mut usize, indent: usize) -> Result<Value, ConfigError> {
+    let mut items = Vec::new();
+
+    while let Some(i) = next(lines, *pos) {
+        let line = &lines[i];
+        if line.indent != indent || !is_item(line.text) {
+            if line.indent > indent {
+                return Err(ConfigError::InvalidConfig);
+            }
+            break;
+        }
+
+        let rest = line.text[1..].trim_start();
+        let value = if rest.is_empty() || rest.starts_with('#') {
+            *pos = i + 1;
+            parse_block(lines, pos, indent)?
+        } else if is_item(rest) || split_key(rest).is_ok() {
+            // A mapping (or list) starting on the line of the item, as if it was on the next line
+            let offset = line.text.len() - rest.len();
+            lines[i] = Line {
+                indent: indent + offset,
+                text: rest,
+            };
+            *pos = i;
+            match is_item(rest) {
+                true => parse_list(lines, pos, indent + offset)?,
+                false => parse_mapping(lines, pos, indent + offset)?,
+            }
+        } else {
+            *pos = i + 1;
+            parse_scalar(rest)?
+        };
+        items.push(value);
+    }
+
+    Ok(Value::Array(items))
+}
+
+/// Split a `key: value` line into its key and the rest of the line
+fn split_key(text: &str) -> Result<(String, &str), ConfigError> {
+    let (key, rest) = match text.chars().next() {
+        Some(i @ ('"' | '\'')) => {
+            let (key, len) = parse_quoted(text, i)?;
+            (key, &text[len..])
+        }
+        _ => {
+            let end = text
+                .match_indices(':')
+                .map(|(n, _)| n)
+                .find(|n| text[n + 1..].is_empty() || text[n + 1..].starts_with(' '))
+                .ok_or(ConfigError::InvalidConfig)?;
+            (text[..end].trim_end().to_owned(), &text[end..])
+        }
+    };
+
+    match rest.strip_prefix(':') {
+        Some(rest) if !key.is_empty() && !key.starts_with('#') => Ok((key, rest.trim())),
+        _ => Err(ConfigError::InvalidConfig),
+    }
+}
+
+/// Parse a scalar (or flow list) value
+fn parse_scalar(text: &str) -> Result<Value, ConfigError> {
+    let text = text.trim();
+    match text.chars().next() {
+        None | Some('#') => Ok(Value::Null),
+        Some(i @ ('"' | '\'')) => {
+            let (value, len) = parse_quoted(text, i)?;
+            match is_blank(text[len..].trim_start()) {
+                true => Ok(Value::Plain(value)),
+                false => Err(ConfigError::InvalidConfig),
+            }
+        }
+        Some('[') => {
+            let inner = remove_comment(text)
+                .strip_prefix('[')
+                .and_then(|i| i.strip_suffix(']'))
+                .ok_or(ConfigError::InvalidConfig)?;
+            inner
+                .split(',')
+                .map(str::trim)
+                .filter(|i| !i.is_empty())
+                .map(parse_scalar)
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::Array)
+        }
+        Some('{' | '&' | '*' | '!' | '@' | '`') => Err(ConfigError::InvalidConfig),
+        _ => match remove_comment(text) {
+            "~" | "null" | "Null" | "NULL" => Ok(Value::Null),
+            i => Ok(Value::Plain(i.to_owned())),
+        },
+    }
+}
+
+/// Remove a comment (` #`) from the end of a plain value
+fn remove_comment(text: &str) -> &str {
+    match text.find(" #") {
+        Some(i) => text[..i].trim_end(),
+        None => text,
+    }
+}
+
+/// Parse a quoted string starting the text, returning it and the length of it with the quotes
+///
+/// Double quoted strings can have escapes, in single quoted ones `''` is a quote.
+fn parse_quoted(text: &str, quote: char) -> Result<(String, usize), ConfigError> {
+    let mut out = String::new();
+    let mut chars = text.char_indices().skip(1).peekable();
+
+    while let Some((n, i)) = chars.next() {
+        match i {
+            '\'' if quote == '\'' => match chars.next_if(|i| i.1 == '\'') {
+                Some(_) => out.push('\''),
+                None => return Ok((out, n + 1)),
+            },
+            '"' if quote == '"' => return Ok((out, n + 1)),
+            '\\' if quote == '"' => match chars.next().ok_or(ConfigError::InvalidConfig)?.1 {
+                'n' => out.push('\n'),
+                't' => out.push('\t'),
+                'r' => out.push('\r'),
+                '0' => out.push('\0'),
+                i @ ('"' | '\\' | '/' | ' ') => out.push(i),
+                _ => return Err(ConfigError::InvalidConfig),
+            },
+            i => out.push(i),
+        }
+    }
+
+    Err(ConfigError::InvalidConfig)
+}
+
+/// Parse a `|` (literal) or `>` (folded) block scalar, the lines indented more than `indent` after `pos`
+///
+/// `-` after the indicator removes the final line break, `+` keeps all of them.
+fn parse_block_scalar(
+    lines: &[Line],
+    pos: &mut usize,
+    indent: usize,
+    header: &str,
+) -> Result<Value, ConfigError> {
+    let header = remove_comment(header);
+    let folded = header.starts_with('>');
+    let chomp = match &header[1..] {
+        "" => None,
+        "-" => Some(false),
+        "+" => Some(true),
+        _ => return Err(ConfigError::InvalidConfig),
+    };
+
+    let mut body = Vec::new();
+    let mut block_indent = None;
+    while let Some(line) = lines.get(*pos) {
+        if !line.text.is_empty() {
+            if line.indent <= indent {
+                break;
+            }
+            let at = *block_indent.get_or_insert(line.indent);
+            if line.indent < at {
+                break;
+            }
+            body.push(format!("{}{}", " ".repeat(line.indent - at), line.text));
+        } else {
+            body.push(String::new());
+        }
+        *pos += 1;
+    }
+
+    // Trailing empty lines belong to whatever comes next, unless they are kept
+    let mut trailing = 0;
+    while body.last().is_some_and(|i| i.is_empty()) {
+        body.pop();
+        trailing += 1;
+    }
+
+    let mut out = String::new();
+    for (n, i) in body.iter().enumerate() {
+        if n > 0 {
+            let more = i.starts_with(' ') || body[n - 1].starts_with(' ');
+            match folded && !i.is_empty() && !body[n - 1].is_empty() && !more {
+                true => out.push(' '),
+                false => out.push('\n'),
+            }
+        }
+        out.push_str(i);
+    }
+
+    match chomp {
+        _ if body.is_empty() => {}
+        Some(false) => {}
+        Some(true) => out.push_str(&"\n".repeat(trailing + 1)),
+        None => out.push('\n'),
+    }
+    Ok(Value::Plain(out))
+}
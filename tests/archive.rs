@@ -0,0 +1,65 @@
+#![cfg(feature = "archive")]
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+
+use simple_config_parser::{Config, ConfigError};
+
+/// Get a path in the temp dir
+fn temp_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("scp-{}-{}", std::process::id(), name))
+}
+
+#[test]
+/// Test loading configs out of tar archives
+fn test_tar_archive() {
+    let path = temp_path("test.tar");
+    let mut builder = tar::Builder::new(File::create(&path).unwrap());
+    for (name, contents) in [("README", "hi"), ("etc/app.cfg", "hello = World")] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, name, contents.as_bytes())
+            .unwrap();
+    }
+    builder.finish().unwrap();
+    drop(builder);
+
+    let cfg = Config::from_archive(&path, "etc/app.cfg").unwrap();
+    assert_eq!(cfg.get_str("hello").unwrap(), "World");
+    assert!(cfg.load_report().sources[0]
+        .source
+        .ends_with("test.tar:etc/app.cfg"));
+
+    let cfg = Config::from_archive(&path, "./etc/app.cfg").unwrap();
+    assert_eq!(cfg.get_str("hello").unwrap(), "World");
+
+    match Config::from_archive(&path, "missing.cfg") {
+        Err(ConfigError::Io(e)) => assert_eq!(e.kind(), ErrorKind::NotFound),
+        _ => panic!("Member should not be found"),
+    }
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+/// Test loading configs out of zip archives
+fn test_zip_archive() {
+    let path = temp_path("test.zip");
+    let mut zip = zip::ZipWriter::new(File::create(&path).unwrap());
+    zip.start_file("app.cfg", zip::write::SimpleFileOptions::default())
+        .unwrap();
+    zip.write_all(b"[server]\nport = 8080").unwrap();
+    zip.finish().unwrap();
+
+    let cfg = Config::from_archive(&path, "app.cfg").unwrap();
+    assert_eq!(cfg.get::<u16>("port").unwrap(), 8080);
+
+    match Config::from_archive(&path, "other.cfg") {
+        Err(ConfigError::Io(e)) => assert_eq!(e.kind(), ErrorKind::NotFound),
+        _ => panic!("Member should not be found"),
+    }
+    fs::remove_file(path).unwrap();
+}
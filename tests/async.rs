@@ -0,0 +1,21 @@
+#![cfg(feature = "async")]
+use simple_config_parser::{Config, ConfigError};
+
+/// Run a future to completion on a new runtime
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+#[test]
+/// Test files can be loaded with tokio
+fn test_file_async() {
+    let cfg = block_on(Config::new().file_async("config.cfg")).unwrap();
+    assert_eq!(cfg.get_str("hello").unwrap(), "World");
+    assert_eq!(cfg.load_report().sources[0].source, "config.cfg");
+
+    let err = block_on(Config::new().file_async("nonexistent.cfg"));
+    assert!(matches!(err, Err(ConfigError::FileNotFound(_))));
+}
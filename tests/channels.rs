@@ -0,0 +1,66 @@
+use std::sync::mpsc;
+
+use simple_config_parser::Config;
+
+/// Config to send changes from
+fn config() -> Config {
+    Config::new().text("a = 1\nb = 2").unwrap()
+}
+
+#[test]
+/// Test sending change events to std channels
+fn test_std_channels() {
+    let mut cfg = config();
+    let (tx, rx) = mpsc::sync_channel(4);
+    cfg.subscribe_with(&["a"], tx);
+
+    cfg.set("a", "3");
+    cfg.set("b", "3");
+    cfg.set("a", "4");
+
+    let events = rx.try_iter().collect::<Vec<_>>();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[1].changes[0].old.as_deref(), Some("3"));
+
+    // Subscriptions are dropped with their receivers
+    drop(rx);
+    cfg.set("a", "5");
+    cfg.set("a", "6");
+}
+
+#[test]
+#[cfg(feature = "crossbeam")]
+/// Test sending change events to crossbeam channels
+fn test_crossbeam_channel() {
+    let mut cfg = config();
+    let (tx, rx) = crossbeam_channel::unbounded();
+    cfg.subscribe_with(&[], tx);
+
+    cfg.remove("b");
+    let event = rx.try_recv().unwrap();
+    assert_eq!(event.changes[0].key, "b");
+    assert_eq!(event.changes[0].new, None);
+}
+
+#[test]
+#[cfg(feature = "tokio")]
+/// Test sending change events to tokio channels
+fn test_tokio_channels() {
+    use simple_config_parser::ChangeEvent;
+
+    let mut cfg = config();
+    let (tx, mut broadcast) = tokio::sync::broadcast::channel(4);
+    cfg.subscribe_with(&["a"], tx);
+    let (tx, watch) = tokio::sync::watch::channel(ChangeEvent {
+        changes: Vec::new(),
+    });
+    cfg.subscribe_with(&["b"], tx);
+
+    cfg.set("a", "2");
+    cfg.set("b", "3");
+    cfg.set("b", "4");
+
+    assert_eq!(broadcast.try_recv().unwrap().changes[0].key, "a");
+    assert!(broadcast.try_recv().is_err());
+    assert_eq!(watch.borrow().changes[0].new.as_deref(), Some("4"));
+}
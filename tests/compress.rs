@@ -0,0 +1,66 @@
+#![cfg(feature = "file")]
+
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use simple_config_parser::{Config, ConfigError};
+
+/// Write a file to the temp dir and get its path
+fn temp_file(name: &str, contents: &[u8]) -> PathBuf {
+    let path = env::temp_dir().join(format!("scp-{}-{}", std::process::id(), name));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+#[cfg(feature = "gzip")]
+/// Test loading gzip compressed configs
+fn test_gzip() {
+    use std::io::Write;
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(b"hello = World\n[a]\nb = 1").unwrap();
+    let path = temp_file("test.cfg.gz", &encoder.finish().unwrap());
+
+    let cfg = Config::new().file(&path).unwrap();
+    assert_eq!(cfg.get_str("hello").unwrap(), "World");
+    assert_eq!(cfg.get::<u8>("b").unwrap(), 1);
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+#[cfg(feature = "zstd")]
+/// Test loading zstd compressed configs
+fn test_zstd() {
+    let data = zstd::encode_all(&b"hello = World"[..], 0).unwrap();
+    let path = temp_file("test.cfg.zst", &data);
+
+    let cfg = Config::new().file_optional(&path);
+    assert_eq!(cfg.get_str("hello").unwrap(), "World");
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+/// Test compressed files need their feature and bad data is an error
+fn test_compressed_errors() {
+    let path = temp_file("bad.cfg.gz", b"not gzip");
+    let result = Config::new().file(&path);
+    fs::remove_file(path).unwrap();
+
+    match result.err().unwrap() {
+        #[cfg(not(feature = "gzip"))]
+        ConfigError::Io(e) => assert_eq!(e.kind(), ErrorKind::Unsupported),
+        #[cfg(feature = "gzip")]
+        ConfigError::Io(e) => assert_ne!(e.kind(), ErrorKind::Unsupported),
+        e => panic!("Unexpected error: {}", e),
+    }
+
+    let path = temp_file("plain.cfg.zst.cfg", b"a = 1");
+    assert_eq!(
+        Config::new().file(&path).unwrap().get_str("a").unwrap(),
+        "1"
+    );
+    fs::remove_file(path).unwrap();
+}
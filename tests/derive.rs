@@ -0,0 +1,101 @@
+#![cfg(feature = "derive")]
+
+use simple_config_parser::{Config, ConfigEnum, ConfigError, FromConfig};
+
+#[derive(Debug, FromConfig)]
+#[config(section = "server")]
+struct Server {
+    host: String,
+    #[config(default = 8080)]
+    port: u16,
+    #[config(default)]
+    workers: u8,
+    #[config(rename = "tls")]
+    use_tls: Option<bool>,
+    #[config(section = "database", rename = "url")]
+    database_url: String,
+}
+
+#[test]
+/// Test loading a struct with every kind of field attribute
+fn test_derive_from_config() {
+    let cfg = Config::new()
+        .text("[server]\nhost = localhost\ntls = true\n[database]\nurl = postgres://db")
+        .unwrap();
+
+    let server = Server::from_config(&cfg).unwrap();
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.port, 8080);
+    assert_eq!(server.workers, 0);
+    assert_eq!(server.use_tls, Some(true));
+    assert_eq!(server.database_url, "postgres://db");
+}
+
+#[test]
+/// Test every missing or invalid field is reported
+fn test_derive_errors() {
+    let cfg = Config::new()
+        .text("[server]\nport = http\ntls = maybe")
+        .unwrap();
+
+    let errors = Server::from_config(&cfg).unwrap_err().fields;
+    let fields = errors.iter().map(|i| i.field).collect::<Vec<_>>();
+    assert_eq!(fields, ["host", "port", "use_tls", "database_url"]);
+
+    assert!(matches!(errors[0].error, ConfigError::NoItem));
+    assert!(matches!(errors[1].error, ConfigError::InvalidType(..)));
+    assert_eq!(errors[2].key, "tls");
+    assert_eq!(errors[3].section, Some("database"));
+}
+
+#[derive(Debug, PartialEq, ConfigEnum)]
+enum LogLevel {
+    Debug,
+    Info,
+    VeryVerbose,
+    HTTPTrace,
+    #[config(rename = "warn")]
+    Warning,
+}
+
+#[derive(Debug, FromConfig)]
+struct Logging {
+    level: LogLevel,
+    targets: Option<LogLevel>,
+}
+
+#[test]
+/// Test reading enums by the names of their variants
+fn test_derive_config_enum() {
+    assert_eq!(
+        LogLevel::VARIANTS,
+        ["debug", "info", "very_verbose", "http_trace", "warn"]
+    );
+
+    let cfg = Config::new()
+        .text("level = Debug\nverbose = VERY_VERBOSE\ntrace = http_trace\nwarn = WARN\nlevels = info, warn\nbad = warning")
+        .unwrap();
+
+    assert_eq!(cfg.get_enum::<LogLevel>("level").unwrap(), LogLevel::Debug);
+    assert_eq!(
+        cfg.get_enum::<LogLevel>("verbose").unwrap(),
+        LogLevel::VeryVerbose
+    );
+    assert_eq!(cfg.get::<LogLevel>("trace").unwrap(), LogLevel::HTTPTrace);
+    assert_eq!(cfg.get::<LogLevel>("warn").unwrap(), LogLevel::Warning);
+    assert_eq!(
+        cfg.get_vec::<LogLevel>("levels").unwrap(),
+        [LogLevel::Info, LogLevel::Warning]
+    );
+    assert_eq!(
+        cfg.get_enum::<LogLevel>("bad").unwrap_err().to_string(),
+        "Value of `bad` (`warning`) must be one of `debug`, `info`, `very_verbose`, `http_trace`, `warn`"
+    );
+    assert!(matches!(
+        cfg.get_enum::<LogLevel>("missing"),
+        Err(ConfigError::NoItem)
+    ));
+
+    let logging = Logging::from_config(&cfg).unwrap();
+    assert_eq!((logging.level, logging.targets), (LogLevel::Debug, None));
+}
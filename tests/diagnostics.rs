@@ -0,0 +1,69 @@
+#![cfg(feature = "diagnostics")]
+
+use simple_config_parser::{diagnose, Schema, SchemaKey, ValueType};
+
+const SCHEMA: Schema = Schema::section(
+    "server",
+    &[
+        SchemaKey::required("name", ValueType::Str),
+        SchemaKey::required("port", ValueType::Int),
+        SchemaKey::optional("level", ValueType::Int).range(1.0, 5.0),
+    ],
+);
+
+#[test]
+/// Test errors are rendered with the lines they are on
+fn test_diagnose() {
+    let found = diagnose(
+        "app.cfg",
+        "[server]\nname = web\n[broken\nport = 80",
+        &SCHEMA,
+    );
+    assert_eq!(found.diagnostics.len(), 1);
+    assert_eq!(
+        found.to_string(),
+        "error: section header is not closed\n --> app.cfg:3:1\n  |\n3 | [broken\n  | ^^^^^^^ expected a `]` at the end"
+    );
+
+    let found = diagnose(
+        "app.cfg",
+        "[server]\nport = eighty ; web\nlevel = 9",
+        &SCHEMA,
+    );
+    assert!(!found.is_ok());
+    let lines = found
+        .diagnostics
+        .iter()
+        .map(|i| (i.message.as_str(), i.line, i.span.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        vec![
+            ("`server.name` is missing", None, None),
+            ("`server.port` is invalid", Some(2), Some(7..13)),
+            ("`server.level` is invalid", Some(3), Some(8..9)),
+        ]
+    );
+
+    let rendered = found.render(false);
+    assert!(rendered.contains("2 | port = eighty ; web\n  |        ^^^^^^ expected an integer"));
+    assert!(found.render(true).contains("\x1b[1;31merror\x1b[0m"));
+
+    let found = diagnose("app.cfg", "text = \"\"\"\nnever closed", &Schema::new(&[]));
+    assert_eq!(found.diagnostics[0].line, Some(1));
+    assert_eq!(found.diagnostics[0].span, Some(7..10));
+
+    let found = diagnose(
+        "app.cfg",
+        "[server]\nname = web\nprot = 80",
+        &SCHEMA.deny_unknown(),
+    );
+    let unknown = found.diagnostics.last().unwrap();
+    assert_eq!(unknown.span, Some(0..4));
+    assert_eq!(
+        unknown.label.as_deref(),
+        Some("did you mean `server.port`?")
+    );
+
+    assert!(diagnose("app.cfg", "[server]\nname = web\nport = 80", &SCHEMA).is_ok());
+}
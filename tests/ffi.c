@@ -0,0 +1,43 @@
+/*
+ * Calls the C bindings through include/simple_config_parser.h, linked against the cdylib.
+ * Built and run by CI, see .github/workflows/main.yml
+ */
+#include <stdio.h>
+#include <string.h>
+
+#include "simple_config_parser.h"
+
+#define CHECK(x)                                                       \
+    if (!(x)) {                                                        \
+        fprintf(stderr, "%s:%d: %s failed\n", __FILE__, __LINE__, #x); \
+        return 1;                                                      \
+    }
+
+int main(void) {
+    char buf[32];
+    ScpConfig *cfg = scp_config_text("name = app\n[db]\nhost = localhost");
+    CHECK(cfg != NULL);
+    CHECK(scp_config_get(cfg, "db.host", buf, sizeof(buf)) == SCP_OK);
+    CHECK(strcmp(buf, "localhost") == 0);
+    CHECK(scp_config_get(cfg, "db.host", buf, 4) == SCP_BUFFER_TOO_SMALL);
+    CHECK(scp_config_get(cfg, "db.user", buf, sizeof(buf)) == SCP_NO_ITEM);
+    scp_config_free(cfg);
+
+    FILE *file = fopen("target/ffi-c.cfg", "w");
+    CHECK(file != NULL);
+    fputs("[db]\nuser = admin\n", file);
+    fclose(file);
+
+    cfg = scp_config_new();
+    CHECK(scp_config_load_file(cfg, "target/ffi-c.cfg") == SCP_OK);
+    CHECK(scp_config_load_file(cfg, "target/missing.cfg") == SCP_IO_ERROR);
+    char *user = scp_config_get_str(cfg, "db.user");
+    CHECK(user != NULL && strcmp(user, "admin") == 0);
+    scp_str_free(user);
+    CHECK(scp_config_get_str(cfg, "db.port") == NULL);
+    scp_config_free(cfg);
+
+    CHECK(scp_config_file("target/missing.cfg") == NULL);
+    remove("target/ffi-c.cfg");
+    return 0;
+}
@@ -0,0 +1,211 @@
+#![cfg(feature = "ffi")]
+use std::ffi::{CStr, CString};
+use std::ptr;
+
+use simple_config_parser::ffi::{self, *};
+use simple_config_parser::Config;
+
+#[test]
+/// Test values can be read through the C bindings
+fn test_ffi() {
+    let text = CString::new("name = app\n[db]\nhost = localhost\nport = 5432").unwrap();
+    let key = CString::new("db.host").unwrap();
+    let missing = CString::new("db.user").unwrap();
+    let mut buf = [0 as std::os::raw::c_char; 32];
+
+    unsafe {
+        let cfg = scp_config_text(text.as_ptr());
+        assert!(!cfg.is_null());
+
+        assert_eq!(
+            scp_config_get(cfg, key.as_ptr(), buf.as_mut_ptr(), buf.len()),
+            SCP_OK
+        );
+        assert_eq!(CStr::from_ptr(buf.as_ptr()).to_str().unwrap(), "localhost");
+        assert_eq!(
+            scp_config_get(cfg, key.as_ptr(), buf.as_mut_ptr(), 9),
+            SCP_BUFFER_TOO_SMALL
+        );
+        assert_eq!(
+            scp_config_get(cfg, missing.as_ptr(), buf.as_mut_ptr(), buf.len()),
+            SCP_NO_ITEM
+        );
+        assert_eq!(
+            scp_config_get(cfg, ptr::null(), buf.as_mut_ptr(), buf.len()),
+            SCP_INVALID_ARGUMENT
+        );
+        assert_eq!(
+            scp_config_get(ptr::null(), key.as_ptr(), buf.as_mut_ptr(), buf.len()),
+            SCP_INVALID_ARGUMENT
+        );
+
+        scp_config_free(cfg);
+
+        let file = std::env::temp_dir().join(format!("scp-ffi-{}.cfg", std::process::id()));
+        std::fs::write(&file, "name = web app").unwrap();
+        let path = CString::new(file.to_str().unwrap()).unwrap();
+        let name = CString::new("name").unwrap();
+        let cfg = scp_config_file(path.as_ptr());
+        assert_eq!(
+            scp_config_get(cfg, name.as_ptr(), buf.as_mut_ptr(), buf.len()),
+            SCP_OK
+        );
+        assert_eq!(CStr::from_ptr(buf.as_ptr()).to_str().unwrap(), "web app");
+        scp_config_free(cfg);
+        std::fs::remove_file(file).unwrap();
+
+        let path = CString::new("nonexistent.cfg").unwrap();
+        assert!(scp_config_file(path.as_ptr()).is_null());
+        assert!(scp_config_text(ptr::null()).is_null());
+        scp_config_free(ptr::null_mut());
+    }
+
+    let cfg = Config::new().text("empty =").unwrap();
+    let mut buf = [0xff; 1];
+    assert_eq!(cfg.get_c_str("empty", &mut buf), ffi::SCP_OK);
+    assert_eq!(buf, [0]);
+    assert_eq!(cfg.get_c_str("empty", &mut []), ffi::SCP_BUFFER_TOO_SMALL);
+}
+
+#[test]
+/// Test files can be layered into a config and values read as new strings
+fn test_ffi_load_file() {
+    let dir = std::env::temp_dir().join(format!("scp-ffi-load-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("base.cfg"), "[db]\nhost = localhost\nport = 5432").unwrap();
+    std::fs::write(dir.join("local.cfg"), "[db]\nport = 6543").unwrap();
+    std::fs::write(dir.join("broken.cfg"), "[db").unwrap();
+    let path = |i: &str| CString::new(dir.join(i).to_str().unwrap()).unwrap();
+    let (host, port) = (
+        CString::new("db.host").unwrap(),
+        CString::new("db.port").unwrap(),
+    );
+
+    unsafe {
+        let cfg = scp_config_new();
+        assert_eq!(scp_config_load_file(cfg, path("base.cfg").as_ptr()), SCP_OK);
+        assert_eq!(
+            scp_config_load_file(cfg, path("local.cfg").as_ptr()),
+            SCP_OK
+        );
+        assert_eq!(
+            scp_config_load_file(cfg, path("missing.cfg").as_ptr()),
+            SCP_IO_ERROR
+        );
+        assert_eq!(
+            scp_config_load_file(cfg, path("broken.cfg").as_ptr()),
+            SCP_ERROR
+        );
+        assert_eq!(scp_config_load_file(cfg, ptr::null()), SCP_INVALID_ARGUMENT);
+
+        // Files that can't be read are I/O errors, even if it's a file they extend
+        std::fs::write(dir.join("child.cfg"), "extends = missing.cfg").unwrap();
+        assert_eq!(
+            scp_config_load_file(cfg, path("child.cfg").as_ptr()),
+            SCP_IO_ERROR
+        );
+        assert_eq!(scp_config_load_file(cfg, path("").as_ptr()), SCP_IO_ERROR);
+
+        let value = scp_config_get_str(cfg, host.as_ptr());
+        assert_eq!(CStr::from_ptr(value).to_str().unwrap(), "localhost");
+        scp_str_free(value);
+        let value = scp_config_get_str(cfg, port.as_ptr());
+        assert_eq!(CStr::from_ptr(value).to_str().unwrap(), "6543");
+        scp_str_free(value);
+
+        let missing = CString::new("db.user").unwrap();
+        assert!(scp_config_get_str(cfg, missing.as_ptr()).is_null());
+        assert!(scp_config_get_str(ptr::null(), host.as_ptr()).is_null());
+        scp_str_free(ptr::null_mut());
+        scp_config_free(cfg);
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+/// Test the C header matches the bindings, run with `SCP_WRITE_HEADER=1` to write it again after changing them
+fn test_ffi_header() {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/include/simple_config_parser.h"
+    );
+    let header = c_header(include_str!("../src/ffi.rs"));
+
+    if std::env::var_os("SCP_WRITE_HEADER").is_some() {
+        std::fs::write(path, &header).unwrap();
+    }
+    assert_eq!(
+        std::fs::read_to_string(path).unwrap(),
+        header,
+        "The C header is out of date, run the test with `SCP_WRITE_HEADER=1`"
+    );
+}
+
+/// Write the C header for the error codes and functions of the bindings
+fn c_header(source: &str) -> String {
+    let mut out = String::from(
+        "/*
+ * C bindings for simple_config_parser, built with the `ffi` feature.
+ * Written from src/ffi.rs by `test_ffi_header` in tests/ffi.rs, see src/ffi.rs for how each function behaves.
+ */
+#ifndef SIMPLE_CONFIG_PARSER_H
+#define SIMPLE_CONFIG_PARSER_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern \"C\" {
+#endif
+
+/* Error codes */
+",
+    );
+    for i in source.lines().filter_map(|i| i.strip_prefix("pub const ")) {
+        let (name, value) = i.split_once(": c_int = ").unwrap();
+        out += &format!("#define {} {}\n", name, value.trim_end_matches(';'));
+    }
+
+    out += "\n/* Opaque config handle, freed with scp_config_free */\ntypedef struct ScpConfig ScpConfig;\n\n";
+    for i in source.split("extern \"C\" fn ").skip(1) {
+        let (name, rest) = i.split_once('(').unwrap();
+        let (args, rest) = rest.split_once(')').unwrap();
+        let ret = rest[..rest.find('{').unwrap()].trim();
+        let args = args
+            .split(',')
+            .map(str::trim)
+            .filter(|i| !i.is_empty())
+            .map(|i| {
+                let (name, ty) = i.split_once(": ").unwrap();
+                c_decl(ty, name)
+            })
+            .collect::<Vec<_>>();
+
+        let args = match args.is_empty() {
+            true => "void".to_owned(),
+            false => args.join(", "),
+        };
+        out += &c_decl(
+            ret.trim_start_matches("->").trim(),
+            &format!("{}({})", name, args),
+        );
+        out += ";\n";
+    }
+
+    out + "\n#ifdef __cplusplus\n}\n#endif\n\n#endif\n"
+}
+
+/// Declare `name` with the C type of a Rust type from the bindings
+fn c_decl(ty: &str, name: &str) -> String {
+    let ty = match ty {
+        "*const c_char" => "const char *",
+        "*mut c_char" => "char *",
+        "*const ScpConfig" => "const ScpConfig *",
+        "*mut ScpConfig" => "ScpConfig *",
+        "usize" => "size_t ",
+        "c_int" => "int ",
+        "" => "void ",
+        i => panic!("No C type for `{}`, add it to `c_decl`", i),
+    };
+    format!("{}{}", ty, name)
+}
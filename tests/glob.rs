@@ -0,0 +1,26 @@
+#![cfg(feature = "glob")]
+
+use simple_config_parser::{Config, ConfigError};
+
+#[test]
+/// Test glob values are compiled into matchers
+fn test_get_glob() {
+    let cfg = Config::new()
+        .text("include = src/**/*.rs\nsingle = *.cfg\nbad = [a-\n")
+        .unwrap();
+
+    let include = cfg.get_glob("include").unwrap();
+    assert!(include.is_match("src/main.rs"));
+    assert!(include.is_match("src/a/b/lib.rs"));
+    assert!(!include.is_match("tests/test.rs"));
+
+    let single = cfg.get_glob("single").unwrap();
+    assert!(single.is_match("app.cfg"));
+    assert!(!single.is_match("conf/app.cfg"));
+
+    assert!(matches!(
+        cfg.get_glob("bad"),
+        Err(ConfigError::InvalidType(key, _, target)) if key == "bad" && target == "glob pattern"
+    ));
+    assert!(matches!(cfg.get_glob("missing"), Err(ConfigError::NoItem)));
+}
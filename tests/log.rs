@@ -0,0 +1,37 @@
+#![cfg(feature = "log")]
+use simple_config_parser::{Config, ConfigError, LogFormat};
+
+#[test]
+/// Test the logging settings are read from the log keys
+fn test_set_log_level() {
+    let err = Config::new()
+        .text("[log]\nformat = xml")
+        .unwrap()
+        .set_log_level();
+    assert!(matches!(err, Err(ConfigError::InvalidValue(_))));
+    let err = Config::new()
+        .text("[log]\nlevel = loud")
+        .unwrap()
+        .set_log_level();
+    assert!(matches!(err, Err(ConfigError::InvalidType(..))));
+
+    let settings = Config::new().log_settings().unwrap();
+    assert_eq!(settings.level, log::LevelFilter::Info);
+    assert_eq!(settings.format, LogFormat::Text);
+    assert!(settings.open_file().unwrap().is_none());
+
+    let file = std::env::temp_dir().join(format!("scp-log-{}.log", std::process::id()));
+    let cfg = Config::new()
+        .text(format!(
+            "[log]\nlevel = warn\nformat = JSON\nfile = {}",
+            file.display()
+        ))
+        .unwrap();
+
+    let settings = cfg.set_log_level().unwrap();
+    assert_eq!(log::max_level(), log::LevelFilter::Warn);
+    assert_eq!(settings.format, LogFormat::Json);
+    assert_eq!(settings.file.as_deref(), Some(file.as_path()));
+    assert!(settings.open_file().unwrap().is_some());
+    std::fs::remove_file(file).unwrap();
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "log")]
+//! The events are checked with a logger made here, in their own test binary as `set_log_level` changes the level of the `log` crate
+use std::sync::Mutex;
+
+use log::kv::{Key, Value, VisitSource};
+use log::{LevelFilter, Log, Metadata, Record};
+
+use simple_config_parser::{Config, SharedConfig};
+
+static EVENTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Records the events of this crate as `LEVEL message {key=value, ...}`
+struct Capture;
+
+impl Log for Capture {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.target() == "simple_config_parser"
+    }
+
+    fn log(&self, record: &Record) {
+        struct Visit(Vec<String>);
+        impl<'kvs> VisitSource<'kvs> for Visit {
+            fn visit_pair(
+                &mut self,
+                key: Key<'kvs>,
+                value: Value<'kvs>,
+            ) -> Result<(), log::kv::Error> {
+                self.0.push(format!("{}={}", key, value));
+                Ok(())
+            }
+        }
+
+        if self.enabled(record.metadata()) {
+            let mut visit = Visit(Vec::new());
+            record.key_values().visit(&mut visit).unwrap();
+            visit.0.retain(|i| !i.starts_with("duration_ms="));
+            EVENTS.lock().unwrap().push(format!(
+                "{} {} {{{}}}",
+                record.level(),
+                record.args(),
+                visit.0.join(", ")
+            ));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+#[test]
+/// Test loading configs logs where each value came from
+fn test_log_events() {
+    log::set_logger(&Capture).unwrap();
+    log::set_max_level(LevelFilter::Debug);
+
+    std::env::set_var("SCP_LOG_EVENTS_PORT", "9090");
+    let mut cfg = Config::new()
+        .skip_invalid_lines(true)
+        .text("host = localhost\nport = 80 ; env:SCP_LOG_EVENTS_PORT\n[broken")
+        .unwrap()
+        .args(["--host=example.com"])
+        .unwrap();
+    cfg.reload_text("host = localhost\nport = 8080").unwrap();
+    let shared = SharedConfig::new(cfg);
+    shared.replace(Config::new().text("host = a").unwrap());
+
+    let events = EVENTS.lock().unwrap();
+    // Without how long loading took
+    let events = events
+        .iter()
+        .map(|i| match i.find(" in ") {
+            Some(n) if i.starts_with("INFO Loaded") => {
+                format!("{}{}", &i[..n], &i[i.find(" {").unwrap()..])
+            }
+            _ => i.to_owned(),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        [
+            "WARN Skipped line 3 of <text>: Config data is not valid {source=<text>, line=3}",
+            "DEBUG `port` in <text> is set from env:SCP_LOG_EVENTS_PORT {key=port, from=env:SCP_LOG_EVENTS_PORT, source=<text>}",
+            "INFO Loaded 2 entries from <text> {source=<text>, entries=2}",
+            "DEBUG `host` is overridden by <args> {key=host, source=<args>}",
+            "INFO Loaded 1 entries from <args> {source=<args>, entries=1}",
+            "INFO Loaded 2 entries from <text> {source=<text>, entries=2}",
+            "INFO Reloaded <text> {source=<text>, partial=false}",
+            "INFO Loaded 1 entries from <text> {source=<text>, entries=1}",
+            "INFO Replaced shared config, 2 values changed {changes=2}",
+        ]
+    );
+}
@@ -1,4 +1,10 @@
-use simple_config_parser::Config;
+use std::time::Duration;
+
+use simple_config_parser::{
+    Config, ConfigError, Deprecation, DuplicatePolicy, Format, RemovePolicy, Sandbox, UnsetEnv,
+};
+#[cfg(feature = "file")]
+use simple_config_parser::{SaveGuard, StalePolicy, Staleness};
 
 #[test]
 /// Test parsing a config string
@@ -9,9 +15,10 @@ fn test_config_from_string() {
 
     assert_eq!(cfg.get_str("hello").unwrap(), "world");
     assert_eq!(cfg.get_str("rust").unwrap(), "is great");
-    assert_eq!(cfg.get_str("test").unwrap(), "\"TEST\"");
+    assert_eq!(cfg.get_str("test").unwrap(), "TEST");
 }
 
+#[cfg(feature = "file")]
 #[test]
 /// Test loading and parsing a config file
 fn test_config_from_file() {
@@ -19,7 +26,7 @@ fn test_config_from_file() {
 
     assert_eq!(cfg.get_str("hello").unwrap(), "World");
     assert_eq!(cfg.get_str("rust").unwrap(), "Is great");
-    assert_eq!(cfg.get_str("test").unwrap(), "\"TEST\"");
+    assert_eq!(cfg.get_str("test").unwrap(), "TEST");
 }
 
 #[test]
@@ -46,7 +53,7 @@ fn test_case_sensitivity() {
 
     assert_eq!(cfg.get_str("hello").unwrap(), "world");
     assert_eq!(cfg.get_str("RUST").unwrap(), "is great");
-    assert_eq!(cfg.get_str("tEsT").unwrap(), "\"TEST\"");
+    assert_eq!(cfg.get_str("tEsT").unwrap(), "TEST");
 }
 
 #[test]
@@ -58,7 +65,7 @@ fn test_comments_in_file() {
 
     assert_eq!(cfg.get_str("hello").unwrap(), "world");
     assert_eq!(cfg.get_str("RUST").unwrap(), "is great");
-    assert_eq!(cfg.get_str("tEsT").unwrap(), "\"TEST\"");
+    assert_eq!(cfg.get_str("tEsT").unwrap(), "TEST");
 }
 
 #[test]
@@ -70,7 +77,7 @@ fn test_ignoring_sections() {
 
     assert_eq!(cfg.get_str("hello").unwrap(), "world");
     assert_eq!(cfg.get_str("rust").unwrap(), "is great");
-    assert_eq!(cfg.get_str("test").unwrap(), "\"TEST\"");
+    assert_eq!(cfg.get_str("test").unwrap(), "TEST");
 }
 
 #[test]
@@ -83,6 +90,7 @@ fn test_last_key_wins() {
 
 #[test]
 /// Test getting value as a bool
+#[allow(clippy::bool_assert_comparison)]
 fn test_bool_value() {
     let cfg = Config::new().text("test = true\ntset = false").unwrap();
 
@@ -100,6 +108,7 @@ fn test_int_value() {
 }
 
 #[test]
+#[allow(clippy::approx_constant)]
 /// Test getting value as a float
 fn test_float_value() {
     let cfg = Config::new()
@@ -122,3 +131,4382 @@ fn test_load_priority() {
     assert_eq!(cfg.get_str("a").unwrap(), "2");
     assert_eq!(cfg.get_str("b").unwrap(), "4");
 }
+
+#[test]
+/// Test getting base64 and escaped values as bytes
+fn test_bytes_value() {
+    let cfg = Config::new()
+        .text("a = base64:SGVsbG8=\nb = base64:SGVsbG8\nc = \\x00\\xffHi\\\\\nd = base64:S")
+        .unwrap();
+
+    assert_eq!(cfg.get_bytes("a").unwrap(), b"Hello");
+    assert_eq!(cfg.get_bytes("b").unwrap(), b"Hello");
+    assert_eq!(cfg.get_bytes("c").unwrap(), b"\x00\xffHi\\");
+    assert!(cfg.get_bytes("d").is_err());
+}
+
+#[cfg(feature = "file")]
+#[test]
+/// Test loading a config file with a timeout
+fn test_file_timeout() {
+    let cfg = Config::new()
+        .file_timeout("config.cfg", Duration::from_secs(5))
+        .unwrap();
+
+    assert_eq!(cfg.get_str("hello").unwrap(), "World");
+    assert!(matches!(
+        Config::new().file_timeout("nonexistent.cfg", Duration::from_secs(5)),
+        Err(ConfigError::FileNotFound(_))
+    ));
+}
+
+#[cfg(all(feature = "file", unix))]
+#[test]
+/// Test the timeout of loading a file covers the files it includes and extends
+fn test_file_timeout_includes() {
+    let dir = std::env::temp_dir().join(format!("scp-timeout-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    // Reading a fifo with nothing writing to it never finishes
+    let fifo = dir.join("hang.cfg");
+    let made = std::process::Command::new("mkfifo")
+        .arg(&fifo)
+        .status()
+        .unwrap();
+    assert!(made.success());
+    std::fs::write(dir.join("include.cfg"), "!include hang.cfg\n").unwrap();
+    std::fs::write(dir.join("extends.cfg"), "extends = hang.cfg\n").unwrap();
+
+    let timeout = Duration::from_millis(100);
+    for file in ["include.cfg", "extends.cfg"] {
+        let start = std::time::Instant::now();
+        let err = Config::new()
+            .file_timeout(dir.join(file), timeout)
+            .unwrap_err();
+        assert!(start.elapsed() < Duration::from_secs(5));
+        match err {
+            ConfigError::Include(path, e) => {
+                assert_eq!(path, fifo);
+                assert!(matches!(*e, ConfigError::Timeout(i) if i == timeout));
+            }
+            e => panic!("{:?}", e),
+        }
+    }
+
+    // Later reads are not timed
+    std::thread::sleep(timeout);
+    assert!(Config::new().file("config.cfg").is_ok());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(feature = "file")]
+#[test]
+/// Test optional sources that fail to load are skipped and reported
+fn test_file_optional() {
+    let cfg = Config::new()
+        .text("hello = people\nrust = is great")
+        .unwrap()
+        .file_optional("nonexistent.cfg")
+        .file_optional("config.cfg");
+
+    assert_eq!(cfg.get_str("hello").unwrap(), "World");
+    assert_eq!(cfg.get_str("rust").unwrap(), "Is great");
+
+    let failures = cfg.load_report().failures().collect::<Vec<_>>();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].source, "nonexistent.cfg");
+    assert!(matches!(
+        failures[0].error,
+        Some(ConfigError::FileNotFound(_))
+    ));
+}
+
+#[test]
+/// Test the load report lists every source with counts and overrides
+fn test_load_report() {
+    let cfg = Config::new()
+        .text("a = 1\nb = 2")
+        .unwrap()
+        .text("b = 3\nc = 4\nb = 5")
+        .unwrap();
+
+    let sources = &cfg.load_report().sources;
+    assert_eq!(sources.len(), 2);
+    assert_eq!(sources[0].source, "<text>");
+    assert_eq!(sources[0].entries, 2);
+    assert!(sources[0].overridden.is_empty());
+    assert_eq!(sources[1].entries, 3);
+    assert_eq!(sources[1].overridden, ["b"]);
+    assert_eq!(cfg.get_str("a").unwrap(), "1");
+}
+
+#[test]
+/// Test reading keys through section views
+fn test_sections() {
+    let cfg = Config::new()
+        .text("name = app\n[Database]\nhost = db\nport = 5432\n[server]\nport = 80\n[database]\nport = 5433")
+        .unwrap();
+
+    assert_eq!(cfg.section_names(), ["database", "server"]);
+
+    let db = cfg.section("DATABASE").unwrap();
+    assert_eq!(db.name(), "database");
+    assert_eq!(db.get_str("host").unwrap(), "db");
+    assert_eq!(db.get::<u16>("port").unwrap(), 5433);
+    assert_eq!(db.keys(), ["host", "port"]);
+    assert!(db.get_str("name").is_err());
+
+    let server = cfg.section("server").unwrap();
+    assert_eq!(server.get::<u16>("port").unwrap(), 80);
+    assert!(cfg.section("nope").is_none());
+
+    // Flat access still works
+    assert_eq!(cfg.get_str("name").unwrap(), "app");
+    assert_eq!(cfg.get_str("host").unwrap(), "db");
+}
+
+#[test]
+/// Test an unclosed section header is invalid
+fn test_invalid_section() {
+    assert!(matches!(
+        Config::new().text("[section\nhello = world"),
+        Err(ConfigError::InvalidConfig)
+    ));
+}
+
+#[test]
+/// Test setting values and writing the config back out
+fn test_set_and_to_string() {
+    let mut cfg = Config::new()
+        .text("a = 1\n[server]\nport = 80\n[db]\nhost = x")
+        .unwrap();
+
+    cfg.set("port", "8080");
+    cfg.set("New Key", "hello");
+
+    assert_eq!(
+        cfg.to_string(),
+        "a = 1\nnewkey = hello\n[server]\nport = 8080\n[db]\nhost = x\n"
+    );
+
+    let cfg = Config::new().text(cfg).unwrap();
+    assert_eq!(
+        cfg.section("server").unwrap().get::<u16>("port").unwrap(),
+        8080
+    );
+}
+
+#[cfg(feature = "file")]
+#[test]
+/// Test saving a config to a file and loading it again
+fn test_save() {
+    let path = std::env::temp_dir().join(format!("scp-test-save-{}.cfg", std::process::id()));
+    let mut cfg = Config::new();
+
+    assert!(matches!(cfg.save(), Err(ConfigError::NoFileDefined)));
+    cfg.set("hello", "world");
+    cfg.save_to(&path).unwrap();
+
+    let mut cfg = Config::new().file(&path).unwrap();
+    cfg.set("hello", "people");
+    cfg.save().unwrap();
+
+    let cfg = Config::new().file(&path).unwrap();
+    assert_eq!(cfg.get_str("hello").unwrap(), "people");
+    std::fs::remove_file(path).unwrap();
+}
+
+#[cfg(feature = "file")]
+#[test]
+/// Test setting dotted names updates and adds keys in their section, after saving too
+fn test_set_dotted() {
+    let path = std::env::temp_dir().join(format!("scp-test-set-dotted-{}.cfg", std::process::id()));
+    std::fs::write(&path, "name = app\n[db]\nhost = old\n\n[web]\nport = 80\n").unwrap();
+
+    let mut cfg = Config::new().file(&path).unwrap();
+    cfg.set("db.host", "new");
+    cfg.set("db.user", "admin");
+    cfg.set("cache.size", "64");
+    assert_eq!(cfg.section("db").unwrap().get_str("host").unwrap(), "new");
+    assert_eq!(cfg.get_str("db.host").unwrap(), "new");
+    cfg.save().unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "name = app\n[db]\nhost = new\nuser = admin\n\n[web]\nport = 80\n[cache]\nsize = 64\n"
+    );
+    let cfg = Config::new().file(&path).unwrap();
+    assert_eq!(cfg.get_str("db.host").unwrap(), "new");
+    assert_eq!(cfg.section("db").unwrap().get_str("user").unwrap(), "admin");
+    assert_eq!(cfg.section("cache").unwrap().get::<u8>("size").unwrap(), 64);
+    assert_eq!(cfg.data.lookup("host").count(), 1);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+/// Test saving keeps comments, blank lines and formatting
+fn test_round_trip_formatting() {
+    let text =
+        "; Main config\n\nname   =  app  # The name\n\n# Server stuff\n[Server]\n  port=80;http\n";
+    let mut cfg = Config::new().text(text).unwrap();
+
+    assert_eq!(cfg.to_string(), text);
+
+    cfg.set("port", "8080");
+    cfg.set("name", "other");
+    cfg.set("debug", "true");
+
+    assert_eq!(
+        cfg.to_string(),
+        "; Main config\n\nname   =  other  # The name\ndebug = true\n\n# Server stuff\n[Server]\n  port=8080;http\n"
+    );
+}
+
+#[test]
+/// Test percent decoding values and building URLs from keys
+fn test_percent_and_url() {
+    let cfg = Config::new()
+        .text("a = 100%25%20done\nb = bad%2\nuser = m:e\nhost = example.com\nport = 80")
+        .unwrap();
+
+    assert_eq!(cfg.get_percent_decoded("a").unwrap(), "100% done");
+    assert!(matches!(
+        cfg.get_percent_decoded("b"),
+        Err(ConfigError::InvalidType(key, value, _)) if key == "b" && value == "bad%2"
+    ));
+    assert_eq!(
+        cfg.build_url(&["user", "pass", "host", "port"]).unwrap(),
+        "m%3Ae@example.com:80"
+    );
+    assert_eq!(
+        cfg.build_url(&["no_user", "pass", "host", "no_port"])
+            .unwrap(),
+        "example.com"
+    );
+    assert!(matches!(
+        cfg.build_url(&["user", "pass", "no_host", "port"]),
+        Err(ConfigError::NoItem)
+    ));
+}
+
+#[cfg(feature = "file")]
+#[test]
+/// Test stale config warnings
+fn test_check_stale() {
+    let mut cfg = Config::new().file("config.cfg").unwrap();
+    let policy = StalePolicy {
+        max_file_age: Some(Duration::ZERO),
+        max_unvalidated: None,
+    };
+    assert!(matches!(
+        cfg.check_stale(&policy)[..],
+        [Staleness::OldFile(_)]
+    ));
+
+    let policy = StalePolicy {
+        max_file_age: None,
+        max_unvalidated: Some(Duration::from_millis(10)),
+    };
+    assert!(cfg.check_stale(&policy).is_empty());
+    std::thread::sleep(Duration::from_millis(20));
+    assert!(matches!(
+        cfg.check_stale(&policy)[..],
+        [Staleness::NotRevalidated(_)]
+    ));
+    cfg.revalidate();
+    assert!(cfg.check_stale(&policy).is_empty());
+
+    let text = Config::new().text("a = 1").unwrap();
+    assert!(text.check_stale(&StalePolicy::default()).is_empty());
+}
+
+#[test]
+/// Test removing keys deletes or comments out their lines
+fn test_remove() {
+    let text = "a = 1\nb = 2 ; Comment\n[s]\nb = 3\nc = 4";
+    let mut cfg = Config::new().text(text).unwrap();
+
+    // A bare key is the most recent definition, in any section
+    assert_eq!(cfg.remove("B").unwrap(), "3");
+    assert_eq!(cfg.get_str("b").unwrap(), "2");
+    assert_eq!(cfg.remove("b").unwrap(), "2");
+    assert!(cfg.remove("b").is_none());
+    assert!(cfg.get_str("b").is_err());
+    cfg.set("c", "5");
+    assert_eq!(cfg.to_string(), "a = 1\n[s]\nc = 5\n");
+
+    let mut cfg = Config::new()
+        .remove_policy(RemovePolicy::CommentOut)
+        .text(text)
+        .unwrap();
+    cfg.remove("b");
+    cfg.remove("b");
+    cfg.set("a", "0");
+
+    let out = cfg.to_string();
+    let lines = out.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "a = 0");
+    assert!(lines[1].starts_with("# removed 20"));
+    assert!(lines[1].ends_with(": b = 2 ; Comment"));
+    assert!(lines[3].ends_with(": b = 3"));
+    assert_eq!(lines[4], "c = 4");
+
+    // Commented out lines are ignored when loading again
+    let cfg = Config::new().text(out).unwrap();
+    assert!(cfg.get_str("b").is_err());
+
+    // Dotted names only remove the key in their section
+    let mut cfg = Config::new()
+        .text("host = a\nhost = b\n[db]\nhost = c\n[cache]\nhost = d")
+        .unwrap();
+    assert_eq!(cfg.remove("db.host").unwrap(), "c");
+    assert!(cfg.get_str("db.host").is_err());
+    assert_eq!(cfg.get_str("cache.host").unwrap(), "d");
+    assert_eq!(
+        cfg.to_string(),
+        "host = a\nhost = b\n[db]\n[cache]\nhost = d\n"
+    );
+    assert!(cfg.remove("db.host").is_none());
+
+    // A bare key in a section leaves the other sections alone
+    assert_eq!(cfg.remove("host").unwrap(), "d");
+    assert_eq!(cfg.to_string(), "host = a\nhost = b\n[db]\n[cache]\n");
+    assert_eq!(cfg.remove("host").unwrap(), "b");
+    assert_eq!(cfg.to_string(), "[db]\n[cache]\n");
+}
+
+#[cfg(feature = "file")]
+#[test]
+/// Test guarded saving respects managed-by markers
+fn test_save_guarded() {
+    let path = std::env::temp_dir().join(format!("scp-test-guard-{}.cfg", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    // New files can always be written
+    let mut cfg = Config::new();
+    cfg.set("a", "1");
+    cfg.set_managed_by("bot");
+    cfg.save_to(&path).unwrap();
+
+    let mut cfg = Config::new().file(&path).unwrap();
+    assert_eq!(cfg.managed_by().unwrap(), "bot");
+    cfg.set("a", "2");
+    cfg.save_guarded(SaveGuard::ManagedBy("bot")).unwrap();
+    assert!(matches!(
+        cfg.save_guarded(SaveGuard::ManagedBy("other")),
+        Err(ConfigError::WriteProtected)
+    ));
+    assert!(matches!(
+        cfg.save_guarded(SaveGuard::Unmanaged),
+        Err(ConfigError::WriteProtected)
+    ));
+
+    // Someone takes the file over by hand
+    std::fs::write(&path, "a = 3").unwrap();
+    let cfg = Config::new().file(&path).unwrap();
+    assert!(cfg.managed_by().is_none());
+    assert!(cfg.save_guarded(SaveGuard::ManagedBy("bot")).is_err());
+    cfg.save_guarded(SaveGuard::Unmanaged).unwrap();
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+/// Test falling back through a list of keys
+fn test_get_first() {
+    let cfg = Config::new().text("old = 1\nnew = 2\nbad = x").unwrap();
+
+    assert_eq!(cfg.get_first::<u8>(&["new", "old"]).unwrap(), (2, "new"));
+    assert_eq!(cfg.get_first::<u8>(&["newer", "old"]).unwrap(), (1, "old"));
+    assert!(matches!(
+        cfg.get_first::<u8>(&["bad", "old"]),
+        Err(ConfigError::InvalidType(..))
+    ));
+    assert!(matches!(
+        cfg.get_first::<u8>(&["a", "b"]),
+        Err(ConfigError::NoItem)
+    ));
+}
+
+#[cfg(feature = "file")]
+#[test]
+/// Test errors can be displayed and used as `std::error::Error`
+fn test_error_display() {
+    let error = Config::new().file("nonexistent.cfg").err().unwrap();
+    match &error {
+        ConfigError::FileNotFound(i) => assert_eq!(i, std::path::Path::new("nonexistent.cfg")),
+        _ => panic!("Expected a not found error"),
+    }
+    assert_eq!(error.io_kind(), Some(std::io::ErrorKind::NotFound));
+    let include = Config::new()
+        .text("!include nonexistent.cfg")
+        .err()
+        .unwrap();
+    assert_eq!(include.io_kind(), Some(std::io::ErrorKind::NotFound));
+    assert_eq!(ConfigError::NoItem.io_kind(), None);
+
+    let error: Box<dyn std::error::Error> = Box::new(error);
+    assert_eq!(error.to_string(), "Config file `nonexistent.cfg` not found");
+    assert!(error.source().is_none());
+
+    let error = ConfigError::FileRead("a.cfg".into(), std::io::Error::other("Disk on fire"));
+    assert_eq!(error.to_string(), "Error reading `a.cfg`: Disk on fire");
+    assert!(std::error::Error::source(&error).is_some());
+    assert_eq!(
+        ConfigError::NoItem.to_string(),
+        "No config item for the key exists"
+    );
+}
+
+#[test]
+/// Test splitting values into shell style arguments
+fn test_get_args() {
+    let cfg = Config::new()
+        .text(concat!(
+            "a = cmd  one\ttwo\n",
+            "b = say \"a \\\"quoted\\\" \\\\ \\n word\" 'it''s' \\ x\n",
+            "c = \"\" ''\n",
+            "d = \"unclosed\n",
+            "e = trailing\\"
+        ))
+        .unwrap();
+
+    assert_eq!(cfg.get_args("a").unwrap(), ["cmd", "one", "two"]);
+    assert_eq!(
+        cfg.get_args("b").unwrap(),
+        ["say", "a \"quoted\" \\ \\n word", "its", " x"]
+    );
+    assert_eq!(cfg.get_args("c").unwrap(), ["", ""]);
+    assert!(matches!(
+        cfg.get_args("d"),
+        Err(ConfigError::InvalidType(key, _, target)) if key == "d" && target == "arguments"
+    ));
+    assert!(matches!(
+        cfg.get_args("e"),
+        Err(ConfigError::InvalidType(..))
+    ));
+}
+
+#[test]
+/// Test quoted values keep comment chars and decode escapes
+fn test_quoted_values() {
+    let cfg = Config::new()
+        .text(concat!(
+            "a = \"# not ; a = comment\" ; comment\n",
+            "b = \"say \\\"hi\\\"\\n\\tand \\\\ \\x\"\n",
+            "c = \"  spaces  \"\n",
+            "d = \"a\" b\n",
+            "e = \"unclosed # comment\n",
+            "f = \"\"",
+        ))
+        .unwrap();
+
+    assert_eq!(cfg.get_str("a").unwrap(), "# not ; a = comment");
+    assert_eq!(cfg.get_str("b").unwrap(), "say \"hi\"\n\tand \\ \\x");
+    assert_eq!(cfg.get_str("c").unwrap(), "  spaces  ");
+    assert_eq!(cfg.get_str("d").unwrap(), "\"a\" b");
+    assert_eq!(cfg.get_str("e").unwrap(), "\"unclosed");
+    assert_eq!(cfg.get_str("f").unwrap(), "");
+}
+
+#[test]
+/// Test values are quoted when written out and read back the same
+fn test_quoted_round_trip() {
+    let mut cfg = Config::new().text("a = \"# kept\" ; and this").unwrap();
+    assert_eq!(cfg.to_string(), "a = \"# kept\" ; and this\n");
+
+    cfg.set("a", "x # y");
+    cfg.set("b", " \"quoted\"\n\\ ");
+    cfg.set("c", "plain \\ value");
+    assert_eq!(
+        cfg.to_string(),
+        "a = \"x # y\" ; and this\nb = \"\"\"\n \"quoted\"\n\\ \"\"\"\nc = plain \\ value\n"
+    );
+
+    let cfg = Config::new().text(cfg).unwrap();
+    assert_eq!(cfg.get_str("a").unwrap(), "x # y");
+    assert_eq!(cfg.get_str("b").unwrap(), " \"quoted\"\n\\ ");
+    assert_eq!(cfg.get_str("c").unwrap(), "plain \\ value");
+
+    let mut cfg = Config::new();
+    cfg.set("crlf", "line 1\r\nline 2");
+    cfg.set("cr", "a\rb");
+    assert_eq!(
+        cfg.to_string(),
+        "crlf = \"line 1\\r\\nline 2\"\ncr = \"a\\rb\"\n"
+    );
+
+    let cfg = Config::new().text(cfg).unwrap();
+    assert_eq!(cfg.get_str("crlf").unwrap(), "line 1\r\nline 2");
+    assert_eq!(cfg.get_str("cr").unwrap(), "a\rb");
+}
+
+#[test]
+/// Test picking the parser from a content type
+fn test_content_type() {
+    assert_eq!(Format::from_content_type("text/plain"), Some(Format::Ini));
+    assert_eq!(
+        Format::from_content_type("application/x-ini; charset=\"utf-8\""),
+        Some(Format::Ini)
+    );
+    assert_eq!(
+        Format::from_content_type("text/plain; charset=latin1"),
+        None
+    );
+    assert_eq!(Format::from_content_type("application/json"), None);
+
+    let cfg = Config::new()
+        .text_with_content_type("a = 1\n[b]\nc = 2", "text/x-ini")
+        .unwrap();
+    assert_eq!(cfg.get::<u8>("c").unwrap(), 2);
+
+    match Config::new().text_with_content_type("{}", "application/json") {
+        Err(ConfigError::UnsupportedFormat(i)) => assert_eq!(i, "application/json"),
+        _ => panic!("Content type should be unsupported"),
+    }
+}
+
+#[test]
+/// Test multi-line values between triple quotes
+fn test_multi_line_values() {
+    let text = concat!(
+        "key = \"\"\"\n",
+        "-----BEGIN KEY-----\n",
+        "  ab#cd;\n",
+        "-----END KEY-----\"\"\" ; comment\n",
+        "sql = \"\"\"SELECT *\n",
+        "FROM users\"\"\"\n",
+        "one = \"\"\"a \\n b\"\"\"\n",
+        "after = 1",
+    );
+    let mut cfg = Config::new().text(text).unwrap();
+
+    assert_eq!(
+        cfg.get_str("key").unwrap(),
+        "-----BEGIN KEY-----\n  ab#cd;\n-----END KEY-----"
+    );
+    assert_eq!(cfg.get_str("sql").unwrap(), "SELECT *\nFROM users");
+    assert_eq!(cfg.get_str("one").unwrap(), "a \\n b");
+    assert_eq!(cfg.get::<u8>("after").unwrap(), 1);
+    assert_eq!(cfg.to_string(), format!("{}\n", text));
+
+    cfg.set("sql", "SELECT 1\nFROM dual");
+    let cfg = Config::new().text(cfg).unwrap();
+    assert_eq!(cfg.get_str("sql").unwrap(), "SELECT 1\nFROM dual");
+
+    let mut cfg = Config::new()
+        .text("a = \"\"\"\n1\n2\"\"\"\nb = 2")
+        .unwrap()
+        .remove_policy(RemovePolicy::CommentOut);
+    cfg.remove("a");
+    let text = cfg.to_string();
+    let lines = text.lines().collect::<Vec<_>>();
+    assert!(lines[0].starts_with("# removed ") && lines[0].ends_with(": a = \"\"\""));
+    assert_eq!(lines[1..], ["# 1", "# 2\"\"\"", "b = 2"]);
+
+    assert!(matches!(
+        Config::new().text("a = \"\"\"\nnot closed"),
+        Err(ConfigError::InvalidConfig)
+    ));
+    assert!(matches!(
+        Config::new().text("a = \"\"\"x\"\"\" y"),
+        Err(ConfigError::InvalidConfig)
+    ));
+}
+
+#[test]
+/// Test reading list values
+fn test_get_vec() {
+    let cfg = Config::new()
+        .text("ports = 80, 443 ,8080\nempty =\nnames = a,b c\nbad = 1, x\n[s]\nids = 1,2")
+        .unwrap();
+
+    assert_eq!(cfg.get_vec::<u16>("ports").unwrap(), [80, 443, 8080]);
+    assert_eq!(cfg.get_vec::<u16>("empty").unwrap(), Vec::<u16>::new());
+    assert_eq!(cfg.get_vec::<String>("names").unwrap(), ["a", "b c"]);
+    assert!(matches!(
+        cfg.get_vec::<u8>("bad"),
+        Err(ConfigError::InvalidType(..))
+    ));
+    assert!(matches!(
+        cfg.get_vec::<u8>("missing"),
+        Err(ConfigError::NoItem)
+    ));
+    assert_eq!(
+        cfg.section("s").unwrap().get_vec::<u8>("ids").unwrap(),
+        [1, 2]
+    );
+
+    let cfg = cfg.list_delimiter(' ');
+    assert_eq!(cfg.get_vec::<String>("names").unwrap(), ["a,b", "c"]);
+}
+
+#[test]
+/// Test the map like API dedups keys with the last definition winning
+fn test_key_iteration() {
+    let mut cfg = Config::new()
+        .text("a = 1\nB = 2\n[s]\na = 3\nc = 4")
+        .unwrap();
+
+    assert_eq!(cfg.keys(), ["a", "b", "c"]);
+    assert_eq!(
+        cfg.iter().collect::<Vec<_>>(),
+        [("a", "3"), ("b", "2"), ("c", "4")]
+    );
+    assert!(cfg.contains_key("A"));
+    assert!(!cfg.contains_key("d"));
+    assert_eq!(cfg.len(), 3);
+    assert!(!cfg.is_empty());
+
+    assert_eq!(cfg.remove("a").unwrap(), "3");
+    assert_eq!(cfg.remove("a").unwrap(), "1");
+    assert_eq!(cfg.keys(), ["b", "c"]);
+    assert_eq!(cfg.len(), 2);
+
+    let cfg = Config::new();
+    assert!(cfg.is_empty());
+    assert_eq!(cfg.len(), 0);
+    assert_eq!(cfg.iter().count(), 0);
+}
+
+#[test]
+/// Test reading config piped into stdin
+/// (the test runs itself with stdin piped and checks the child read it)
+fn test_stdin() {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    if std::env::var_os("SCP_STDIN_CHILD").is_some() {
+        let cfg = Config::new().stdin().unwrap();
+        assert_eq!(cfg.get_str("hello").unwrap(), "World");
+        return;
+    }
+
+    let mut child = Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", "test_stdin", "--test-threads", "1"])
+        .env("SCP_STDIN_CHILD", "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"hello = World")
+        .unwrap();
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+/// Test defaults for missing keys and layered default configs
+fn test_defaults() {
+    let cfg = Config::new().text("port = 8080\nbad = x").unwrap();
+    assert_eq!(cfg.get_or("port", 80u16).unwrap(), 8080);
+    assert_eq!(cfg.get_or("missing", 80u16).unwrap(), 80);
+    assert!(matches!(
+        cfg.get_or("bad", 1u8),
+        Err(ConfigError::InvalidType(..))
+    ));
+    assert_eq!(cfg.get_str_or("missing", "a"), "a");
+
+    let base = Config::new().text("a = 1\n[db]\nhost = localhost").unwrap();
+    let defaults = Config::new()
+        .with_defaults(base)
+        .text("b = 2\n[db]\nport = 5432")
+        .unwrap();
+    let mut cfg = Config::new()
+        .with_defaults(defaults)
+        .text("b = 3\n[db]\nhost = db.local")
+        .unwrap();
+
+    assert_eq!(cfg.get::<u8>("a").unwrap(), 1);
+    assert_eq!(cfg.get::<u8>("b").unwrap(), 3);
+    assert_eq!(cfg.keys(), ["a", "host", "b", "port"]);
+    assert_eq!(cfg.section_names(), ["db"]);
+
+    let db = cfg.section("db").unwrap();
+    assert_eq!(db.get_str("host").unwrap(), "db.local");
+    assert_eq!(db.get::<u16>("port").unwrap(), 5432);
+    assert_eq!(db.keys(), ["host", "port"]);
+
+    // Only the config's own values are written
+    assert_eq!(cfg.to_string(), "b = 3\n[db]\nhost = db.local\n");
+    cfg.remove("b");
+    assert_eq!(cfg.get::<u8>("b").unwrap(), 2);
+    assert!(!Config::new().with_defaults(Config::new()).contains_key("a"));
+}
+
+#[test]
+/// Test deprecated keys become errors once the app version removes them
+fn test_deprecations() {
+    let text = "old = 1\nlegacy = 2\nnew = 3\n[s]\nold = 4";
+    let build = |version: &str| {
+        Config::new()
+            .app_version(version)
+            .deprecate(Deprecation::new("Old").removed_in("2.0"))
+            .deprecate(Deprecation::new("legacy").note("use `new`"))
+            .deprecate(Deprecation::new("unused").removed_in("1.0.0"))
+            .text(text)
+            .unwrap()
+    };
+
+    let cfg = build("1.9.9");
+    assert_eq!(cfg.get::<u8>("old").unwrap(), 4);
+    let warnings = cfg.check_deprecations().unwrap();
+    assert_eq!(warnings.len(), 2);
+    assert_eq!(warnings[1].note.as_deref(), Some("use `new`"));
+
+    for version in ["2.0.0", "v2.0.0-rc.1", "10.1"] {
+        let cfg = build(version);
+        match cfg.get::<u8>("old") {
+            Err(ConfigError::RemovedKey(i)) => assert_eq!(i, "old"),
+            _ => panic!("`old` should be removed in {}", version),
+        }
+        assert!(matches!(
+            cfg.section("s").unwrap().get_str("old"),
+            Err(ConfigError::RemovedKey(_))
+        ));
+        assert!(matches!(
+            cfg.check_deprecations(),
+            Err(ConfigError::RemovedKey(_))
+        ));
+        assert_eq!(cfg.get::<u8>("legacy").unwrap(), 2);
+        assert!(matches!(cfg.get_str("unused"), Err(ConfigError::NoItem)));
+    }
+
+    // With no app version keys are never removed
+    let cfg = Config::new()
+        .deprecate(Deprecation::new("old").removed_in("0.1"))
+        .text(text)
+        .unwrap();
+    assert_eq!(cfg.get::<u8>("old").unwrap(), 4);
+}
+
+#[test]
+/// Test checking configs against composed section schemas
+fn test_schema() {
+    use simple_config_parser::{Schema, SchemaKey, ValueType};
+
+    const DB: Schema = Schema::section(
+        "Database",
+        &[
+            SchemaKey::required("host", ValueType::Str),
+            SchemaKey::optional("port", ValueType::Int),
+        ],
+    );
+    const SERVER: Schema = Schema::section(
+        "server",
+        &[
+            SchemaKey::required("ratio", ValueType::Float),
+            SchemaKey::optional("debug", ValueType::Bool),
+        ],
+    );
+    const ROOT: Schema = Schema::new(&[SchemaKey::required("name", ValueType::Str)]);
+
+    let schema = ROOT + DB + SERVER;
+    assert_eq!(schema.keys().len(), 5);
+
+    let cfg = Config::new()
+        .text("name = app\n[database]\nhost = db\nport = 5432\n[server]\nratio = 0.5")
+        .unwrap();
+    assert!(cfg.validate(&schema).is_ok());
+
+    // Keys in other sections don't count
+    let cfg = Config::new()
+        .text("[database]\nport = x\n[server]\nhost = db\nratio = 1\ndebug = yes")
+        .unwrap();
+    let errors = cfg.validate(&schema).unwrap_err();
+    let errors = errors
+        .keys
+        .iter()
+        .map(|i| (i.section, i.key, matches!(i.error, ConfigError::NoItem)))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        errors,
+        [
+            (None, "name", true),
+            (Some("Database"), "host", true),
+            (Some("Database"), "port", false),
+            (Some("server"), "debug", false),
+        ]
+    );
+}
+
+#[test]
+/// Test expanding environment variables in values
+fn test_env_interpolation() {
+    std::env::set_var("SCP_TEST_USER", "admin");
+    std::env::remove_var("SCP_TEST_UNSET");
+    let text = concat!(
+        "a = ${env:SCP_TEST_USER}@${env:SCP_TEST_USER}\n",
+        "b = \"$${env:SCP_TEST_USER} ${env:SCP_TEST_UNSET}\"\n",
+        "c = ${other} ${env:SCP_TEST_USER",
+    );
+
+    let mut cfg = Config::new().expand_env(UnsetEnv::Keep).text(text).unwrap();
+    assert_eq!(cfg.get_str("a").unwrap(), "admin@admin");
+    assert_eq!(
+        cfg.get_str("b").unwrap(),
+        "${env:SCP_TEST_USER} ${env:SCP_TEST_UNSET}"
+    );
+    assert_eq!(cfg.get_str("c").unwrap(), "${other} ${env:SCP_TEST_USER");
+
+    // References are kept when writing unchanged values
+    cfg.set("c", "new");
+    assert_eq!(
+        cfg.to_string(),
+        format!("{}\n", text.replace("${other} ${env:SCP_TEST_USER", "new"))
+    );
+
+    match Config::new().expand_env(UnsetEnv::Error).text(text) {
+        Err(ConfigError::MissingEnv(i)) => assert_eq!(i, "SCP_TEST_UNSET"),
+        _ => panic!("Unset variable should be an error"),
+    }
+
+    // Off by default
+    let cfg = Config::new().text(text).unwrap();
+    assert_eq!(
+        cfg.get_str("a").unwrap(),
+        "${env:SCP_TEST_USER}@${env:SCP_TEST_USER}"
+    );
+}
+
+#[test]
+/// Test libraries registering namespaces with defaults and schemas
+fn test_namespaces() {
+    use simple_config_parser::{Namespace, SchemaKey, ValueType};
+
+    const KEYS: &[SchemaKey] = &[
+        SchemaKey::required("url", ValueType::Str),
+        SchemaKey::optional("pool", ValueType::Int),
+    ];
+
+    let mut cfg = Config::new()
+        .text("pool = 1\n[db]\npool = 8\n[cache]\nsize = 10")
+        .unwrap();
+    let db = Namespace::new("DB")
+        .default("url", "sqlite://")
+        .schema(KEYS);
+    assert!(cfg.register(db).is_ok());
+    assert!(cfg
+        .register(Namespace::new("cache").default("ttl", "60"))
+        .is_ok());
+
+    let db = cfg.namespace("db");
+    assert_eq!(db.get_str("url").unwrap(), "sqlite://");
+    assert_eq!(db.get::<u8>("pool").unwrap(), 8);
+    assert_eq!(cfg.namespace("cache").get::<u8>("ttl").unwrap(), 60);
+    assert_eq!(cfg.namespace("cache").keys(), ["ttl", "size"]);
+    assert!(matches!(
+        cfg.namespace("other").get_str("url"),
+        Err(ConfigError::NoItem)
+    ));
+
+    // Defaults are not saved
+    assert_eq!(
+        cfg.to_string(),
+        "pool = 1\n[db]\npool = 8\n[cache]\nsize = 10\n"
+    );
+
+    // Schemas are checked on registration and again after changes
+    let bad = Namespace::new("bad").schema(KEYS);
+    assert_eq!(cfg.register(bad).unwrap_err().keys[0].key, "url");
+    cfg.set("pool", "x");
+    assert_eq!(cfg.validate_namespaces().unwrap_err().keys.len(), 2);
+}
+
+#[test]
+/// Test filtering, coalescing and debouncing change events
+fn test_change_events() {
+    use std::thread;
+
+    let mut cfg = Config::new()
+        .text("a = 1\n[db]\nhost = localhost\nport = 5432")
+        .unwrap();
+    let all = cfg.subscribe(&[]);
+    let db = cfg.subscribe(&["db.*"]);
+    let port = cfg.subscribe(&["DB.p?rt"]);
+    drop(cfg.subscribe(&[]));
+
+    cfg.set("a", "2");
+    cfg.set("a", "1");
+    cfg.set("host", "db1");
+    cfg.remove("port");
+    cfg = cfg.text("b = 3\nhost = db1").unwrap();
+
+    let event = all.try_recv().unwrap();
+    let keys = event
+        .changes
+        .iter()
+        .map(|i| i.key.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(keys, ["db.host", "db.port", "b"]);
+    assert_eq!(event.changes[1].new, None);
+    assert!(all.try_recv().is_none());
+
+    assert_eq!(db.try_recv().unwrap().changes.len(), 2);
+    assert_eq!(port.try_recv().unwrap().changes[0].key, "db.port");
+
+    // Rapid changes within the debounce window are one event
+    let changes = cfg.subscribe(&["a"]).debounce(Duration::from_millis(100));
+    let writer = thread::spawn(move || {
+        for i in 0..5 {
+            cfg.set("a", &i.to_string());
+            thread::sleep(Duration::from_millis(5));
+        }
+        cfg
+    });
+
+    let event = changes.recv().unwrap();
+    assert_eq!(event.changes.len(), 1);
+    assert_eq!(event.changes[0].old.as_deref(), Some("1"));
+    assert_eq!(event.changes[0].new.as_deref(), Some("4"));
+
+    drop(writer.join().unwrap());
+    assert!(changes.recv().is_none());
+}
+
+#[cfg(feature = "file")]
+#[test]
+/// Test including files relative to the including file
+fn test_include() {
+    use std::fs;
+
+    let dir = std::env::temp_dir().join(format!("scp-include-{}", std::process::id()));
+    fs::create_dir_all(dir.join("sub")).unwrap();
+    let main = "a = 1\n!include \"sub/a.cfg\" ; comment\nb = 3\n[s]\nc = 1";
+    fs::write(dir.join("main.cfg"), main).unwrap();
+    fs::write(
+        dir.join("sub/a.cfg"),
+        "a = 2\nb = 2\n!include b.cfg\n[s]\nd = 1",
+    )
+    .unwrap();
+    fs::write(dir.join("sub/b.cfg"), "e = 5").unwrap();
+    fs::write(dir.join("loop.cfg"), "!include loop.cfg").unwrap();
+    fs::write(dir.join("bad.cfg"), "!include sub/bad.cfg").unwrap();
+    fs::write(dir.join("sub/bad.cfg"), "no equals").unwrap();
+
+    let mut cfg = Config::new().file(dir.join("main.cfg")).unwrap();
+    assert_eq!(cfg.get::<u8>("a").unwrap(), 2);
+    assert_eq!(cfg.get::<u8>("b").unwrap(), 3);
+    assert_eq!(cfg.get::<u8>("e").unwrap(), 5);
+    assert_eq!(cfg.section("s").unwrap().keys(), ["d", "c"]);
+
+    // Included entries are not written out
+    assert_eq!(cfg.to_string(), format!("{}\n", main));
+    cfg.remove("b");
+    assert_eq!(
+        cfg.to_string(),
+        "a = 1\n!include \"sub/a.cfg\" ; comment\n[s]\nc = 1\n"
+    );
+    assert_eq!(cfg.get::<u8>("e").unwrap(), 5);
+
+    match Config::new().file(dir.join("bad.cfg")) {
+        Err(ConfigError::Include(file, e)) => {
+            assert!(file.ends_with("sub/bad.cfg"));
+            assert!(matches!(*e, ConfigError::InvalidConfig));
+        }
+        _ => panic!("Include should fail"),
+    }
+
+    let mut error = match Config::new().file(dir.join("loop.cfg")) {
+        Err(e) => e,
+        Ok(_) => panic!("Include loop should fail"),
+    };
+    while let ConfigError::Include(_, e) = error {
+        error = *e;
+    }
+    assert!(matches!(error, ConfigError::IncludeDepth));
+
+    // Text is relative to the current directory
+    let cfg = Config::new().text("!include config.cfg").unwrap();
+    assert_eq!(cfg.get_str("hello").unwrap(), "World");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(feature = "file")]
+#[test]
+/// Test checking config files for `--check-config`
+fn test_check() {
+    use simple_config_parser::{check, Schema, SchemaKey, Severity, ValueType};
+
+    const SCHEMA: Schema = Schema::section(
+        "server",
+        &[
+            SchemaKey::required("port", ValueType::Int),
+            SchemaKey::required("host", ValueType::Str),
+        ],
+    );
+
+    let dir = std::env::temp_dir();
+    let path = dir.join(format!("scp-check-{}.cfg", std::process::id()));
+    std::fs::write(&path, "[server]\nport = 80\nport = eighty\ntypo = 1").unwrap();
+
+    let report = check(&path, &SCHEMA);
+    let issues = report
+        .issues
+        .iter()
+        .map(|i| (i.severity, i.key.as_deref().unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        issues,
+        [
+            (Severity::Error, "server.port"),
+            (Severity::Error, "server.host"),
+            (Severity::Warning, "server.port"),
+            (Severity::Warning, "server.typo"),
+        ]
+    );
+    assert!(!report.is_ok());
+    assert_eq!(report.exit_code(), 2);
+    assert!(report
+        .to_string()
+        .contains(": error: server.host: Required key is missing\n"));
+    assert!(report.to_json().contains(
+        "{\"severity\":\"warning\",\"key\":\"server.typo\",\"message\":\"Unknown key\"}"
+    ));
+
+    std::fs::write(
+        &path,
+        "[Server]\nport = 80\nhost = \"a \\\"b\\\"\"\nextra = 1",
+    )
+    .unwrap();
+    let report = check(&path, &SCHEMA);
+    assert!(report.is_ok());
+    assert_eq!(report.exit_code(), 1);
+
+    std::fs::remove_file(&path).unwrap();
+    let report = check(&path, &SCHEMA);
+    assert_eq!(report.issues[0].key, None);
+    assert!(report.to_json().contains("\"ok\":false"));
+    assert_eq!(report.exit_code(), 2);
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_toml() {
+    let text = r#"# Server settings
+title = "A \"quoted\" \u00e9 title" # trailing comment
+literal = 'C:\path'
+
+[server]
+port = 8_080
+mask = 0xff
+ratio = -1.5e2
+enabled = true
+"quoted key".name = "x"
+hosts = [
+    "a", # first
+    "b",
+]
+
+[db.primary]
+ports = [1, 2, 3]
+"#;
+    let mut cfg = Config::new().format(Format::Toml).text(text).unwrap();
+
+    assert_eq!(cfg.get_str("title").unwrap(), "A \"quoted\" é title");
+    assert_eq!(cfg.get_str("literal").unwrap(), "C:\\path");
+
+    let server = cfg.section("server").unwrap();
+    assert_eq!(server.get::<u16>("port").unwrap(), 8080);
+    assert_eq!(server.get::<u8>("mask").unwrap(), 255);
+    assert_eq!(server.get::<f64>("ratio").unwrap(), -150.0);
+    assert!(server.get::<bool>("enabled").unwrap());
+    assert_eq!(server.get_str("quotedkey.name").unwrap(), "x");
+    assert_eq!(server.get_vec::<String>("hosts").unwrap(), ["a", "b"]);
+
+    let db = cfg.section("db.primary").unwrap();
+    assert_eq!(db.get_vec::<u8>("ports").unwrap(), [1, 2, 3]);
+
+    // Unchanged lines are saved as they were
+    cfg.set("port", "9000");
+    let out = cfg.to_string();
+    assert!(out.contains("port = 9000\n"));
+    assert!(out.contains("hosts = [\n    \"a\", # first\n    \"b\",\n]\n"));
+
+    for i in ["[[items]", "a = [1, 2", "a = \"open", "a = nope", "a = 1 2"] {
+        assert!(matches!(
+            Config::new().format(Format::Toml).text(i),
+            Err(ConfigError::InvalidConfig)
+        ));
+    }
+
+    // Content types only change the format of their text
+    let cfg = Config::new()
+        .text_with_content_type("a = 'b'", "application/toml")
+        .unwrap()
+        .text("c = 'd'")
+        .unwrap();
+    assert_eq!(cfg.get_str("a").unwrap(), "b");
+    assert_eq!(cfg.get_str("c").unwrap(), "'d'");
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_json() {
+    let text = r#"{
+        "server": {"port": 8080, "host": "example.com", "tls": {"enabled": false}},
+        "ratio": -1.5e2,
+        "name": "café \"x\" 😀",
+        "tags": ["a", "b", 3],
+        "replicas": [{"host": "a"}, {"host": "b"}],
+        "empty": [],
+        "missing": null
+    }"#;
+    let cfg = Config::new().json(text).unwrap();
+
+    assert_eq!(cfg.get::<u16>("server.port").unwrap(), 8080);
+    assert_eq!(cfg.get_str("server.host").unwrap(), "example.com");
+    assert!(!cfg.get::<bool>("server.tls.enabled").unwrap());
+    assert_eq!(cfg.get::<f64>("ratio").unwrap(), -150.0);
+    assert_eq!(cfg.get_str("name").unwrap(), "café \"x\" 😀");
+    assert_eq!(cfg.get_vec::<String>("tags").unwrap(), ["a", "b", "3"]);
+    assert_eq!(cfg.get_str("replicas.1.host").unwrap(), "b");
+    assert_eq!(cfg.get_str("empty").unwrap(), "");
+    assert!(matches!(cfg.get_str("missing"), Err(ConfigError::NoItem)));
+
+    // Later sources still override
+    let cfg = cfg.text("server.port = 9000").unwrap();
+    assert_eq!(cfg.get::<u16>("server.port").unwrap(), 9000);
+
+    for i in [
+        "[1, 2]",
+        "{\"a\": }",
+        "{\"a\": 1,}",
+        "{\"a\": 1} x",
+        "{\"a\": nope}",
+    ] {
+        assert!(matches!(
+            Config::new().json(i),
+            Err(ConfigError::InvalidConfig)
+        ));
+    }
+}
+
+#[cfg(feature = "file")]
+#[test]
+fn test_occurrences() {
+    use std::fs;
+
+    let dir = std::env::temp_dir().join(format!("scp-occurrences-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let main = dir.join("main.cfg");
+    let other = dir.join("other.cfg");
+    fs::write(
+        &main,
+        "# Main\nport = 1\nlong = \"\"\"\na\n\"\"\"\n!include other.cfg\nport = 3",
+    )
+    .unwrap();
+    fs::write(&other, "\n[server]\nport = 2").unwrap();
+
+    let mut cfg = Config::new().file(&main).unwrap().text("port = 4").unwrap();
+    cfg.set("new", "5");
+
+    let found = cfg
+        .occurrences("PORT")
+        .into_iter()
+        .map(|i| (i.source, i.line, i.value))
+        .collect::<Vec<_>>();
+    let main = main.display().to_string();
+    let other = other.display().to_string();
+    assert_eq!(
+        found,
+        [
+            (main.to_owned(), Some(2), "1".to_owned()),
+            (other, Some(3), "2".to_owned()),
+            (main.to_owned(), Some(7), "3".to_owned()),
+            ("<text>".to_owned(), Some(1), "4".to_owned()),
+        ]
+    );
+    assert_eq!(
+        cfg.occurrences("port")[1].section.as_deref(),
+        Some("server")
+    );
+
+    let new = &cfg.occurrences("new")[0];
+    assert_eq!((new.source.as_str(), new.line), ("<set>", None));
+    assert!(cfg.occurrences("missing").is_empty());
+
+    // Removing a key keeps the others in line
+    cfg.remove("long");
+    assert_eq!(cfg.occurrences("port")[2].line, Some(7));
+    assert_eq!(cfg.occurrences("new")[0].source, "<set>");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_subtree() {
+    let cfg = Config::new()
+        .text("port = 1\nserver.host = a\n[server]\nport = 80\ntls.cert = x.pem\n[server.tls]\nenabled = true\n[other]\nhost = b")
+        .unwrap();
+
+    // Dotted names reach keys in sections
+    assert_eq!(cfg.get::<u16>("server.port").unwrap(), 80);
+    assert_eq!(cfg.get_str("SERVER.TLS.ENABLED").unwrap(), "true");
+    assert!(cfg.contains_key("server.tls.cert"));
+    assert!(!cfg.contains_key("other.port"));
+    assert_eq!(cfg.occurrences("server.port").len(), 1);
+
+    let server = cfg.subtree("server").unwrap();
+    assert_eq!(server.prefix(), "server");
+    assert_eq!(server.get_str("host").unwrap(), "a");
+    assert_eq!(server.get::<u16>("port").unwrap(), 80);
+    assert_eq!(server.keys(), ["host", "port", "tls.cert", "tls.enabled"]);
+    assert!(matches!(
+        server.get_str("missing"),
+        Err(ConfigError::NoItem)
+    ));
+
+    let tls = server.subtree("tls").unwrap();
+    assert_eq!(tls.keys(), ["cert", "enabled"]);
+    assert!(tls.get::<bool>("enabled").unwrap());
+    assert_eq!(
+        cfg.subtree("server.tls").unwrap().get_str("cert").unwrap(),
+        "x.pem"
+    );
+
+    assert!(cfg.subtree("serv").is_none());
+    assert!(server.subtree("nope").is_none());
+
+    // Works for flattened JSON too
+    let cfg = Config::new().json(r#"{"db": {"port": 5432}}"#).unwrap();
+    assert_eq!(cfg.subtree("db").unwrap().get::<u16>("port").unwrap(), 5432);
+}
+
+#[test]
+fn test_metadata() {
+    use std::time::SystemTime;
+
+    // Nothing is recorded by default
+    let cfg = Config::new().text("a = 1").unwrap();
+    assert_eq!(cfg.metadata("a").unwrap().changed, None);
+
+    let before = SystemTime::now();
+    let mut cfg = Config::new().timestamps(true).text("a = 1\nb = 2").unwrap();
+    let loaded = cfg.metadata("b").unwrap().changed.unwrap();
+    assert!(loaded >= before);
+
+    // Setting the same value is not a change
+    cfg.set("b", "2");
+    assert_eq!(cfg.metadata("b").unwrap().changed, Some(loaded));
+    assert_eq!(cfg.metadata("b").unwrap().line, Some(2));
+
+    cfg.set("b", "3");
+    let meta = cfg.metadata("b").unwrap();
+    assert_eq!((meta.source.as_str(), meta.line), ("<set>", None));
+    assert!(meta.changed.unwrap() >= loaded);
+    assert_eq!(cfg.metadata("a").unwrap().changed, Some(loaded));
+
+    assert!(cfg.metadata("missing").is_none());
+    let cfg = Config::new().with_defaults(cfg);
+    assert!(cfg.metadata("a").is_none());
+}
+
+#[test]
+fn test_sandbox() {
+    let sandbox = Sandbox::new(&["name", "Theme.Color", "limits.rate"])
+        .max_size(64)
+        .max_entries(3)
+        .max_value_len(8);
+
+    let cfg = sandbox
+        .parse("name = plugin\nlimits.rate = 5\n[theme]\ncolor = red")
+        .unwrap();
+    assert_eq!(cfg.get_str("theme.color").unwrap(), "red");
+    assert_eq!(cfg.get::<u8>("limits.rate").unwrap(), 5);
+
+    // Env references are kept as text
+    std::env::set_var("SCP_SANDBOX_SECRET", "hunter2");
+    let cfg = Sandbox::new(&["name"])
+        .parse("name = ${env:SCP_SANDBOX_SECRET}")
+        .unwrap();
+    assert_eq!(cfg.get_str("name").unwrap(), "${env:SCP_SANDBOX_SECRET}");
+
+    let reason = |text: &str| match sandbox.parse(text) {
+        Err(ConfigError::Restricted(i)) => i,
+        _ => panic!("`{}` should be rejected", text),
+    };
+    assert_eq!(reason("color = red"), "Key `color` is not allowed");
+    assert_eq!(
+        reason("[limits]\nrate = 1\nburst = 2"),
+        "Key `limits.burst` is not allowed"
+    );
+    assert_eq!(reason("!include other.cfg"), "Includes are not allowed");
+    assert_eq!(
+        reason("name = a\nname = b\nname = c\nname = d"),
+        "Over 3 entries are defined"
+    );
+    assert_eq!(
+        reason("name = very long name"),
+        "Value of `name` is over 8 bytes"
+    );
+    assert_eq!(reason(&"#".repeat(65)), "Text is over 64 bytes");
+
+    assert!(matches!(
+        sandbox.parse("bad line"),
+        Err(ConfigError::InvalidConfig)
+    ));
+}
+
+#[test]
+fn test_durations_and_sizes() {
+    let cfg = Config::new()
+        .text("a = 30s\nb = 5m\nc = 2h30m\nd = 1.5s\ne = 1d 2h\nf = 250ms\ng = 0\nh = 10\ni = 3x\nj = s\n[server]\nk = 10us")
+        .unwrap();
+
+    assert_eq!(cfg.get_duration("a").unwrap(), Duration::from_secs(30));
+    assert_eq!(cfg.get_duration("b").unwrap(), Duration::from_secs(300));
+    assert_eq!(cfg.get_duration("c").unwrap(), Duration::from_secs(9000));
+    assert_eq!(cfg.get_duration("d").unwrap(), Duration::from_millis(1500));
+    assert_eq!(cfg.get_duration("f").unwrap(), Duration::from_millis(250));
+    assert_eq!(cfg.get_duration("g").unwrap(), Duration::ZERO);
+    for i in ["e", "h", "i", "j"] {
+        assert!(matches!(
+            cfg.get_duration(i),
+            Err(ConfigError::InvalidType(..))
+        ));
+    }
+    assert!(matches!(cfg.get_duration("z"), Err(ConfigError::NoItem)));
+    let server = cfg.section("server").unwrap();
+    assert_eq!(server.get_duration("k").unwrap(), Duration::from_micros(10));
+
+    let cfg = Config::new()
+        .text("a = 512K\nb = 10MiB\nc = 1.5GB\nd = 100\ne = 2 kb\nf = 1t\ng = 10XB\nh = 99999999999T\n[disk]\nsize = 4GiB")
+        .unwrap();
+
+    assert_eq!(cfg.get_byte_size("a").unwrap(), 512 * 1024);
+    assert_eq!(cfg.get_byte_size("b").unwrap(), 10 * 1024 * 1024);
+    assert_eq!(cfg.get_byte_size("c").unwrap(), 1_500_000_000);
+    assert_eq!(cfg.get_byte_size("d").unwrap(), 100);
+    assert_eq!(cfg.get_byte_size("e").unwrap(), 2000);
+    assert_eq!(cfg.get_byte_size("f").unwrap(), 1 << 40);
+    assert!(matches!(
+        cfg.get_byte_size("g"),
+        Err(ConfigError::InvalidType(..))
+    ));
+    assert!(matches!(
+        cfg.get_byte_size("h"),
+        Err(ConfigError::InvalidType(..))
+    ));
+    let disk = cfg.subtree("disk").unwrap();
+    assert_eq!(disk.get_byte_size("size").unwrap(), 4 << 30);
+}
+
+#[cfg(feature = "file")]
+#[test]
+fn test_pin() {
+    use std::fs;
+
+    let dir = std::env::temp_dir().join(format!("scp-pin-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let system = dir.join("system.cfg");
+    let user = dir.join("user.cfg");
+    fs::write(&system, "[security]\ntls = required\nciphers = strong").unwrap();
+    fs::write(&user, "name = me\n[security]\ntls = off").unwrap();
+
+    let cfg = Config::new()
+        .text("name = default\ntls = off")
+        .unwrap()
+        .file(&system)
+        .unwrap()
+        .pin()
+        .file_optional(&user);
+
+    // The user layer was not loaded as it changes a pinned key
+    let failure = cfg.load_report().failures().next().unwrap();
+    match &failure.error {
+        Some(ConfigError::Pinned(i)) => {
+            assert_eq!(i.key, "security.tls");
+            assert_eq!(i.pinned_by, system.display().to_string());
+            assert_eq!(i.source, user.display().to_string());
+        }
+        _ => panic!("User layer should violate a pin"),
+    }
+    assert_eq!(cfg.get_str("name").unwrap(), "default");
+    assert_eq!(cfg.get_str("security.tls").unwrap(), "required");
+
+    // Only the pinned layer's keys are pinned
+    let mut cfg = cfg.text("name = changed").unwrap();
+    assert!(cfg.try_set("name", "set").is_ok());
+    assert_eq!(cfg.get_str("name").unwrap(), "set");
+
+    cfg.set("ciphers", "weak");
+    assert_eq!(cfg.get_str("ciphers").unwrap(), "strong");
+    let err = cfg.try_set("CIPHERS", "weak").err().unwrap();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "`security.ciphers` is pinned by `{}` and can't be changed by `<set>`",
+            system.display()
+        )
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "file")]
+#[test]
+fn test_get_path() {
+    use std::fs;
+    use std::path::Path;
+
+    let dir = std::env::temp_dir().join(format!("scp-path-{}", std::process::id()));
+    fs::create_dir_all(dir.join("sub")).unwrap();
+    fs::write(
+        dir.join("app.cfg"),
+        "data = data\nlogs = /var/log\n!include sub/more.cfg",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("sub/more.cfg"),
+        "[cache]\ndir = ../cache\nhome = ~/.cache\nuser = ~bob/x",
+    )
+    .unwrap();
+
+    let cfg = Config::new()
+        .file(dir.join("app.cfg"))
+        .unwrap()
+        .text("text = rel/path")
+        .unwrap();
+    assert_eq!(cfg.get_path("data").unwrap(), dir.join("data"));
+    assert_eq!(cfg.get_path("logs").unwrap(), Path::new("/var/log"));
+    assert_eq!(cfg.get_path("text").unwrap(), Path::new("rel/path"));
+
+    // Included values are relative to their own file
+    let cache = cfg.section("cache").unwrap();
+    assert_eq!(cache.get_path("dir").unwrap(), dir.join("sub/../cache"));
+    assert_eq!(cache.get_path("user").unwrap(), dir.join("sub/~bob/x"));
+
+    let home = std::env::var_os("HOME").unwrap();
+    assert_eq!(
+        cfg.get_path("home").unwrap(),
+        Path::new(&home).join(".cache")
+    );
+
+    match cfg.get_existing_path("data") {
+        Err(ConfigError::Io(e)) => assert_eq!(e.kind(), std::io::ErrorKind::NotFound),
+        _ => panic!("Path should not exist"),
+    }
+    fs::create_dir(dir.join("data")).unwrap();
+    assert_eq!(cfg.get_existing_path("data").unwrap(), dir.join("data"));
+
+    // Defaults resolve from their own files
+    let cfg = Config::new().with_defaults(cfg);
+    assert_eq!(cfg.get_path("data").unwrap(), dir.join("data"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_get_bool() {
+    let cfg = Config::new()
+        .text("a = true\nb = FALSE\nc = Yes\nd = no\ne = ON\nf = off\ng = 1\nh = 0\ni = maybe\nj = 2\n[s]\nk = On")
+        .unwrap();
+
+    let values = ["a", "b", "c", "d", "e", "f", "g", "h"]
+        .iter()
+        .map(|i| cfg.get_bool(i).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(values, [true, false, true, false, true, false, true, false]);
+
+    assert!(matches!(
+        cfg.get_bool("i"),
+        Err(ConfigError::InvalidType(..))
+    ));
+    assert!(matches!(
+        cfg.get_bool("j"),
+        Err(ConfigError::InvalidType(..))
+    ));
+    assert!(matches!(cfg.get_bool("z"), Err(ConfigError::NoItem)));
+    assert!(cfg.section("s").unwrap().get_bool("k").unwrap());
+    assert!(cfg.subtree("s").unwrap().get_bool("k").unwrap());
+}
+
+#[test]
+fn test_resolvers() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let mut cfg = Config::new()
+        .resolver("vault", move |reference: &str| {
+            let n = counter.fetch_add(1, Ordering::SeqCst);
+            match reference {
+                "missing" => Err(ConfigError::NoItem),
+                i => Ok(format!("{}-{}", i, n)),
+            }
+        })
+        .resolver_ttl(Duration::from_millis(50))
+        .text("url = postgres://app:${vault:pw}@db\nother = ${nope:x} $${vault:pw}\nbad = ${vault:missing}")
+        .unwrap();
+
+    assert_eq!(cfg.get_resolved("url").unwrap(), "postgres://app:pw-0@db");
+    assert_eq!(cfg.get_resolved("URL").unwrap(), "postgres://app:pw-0@db");
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    // Unknown names and escapes are kept
+    assert_eq!(cfg.get_resolved("other").unwrap(), "${nope:x} ${vault:pw}");
+    assert!(matches!(cfg.get_resolved("bad"), Err(ConfigError::NoItem)));
+    assert!(matches!(cfg.get_resolved("none"), Err(ConfigError::NoItem)));
+
+    // Expired, invalidated and changed values are resolved again
+    thread::sleep(Duration::from_millis(60));
+    assert_eq!(cfg.get_resolved("url").unwrap(), "postgres://app:pw-2@db");
+    cfg.invalidate("url");
+    assert_eq!(cfg.get_resolved("url").unwrap(), "postgres://app:pw-3@db");
+    cfg.set("url", "${vault:new}");
+    assert_eq!(cfg.get_resolved("url").unwrap(), "new-4");
+    cfg.invalidate_all();
+    assert_eq!(cfg.get_resolved("url").unwrap(), "new-5");
+}
+
+#[cfg(feature = "file")]
+#[test]
+fn test_dependency_graph() {
+    use std::fs;
+
+    let dir = std::env::temp_dir().join(format!("scp-graph-{}", std::process::id()));
+    fs::create_dir_all(dir.join("sub")).unwrap();
+    let main = dir.join("main.cfg");
+    let a = dir.join("sub/a.cfg");
+    let b = dir.join("sub/b.cfg");
+    fs::write(
+        &main,
+        "!include sub/a.cfg\n!include sub/b.cfg\nhome = ${env:SCP_GRAPH_HOME}/x",
+    )
+    .unwrap();
+    fs::write(
+        &a,
+        "!include b.cfg\n[db]\npassword = ${vault:db} $${not:this}",
+    )
+    .unwrap();
+    fs::write(&b, "b = 1").unwrap();
+
+    std::env::set_var("SCP_GRAPH_HOME", "/home");
+    let cfg = Config::new()
+        .expand_env(UnsetEnv::Error)
+        .file(&main)
+        .unwrap();
+    assert_eq!(cfg.get_str("home").unwrap(), "/home/x");
+
+    let graph = cfg.dependency_graph();
+    let [main, a, b] = [&main, &a, &b].map(|i| i.display().to_string());
+    assert_eq!(
+        graph.includes,
+        [
+            (main.to_owned(), a.to_owned()),
+            (a.to_owned(), b.to_owned()),
+            (main.to_owned(), b.to_owned()),
+        ]
+    );
+    assert_eq!(
+        graph.references,
+        [
+            ("db.password".to_owned(), "vault:db".to_owned()),
+            ("home".to_owned(), "env:SCP_GRAPH_HOME".to_owned()),
+        ]
+    );
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph config {\n"));
+    assert!(dot.contains(&format!("\"{}\" -> \"{}\" [label=\"includes\"];", main, a)));
+    assert!(dot.contains("\"home\" -> \"env:SCP_GRAPH_HOME\" [label=\"references\"];"));
+
+    let json = graph.to_json();
+    assert!(json.contains("\"references\":[{\"from\":\"db.password\",\"to\":\"vault:db\"},"));
+
+    // Changed values lose the references they were loaded with
+    let mut cfg = cfg;
+    cfg.set("home", "/other");
+    assert_eq!(cfg.dependency_graph().references.len(), 1);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_schema_rules() {
+    use simple_config_parser::{Schema, SchemaKey, ValueType};
+
+    const SERVER: &[SchemaKey] = &[
+        SchemaKey::required("port", ValueType::Int).range(1.0, 65535.0),
+        SchemaKey::required("workers", ValueType::Int).default("4"),
+        SchemaKey::optional("ratio", ValueType::Float).range(0.0, 1.0),
+        SchemaKey::optional("name", ValueType::Str).range(0.0, 1.0),
+    ];
+    const ROOT: Schema =
+        Schema::new(&[SchemaKey::optional("debug", ValueType::Bool).default("false")]);
+    let schema = Schema::section("server", SERVER).deny_unknown() + ROOT;
+
+    let cfg = Config::new()
+        .text("debug = true\n[server]\nport = 8080\nratio = 1\nname = long")
+        .unwrap();
+    assert!(schema.validate(&cfg).is_ok());
+
+    let cfg = Config::new()
+        .text("prot = 1\n[server]\nport = 70000\nratio = 1.5\nratio = 0.5\nextra = 1\nextra = 2\n[other]\ndebug = true")
+        .unwrap();
+    let errors = schema.validate(&cfg).unwrap_err();
+    assert_eq!(errors.keys.len(), 1);
+    assert_eq!(errors.keys[0].key, "port");
+    match &errors.keys[0].error {
+        ConfigError::InvalidValue(i) => assert_eq!(i, "must be from 1 to 65535"),
+        _ => panic!("Port should be out of range"),
+    }
+    assert_eq!(errors.unknown, ["prot", "server.extra"]);
+
+    // Schemas that don't deny unknown keys allow them
+    assert!(cfg.validate(&ROOT).is_ok());
+
+    let defaults = schema.defaults();
+    assert_eq!(defaults.get::<u8>("server.workers").unwrap(), 4);
+    assert_eq!(defaults.get_str("debug").unwrap(), "false");
+    assert!(defaults.get_str("port").is_err());
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn test_schema_pattern() {
+    use simple_config_parser::{Schema, SchemaKey, ValueType};
+
+    const KEYS: &[SchemaKey] = &[
+        SchemaKey::required("name", ValueType::Str).pattern("[a-z]+"),
+        SchemaKey::optional("bad", ValueType::Str).pattern("("),
+    ];
+    let schema = Schema::new(KEYS);
+
+    assert!(Config::new()
+        .text("name = app")
+        .unwrap()
+        .validate(&schema)
+        .is_ok());
+
+    let cfg = Config::new().text("name = app2\nbad = x").unwrap();
+    let errors = cfg
+        .validate(&schema)
+        .unwrap_err()
+        .keys
+        .into_iter()
+        .map(|i| i.error.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        errors,
+        [
+            "Value must match `[a-z]+`",
+            "Value can't be checked, `(` is not a valid regex"
+        ]
+    );
+}
+
+#[test]
+/// Test keys that are not expected are found with typo suggestions
+fn test_unknown_keys() {
+    let cfg = Config::new()
+        .text("host = localhost\nprot = 8080\n[server]\nnmae = web\nname = app\nprot = 1")
+        .unwrap();
+
+    let unknown = cfg.unknown_keys(&["host", "port", "server.name", "server.port"]);
+    let found = unknown
+        .iter()
+        .map(|i| (i.key.as_str(), i.suggestion.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        vec![
+            ("prot", Some("port")),
+            ("server.nmae", Some("server.name")),
+            ("server.prot", Some("server.port")),
+        ]
+    );
+
+    let unknown = cfg.unknown_keys(&["HOST", "Server.Name"]);
+    assert_eq!(unknown[0].to_string(), "Unknown key `prot`");
+    assert!(cfg
+        .unknown_keys(&["host", "prot", "server.nmae", "server.name", "server.prot"])
+        .is_empty());
+}
+
+#[test]
+/// Test the case and spaces of keys can be kept
+fn test_key_normalization() {
+    let text = "Name = A\nname = b\nfull name = Connor\n[Server]\nPort = 80";
+
+    let cfg = Config::new().text(text).unwrap();
+    assert_eq!(cfg.get_str("NAME").unwrap(), "b");
+    assert_eq!(cfg.get_str("fullname").unwrap(), "Connor");
+    assert!(cfg.section("server").is_some());
+
+    let cfg = Config::builder()
+        .case_sensitive(true)
+        .build()
+        .unwrap()
+        .text(text)
+        .unwrap();
+    assert_eq!(cfg.get_str("Name").unwrap(), "A");
+    assert_eq!(cfg.get_str("name").unwrap(), "b");
+    assert!(cfg.get_str("NAME").is_err());
+    assert!(cfg.section("server").is_none());
+    assert_eq!(
+        cfg.section("Server").unwrap().get::<u16>("Port").unwrap(),
+        80
+    );
+    assert_eq!(cfg.get::<u16>("Server.Port").unwrap(), 80);
+
+    let mut cfg = Config::builder()
+        .preserve_key_spaces(true)
+        .build()
+        .unwrap()
+        .text(text)
+        .unwrap();
+    assert_eq!(cfg.get_str("Full Name").unwrap(), "Connor");
+    assert!(cfg.get_str("fullname").is_err());
+
+    cfg.set(" full name ", "Slade");
+    assert_eq!(cfg.get_str("full name").unwrap(), "Slade");
+    assert_eq!(cfg.remove("Full Name").as_deref(), Some("Slade"));
+}
+
+#[test]
+/// Test error messages can come from a catalog
+fn test_localize_errors() {
+    use simple_config_parser::{Schema, SchemaKey, ValueType};
+
+    let mut catalog = std::collections::HashMap::new();
+    catalog.insert("include", "Dans `{0}` : {1}");
+    catalog.insert("no-item", "Aucune valeur");
+    catalog.insert("schema-error", "Configuration invalide :");
+    catalog.insert("schema-missing", "`{0}` manque");
+
+    let error = ConfigError::Include("a.cfg".into(), Box::new(ConfigError::NoItem));
+    assert_eq!(error.id(), "include");
+    assert_eq!(error.args(), ["a.cfg", "No config item for the key exists"]);
+    assert_eq!(error.localize(&catalog), "Dans `a.cfg` : Aucune valeur");
+    assert_eq!(
+        ConfigError::InvalidConfig.localize(&catalog),
+        "Config data is not valid"
+    );
+
+    const SCHEMA: Schema = Schema::new(&[
+        SchemaKey::required("name", ValueType::Str),
+        SchemaKey::required("port", ValueType::Int),
+    ]);
+    let cfg = Config::new().text("port = eighty").unwrap();
+    let error = cfg.validate(&SCHEMA).unwrap_err();
+    assert_eq!(
+        error.localize(&catalog),
+        "Configuration invalide :\n - `name` manque\n - `port` is invalid (Error parsing config value)\n"
+    );
+    assert_eq!(
+        error.localize(&std::collections::HashMap::<String, String>::new()),
+        error.to_string()
+    );
+}
+
+#[test]
+/// Test anchors can be defined once and used by later values
+fn test_anchors() {
+    let text = "base = &timeout 30s\nread = *timeout\nglob = *.cfg\nwrite = *timeout\nbase = &timeout 1m\nlast = *timeout";
+    let mut cfg = Config::new().anchors(true).text(text).unwrap();
+
+    assert_eq!(cfg.get_str("read").unwrap(), "30s");
+    assert_eq!(cfg.get_str("write").unwrap(), "30s");
+    assert_eq!(cfg.get_str("glob").unwrap(), "*.cfg");
+    assert_eq!(cfg.get_str("base").unwrap(), "1m");
+    assert_eq!(cfg.get_str("last").unwrap(), "1m");
+
+    // Saving keeps the aliases of unchanged values
+    cfg.set("read", "10s");
+    assert_eq!(
+        cfg.to_string(),
+        "base = &timeout 30s\nread = 10s\nglob = *.cfg\nwrite = *timeout\nbase = &timeout 1m\nlast = *timeout\n"
+    );
+
+    // Anchors are off by default
+    let cfg = Config::new().text(text).unwrap();
+    assert_eq!(cfg.get_str("read").unwrap(), "*timeout");
+
+    let err = Config::new()
+        .anchors(true)
+        .text("a = *timeout\nb = &timeout 1s");
+    assert!(matches!(err, Err(ConfigError::UnknownAnchor(i)) if i == "timeout"));
+}
+
+#[test]
+/// Test which chars start comments can be changed
+fn test_comment_options() {
+    let text =
+        "; Comment\nurl = https://a.com/#top ; Comment\nlist = a;b\n[section] # Comment\nkey = 1";
+
+    let cfg = Config::new().text(text).unwrap();
+    assert_eq!(cfg.get_str("url").unwrap(), "https://a.com/");
+    assert_eq!(cfg.get_str("list").unwrap(), "a");
+
+    let cfg = Config::builder()
+        .inline_comment_space(true)
+        .build()
+        .unwrap()
+        .text(text)
+        .unwrap();
+    assert_eq!(cfg.get_str("url").unwrap(), "https://a.com/#top");
+    assert_eq!(cfg.get_str("list").unwrap(), "a;b");
+    assert!(cfg.section("section").is_some());
+
+    let cfg = Config::builder()
+        .inline_comments(false)
+        .build()
+        .unwrap()
+        .text("# Comment\nurl = https://a.com/#top ; Text\nquoted = \"a\" # b")
+        .unwrap();
+    assert_eq!(cfg.get_str("url").unwrap(), "https://a.com/#top ; Text");
+    assert_eq!(cfg.get_str("quoted").unwrap(), "\"a\" # b");
+
+    let cfg = Config::builder()
+        .comment_chars(&['%'])
+        .build()
+        .unwrap()
+        .text("% Comment\ncolor = #fff % White")
+        .unwrap();
+    assert_eq!(cfg.get_str("color").unwrap(), "#fff");
+    assert!(Config::builder()
+        .comment_chars(&['%'])
+        .build()
+        .unwrap()
+        .text("# Not a comment")
+        .is_err());
+}
+
+#[test]
+/// Test the policies for keys defined more than once
+fn test_duplicate_policy() {
+    let text = "listen = 80\n[tls]\nlisten = 443\n[tls]\nlisten = 8443";
+
+    let cfg = Config::new().text(text).unwrap();
+    assert_eq!(cfg.get::<u16>("tls.listen").unwrap(), 8443);
+    assert_eq!(cfg.get_all::<u16>("listen").unwrap(), [8443]);
+
+    // Ignored lines are kept when saving
+    let mut cfg = Config::new()
+        .duplicates(DuplicatePolicy::FirstWins)
+        .text(text)
+        .unwrap();
+    assert_eq!(cfg.get::<u16>("tls.listen").unwrap(), 443);
+    assert_eq!(cfg.occurrences("tls.listen").len(), 1);
+    cfg.set("listen", "81");
+    assert_eq!(
+        cfg.to_string(),
+        "listen = 80\n[tls]\nlisten = 81\n[tls]\nlisten = 8443\n"
+    );
+
+    let err = Config::new()
+        .duplicates(DuplicatePolicy::Error)
+        .text(text)
+        .err()
+        .unwrap();
+    assert_eq!(
+        err.to_string(),
+        "Config key `tls.listen` is defined more than once"
+    );
+
+    let cfg = Config::new()
+        .duplicates(DuplicatePolicy::Collect)
+        .text(text)
+        .unwrap()
+        .text("listen = 8080")
+        .unwrap();
+    assert_eq!(cfg.get::<u16>("listen").unwrap(), 8080);
+    assert_eq!(cfg.get_all::<u16>("listen").unwrap(), [80, 443, 8443, 8080]);
+    assert_eq!(cfg.get_all::<u16>("tls.listen").unwrap(), [443, 8443]);
+    assert!(matches!(
+        cfg.get_all::<u16>("other"),
+        Err(ConfigError::NoItem)
+    ));
+}
+
+#[cfg(feature = "json")]
+#[test]
+/// Test lists of objects are read from indexed keys or repeated sections
+fn test_objects() {
+    let cfg = Config::new()
+        .text("[plugin.1]\nname = b\n[plugin.0]\nname = a\npath = /a\n[plugin.x]\nname = c")
+        .unwrap()
+        .json(r#"{"hook": [{"name": "c"}, {"name": "d"}]}"#)
+        .unwrap();
+    let names = |prefix| {
+        cfg.objects(prefix)
+            .iter()
+            .map(|i| i.get_str("name").unwrap())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names("plugin"), ["a", "b"]);
+    assert_eq!(names("hook"), ["c", "d"]);
+
+    let plugins = cfg.objects("plugin");
+    assert_eq!(plugins[0].get_str("path").unwrap(), "/a");
+    assert_eq!(plugins[0].to_string(), "name = a\npath = /a\n");
+
+    let cfg = Config::new()
+        .text("[job]\nname = backup\n[other]\nname = x\n[Job]\nname = cleanup\nopts.dry = true")
+        .unwrap();
+    let jobs = cfg.objects("job");
+    assert_eq!(jobs.len(), 2);
+    assert_eq!(jobs[0].get_str("name").unwrap(), "backup");
+    assert!(jobs[1].get::<bool>("opts.dry").unwrap());
+    assert!(cfg.objects("none").is_empty());
+}
+
+#[test]
+/// Test merging configs with each strategy
+fn test_merge() {
+    use simple_config_parser::MergeStrategy;
+
+    let system = || {
+        Config::new()
+            .text("theme = dark\n[font]\nsize = 10")
+            .unwrap()
+    };
+    let user = || {
+        Config::new()
+            .text("# User\ntheme = light\n[font]\nsize = 12\nfamily = mono")
+            .unwrap()
+    };
+
+    let cfg = system().merge(user(), MergeStrategy::OverrideWith).unwrap();
+    assert_eq!(cfg.get_str("theme").unwrap(), "light");
+    assert_eq!(cfg.get::<u8>("font.size").unwrap(), 12);
+    assert_eq!(cfg.occurrences("theme")[1].source, "<text>");
+    assert_eq!(cfg.occurrences("theme")[1].line, Some(2));
+
+    let cfg = system().merge(user(), MergeStrategy::KeepExisting).unwrap();
+    assert_eq!(cfg.get_str("theme").unwrap(), "dark");
+    assert_eq!(cfg.get::<u8>("font.size").unwrap(), 10);
+    assert_eq!(cfg.get_str("font.family").unwrap(), "mono");
+    assert_eq!(
+        cfg.to_string(),
+        "theme = dark\n[font]\nsize = 10\n# User\n[font]\nfamily = mono\n"
+    );
+
+    let mut seen = Vec::new();
+    let resolve = MergeStrategy::Resolve(Box::new(move |i| {
+        seen.push(i.key.to_owned());
+        assert_eq!(seen.len(), 1 + (i.key == "font.size") as usize);
+        i.existing.max(i.incoming).to_owned()
+    }));
+    let cfg = system().merge(user(), resolve).unwrap();
+    assert_eq!(cfg.get_str("theme").unwrap(), "light");
+    assert_eq!(cfg.get::<u8>("font.size").unwrap(), 12);
+}
+
+#[test]
+/// Test keys are overridden by the variables named in their comments
+fn test_env_override_comments() {
+    std::env::set_var("SCP_TEST_OVERRIDE_PORT", "9090");
+    std::env::remove_var("SCP_TEST_OVERRIDE_UNSET");
+
+    let text = "port = 8080  ; env:SCP_TEST_OVERRIDE_PORT\nhost = localhost # from env:SCP_TEST_OVERRIDE_UNSET\nmotd = \"\"\"\nHi\n\"\"\" # env:SCP_TEST_OVERRIDE_PORT\nname = env:SCP_TEST_OVERRIDE_PORT";
+    let cfg = Config::new().text(text).unwrap();
+
+    assert_eq!(cfg.get::<u16>("port").unwrap(), 9090);
+    assert_eq!(cfg.get_str("host").unwrap(), "localhost");
+    assert_eq!(cfg.get_str("motd").unwrap(), "9090");
+    assert_eq!(cfg.get_str("name").unwrap(), "env:SCP_TEST_OVERRIDE_PORT");
+
+    // The file's value is kept when saving
+    assert_eq!(cfg.to_string(), format!("{}\n", text));
+}
+
+#[test]
+/// Test the differences between two configs
+fn test_diff() {
+    let old = Config::new()
+        .text("port = 80\nport = 81\nhost = localhost\n[db]\nuser = app")
+        .unwrap();
+    let new = Config::new()
+        .text("[db]\nuser = admin\n[server]\nport = 81")
+        .unwrap();
+
+    let diff = old.diff(&new);
+    let changes = diff
+        .changes
+        .iter()
+        .map(|i| (i.key.as_str(), i.old.as_deref(), i.new.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        changes,
+        vec![
+            ("port", Some("81"), None),
+            ("host", Some("localhost"), None),
+            ("db.user", Some("app"), Some("admin")),
+            ("server.port", None, Some("81")),
+        ]
+    );
+    assert_eq!(diff.removed().count(), 2);
+    assert_eq!(diff.changed().count(), 1);
+    assert_eq!(diff.added().count(), 1);
+
+    assert!(old.diff(&old).is_empty());
+    assert_eq!(
+        new.diff(&Config::new()).to_string(),
+        "- db.user = admin\n- server.port = 81"
+    );
+}
+
+#[test]
+/// Test overridden values are noted when saving
+fn test_annotate_overrides() {
+    std::env::set_var("SCP_TEST_ANNOTATE_HOST", "example.com");
+    std::env::set_var("SCP_TEST_ANNOTATE_DIR", "/srv");
+
+    let text = "[server]\n# effective: host = old (from env:OLD)\nhost = localhost ; env:SCP_TEST_ANNOTATE_HOST\nroot = ${env:SCP_TEST_ANNOTATE_DIR}/www\nport = 80";
+    let mut cfg = Config::new()
+        .expand_env(UnsetEnv::Keep)
+        .annotate_overrides(true)
+        .text(text)
+        .unwrap();
+    assert_eq!(cfg.get_str("host").unwrap(), "example.com");
+
+    cfg.set("port", "8080");
+    assert_eq!(
+        cfg.to_string(),
+        "[server]\n# effective: host = example.com (from env:SCP_TEST_ANNOTATE_HOST)\nhost = localhost ; env:SCP_TEST_ANNOTATE_HOST\n# effective: root = /srv/www (from env)\nroot = ${env:SCP_TEST_ANNOTATE_DIR}/www\nport = 8080\n"
+    );
+
+    // Not noted by default
+    let cfg = Config::new().text(text).unwrap();
+    assert_eq!(cfg.to_string(), format!("{}\n", text));
+}
+
+#[test]
+/// Test configs can be built up and cleared without parsing text
+fn test_set_typed_and_clear() {
+    let mut cfg = Config::new();
+    cfg.set_typed("port", 8080u16);
+    cfg.set_typed("ratio", 0.5);
+    cfg.set("name", "app");
+    assert_eq!(cfg.get::<u16>("port").unwrap(), 8080);
+    assert_eq!(cfg.get::<f64>("ratio").unwrap(), 0.5);
+
+    let changes = cfg.subscribe(&[]);
+    cfg.clear();
+    assert!(cfg.get_str("name").is_err());
+    assert_eq!(cfg.to_string(), "");
+    assert_eq!(changes.try_recv().unwrap().changes.len(), 3);
+
+    let mut cfg = Config::new()
+        .remove_policy(RemovePolicy::CommentOut)
+        .text("[db]\nhost = localhost")
+        .unwrap();
+    cfg.clear();
+    assert!(cfg.to_string().starts_with("[db]\n# removed "));
+    assert!(cfg.occurrences("host").is_empty());
+}
+
+#[cfg(all(feature = "file", feature = "json"))]
+#[test]
+/// Test a builder can queue sources and return all their errors at once
+fn test_builder_sources() {
+    std::env::set_var("BUILDER_TEST_LOG_LEVEL", "debug");
+    std::env::set_var("BUILDER_TEST_SERVER__PORT", "9090");
+
+    let cfg = Config::builder()
+        .text("[server]\nport = 80\nhost = localhost")
+        .json(r#"{"server": {"port": 8080}}"#)
+        .env("BUILDER_TEST_")
+        .file_optional("nonexistent.cfg")
+        .build()
+        .unwrap();
+    assert_eq!(cfg.get::<u16>("server.port").unwrap(), 9090);
+    assert_eq!(cfg.get_str("server.host").unwrap(), "localhost");
+    assert_eq!(cfg.get_str("log_level").unwrap(), "debug");
+    assert_eq!(cfg.load_report().failures().count(), 1);
+
+    let err = Config::builder()
+        .file("nonexistent.cfg")
+        .text("[unclosed")
+        .text("ok = true")
+        .build();
+    let err = match err {
+        Err(i) => i,
+        Ok(_) => panic!("Expected the sources to fail"),
+    };
+    let sources = match &err {
+        ConfigError::Sources(i) => i,
+        _ => panic!("Expected ConfigError::Sources"),
+    };
+    assert_eq!(sources.len(), 2);
+    assert_eq!(sources[0].0, "nonexistent.cfg");
+    assert!(matches!(sources[0].1, ConfigError::FileNotFound(_)));
+    assert_eq!(sources[1].0, "<text>");
+    assert!(err
+        .to_string()
+        .starts_with("2 config sources failed to load\n - nonexistent.cfg: "));
+
+    assert!(Config::builder().build().unwrap().is_empty());
+}
+
+#[test]
+/// Test command line arguments override the other sources
+fn test_args_overlay() {
+    std::env::set_var("ARGS_TEST_PORT", "9090");
+    std::env::set_var("ARGS_TEST_HOST", "env.local");
+
+    let cfg = Config::builder()
+        .text("port = 80\nhost = localhost\n[log]\nlevel = info")
+        .env("ARGS_TEST_")
+        .args([
+            "app",
+            "--port=8080",
+            "--Log.Level=debug",
+            "--dry-run",
+            "--=x",
+            "--",
+            "--host=b",
+        ])
+        .build()
+        .unwrap();
+
+    assert_eq!(cfg.get::<u16>("port").unwrap(), 8080);
+    assert_eq!(cfg.get_str("host").unwrap(), "env.local");
+    assert_eq!(cfg.get_str("log.level").unwrap(), "debug");
+    assert!(cfg.get_bool("dry-run").unwrap());
+    assert_eq!(cfg.load_report().sources[2].entries, 3);
+}
+
+#[test]
+/// Test effective entries are grouped by section in the order they were first seen
+fn test_iter_effective() {
+    let cfg = Config::new()
+        .text("[b]\nx = 1\n[a]\ny = 2\n[b]\nz = 3\nx = 4")
+        .unwrap()
+        .text("top = 5\n[a]\ny = 6\nw = 7")
+        .unwrap();
+
+    let entries = cfg
+        .iter_effective()
+        .map(|i| (i.section.as_deref(), i.key.as_str(), i.value.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        entries,
+        [
+            (Some("b"), "x", "4"),
+            (Some("b"), "z", "3"),
+            (Some("a"), "y", "6"),
+            (Some("a"), "w", "7"),
+            (None, "top", "5"),
+        ]
+    );
+}
+
+#[test]
+/// Test configs can be parsed a line at a time from a reader
+fn test_reader() {
+    use std::io::{self, BufRead, BufReader, Read};
+
+    let text = "# Comment\nname = app\nmotd = \"\"\"\nHello\nWorld\n\"\"\"\n[server]\nport = 80";
+    let cfg = Config::new()
+        .reader(BufReader::with_capacity(4, text.as_bytes()))
+        .unwrap();
+    assert_eq!(cfg.get_str("motd").unwrap(), "Hello\nWorld\n");
+    assert_eq!(cfg.get::<u16>("server.port").unwrap(), 80);
+    assert_eq!(cfg.to_string(), format!("{}\n", text));
+    assert_eq!(cfg.load_report().sources[0].source, "<reader>");
+
+    // A reader that fails after the first line
+    struct Failing(bool);
+    impl Read for Failing {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match std::mem::replace(&mut self.0, true) {
+                false => (&b"a = 1\n"[..]).read(buf),
+                true => Err(io::Error::other("Connection reset")),
+            }
+        }
+    }
+
+    let reader: Box<dyn BufRead> = Box::new(BufReader::new(Failing(false)));
+    assert!(matches!(
+        Config::new().reader(reader),
+        Err(ConfigError::Io(i)) if i.to_string() == "Connection reset"
+    ));
+}
+
+#[test]
+/// Test errors reading values can say where the value came from
+fn test_error_provenance() {
+    std::env::set_var("PROVENANCE_TEST_WORKERS", "many");
+
+    let cfg = Config::new()
+        .error_provenance(true)
+        .with_defaults(Config::new().text("retries = often").unwrap())
+        .text("[server]\nport = 80\ntls = maybe")
+        .unwrap()
+        .env("PROVENANCE_TEST_")
+        .unwrap();
+
+    assert_eq!(cfg.get::<u16>("port").unwrap(), 80);
+    let err = cfg.section("server").unwrap().get_bool("tls").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`server.tls` from <text> line 3: Value of `server.tls` (`maybe`) can't be read as bool"
+    );
+    let err = cfg.get::<u8>("workers").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`workers` from <env:PROVENANCE_TEST_>: Value of `workers` (`many`) can't be read as u8"
+    );
+    match cfg.get::<u8>("retries") {
+        Err(ConfigError::Value(key, at, e)) => {
+            assert_eq!(key, "retries");
+            assert_eq!((at.source.as_str(), at.line), ("<text>", Some(1)));
+            assert_eq!(at.value, "often");
+            assert!(matches!(*e, ConfigError::InvalidType(..)));
+        }
+        _ => panic!("Expected ConfigError::Value"),
+    }
+    assert!(matches!(cfg.get::<u8>("missing"), Err(ConfigError::NoItem)));
+
+    // Off by default
+    let cfg = Config::new().text("port = eighty").unwrap();
+    assert!(matches!(
+        cfg.get::<u16>("port"),
+        Err(ConfigError::InvalidType(..))
+    ));
+}
+
+#[test]
+/// Test the support bundle redacts secrets and includes the reports
+fn test_support_bundle() {
+    use simple_config_parser::{Deprecation, Schema, SchemaKey, ValueType};
+
+    const SCHEMA: Schema = Schema::new(&[
+        SchemaKey::required("port", ValueType::Int),
+        SchemaKey::required("name", ValueType::Str),
+    ]);
+
+    let text = "port = 80\nmax_conns = 10\nAPI_TOKEN = abc123\n[db]\ndb-password = hunter2";
+    let cfg = Config::new()
+        .deprecate(Deprecation::new("max_conns").note("use `max_connections`"))
+        .text(text)
+        .unwrap()
+        .args(["--port=8080"])
+        .unwrap();
+
+    let bundle = cfg.support_bundle(Some(&SCHEMA));
+    assert!(bundle.starts_with("# Config support bundle\nfingerprint: "));
+    assert!(bundle.contains(
+        "\n## Values\nport = 8080 (<args>)\nmax_conns = 10 (<text> line 2)\napi_token = <redacted> (<text> line 3)\ndb.db-password = <redacted> (<text> line 5)\n"
+    ));
+    assert!(!bundle.contains("abc123") && !bundle.contains("hunter2"));
+    assert!(bundle.contains("\n## Load report\n<text>: 4 entries in "));
+    assert!(bundle.contains("\n## Warnings\n - `max_conns` is deprecated, use `max_connections`\n"));
+    assert!(bundle.ends_with("\n## Validation\nInvalid config:\n - `name` is missing\n"));
+
+    // The fingerprint changes with secret values too
+    let fingerprint = |i: &str| i.lines().nth(1).unwrap().to_owned();
+    let other = Config::new()
+        .text(text.replace("hunter2", "hunter3"))
+        .unwrap()
+        .args(["--port=8080"])
+        .unwrap();
+    assert_ne!(
+        fingerprint(&bundle),
+        fingerprint(&other.support_bundle(None))
+    );
+    assert!(!other.support_bundle(None).contains("## Validation"));
+}
+
+#[cfg(feature = "json")]
+#[test]
+/// Test processors change the values of matching keys in order
+fn test_processors() {
+    let text = "name =  app \n[db]\nurl = postgres://{host}/app\nhost = db.local";
+    let mut cfg = Config::new()
+        .processor("*", |i: &str| Ok(i.trim().to_owned()))
+        .processor("db.url", |i: &str| Ok(i.replace("{host}", "db.local")))
+        .processor("db.*", |i: &str| Ok(format!("{}!", i)))
+        .text(text)
+        .unwrap()
+        .args(["--db.host=other"])
+        .unwrap();
+
+    assert_eq!(cfg.get_str("db.url").unwrap(), "postgres://db.local/app!");
+    assert_eq!(cfg.get_str("db.host").unwrap(), "other!");
+    assert_eq!(cfg.get_str("name").unwrap(), "app");
+
+    // Saving keeps the text of unchanged values
+    cfg.set("name", "web");
+    assert_eq!(
+        cfg.to_string(),
+        "db.host = other!\nname =  web \n[db]\nurl = postgres://{host}/app\nhost = db.local\n"
+    );
+
+    let err = Config::new()
+        .processor("nope", |_: &str| Err(ConfigError::ParseError))
+        .json(r#"{"nope": 1}"#);
+    assert!(matches!(err, Err(ConfigError::ParseError)));
+}
+
+#[test]
+/// Test borrowed configs parse like `Config` without copying unchanged text
+fn test_config_ref() {
+    use simple_config_parser::ConfigRef;
+    use std::borrow::Cow;
+
+    let text = "; Comment\nName = app\nfull name = Connor\r\n[Server]\nport = 80\nport = 8080 # Comment\nmotd = \"\"\"\nHello\nWorld\n\"\"\" ; Comment\ntag = \"a \\\"b\\\"\"\n[db]\nurl = postgres://localhost";
+    let cfg = ConfigRef::parse(text).unwrap();
+    let owned = Config::new().text(text).unwrap();
+
+    for key in ["name", "fullname", "server.port", "motd", "tag", "url"] {
+        assert_eq!(cfg.get_str(key).unwrap(), owned.get_str(key).unwrap());
+    }
+    assert_eq!(cfg.get::<u16>("PORT").unwrap(), 8080);
+    assert!(matches!(cfg.get_str("missing"), Err(ConfigError::NoItem)));
+
+    assert!(matches!(cfg.data[0].key, Cow::Owned(_)));
+    assert!(matches!(cfg.data[0].value, Cow::Borrowed("app")));
+    assert!(matches!(cfg.data[2].section, Some(Cow::Owned(_))));
+    assert!(matches!(cfg.data[4].value, Cow::Borrowed("Hello\nWorld\n")));
+    assert!(matches!(cfg.data[5].value, Cow::Owned(_)));
+    assert!(matches!(cfg.data[6].section, Some(Cow::Borrowed("db"))));
+
+    assert!(matches!(
+        ConfigRef::parse("[broken"),
+        Err(ConfigError::InvalidConfig)
+    ));
+    assert!(matches!(
+        ConfigRef::parse("a = \"\"\"\nb"),
+        Err(ConfigError::InvalidConfig)
+    ));
+    assert!(matches!(
+        ConfigRef::parse("!include other.cfg"),
+        Err(ConfigError::Restricted(_))
+    ));
+}
+
+#[test]
+/// Test borrowed configs read the same entries as `Config`, and fail on the same text
+fn test_config_ref_matches_config() {
+    use simple_config_parser::ConfigRef;
+
+    let corpus = [
+        "",
+        "\u{feff}a = 1",
+        "a = 1\r\nb = 2\rc = 3\n\r\nd = 4",
+        "[[name]]\na = 1\n[[name]]\na = 2\n[[Other Name]] ; Comment\nb = 3",
+        "[name] ; Comment\n[ Spaced Name ]\na = 1",
+        "[broken\na = 1",
+        "[]\na = 1",
+        "a = \"\"\"\r\nline 1\r\nline 2\r\n\"\"\" ; Comment\r\nb = 2",
+        "a = \"\"\"\rline\r\"\"\"\rb = 2",
+        "a = \"\"\"one line\"\"\"",
+        "a = \"\"\"\nline\n\"\"\" junk",
+        "a = \"\"\"\nnever closed",
+        "a = \"quoted \\\"value\\\"\" ; Comment",
+        "a = \"quoted\" then text",
+        "a = \"unclosed",
+        "a = b = c",
+        "a =",
+        "a=b;c ; d",
+        "Key Name = value # Comment",
+        "\tTabbed\t= value\t",
+        "servers[0].host = a\nservers[x] = b",
+        "ÄÖÜ = Ünicode\n[SÉCTION]\nΣΑΣ = σ",
+        "; Comment\n# Comment\n   \n\n",
+        "just a key",
+        "= no key",
+        "a ; = b",
+    ];
+
+    for text in corpus {
+        let owned = Config::new().text(text);
+        let borrowed = ConfigRef::parse(text);
+        match (owned, borrowed) {
+            (Ok(owned), Ok(borrowed)) => {
+                let owned = owned
+                    .data
+                    .iter()
+                    .map(|i| (i.section.as_deref(), i.key.as_str(), i.value.as_str()))
+                    .collect::<Vec<_>>();
+                let borrowed = borrowed
+                    .data
+                    .iter()
+                    .map(|i| (i.section.as_deref(), &*i.key, &*i.value))
+                    .collect::<Vec<_>>();
+                assert_eq!(owned, borrowed, "{:?}", text);
+            }
+            (Err(_), Err(_)) => {}
+            (owned, borrowed) => panic!("{:?}: {:?} vs {:?}", text, owned, borrowed),
+        }
+    }
+}
+
+#[cfg(feature = "file")]
+#[test]
+/// Test lazy configs are loaded once, on first use, even from many threads
+fn test_lazy_config() {
+    use simple_config_parser::LazyConfig;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let loads = Arc::new(AtomicUsize::new(0));
+    let counter = loads.clone();
+    let cfg = Arc::new(LazyConfig::with(move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Config::new().text("workers = 4")
+    }));
+    assert!(!cfg.is_loaded());
+    assert_eq!(loads.load(Ordering::SeqCst), 0);
+
+    let threads = (0..8)
+        .map(|_| {
+            let cfg = cfg.clone();
+            std::thread::spawn(move || cfg.force().unwrap().get::<u8>("workers").unwrap())
+        })
+        .collect::<Vec<_>>();
+    for i in threads {
+        assert_eq!(i.join().unwrap(), 4);
+    }
+    assert!(cfg.is_loaded());
+    assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+    let missing = LazyConfig::new("nonexistent.cfg");
+    assert!(matches!(missing.force(), Err(ConfigError::FileNotFound(_))));
+    assert!(missing.is_loaded());
+}
+
+#[test]
+/// Test the types of values are inferred
+fn test_get_value() {
+    use simple_config_parser::Value;
+
+    let mut cfg = Config::new()
+        .with_defaults(Config::new().text("workers = 4").unwrap())
+        .text("a = -12\nb = 1e3\nc = FALSE\nd = [1, 2.5, 'x, y', [it's, []]]\ne = inf\nf = 1.2.3\ng = \"\"")
+        .unwrap()
+        .text("about = fast, small\nempty = []")
+        .unwrap();
+
+    assert_eq!(cfg.get_value("a").unwrap(), &Value::Int(-12));
+    assert_eq!(cfg.get_value("b").unwrap().as_float(), Some(1000.0));
+    assert_eq!(cfg.get_value("c").unwrap(), &Value::Bool(false));
+    let d = cfg.get_value("d").unwrap();
+    assert_eq!(
+        d,
+        &Value::List(vec![
+            Value::Int(1),
+            Value::Float(2.5),
+            Value::Str("x, y".to_owned()),
+            Value::List(vec![Value::Str("it's".to_owned()), Value::List(vec![])]),
+        ])
+    );
+    assert_eq!(d.to_string(), "[1, 2.5, \"x, y\", [it's, []]]");
+    assert_eq!(&Value::infer(&d.to_string()), d);
+    assert_eq!(cfg.get_value("e").unwrap().as_str(), Some("inf"));
+    assert_eq!(cfg.get_value("f").unwrap().as_str(), Some("1.2.3"));
+    assert_eq!(cfg.get_value("g").unwrap(), &Value::Str(String::new()));
+    assert_eq!(Value::Int(2).as_float(), Some(2.0));
+    assert!(matches!(cfg.get_value("h"), Err(ConfigError::NoItem)));
+
+    // Only values in brackets are lists
+    assert_eq!(
+        cfg.get_value("about").unwrap().as_str(),
+        Some("fast, small")
+    );
+    assert_eq!(cfg.get_value("empty").unwrap().as_list(), Some(&[][..]));
+
+    // Defaults have their values inferred too, and setting a value infers it again
+    assert_eq!(cfg.get_value("workers").unwrap(), &Value::Int(4));
+    cfg.set("a", "true");
+    assert_eq!(cfg.get_value("a").unwrap(), &Value::Bool(true));
+    cfg.set("new", "[1]");
+    assert_eq!(cfg.get_value("new").unwrap().as_list().unwrap().len(), 1);
+}
+
+#[test]
+/// Test keys can use `[index]` for items of a list
+fn test_indexed_keys() {
+    let mut cfg = Config::new()
+        .text("servers[0].host = a\nservers[2].host = c\nmatrix[1][0] = x\nodd[a] = 1\n[pool]\nworkers[0] = 4")
+        .unwrap();
+
+    assert_eq!(cfg.index_len("servers"), 3);
+    assert_eq!(cfg.index_len("pool.workers"), 1);
+    assert_eq!(cfg.index_len("missing"), 0);
+    assert_eq!(
+        cfg.get_indexed::<String>("servers", 2, "host").unwrap(),
+        "c"
+    );
+    assert!(matches!(
+        cfg.get_indexed::<String>("servers", 1, "host"),
+        Err(ConfigError::NoItem)
+    ));
+    assert_eq!(cfg.get_str("servers.0.host").unwrap(), "a");
+    assert_eq!(cfg.get_str("matrix.1.0").unwrap(), "x");
+    assert_eq!(cfg.get_str("odd[a]").unwrap(), "1");
+    assert_eq!(cfg.get::<u8>("pool.workers[0]").unwrap(), 4);
+    assert_eq!(cfg.objects("servers")[1].get_str("host").unwrap(), "c");
+
+    // Saving keeps the brackets
+    cfg.set("servers[0].host", "z");
+    assert!(cfg
+        .to_string()
+        .starts_with("servers[0].host = z\nservers[2].host = c\n"));
+}
+
+#[test]
+/// Test reading a key sets it to the default if it's missing
+fn test_get_or_insert() {
+    let fallback = Config::new().text("level = info").unwrap();
+    let mut cfg = Config::new()
+        .with_defaults(fallback)
+        .text("[server]\nport = 8080")
+        .unwrap();
+
+    assert_eq!(cfg.get_or_insert::<u16>("server.port", "80").unwrap(), 8080);
+    assert_eq!(
+        cfg.get_or_insert::<String>("level", "warn").unwrap(),
+        "warn"
+    );
+    assert!(!cfg.get_or_insert::<bool>("quiet", "false").unwrap());
+    assert!(matches!(
+        cfg.get_or_insert::<u8>("workers", "many"),
+        Err(ConfigError::InvalidType(..))
+    ));
+
+    assert_eq!(
+        cfg.to_string(),
+        "level = warn\nquiet = false\nworkers = many\n[server]\nport = 8080\n"
+    );
+}
+
+#[test]
+/// Test generating an example config from a schema
+fn test_schema_template() {
+    use simple_config_parser::{Schema, SchemaKey, ValueType};
+
+    const DB: Schema = Schema::section(
+        "database",
+        &[SchemaKey::required("url", ValueType::Str).doc("Where the database is\nUse a full url")],
+    );
+    const APP: Schema = Schema::new(&[
+        SchemaKey::optional("debug", ValueType::Bool),
+        SchemaKey::required("workers", ValueType::Int).default("4"),
+    ]);
+
+    let schema = DB + APP;
+    let template = schema.generate_template();
+    assert_eq!(
+        template,
+        "# true or false, optional\n# debug =\n\n# integer, required\n# workers = 4\n\n\
+         [database]\n# Where the database is\n# Use a full url\n# text, required\nurl =\n"
+    );
+
+    // The template is valid once the required keys are filled in
+    let cfg = Config::new()
+        .text(template.replace("url =", "url = sqlite://app.db"))
+        .unwrap()
+        .with_defaults(schema.defaults());
+    assert!(cfg.validate(&schema).is_ok());
+    assert_eq!(cfg.get::<u8>("workers").unwrap(), 4);
+}
+
+#[test]
+/// Test entries with an `until` comment stop being used after that time
+fn test_expiry() {
+    let text = "\
+mode = normal
+mode = maintenance ; until 2001-02-03T04:05Z
+[limits]
+rate = 100 # until 9999-12-31
+burst = 5 ; until 2000-01-01 12:00:30
+burst = 9 ; until later
+";
+    let mut cfg = Config::new().expiry(true).text(text).unwrap();
+
+    assert_eq!(cfg.get_str("mode").unwrap(), "normal");
+    assert_eq!(cfg.get::<u32>("limits.rate").unwrap(), 100);
+    assert_eq!(cfg.get::<u32>("limits.burst").unwrap(), 9);
+
+    let expired = cfg.check_expired();
+    assert_eq!(expired.len(), 2);
+    assert_eq!(expired[0].key, "mode");
+    assert_eq!(expired[1].key, "limits.burst");
+    assert_eq!(expired[0].line, Some(2));
+    assert_eq!(
+        expired[0].until,
+        std::time::UNIX_EPOCH + std::time::Duration::from_secs(981_173_100)
+    );
+    assert!(cfg
+        .support_bundle(None)
+        .contains(" - `mode` has expired (<text> line 2)\n"));
+
+    // Setting the key again makes it current
+    cfg.set("mode", "maintenance");
+    assert_eq!(cfg.get_str("mode").unwrap(), "maintenance");
+    assert_eq!(cfg.check_expired().len(), 1);
+
+    // Without expiry the comment is ignored
+    let cfg = Config::new().text(text).unwrap();
+    assert_eq!(cfg.get_str("mode").unwrap(), "maintenance");
+}
+
+#[test]
+/// Test secret values are resolved from the backend and redacted
+fn test_secrets() {
+    let cfg = Config::new()
+        .secrets(|name: &str| match name {
+            "db_pass" => Ok("hunter2".to_owned()),
+            _ => Err(ConfigError::NoItem),
+        })
+        .sensitive("auth.*")
+        .text(
+            "[db]\npass = !secret:db_pass\nport = 5432\nurl = !secret:missing\n[auth]\nsalt = abc",
+        )
+        .unwrap();
+
+    assert_eq!(cfg.get_resolved("db.pass").unwrap(), "hunter2");
+    assert_eq!(cfg.get_str("db.pass").unwrap(), "!secret:db_pass");
+    assert!(matches!(
+        cfg.get_resolved("db.url"),
+        Err(ConfigError::NoItem)
+    ));
+    assert!(cfg.is_sensitive("db.pass"));
+    assert!(cfg.is_sensitive("salt"));
+    assert!(!cfg.is_sensitive("db.port"));
+
+    let bundle = cfg.support_bundle(None);
+    assert!(bundle.contains("auth.salt = <redacted> (<text> line 6)"));
+    assert!(bundle.contains("db.url = <redacted>"));
+    assert!(bundle.contains("db.port = 5432"));
+
+    // Without a backend secrets are left as they are
+    let cfg = Config::new().text("pass = !secret:db_pass").unwrap();
+    assert_eq!(cfg.get_resolved("pass").unwrap(), "!secret:db_pass");
+}
+
+#[cfg(feature = "json")]
+#[test]
+/// Test exporting only the keys that differ from a base config
+fn test_export_delta() {
+    let base = Config::new()
+        .text("name = app\n[db]\nhost = localhost\npool = 4\n[cache]\nttl = 60")
+        .unwrap();
+    let env = Config::new()
+        .text("[db]\nhost = localhost\npool = 4\n[cache]\nttl = 60")
+        .unwrap()
+        .json(r#"{"db": {"host": "db.prod"}, "motd": " hi; there "}"#)
+        .unwrap();
+
+    let delta = env.export_delta(&base);
+    assert_eq!(delta, "db.host = db.prod\nmotd = \" hi; there \"\n");
+    assert_eq!(base.export_delta(&base), "");
+
+    let cfg = Config::new()
+        .text("name = app\n[db]\nhost = localhost\npool = 4\n[cache]\nttl = 60")
+        .unwrap()
+        .text(delta)
+        .unwrap();
+    assert_eq!(cfg.get_str("db.host").unwrap(), "db.prod");
+    assert_eq!(cfg.get_str("motd").unwrap(), " hi; there ");
+}
+
+#[test]
+/// Test debug output lists the values in use with secrets redacted
+fn test_debug_redacted() {
+    let cfg = Config::new()
+        .sensitive("*.webhook")
+        .text("[api]\ntoken = abc\nurl = https://example.com\n[hooks]\nwebhook = https://a:b@hooks")
+        .unwrap()
+        .text("[api]\nurl = https://example.org\nAPI_Key = xyz")
+        .unwrap();
+
+    let debug = format!("{:?}", cfg);
+    assert_eq!(
+        debug,
+        "Config {\n    api.token     = <redacted>\n    api.url       = https://example.org\n    \
+         api.api_key   = <redacted>\n    hooks.webhook = <redacted>\n}"
+    );
+    assert!(!debug.contains("abc") && !debug.contains("xyz"));
+
+    // Display is still the config file, with secrets in it
+    let text = cfg.to_string();
+    assert!(text.contains("token = abc") && text.contains("API_Key = xyz"));
+    assert!(text.contains("webhook = https://a:b@hooks"));
+
+    // Unless it's redacted
+    let redacted = cfg.redacted().to_string();
+    assert_eq!(
+        redacted,
+        text.replace("= abc", "= <redacted>")
+            .replace("= xyz", "= <redacted>")
+            .replace("= https://a:b@hooks", "= <redacted>")
+    );
+    assert!(!redacted.contains("abc") && !redacted.contains("a:b@"));
+}
+
+#[test]
+/// Test a profile's keys are used over the base keys
+fn test_profiles() {
+    let text = "\
+workers = 2
+[db]
+host = localhost
+pool = 4
+[profile.prod]
+workers = 16
+[profile.prod.db]
+host = db.internal
+[profile.staging.db]
+pool = 8
+";
+    let base = Config::new().text(text).unwrap();
+    assert_eq!(base.profile(), None);
+    assert_eq!(base.get::<u8>("workers").unwrap(), 16);
+
+    let cfg = Config::new().text(text).unwrap().with_profile("Prod");
+    assert_eq!(cfg.profile(), Some("prod"));
+    assert_eq!(cfg.get::<u8>("workers").unwrap(), 16);
+    assert_eq!(cfg.get_str("db.host").unwrap(), "db.internal");
+    assert_eq!(cfg.get_str("host").unwrap(), "db.internal");
+    assert_eq!(cfg.get::<u8>("db.pool").unwrap(), 4);
+    assert_eq!(cfg.get::<u8>("profile.staging.db.pool").unwrap(), 8);
+
+    let cfg = Config::new().text(text).unwrap().with_profile("staging");
+    assert_eq!(cfg.get::<u8>("workers").unwrap(), 2);
+    assert_eq!(cfg.get_str("db.host").unwrap(), "localhost");
+    assert_eq!(cfg.get::<u8>("db.pool").unwrap(), 8);
+    assert!(matches!(cfg.get_str("missing"), Err(ConfigError::NoItem)));
+}
+
+#[cfg(feature = "file")]
+#[test]
+/// Test the keys changed since loading are tracked
+fn test_dirty_keys() {
+    let path = std::env::temp_dir().join(format!("scp-test-dirty-{}.cfg", std::process::id()));
+    std::fs::write(&path, "volume = 50\n[ui]\ntheme = light\nscale = 1").unwrap();
+
+    let mut cfg = Config::new().file(&path).unwrap();
+    assert!(!cfg.is_dirty());
+    assert!(!cfg.save_if_dirty().unwrap());
+
+    cfg.set("volume", "50");
+    assert!(!cfg.is_dirty());
+
+    cfg.set("theme", "dark");
+    cfg.remove("scale");
+    cfg.set("theme", "light");
+    assert_eq!(cfg.dirty_keys(), ["ui.theme", "ui.scale"]);
+
+    assert!(cfg.save_if_dirty().unwrap());
+    assert!(!cfg.is_dirty());
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "volume = 50\n[ui]\ntheme = light\n"
+    );
+
+    cfg.clear();
+    assert_eq!(cfg.dirty_keys(), ["volume", "ui.theme"]);
+    cfg.mark_clean();
+    assert!(!cfg.save_if_dirty().unwrap());
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+/// Test comparing the schemas of two versions
+fn test_schema_diff() {
+    use simple_config_parser::{Schema, SchemaChange, SchemaKey, ValueType};
+
+    const V1: Schema = Schema::new(&[
+        SchemaKey::required("name", ValueType::Str),
+        SchemaKey::optional("timeout", ValueType::Int),
+    ]);
+    const V1_DB: Schema = Schema::section("db", &[SchemaKey::required("url", ValueType::Str)]);
+    const V2: Schema = Schema::new(&[
+        SchemaKey::required("name", ValueType::Str),
+        SchemaKey::optional("timeout", ValueType::Float),
+    ]);
+    const V2_DB: Schema = Schema::section(
+        "db",
+        &[
+            SchemaKey::required("dsn", ValueType::Str),
+            SchemaKey::optional("pool", ValueType::Int),
+        ],
+    );
+
+    let old = V1 + V1_DB;
+    assert!(old.diff(&old).is_empty());
+
+    let diff = old.diff(&(V2 + V2_DB));
+    assert_eq!(diff.changes.len(), 4);
+    assert_eq!(
+        diff.changes[0],
+        SchemaChange::Retyped("timeout".to_owned(), ValueType::Int, ValueType::Float)
+    );
+    assert!(matches!(&diff.changes[1], SchemaChange::Removed(name, _) if name == "db.url"));
+    assert_eq!(
+        diff.to_string(),
+        "- `timeout` is now number (was integer)\n- `db.url` was removed\n\
+         - `db.dsn` was added (text, required)\n- `db.pool` was added (integer, optional)\n"
+    );
+    assert_eq!(
+        diff.migration_stub(),
+        "/// Update a config from the old schema to the new one\nfn migrate(cfg: &mut Config) {\n    \
+         // `timeout` is now number (was integer)\n    // TODO: convert the value of `timeout`\n\n    \
+         // `db.url` was removed\n    // TODO: remove `db.url`\n\n    \
+         // `db.dsn` was added\n    // TODO: cfg.set(\"db.dsn\", ...);\n}\n"
+    );
+}
+
+#[test]
+/// Test reads are tracked and changes call back
+fn test_access_and_on_change() {
+    use std::sync::{Arc, Mutex};
+
+    let mut cfg = Config::new()
+        .track_access(true)
+        .text("name = app\n[db]\nhost = localhost\nold_option = 1")
+        .unwrap();
+
+    assert!(cfg.accessed_keys().is_empty());
+    cfg.get_str("host").unwrap();
+    cfg.get::<u8>("name").unwrap_err();
+    cfg.get_str("db.host").unwrap();
+    assert!(cfg.get_str("missing").is_err());
+    assert_eq!(cfg.accessed_keys(), ["db.host", "name"]);
+    assert_eq!(cfg.unused_keys(), ["db.old_option"]);
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let log = events.clone();
+    cfg.on_change(move |i| log.lock().unwrap().push(i.changes.clone()));
+
+    cfg.set("name", "web");
+    cfg.set("name", "web");
+    cfg.remove("old_option");
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0][0].new.as_deref(), Some("web"));
+    assert_eq!(events[1][0].key, "db.old_option");
+
+    // Without tracking nothing is recorded
+    let cfg = Config::new().text("name = app").unwrap();
+    cfg.get_str("name").unwrap();
+    assert!(cfg.accessed_keys().is_empty());
+    assert_eq!(cfg.unused_keys(), ["name"]);
+}
+
+#[test]
+/// Test the config cache stays in its limits
+fn test_config_cache() {
+    use simple_config_parser::ConfigCache;
+
+    let cache = ConfigCache::new().max_entries(2).max_bytes(40);
+    let text = |name: &str| format!("name = {}\n", name);
+    let load = |name: &str| cache.get_or_load(name, || Config::new().text(text(name)));
+
+    load("a").unwrap();
+    load("b").unwrap();
+    load("a").unwrap();
+    // `b` is the least recently used
+    load("c").unwrap();
+    assert!(cache.get("b").is_none());
+    assert!(cache.get("a").is_some());
+
+    let stats = cache.stats();
+    assert_eq!((stats.hits, stats.misses, stats.evictions), (2, 3, 1));
+    assert_eq!((stats.entries, stats.bytes), (2, 18));
+
+    // Too big to keep
+    let big = cache
+        .get_or_load("big", || {
+            Config::new().text(format!("name = {}", "x".repeat(40)))
+        })
+        .unwrap();
+    assert_eq!(big.get_str("name").unwrap().len(), 40);
+    assert!(cache.get("big").is_none());
+
+    // Over the byte limit
+    load("longer-name-d").unwrap();
+    load("longer-name-e").unwrap();
+    assert_eq!(cache.stats().entries, 1);
+    assert!(cache.stats().bytes <= 40);
+
+    let err = cache.get_or_load("bad", || Config::new().text("[bad"));
+    assert!(err.is_err());
+    assert!(cache.get("bad").is_none());
+
+    cache.clear();
+    assert_eq!(cache.stats().entries, 0);
+    assert_eq!(cache.stats().bytes, 0);
+}
+
+#[test]
+/// Test getters for values within limits
+fn test_constrained_getters() {
+    let cfg = Config::new()
+        .error_provenance(true)
+        .text("[server]\nport = 80\nratio = 1.5\nmode = safe\nlevel = loud")
+        .unwrap();
+
+    assert_eq!(cfg.get_range::<u16, _>("port", 1..=65535).unwrap(), 80);
+    assert_eq!(cfg.get_range::<f32, _>("ratio", ..2.0).unwrap(), 1.5);
+    assert_eq!(cfg.get_one_of("mode", &["fast", "safe"]).unwrap(), "safe");
+
+    let err = cfg.get_range::<u16, _>("port", 1024..).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "`server.port` from <text> line 2: Value of `server.port` (`80`) must be at least 1024"
+    );
+    assert!(matches!(err.inner(), ConfigError::InvalidValue(_)));
+    assert_eq!(
+        cfg.get_range::<f32, _>("ratio", 0.0..1.0)
+            .unwrap_err()
+            .inner()
+            .to_string(),
+        "Value of `server.ratio` (`1.5`) must be from 0 to less than 1"
+    );
+    assert!(matches!(
+        cfg.get_range::<u8, _>("level", 0..5).unwrap_err().inner(),
+        ConfigError::InvalidType(..)
+    ));
+    assert!(matches!(
+        cfg.get_one_of("missing", &["a"]),
+        Err(ConfigError::NoItem)
+    ));
+    assert_eq!(
+        cfg.get_one_of("level", &["quiet"])
+            .unwrap_err()
+            .inner()
+            .to_string(),
+        "Value of `server.level` (`loud`) must be one of `quiet`"
+    );
+}
+
+#[test]
+/// Test comparing values to secrets
+fn test_secret_eq() {
+    let cfg = Config::new()
+        .secrets(|name: &str| match name {
+            "token" => Ok("s3cr3t".to_owned()),
+            _ => Err(ConfigError::NoItem),
+        })
+        .text("[auth]\ntoken = !secret:token\nkey = abc\nbroken = !secret:gone\nempty =")
+        .unwrap();
+
+    assert!(cfg.secret_eq("auth.token", "s3cr3t"));
+    assert!(!cfg.secret_eq("auth.token", "!secret:token"));
+    assert!(!cfg.secret_eq("auth.token", "s3cr3"));
+    assert!(!cfg.secret_eq("auth.token", "s3cr3t!"));
+    assert!(cfg.secret_eq("key", "abc"));
+    assert!(!cfg.secret_eq("key", "abd"));
+    assert!(!cfg.secret_eq("broken", ""));
+    assert!(cfg.secret_eq("empty", ""));
+    assert!(!cfg.secret_eq("empty", "\0"));
+}
+
+#[test]
+/// Test reading renamed keys by their old names
+fn test_key_alias() {
+    use std::sync::{Arc, Mutex};
+
+    let warned = Arc::new(Mutex::new(Vec::new()));
+    let log = warned.clone();
+    let cfg = Config::new()
+        .alias("max_conns", "max_connections")
+        .alias("db.addr", "db.host")
+        .alias("old_name", "name")
+        .on_deprecated(move |i| log.lock().unwrap().push(i.key.to_owned()))
+        .text("max_conns = 10\nold_name = a\nname = b\n[db]\naddr = localhost")
+        .unwrap();
+
+    assert_eq!(cfg.get::<u32>("max_connections").unwrap(), 10);
+    assert_eq!(cfg.get_str("db.host").unwrap(), "localhost");
+    assert_eq!(cfg.get_str("name").unwrap(), "b");
+    assert!(cfg.get_str("missing").is_err());
+    assert_eq!(*warned.lock().unwrap(), ["max_conns", "db.addr"]);
+
+    let deprecated = cfg.check_deprecations().unwrap();
+    assert_eq!(deprecated.len(), 3);
+    assert_eq!(deprecated[0].note.as_deref(), Some("use `max_connections`"));
+}
+
+#[cfg(feature = "file")]
+#[test]
+/// Test loading files with invalid UTF-8
+fn test_lossy_utf8() {
+    let path = std::env::temp_dir().join("scp-test-lossy.cfg");
+    std::fs::write(&path, b"; caf\xe9\nname = app\nmotd = hi \xff\xfe").unwrap();
+
+    assert!(matches!(
+        Config::new().file(&path),
+        Err(ConfigError::InvalidUtf8(1, 5))
+    ));
+
+    let cfg = Config::new().lossy_utf8(true).file(&path).unwrap();
+    assert_eq!(cfg.get_str("name").unwrap(), "app");
+    assert_eq!(cfg.get_str("motd").unwrap(), "hi \u{fffd}\u{fffd}");
+    assert_eq!(cfg.load_report().sources[0].invalid_utf8, [1, 3]);
+    assert!(cfg
+        .load_report()
+        .to_string()
+        .contains("invalid UTF-8 on lines [1, 3]"));
+
+    let cfg = Config::new().lossy_utf8(true).text("a = b").unwrap();
+    assert!(cfg.load_report().sources[0].invalid_utf8.is_empty());
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+/// Test the colon separator and flag lines
+fn test_colon_and_flags() {
+    let text = "debug\n[server]\nhost: localhost\nport:8080\nurl = http://a:1/\ntls\n";
+    assert!(Config::new().text(text).is_err());
+
+    let mut cfg = Config::builder()
+        .colon_separator(true)
+        .flags(true)
+        .build()
+        .unwrap()
+        .text(text)
+        .unwrap();
+
+    assert!(cfg.get::<bool>("debug").unwrap());
+    assert_eq!(cfg.get_str("server.host").unwrap(), "localhost");
+    assert_eq!(cfg.get::<u16>("server.port").unwrap(), 8080);
+    assert_eq!(cfg.get_str("server.url").unwrap(), "http://a:1/");
+    assert!(cfg.get::<bool>("server.tls").unwrap());
+    assert_eq!(cfg.to_string(), text);
+
+    cfg.set("debug", "false");
+    cfg.set("port", "80");
+    assert_eq!(
+        cfg.to_string(),
+        "debug = false\n[server]\nhost: localhost\nport:80\nurl = http://a:1/\ntls\n"
+    );
+
+    let cfg = Config::builder().colon_separator(true).build().unwrap();
+    assert!(cfg.text("verbose").is_err());
+}
+
+#[test]
+/// Test the index of entries staying correct as the config changes
+fn test_entry_map() {
+    let mut cfg = Config::new()
+        .duplicates(DuplicatePolicy::Collect)
+        .text("a = 1\n[s]\na = 2\nb = 3\n[t]\na = 4")
+        .unwrap();
+
+    let values = |cfg: &Config, key: &str| {
+        cfg.data
+            .lookup(key)
+            .map(|i| i.value.to_owned())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(values(&cfg, "a"), ["1", "2", "4"]);
+    assert_eq!(values(&cfg, "s.a"), ["2"]);
+    assert!(values(&cfg, "s").is_empty());
+    assert_eq!(cfg.get_str("s.a").unwrap(), "2");
+    assert_eq!(cfg.get_str("a").unwrap(), "4");
+
+    cfg.remove("a");
+    assert_eq!(values(&cfg, "a"), ["1", "2"]);
+    assert!(values(&cfg, "t.a").is_empty());
+    assert_eq!(values(&cfg, "s.b"), ["3"]);
+    assert_eq!(cfg.get_str("b").unwrap(), "3");
+
+    cfg.set("c", "5");
+    cfg.set("b", "6");
+    assert_eq!(values(&cfg, "c"), ["5"]);
+    assert_eq!(cfg.get_str("s.b").unwrap(), "6");
+    assert_eq!(cfg.data.len(), 4);
+
+    cfg.clear();
+    assert!(values(&cfg, "c").is_empty());
+}
+
+#[cfg(feature = "file")]
+#[test]
+/// Test finding where the values in use were defined
+fn test_source_of() {
+    let dir = std::env::temp_dir();
+    let (base, local) = (
+        dir.join("scp-source-base.cfg"),
+        dir.join("scp-source-local.cfg"),
+    );
+    std::fs::write(&base, "name = app\n[db]\nhost = a\nport = 1").unwrap();
+    std::fs::write(&local, "# Local\n[db]\nhost = b").unwrap();
+
+    let mut cfg = Config::new()
+        .alias("db.addr", "db.url")
+        .file(&base)
+        .unwrap()
+        .file(&local)
+        .unwrap()
+        .text("[db]\naddr = c")
+        .unwrap();
+
+    let host = cfg.source_of("db.host").unwrap();
+    assert_eq!(host.source, local.display().to_string());
+    assert_eq!((host.line, host.value.as_str()), (Some(3), "b"));
+
+    let port = cfg.source_of("port").unwrap();
+    assert_eq!(port.source, base.display().to_string());
+    assert_eq!(port.line, Some(4));
+
+    let url = cfg.source_of("db.url").unwrap();
+    assert_eq!((url.source.as_str(), url.line), ("<text>", Some(2)));
+
+    cfg.set("name", "other");
+    assert_eq!(cfg.source_of("name").unwrap().source, "<set>");
+    assert!(cfg.source_of("missing").is_none());
+
+    std::fs::remove_file(base).unwrap();
+    std::fs::remove_file(local).unwrap();
+}
+
+#[cfg(feature = "toml")]
+#[test]
+/// Test lists of sections written `[[name]]`
+fn test_repeated_sections() {
+    let text = "[[server]]\nhost = a\nport = 80\n[[server]] # Backup\nhost = b\n[db]\nhost = c\n[[server]]\nhost = d";
+    let cfg = Config::new().text(text).unwrap();
+
+    let servers = cfg.sections("server");
+    let hosts = servers
+        .iter()
+        .map(|i| i.get_str("host").unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(hosts, ["a", "b", "d"]);
+    assert_eq!(servers[0].get::<u16>("port").unwrap(), 80);
+    assert!(servers[1].get_str("port").is_err());
+    assert_eq!(cfg.get_str("server.2.host").unwrap(), "d");
+    assert_eq!(cfg.to_string(), format!("{}\n", text));
+
+    assert_eq!(cfg.sections("db")[0].get_str("host").unwrap(), "c");
+    assert!(cfg.sections("missing").is_empty());
+
+    let cfg = Config::new()
+        .text("[server.1]\nhost = y\n[server.0]\nhost = x")
+        .unwrap();
+    assert_eq!(cfg.sections("server")[0].get_str("host").unwrap(), "x");
+
+    let cfg = Config::new()
+        .format(Format::Toml)
+        .text("[[server]]\nhost = \"a\"\n\n[[server]]\nhost = \"b\"")
+        .unwrap();
+    assert_eq!(cfg.sections("server")[1].get_str("host").unwrap(), "b");
+}
+
+#[cfg(all(feature = "json", feature = "toml"))]
+#[test]
+/// Test writing configs as JSON and TOML
+fn test_export_json_toml() {
+    let cfg = Config::new()
+        .with_defaults(Config::new().text("retries = 3").unwrap())
+        .text("a = 1\na.b = 2\nlevel = 007\nratio = -1.5e3\nbig = 99999999999999999999\n[my server]\non = false\npath = C:\\dir\n")
+        .unwrap();
+
+    assert_eq!(
+        cfg.to_json(),
+        r#"{"retries": 3, "a": 1, "a.b": 2, "level": "007", "ratio": -1.5e3, "big": "99999999999999999999", "my server": {"on": false, "path": "C:\\dir"}}"#
+    );
+    assert_eq!(
+        cfg.to_toml(),
+        "retries = 3\na = 1\n\"a.b\" = 2\nlevel = \"007\"\nratio = -1.5e3\nbig = \"99999999999999999999\"\n\n[\"my server\"]\non = false\npath = \"C:\\\\dir\"\n"
+    );
+
+    let json = Config::new().json(cfg.to_json()).unwrap();
+    assert_eq!(json.get_str("myserver.path").unwrap(), "C:\\dir");
+    let toml = Config::new()
+        .format(Format::Toml)
+        .text(cfg.to_toml())
+        .unwrap();
+    assert_eq!(toml.get_str("level").unwrap(), "007");
+
+    assert_eq!(Config::new().to_json(), "{}");
+    assert_eq!(Config::new().to_toml(), "");
+}
+
+#[cfg(all(feature = "file", feature = "json", feature = "toml"))]
+#[test]
+/// Test byte order marks, line endings and other encodings
+fn test_bom_and_line_endings() {
+    let text = "\u{feff}hello = World\r\n[server]\r\nport = 80\rhost = a\r\n";
+    let cfg = Config::new().text(text).unwrap();
+    assert_eq!(cfg.get_str("hello").unwrap(), "World");
+    assert_eq!(cfg.get::<u16>("server.port").unwrap(), 80);
+    assert_eq!(cfg.get_str("server.host").unwrap(), "a");
+    assert_eq!(
+        cfg.to_string(),
+        "hello = World\n[server]\nport = 80\nhost = a\n"
+    );
+
+    let cfg = Config::new().reader(text.as_bytes()).unwrap();
+    assert_eq!(cfg.get_str("hello").unwrap(), "World");
+    assert_eq!(cfg.get_str("server.host").unwrap(), "a");
+
+    let cfg = simple_config_parser::ConfigRef::parse(text).unwrap();
+    assert_eq!(cfg.get_str("hello").unwrap(), "World");
+    assert_eq!(cfg.get_str("server.host").unwrap(), "a");
+
+    let cfg = Config::new().json("\u{feff}{\"a\": 1}").unwrap();
+    assert_eq!(cfg.get::<u8>("a").unwrap(), 1);
+    let cfg = Config::new()
+        .format(Format::Toml)
+        .text("\u{feff}a = 1\r\nb = \"x\"\r\n")
+        .unwrap();
+    assert_eq!(cfg.get_str("b").unwrap(), "x");
+
+    let path = std::env::temp_dir().join("scp-test-latin1.cfg");
+    std::fs::write(&path, b"a = ok\r\nname = Ren\xe9e\r\n").unwrap();
+    let err = Config::new().file(&path).unwrap_err();
+    assert!(matches!(err, ConfigError::InvalidUtf8(2, 18)));
+    assert_eq!(
+        err.to_string(),
+        "Config file is not valid UTF-8 on line 2 (byte 18)"
+    );
+    assert_eq!(err.args(), ["2", "18"]);
+
+    let cfg = Config::new().latin1(true).file(&path).unwrap();
+    assert_eq!(cfg.get_str("name").unwrap(), "Renée");
+    assert_eq!(cfg.load_report().sources[0].invalid_utf8, [2]);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_skip_invalid_lines() {
+    let text = "a = 1\n[server\njunk\n!include scp-test-missing.cfg\nb = \"\"\"\nnever closed";
+    assert!(matches!(
+        Config::new().text(text),
+        Err(ConfigError::InvalidConfig)
+    ));
+
+    let cfg = Config::new().skip_invalid_lines(true).text(text).unwrap();
+    assert_eq!(cfg.get_str("a").unwrap(), "1");
+    assert!(cfg.get_str("b").is_err());
+
+    let errors = cfg.line_errors().collect::<Vec<_>>();
+    let lines = errors.iter().map(|i| i.line).collect::<Vec<_>>();
+    assert_eq!(lines, [2, 3, 4, 5]);
+    assert!(matches!(errors[2].error, ConfigError::Include(..)));
+    assert_eq!(errors[3].text, "b = \"\"\"\nnever closed");
+    assert_eq!(
+        errors[1].to_string(),
+        "line 3: Config data is not valid (`junk`)"
+    );
+    assert!(cfg
+        .load_report()
+        .to_string()
+        .contains(", skipped invalid lines [2, 3, 4, 5]"));
+    assert_eq!(cfg.to_string(), format!("{}\n", text));
+
+    // Any input either parses or fails, skipping lines never fails
+    let mut seed = 0x2545_f491_u32;
+    let parts = [
+        "a",
+        "=",
+        ":",
+        "[",
+        "]",
+        "\"\"\"",
+        "\"",
+        "\\",
+        ";",
+        "#",
+        " ",
+        "\n",
+        "\r",
+        "é",
+        "!include x",
+        "${a}",
+        "${env:X}",
+    ];
+    for _ in 0..2000 {
+        let mut text = String::new();
+        for _ in 0..(seed % 24) {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            text.push_str(parts[seed as usize % parts.len()]);
+        }
+
+        let _ = Config::new().text(&text);
+        let cfg = Config::new().skip_invalid_lines(true).text(&text).unwrap();
+        let _ = cfg.to_string();
+    }
+}
+
+#[test]
+fn test_shared_config() {
+    use simple_config_parser::SharedConfig;
+
+    let shared = SharedConfig::from(Config::new().text("[db]\nhost = a\nport = 1").unwrap());
+    let changes = shared.subscribe(&["db.host"]);
+    let before = shared.load();
+
+    let readers = (0..4)
+        .map(|_| {
+            let shared = shared.clone();
+            std::thread::spawn(move || {
+                for _ in 0..100 {
+                    let host = shared.load().get_str("db.host").unwrap();
+                    assert!(host == "a" || host == "b");
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    shared
+        .reload(|| Config::new().text("[db]\nhost = b\nport = 2"))
+        .unwrap();
+    for i in readers {
+        i.join().unwrap();
+    }
+
+    // Handles taken before the reload keep the old config
+    assert_eq!(before.get_str("db.host").unwrap(), "a");
+    assert_eq!(shared.read().get::<u16>("db.port").unwrap(), 2);
+
+    let event = changes.try_recv().unwrap();
+    assert_eq!(event.changes.len(), 1);
+    assert_eq!(event.changes[0].key, "db.host");
+    assert_eq!(event.changes[0].old.as_deref(), Some("a"));
+
+    assert!(shared.reload(|| Config::new().text("[db")).is_err());
+    assert_eq!(shared.load().get_str("db.host").unwrap(), "b");
+    let old = shared.replace(Config::new());
+    assert_eq!(old.get::<u16>("db.port").unwrap(), 2);
+    assert_eq!(changes.try_recv().unwrap().changes[0].new, None);
+
+    // Changes from replacements on many threads arrive in the order they were made
+    let (tx, rx) = std::sync::mpsc::channel();
+    shared.subscribe_with(&["db.host"], tx);
+    let writers = (0..8)
+        .map(|i| {
+            let shared = shared.clone();
+            std::thread::spawn(move || {
+                for j in 0..50 {
+                    let text = format!("[db]\nhost = {}-{}", i, j);
+                    shared.replace(Config::new().text(text).unwrap());
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for i in writers {
+        i.join().unwrap();
+    }
+
+    let mut host = None;
+    for i in rx.try_iter() {
+        assert_eq!(i.changes[0].old, host);
+        host = i.changes[0].new.to_owned();
+    }
+    assert_eq!(host.unwrap(), shared.load().get_str("db.host").unwrap());
+}
+
+#[cfg(feature = "reload")]
+#[test]
+fn test_shared_config_watch() {
+    use simple_config_parser::SharedConfig;
+
+    let dir = std::env::temp_dir();
+    let base = dir.join(format!("scp-watch-{}.cfg", std::process::id()));
+    let local = dir.join(format!("scp-watch-local-{}.cfg", std::process::id()));
+    std::fs::write(&base, "host = a\nport = 80").unwrap();
+    let _ = std::fs::remove_file(&local);
+
+    let (a, b) = (base.clone(), local.clone());
+    let load = move || Ok(Config::new().file(&a)?.file_optional(&b));
+    let shared = SharedConfig::new(load().unwrap());
+    let watcher = shared.watch(&[&base], Duration::from_millis(5), load);
+
+    // Waits until the config is reloaded with `check` true
+    let wait = |check: &dyn Fn(&Config) -> bool| {
+        let start = std::time::Instant::now();
+        while !check(&shared.load()) {
+            assert!(start.elapsed() < Duration::from_secs(10), "Not reloaded");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    };
+
+    // The missing optional source is retried without a watched file changing
+    std::fs::write(&local, "port = 90").unwrap();
+    wait(&|i| i.get::<u16>("port").is_ok_and(|i| i == 90));
+    assert_eq!(shared.load().load_report().failures().count(), 0);
+
+    // A broken edit keeps the last good config
+    std::fs::write(&base, "[broken").unwrap();
+    let start = std::time::Instant::now();
+    while watcher.last_error().is_none() {
+        assert!(start.elapsed() < Duration::from_secs(10), "Not reloaded");
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert_eq!(shared.load().get_str("host").unwrap(), "a");
+
+    // And fixing it is picked up
+    std::fs::write(&base, "host = b\nport = 80").unwrap();
+    wait(&|i| i.get_str("host").is_ok_and(|i| i == "b"));
+    assert_eq!(shared.load().get::<u16>("port").unwrap(), 90);
+    watcher.stop();
+    assert!(shared.load().load_report().failures().next().is_none());
+
+    std::fs::remove_file(&base).unwrap();
+    std::fs::remove_file(&local).unwrap();
+}
+
+#[cfg(feature = "reload")]
+#[test]
+/// Test watchers warn when the config in use is stale, until it's reloaded
+fn test_watch_stale() {
+    use simple_config_parser::SharedConfig;
+
+    let path = std::env::temp_dir().join(format!("scp-watch-stale-{}.cfg", std::process::id()));
+    std::fs::write(&path, "host = a").unwrap();
+    let file = path.clone();
+    let load = move || Config::new().file(&file);
+    let shared = SharedConfig::new(load().unwrap());
+    let watcher = shared.watch(&[&path], Duration::from_millis(5), load);
+    assert!(watcher.stale().is_empty());
+
+    let policy = StalePolicy {
+        max_file_age: None,
+        max_unvalidated: Some(Duration::from_millis(500)),
+    };
+    watcher.warn_stale(policy.clone());
+    assert!(watcher.stale().is_empty());
+    std::thread::sleep(Duration::from_millis(600));
+    watcher.warn_stale(policy);
+    assert!(matches!(
+        watcher.stale()[..],
+        [Staleness::NotRevalidated(_)]
+    ));
+
+    std::fs::write(&path, "host = bb").unwrap();
+    let start = std::time::Instant::now();
+    while !watcher.stale().is_empty() {
+        assert!(start.elapsed() < Duration::from_secs(10), "Not reloaded");
+        std::thread::sleep(Duration::from_millis(5));
+    }
+    assert_eq!(shared.load().get_str("host").unwrap(), "bb");
+    watcher.stop();
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_from_config_value() {
+    use simple_config_parser::FromConfigValue;
+
+    /// An address and port, written as `host:port`
+    #[derive(Debug, PartialEq)]
+    struct Addr(String, u16);
+
+    impl FromConfigValue for Addr {
+        fn from_config_value(value: &str) -> Result<Self, ConfigError> {
+            let (host, port) = value
+                .rsplit_once(':')
+                .ok_or_else(|| ConfigError::InvalidValue("has no port".to_owned()))?;
+            Ok(Addr(host.to_owned(), u16::from_config_value(port)?))
+        }
+    }
+
+    let cfg = Config::new()
+        .duplicates(DuplicatePolicy::Collect)
+        .text("[db]\nmain = a:1\nreplicas = b:2, c:3\nbad = d\n[peer]\naddr = e:4\naddr = f:5")
+        .unwrap();
+
+    assert_eq!(cfg.get::<Addr>("db.main").unwrap(), Addr("a".into(), 1));
+    let db = cfg.section("db").unwrap();
+    assert_eq!(
+        db.get_vec::<Addr>("replicas").unwrap(),
+        [Addr("b".into(), 2), Addr("c".into(), 3)]
+    );
+    assert!(matches!(
+        db.get::<Addr>("bad"),
+        Err(ConfigError::InvalidValue(_))
+    ));
+    assert_eq!(cfg.get_all::<Addr>("peer.addr").unwrap().len(), 2);
+    assert_eq!(cfg.get_or("db.other", Addr("x".into(), 0)).unwrap().1, 0);
+
+    // FromStr types name the key, value and type when they don't parse
+    let err = cfg.get::<u16>("db.main").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Value of `db.main` (`a:1`) can't be read as u16"
+    );
+    assert_eq!(err.args(), ["db.main", "a:1", "u16"]);
+    assert!(matches!(
+        db.get_vec::<u8>("replicas"),
+        Err(ConfigError::InvalidType(key, value, target))
+            if key == "db.replicas" && value == "b:2" && target == "u8"
+    ));
+    assert!(matches!(
+        cfg.get_bool("db.bad"),
+        Err(ConfigError::InvalidType(_, _, target)) if target == "bool"
+    ));
+    let ips = Config::new()
+        .text("hosts = 10.0.0.1, ::1")
+        .unwrap()
+        .get_vec::<std::net::IpAddr>("hosts")
+        .unwrap();
+    assert!(ips[1].is_loopback());
+}
+
+#[cfg(feature = "file")]
+#[test]
+fn test_atomic_save() {
+    let dir = std::env::temp_dir().join(format!("scp-atomic-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("app.cfg");
+    std::fs::write(&path, "; Settings\nvolume = 10").unwrap();
+
+    let mut cfg = Config::new().file(&path).unwrap();
+    cfg.set("volume", "80");
+    cfg.save().unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "; Settings\nvolume = 80\n"
+    );
+
+    // No temporary files are left behind
+    let files = std::fs::read_dir(&dir).unwrap().count();
+    assert_eq!(files, 1);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode =
+            |path: &std::path::Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+        cfg.save().unwrap();
+        assert_eq!(mode(&path), 0o600);
+
+        // Bits the umask takes away are kept too
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o622)).unwrap();
+        cfg.save().unwrap();
+        assert_eq!(mode(&path), 0o622);
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+
+        // Saving through a symlink replaces the file it points to
+        let link = dir.join("link.cfg");
+        std::os::unix::fs::symlink(&path, &link).unwrap();
+        cfg.set("volume", "90");
+        cfg.save_to(&link).unwrap();
+        assert!(std::fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("volume = 90"));
+
+        cfg = cfg.preserve_permissions(false);
+        cfg.save().unwrap();
+        assert_ne!(mode(&path), 0o600);
+    }
+
+    // Threads saving the same file at once each write their own temporary file
+    let saves = (0..8)
+        .map(|i| {
+            let path = path.clone();
+            std::thread::spawn(move || {
+                let mut cfg = Config::new().text("volume = 0").unwrap();
+                for j in 0..25 {
+                    cfg.set("volume", &format!("{}{}", i, j));
+                    cfg.save_to(&path).unwrap();
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for i in saves {
+        i.join().unwrap();
+    }
+    let cfg = Config::new().file(&path).unwrap();
+    assert!(cfg.get::<u32>("volume").is_ok());
+    let temp = std::fs::read_dir(&dir)
+        .unwrap()
+        .filter(|i| i.as_ref().unwrap().path().extension() == Some("tmp".as_ref()))
+        .count();
+    assert_eq!(temp, 0);
+
+    // A directory can't be replaced, and the temporary file is removed
+    assert!(matches!(cfg.save_to(&dir), Err(ConfigError::Io(_))));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "file")]
+#[test]
+fn test_backups() {
+    let dir = std::env::temp_dir().join(format!("scp-backups-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("app.cfg");
+    std::fs::write(&path, "volume = 1").unwrap();
+    let read = || std::fs::read_to_string(&path).unwrap();
+
+    let mut cfg = Config::new().backups(2).file(&path).unwrap();
+    assert!(cfg.list_backups().is_empty());
+    for i in 2..=4 {
+        cfg.set("volume", &i.to_string());
+        cfg.save().unwrap();
+    }
+    // Saving without changes makes no backup
+    cfg.save().unwrap();
+
+    let backups = cfg.list_backups();
+    assert_eq!(backups.len(), 2);
+    assert_eq!(backups[0].0, dir.join("app.cfg.bak.1"));
+    assert_eq!(
+        std::fs::read_to_string(&backups[0].0).unwrap(),
+        "volume = 3\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(&backups[1].0).unwrap(),
+        "volume = 2\n"
+    );
+    assert!(!dir.join("app.cfg.bak.3").exists());
+
+    cfg.restore_backup(2).unwrap();
+    assert_eq!(read(), "volume = 2\n");
+    // The restore is undone with the newest backup
+    cfg.restore_backup(1).unwrap();
+    assert_eq!(read(), "volume = 4\n");
+
+    assert!(matches!(cfg.restore_backup(5), Err(ConfigError::Io(_))));
+    assert!(matches!(
+        Config::new().restore_backup(1),
+        Err(ConfigError::NoFileDefined)
+    ));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_key_queries() {
+    let cfg = Config::new()
+        .text("name = app\n[Plugin]\nfoo.enabled = true\nbar.enabled = false\nfoo.level = 2\n[plugins]\nx = 1")
+        .unwrap()
+        .text("[plugin]\nbar.enabled = true")
+        .unwrap();
+
+    let plugins = cfg.get_prefixed("PLUGIN.");
+    assert_eq!(
+        plugins,
+        [
+            ("plugin.foo.enabled".to_owned(), "true"),
+            ("plugin.bar.enabled".to_owned(), "true"),
+            ("plugin.foo.level".to_owned(), "2"),
+        ]
+    );
+    assert_eq!(cfg.get_prefixed("plugin").len(), 4);
+    assert!(cfg.get_prefixed("missing.").is_empty());
+
+    let enabled = cfg.keys_matching("plugin.*.enabled");
+    assert_eq!(enabled.len(), 2);
+    assert_eq!(
+        cfg.keys_matching("plugin?.x"),
+        [("plugins.x".to_owned(), "1")]
+    );
+    assert_eq!(cfg.keys_matching("*").len(), 5);
+}
+
+#[test]
+fn test_section_as_map() {
+    let cfg = Config::new()
+        .text("[env]\nPATH = /bin\nHOME = /root\npath = /usr/bin\n[ports]\nhttp = 80\nhttps = 443\n[bad]\na = 1\nb = x")
+        .unwrap();
+
+    let env = cfg.section_as_map("ENV");
+    assert_eq!(env.len(), 2);
+    assert_eq!(env["path"], "/usr/bin");
+    assert_eq!(env["home"], "/root");
+    assert!(cfg.section_as_map("missing").is_empty());
+
+    let ports = cfg.section_as_map_of::<u16>("ports").unwrap();
+    assert_eq!(
+        ports.into_iter().collect::<Vec<_>>(),
+        [("http".to_owned(), 80), ("https".to_owned(), 443)]
+    );
+    assert!(matches!(
+        cfg.section_as_map_of::<u8>("bad"),
+        Err(ConfigError::InvalidType(key, ..)) if key == "bad.b"
+    ));
+    assert!(cfg.section_as_map_of::<u8>("missing").unwrap().is_empty());
+
+    let cfg = Config::builder()
+        .case_sensitive(true)
+        .build()
+        .unwrap()
+        .text("[headers]\nContent-Type = text/plain")
+        .unwrap();
+    let headers = cfg.section("headers").unwrap().to_map();
+    assert_eq!(headers["Content-Type"], "text/plain");
+}
+
+#[cfg(feature = "env-file")]
+#[test]
+fn test_env_file() {
+    let path = std::env::temp_dir().join(format!("scp-test-{}.env", std::process::id()));
+    let write = |text: &str| std::fs::write(&path, text).unwrap();
+    write(concat!(
+        "# Comment\n; Also a comment\n\n",
+        "export NAME=app\n",
+        "PORT = 8080 # inline\n",
+        "URL=http://host/#anchor\n",
+        "SINGLE='p#ss \\n' # comment\n",
+        "DOUBLE=\"a \\\"quote\\\" \\$HOME\\tand\\\\\" # comment\n",
+        "MULTI=\"line 1\nline 2\"\n",
+        "LONG=one \\\ntwo\n",
+        "SERVER__HOST=localhost\n",
+        "EMPTY=\n",
+    ));
+
+    let cfg = Config::new().env_file(&path).unwrap();
+    assert_eq!(cfg.get_str("name").unwrap(), "app");
+    assert_eq!(cfg.get::<u16>("port").unwrap(), 8080);
+    assert_eq!(cfg.get_str("url").unwrap(), "http://host/#anchor");
+    assert_eq!(cfg.get_str("double").unwrap(), "a \"quote\" $HOME\tand\\");
+    assert_eq!(cfg.get_str("multi").unwrap(), "line 1\nline 2");
+    assert_eq!(cfg.get_str("long").unwrap(), "one two");
+    assert_eq!(cfg.get_str("server.host").unwrap(), "localhost");
+    assert_eq!(cfg.get_str("empty").unwrap(), "");
+    assert_eq!(cfg.get_str("single").unwrap(), "p#ss \\n");
+    assert!(matches!(cfg.save(), Err(ConfigError::NoFileDefined)));
+    assert_eq!(
+        cfg.load_report().sources[0].source,
+        path.display().to_string()
+    );
+
+    // Text after a closing quote is not allowed
+    write("SINGLE='it''s # not a comment'");
+    assert!(matches!(
+        Config::new().env_file(&path),
+        Err(ConfigError::InvalidConfig)
+    ));
+    for i in ["NAME", "A B=1", "A=\"open", "A='open\nstill"] {
+        write(i);
+        assert!(matches!(
+            Config::new().env_file(&path),
+            Err(ConfigError::InvalidConfig)
+        ));
+    }
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_windows_ini() {
+    let text = "; Generated\n[General]\nInstall Path=\"C:\\Program Files\\App\\\"\nTitle=\"  padded  \"\nFilter=*.txt;*.log\nColor=#ff0000\nEmpty=\n[general]\nVersion=2";
+    let cfg = Config::builder()
+        .windows_ini(true)
+        .build()
+        .unwrap()
+        .text(text)
+        .unwrap();
+
+    assert_eq!(
+        cfg.get_str("general.install path").unwrap(),
+        "C:\\Program Files\\App\\"
+    );
+    assert_eq!(cfg.get_str("General.Title").unwrap(), "  padded  ");
+    assert_eq!(cfg.get_str("general.filter").unwrap(), "*.txt;*.log");
+    assert_eq!(cfg.get_str("general.color").unwrap(), "#ff0000");
+    assert_eq!(cfg.get_str("general.empty").unwrap(), "");
+    assert_eq!(cfg.section("GENERAL").unwrap().keys().len(), 6);
+    assert_eq!(cfg.to_string(), format!("{}\n", text));
+
+    // The default comments and escapes come back when it's turned off
+    let cfg = Config::builder()
+        .windows_ini(true)
+        .windows_ini(false)
+        .build()
+        .unwrap()
+        .text("a = \"x\\ty\" ; Comment\nfull name = b")
+        .unwrap();
+    assert_eq!(cfg.get_str("a").unwrap(), "x\ty");
+    assert_eq!(cfg.get_str("fullname").unwrap(), "b");
+}
+
+#[test]
+fn test_comments() {
+    let mut cfg = Config::new()
+        .text("# managed-by: setup\n\n# old\nport = 80\n[server]\nhost = localhost")
+        .unwrap();
+
+    cfg.set_with_comment("port", "8080", "Port the server listens on");
+    cfg.set_with_comment("workers", "4", "Worker threads\n\nZero uses one per core");
+    assert!(cfg.comment_section("SERVER", "Web server"));
+    assert!(!cfg.comment_section("missing", "Nothing"));
+    cfg.set_header_comment("Generated config");
+    cfg.set_header_comment("Generated config\nDo not edit");
+
+    assert_eq!(
+        cfg.to_string(),
+        "# managed-by: setup\n# Generated config\n# Do not edit\n\n# Port the server listens on\nport = 8080\n# Worker threads\n#\n# Zero uses one per core\nworkers = 4\n# Web server\n[server]\nhost = localhost\n"
+    );
+    assert_eq!(cfg.managed_by().unwrap(), "setup");
+
+    let cfg = Config::new().text(cfg.to_string()).unwrap();
+    assert_eq!(cfg.get_str("port").unwrap(), "8080");
+    assert_eq!(cfg.get_str("server.host").unwrap(), "localhost");
+}
+
+#[test]
+fn test_required_placeholder() {
+    let cfg = Config::new()
+        .text("host = localhost\nport = <REQUIRED>\n[auth]\ntoken = <REQUIRED>")
+        .unwrap();
+
+    match cfg.get::<u16>("port") {
+        Err(ConfigError::Unset(key)) => assert_eq!(key, "port"),
+        i => panic!("expected unset error, got {:?}", i),
+    }
+    assert!(matches!(cfg.get_or("port", 80), Err(ConfigError::Unset(_))));
+    assert_eq!(
+        cfg.section("auth")
+            .unwrap()
+            .get_str("token")
+            .unwrap_err()
+            .to_string(),
+        "Config key `auth.token` is required but not set"
+    );
+    assert_eq!(cfg.unset_keys(), ["port", "auth.token"]);
+    assert_eq!(cfg.get_str("host").unwrap(), "localhost");
+
+    let cfg = Config::new()
+        .required_placeholder("")
+        .text("port = <REQUIRED>")
+        .unwrap();
+    assert_eq!(cfg.get_str("port").unwrap(), "<REQUIRED>");
+    assert!(cfg.unset_keys().is_empty());
+}
+
+#[cfg(feature = "reload")]
+#[test]
+fn test_reload_text() {
+    let mut cfg = Config::new()
+        .text("name = app\n[server]\nhost = localhost\nport = 80\n[db]\nurl = pg://db")
+        .unwrap();
+    cfg.set("name", "changed");
+    let changes = cfg.subscribe(&[]);
+
+    cfg.reload_text(
+        "name = app\n[server]\nhost = localhost\nport = 8080\nworkers = 4\n[db]\nurl = pg://db",
+    )
+    .unwrap();
+    assert_eq!(cfg.get::<u16>("server.port").unwrap(), 8080);
+    assert_eq!(cfg.get_str("name").unwrap(), "app");
+    assert_eq!(cfg.data.lookup("db.url").next().unwrap().value, "pg://db");
+    assert_eq!(cfg.occurrences("url")[0].line, Some(7));
+    let mut keys = std::iter::from_fn(|| changes.try_recv())
+        .flat_map(|i| i.changes)
+        .map(|i| i.key)
+        .collect::<Vec<_>>();
+    keys.sort();
+    assert_eq!(keys, ["name", "server.port", "server.workers"]);
+
+    // Text that can't be parsed leaves the config as is
+    assert!(cfg.reload_text("[broken").is_err());
+    assert_eq!(cfg.get::<u16>("server.port").unwrap(), 8080);
+
+    // Reloading ends up the same as loading the text
+    let lines = [
+        "a = 1",
+        "b = 2",
+        "[x]",
+        "[y]",
+        "a = 3",
+        "; note",
+        "",
+        "c = \"\"\"",
+        "\"\"\"",
+        "d = 4 # comment",
+    ];
+    let mut seed = 0x9e37_79b9_u32;
+    let mut next = || {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        seed as usize
+    };
+    let mut text = Vec::new();
+    let mut cfg = Config::new();
+    for _ in 0..500 {
+        match next() % 3 {
+            0 if !text.is_empty() => {
+                let at = next() % text.len();
+                text.remove(at);
+            }
+            1 if !text.is_empty() => {
+                let at = next() % text.len();
+                text[at] = lines[next() % lines.len()];
+            }
+            _ => {
+                let at = next() % (text.len() + 1);
+                text.insert(at, lines[next() % lines.len()]);
+            }
+        }
+
+        let joined = text.join("\n");
+        let fresh = match Config::new().text(&joined) {
+            Ok(i) => i,
+            Err(_) => {
+                assert!(cfg.reload_text(&joined).is_err());
+                continue;
+            }
+        };
+        cfg.reload_text(&joined).unwrap();
+        assert_eq!(cfg.data, fresh.data, "{:?}", joined);
+        assert_eq!(cfg.to_string(), fresh.to_string());
+        for i in fresh.data.iter() {
+            assert_eq!(
+                cfg.data.lookup(&i.name()).count(),
+                fresh.data.lookup(&i.name()).count()
+            );
+            assert_eq!(cfg.occurrences(&i.name()), fresh.occurrences(&i.name()));
+        }
+    }
+}
+
+#[cfg(feature = "file")]
+#[test]
+fn test_discover() {
+    let dir = std::env::temp_dir().join(format!("scp-discover-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("discover_test")).unwrap();
+    std::fs::write(dir.join("discover_test/discover_test.cfg"), "a = 1\nb = 1").unwrap();
+    std::fs::write("discover_test.cfg", "b = 2").unwrap();
+    std::env::set_var("XDG_CONFIG_HOME", &dir);
+
+    let paths = Config::search_paths("discover_test.cfg");
+    let cfg = Config::new().discover("discover_test.cfg");
+    std::fs::remove_file("discover_test.cfg").unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    let cfg = cfg.unwrap();
+
+    assert!(paths.contains(&dir.join("discover_test/discover_test.cfg")));
+    assert_eq!(
+        paths.last().unwrap(),
+        std::path::Path::new("discover_test.cfg")
+    );
+    assert_eq!(cfg.get_str("a").unwrap(), "1");
+    assert_eq!(cfg.get_str("b").unwrap(), "2");
+    let used = cfg
+        .load_report()
+        .sources
+        .iter()
+        .map(|i| i.source.to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        used,
+        [
+            dir.join("discover_test/discover_test.cfg")
+                .display()
+                .to_string(),
+            "discover_test.cfg".to_owned()
+        ]
+    );
+    assert!(Config::new()
+        .discover("discover_none.cfg")
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_get_opt() {
+    let cfg = Config::new()
+        .text("port = 8080\nworkers = many\ntoken = <REQUIRED>\n[db]\nhost = localhost")
+        .unwrap();
+
+    assert_eq!(cfg.get_opt::<u16>("port").unwrap(), Some(8080));
+    assert_eq!(
+        cfg.get_opt::<String>("db.host").unwrap().as_deref(),
+        Some("localhost")
+    );
+    assert_eq!(cfg.get_opt::<u16>("timeout").unwrap(), None);
+    assert!(matches!(
+        cfg.get_opt::<u16>("workers"),
+        Err(ConfigError::InvalidType(..))
+    ));
+    assert!(matches!(
+        cfg.get_opt::<String>("token"),
+        Err(ConfigError::Unset(_))
+    ));
+}
+
+#[test]
+fn test_net_addrs() {
+    use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+
+    let cfg = Config::new()
+        .text("ip = 10.0.0.1\nip6 = fe80::1\nlisten = :8080\nv4 = 127.0.0.1:80\nv6 = [::1]:443\nhost = localhost:80\nbad_port = 1.2.3.4:99999")
+        .unwrap();
+
+    assert_eq!(cfg.get_ip("ip").unwrap(), IpAddr::from([10, 0, 0, 1]));
+    assert_eq!(
+        cfg.get_ip("ip6").unwrap(),
+        IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1))
+    );
+    assert_eq!(
+        cfg.get_socket_addr("listen").unwrap(),
+        SocketAddr::from(([0, 0, 0, 0], 8080))
+    );
+    assert_eq!(
+        cfg.get_socket_addr("v4").unwrap(),
+        SocketAddr::from(([127, 0, 0, 1], 80))
+    );
+    assert_eq!(
+        cfg.get_socket_addr("v6").unwrap(),
+        SocketAddr::from((Ipv6Addr::LOCALHOST, 443))
+    );
+
+    // Hostnames are not looked up
+    match cfg.get_socket_addr("host") {
+        Err(ConfigError::InvalidType(key, value, target)) => {
+            assert_eq!((key.as_str(), value.as_str()), ("host", "localhost:80"));
+            assert_eq!(target, "socket address");
+        }
+        i => panic!("{:?}", i),
+    }
+    assert!(matches!(
+        cfg.get_socket_addr("bad_port"),
+        Err(ConfigError::InvalidType(..))
+    ));
+    assert!(
+        matches!(cfg.get_ip("listen"), Err(ConfigError::InvalidType(key, ..)) if key == "listen")
+    );
+    assert!(matches!(cfg.get_ip("missing"), Err(ConfigError::NoItem)));
+}
+
+#[test]
+fn test_subset() {
+    let defaults = Config::new().text("[db]\ntimeout = 30").unwrap();
+    let cfg = Config::new()
+        .text("name = app\n[db]\nhost = localhost\nport = 5432\nport = 6432\npassword = hunter2\n[internal]\ntoken = abc\n[internals]\nkept = 1")
+        .unwrap()
+        .with_defaults(defaults);
+
+    let db = cfg.subset(&["DB.Host", "db.port", "db.timeout", "db.missing"]);
+    assert_eq!(db.get_str("db.host").unwrap(), "localhost");
+    assert_eq!(db.get::<u16>("db.port").unwrap(), 6432);
+    assert_eq!(db.get::<u32>("db.timeout").unwrap(), 30);
+    assert!(matches!(
+        db.get_str("db.password"),
+        Err(ConfigError::NoItem)
+    ));
+    assert!(matches!(db.get_str("name"), Err(ConfigError::NoItem)));
+    assert_eq!(db.data.len(), 3);
+
+    let public = cfg.without_prefix("internal.");
+    assert!(matches!(
+        public.get_str("internal.token"),
+        Err(ConfigError::NoItem)
+    ));
+    assert_eq!(public.get_str("internals.kept").unwrap(), "1");
+    assert_eq!(public.get_str("db.password").unwrap(), "hunter2");
+    #[cfg(feature = "file")]
+    assert!(public.save().is_err());
+}
+
+#[cfg(feature = "file")]
+#[test]
+fn test_fingerprint() {
+    let path = std::env::temp_dir().join("simple_config_parser_fingerprint.cfg");
+    std::fs::write(&path, "[server]\nport = 80\nhost = localhost\n").unwrap();
+    let cfg = Config::new().file(&path).unwrap();
+    let fingerprint = cfg.fingerprint();
+
+    // The same contents are not parsed again, so here `port` is not read as a comment
+    let other = Config::builder().comment_chars(&['p']).build().unwrap();
+    assert!(other.file_if_changed(&path, &cfg).unwrap().is_none());
+
+    // Touched and reformatted, but the same values
+    std::fs::write(
+        &path,
+        "# Comment\n[Server]\nhost=localhost\nport = \"80\"\n",
+    )
+    .unwrap();
+    assert!(Config::new()
+        .file_if_changed(&path, &cfg)
+        .unwrap()
+        .is_none());
+
+    std::fs::write(&path, "[server]\nport = 8080\nhost = localhost\n").unwrap();
+    let new = Config::new().file_if_changed(&path, &cfg).unwrap().unwrap();
+    assert_eq!(new.get::<u16>("server.port").unwrap(), 8080);
+    assert_ne!(new.fingerprint(), fingerprint);
+
+    // Files that include others are parsed to see if those changed
+    let included = std::env::temp_dir().join("simple_config_parser_fingerprint_inc.cfg");
+    std::fs::write(&included, "port = 80").unwrap();
+    std::fs::write(&path, format!("!include {}", included.display())).unwrap();
+    let cfg = Config::new().file(&path).unwrap();
+    std::fs::write(&included, "port = 8080").unwrap();
+    let new = Config::new().file_if_changed(&path, &cfg).unwrap().unwrap();
+    assert_eq!(new.get::<u16>("port").unwrap(), 8080);
+    std::fs::remove_file(&included).unwrap();
+
+    // Moving a key to another section is a change
+    let a = Config::new().text("a = 1\n[b]\nc = 2").unwrap();
+    let b = Config::new().text("[b]\na = 1\nc = 2").unwrap();
+    assert_ne!(a.fingerprint(), b.fingerprint());
+
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(
+        Config::new().file_if_changed(&path, &cfg),
+        Err(ConfigError::FileNotFound(_))
+    ));
+}
+
+#[test]
+fn test_number_forms() {
+    let text = "big = 1_000_000\nhex = 0xff\nneg = -0x10\noct = 0o755\nbin = 0b1010_1010\nprice = 4,99\nlist = 1_0 | 0x2 | 3\nbad = 1__0\nvalue = 1_000\n[server]\nport = 8_080";
+
+    // Off by default
+    let cfg = Config::new().text(text).unwrap();
+    assert!(matches!(
+        cfg.get::<u32>("big"),
+        Err(ConfigError::InvalidType(..))
+    ));
+    assert!(matches!(
+        cfg.get::<u32>("hex"),
+        Err(ConfigError::InvalidType(..))
+    ));
+
+    let cfg = Config::new()
+        .number_underscores(true)
+        .number_prefixes(true)
+        .decimal_comma(true)
+        .list_delimiter('|')
+        .text(text)
+        .unwrap();
+    assert_eq!(cfg.get::<u32>("big").unwrap(), 1_000_000);
+    assert_eq!(cfg.get::<u8>("hex").unwrap(), 255);
+    assert_eq!(cfg.get::<i32>("neg").unwrap(), -16);
+    assert_eq!(cfg.get::<u32>("oct").unwrap(), 0o755);
+    assert_eq!(cfg.get::<u8>("bin").unwrap(), 0b1010_1010);
+    assert_eq!(cfg.get::<f64>("price").unwrap(), 4.99);
+    assert_eq!(cfg.get_vec::<u8>("list").unwrap(), [10, 2, 3]);
+    assert_eq!(
+        cfg.section("server").unwrap().get::<u16>("port").unwrap(),
+        8080
+    );
+    assert_eq!(cfg.get_range::<u16, _>("server.port", 1..).unwrap(), 8080);
+
+    // Values are only rewritten if they can't be read as they are
+    assert_eq!(cfg.get_str("value").unwrap(), "1_000");
+    assert_eq!(cfg.get::<String>("hex").unwrap(), "0xff");
+    match cfg.get::<u32>("bad") {
+        Err(ConfigError::InvalidType(key, value, _)) => {
+            assert_eq!((key, value), ("bad".to_owned(), "1__0".to_owned()))
+        }
+        i => panic!("{:?}", i),
+    }
+    assert!(matches!(
+        cfg.get::<u8>("big"),
+        Err(ConfigError::InvalidType(..))
+    ));
+    assert!(matches!(
+        cfg.get::<u8>("neg"),
+        Err(ConfigError::InvalidType(..))
+    ));
+}
+
+#[cfg(feature = "reload")]
+#[test]
+fn test_extends() {
+    let dir = std::env::temp_dir().join(format!("scp-extends-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("hosts")).unwrap();
+    std::fs::write(
+        dir.join("common.cfg"),
+        "log = info\nworkers = 4\n[db]\nhost = localhost\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("base.cfg"),
+        "extends = common.cfg\nworkers = 8\n[db]\nport = 5432\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("hosts/web.cfg"),
+        "extends = ../base.cfg\nlog = debug\n[db]\nhost = db.internal\n",
+    )
+    .unwrap();
+
+    let mut cfg = Config::new().file(dir.join("hosts/web.cfg")).unwrap();
+    assert_eq!(cfg.get_str("log").unwrap(), "debug");
+    assert_eq!(cfg.get::<u8>("workers").unwrap(), 8);
+    assert_eq!(cfg.get_str("db.host").unwrap(), "db.internal");
+    assert_eq!(cfg.get::<u16>("db.port").unwrap(), 5432);
+    let sources = &cfg.load_report().sources;
+    assert_eq!(sources.len(), 3);
+    assert!(sources[0].source.ends_with("common.cfg"));
+    assert!(sources[2].source.ends_with("web.cfg"));
+
+    // Reloading reads the bases again
+    std::fs::write(
+        dir.join("common.cfg"),
+        "log = info\nworkers = 4\ntimeout = 30\n",
+    )
+    .unwrap();
+    cfg.reload().unwrap();
+    assert_eq!(cfg.get::<u32>("timeout").unwrap(), 30);
+    assert_eq!(cfg.get::<u8>("workers").unwrap(), 8);
+
+    // Loops are found, even through other paths to the same file
+    std::fs::write(dir.join("common.cfg"), "extends = hosts/../hosts/web.cfg\n").unwrap();
+    match Config::new().file(dir.join("hosts/web.cfg")) {
+        Err(e @ ConfigError::ExtendsCycle(_)) => {
+            assert_eq!(e.id(), "extends-cycle");
+            let text = e.to_string();
+            assert!(text.starts_with("Config files extend each other in a loop: "));
+            assert_eq!(text.matches(" -> ").count(), 3);
+        }
+        i => panic!("{:?}", i.map(|_| ())),
+    }
+
+    // Errors name the base that failed
+    std::fs::remove_file(dir.join("common.cfg")).unwrap();
+    match Config::new().file(dir.join("base.cfg")) {
+        Err(ConfigError::Include(file, e)) => {
+            assert!(file.ends_with("common.cfg"));
+            assert!(matches!(*e, ConfigError::FileNotFound(_)));
+        }
+        i => panic!("{:?}", i.map(|_| ())),
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "file")]
+#[test]
+/// Test extends is followed by every way of loading a file
+fn test_extends_file_sources() {
+    let dir = std::env::temp_dir().join(format!("scp-extends-sources-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("base.cfg"), "workers = 4\nlog = info\n").unwrap();
+    std::fs::write(dir.join("app.cfg"), "extends = base.cfg\nlog = debug\n").unwrap();
+    std::fs::write(
+        dir.join("broken.cfg"),
+        "extends = missing.cfg\nlog = warn\n",
+    )
+    .unwrap();
+
+    let cfg = Config::new().file_optional(dir.join("app.cfg"));
+    assert_eq!(cfg.get::<u8>("workers").unwrap(), 4);
+    assert_eq!(cfg.get_str("log").unwrap(), "debug");
+    assert_eq!(cfg.load_report().sources.len(), 2);
+
+    // A base that can't be loaded leaves the config as is
+    let cfg = cfg.file_optional(dir.join("broken.cfg"));
+    assert_eq!(cfg.get_str("log").unwrap(), "debug");
+    assert_eq!(cfg.load_report().failures().count(), 1);
+
+    let cfg = Config::new()
+        .file_timeout(dir.join("app.cfg"), Duration::from_secs(5))
+        .unwrap();
+    assert_eq!(cfg.get::<u8>("workers").unwrap(), 4);
+    assert_eq!(cfg.get_str("log").unwrap(), "debug");
+    assert_eq!(cfg.load_report().sources.len(), 2);
+    assert!(Config::new()
+        .file_timeout(dir.join("broken.cfg"), Duration::from_secs(5))
+        .is_err());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(feature = "file")]
+#[test]
+fn test_save_normalized() {
+    let path = std::env::temp_dir().join(format!("scp-normalized-{}.cfg", std::process::id()));
+    std::fs::write(
+        &path,
+        "; Settings\nzeta=1\nalpha   =  \"two words \"\n[web]\nport = 80\n\n[api]\nkey = \"a # b\"\n[web]\nhost = x\nport = 8080\n",
+    )
+    .unwrap();
+    let mut cfg = Config::new().file(&path).unwrap();
+    cfg.set("beta", "line 1\nline 2");
+
+    cfg.save_normalized(true).unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        text,
+        "alpha = \"two words \"\nbeta = \"\"\"\nline 1\nline 2\"\"\"\nzeta = 1\n\n[api]\nkey = \"a # b\"\n\n[web]\nhost = x\nport = 8080\n"
+    );
+
+    // Reading it back gives the same values, and saving again changes nothing
+    let cfg = Config::new().file(&path).unwrap();
+    assert_eq!(cfg.get_str("beta").unwrap(), "line 1\nline 2");
+    assert_eq!(cfg.get_str("api.key").unwrap(), "a # b");
+    assert_eq!(cfg.to_normalized(true), text);
+    assert_eq!(cfg.to_normalized(false), text);
+
+    assert!(matches!(
+        Config::new().save_normalized(true),
+        Err(ConfigError::NoFileDefined)
+    ));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "file")]
+#[test]
+fn test_files() {
+    let dir = std::env::temp_dir().join(format!("scp-files-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("base.cfg"), "name = app\nport = 80").unwrap();
+    std::fs::write(dir.join("db.cfg"), "[db]\nhost = localhost").unwrap();
+    std::fs::write(dir.join("local.cfg"), "extends = base.cfg\nport = 8080").unwrap();
+
+    let files = ["base.cfg", "db.cfg", "local.cfg"].map(|i| dir.join(i));
+    let cfg = Config::new().files(&files).unwrap();
+    assert_eq!(cfg.get_str("name").unwrap(), "app");
+    assert_eq!(cfg.get::<u16>("port").unwrap(), 8080);
+    assert_eq!(cfg.get_str("db.host").unwrap(), "localhost");
+    assert_eq!(cfg.load_report().sources.len(), 4);
+
+    // Every failure is returned, not only the first
+    let files = ["missing.cfg", "base.cfg", "other.cfg"].map(|i| dir.join(i));
+    match Config::new().files(&files) {
+        Err(ConfigError::Sources(i)) => {
+            assert_eq!(i.len(), 2);
+            assert!(i[0].0.ends_with("missing.cfg"));
+            assert!(i[1].0.ends_with("other.cfg"));
+            assert!(matches!(i[1].1, ConfigError::FileNotFound(_)));
+        }
+        _ => panic!("Expected ConfigError::Sources"),
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_transform() {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let cfg = Config::new()
+        .transform(|_, value| Some(value.trim_matches('\'').trim().to_owned()))
+        .transform(move |key, value| {
+            tx.send(key.to_owned()).unwrap();
+            (key != "db.password").then(|| value.to_owned())
+        })
+        .processor("db.*", |i: &str| Ok(i.to_uppercase()))
+        .text("name = ' app '\n[db]\nhost = local ; Comment\npassword = hunter2\nport = 5432")
+        .unwrap();
+    let seen = rx.try_iter().collect::<Vec<_>>();
+
+    assert_eq!(seen, ["name", "db.host", "db.password", "db.port"]);
+    assert_eq!(cfg.get_str("name").unwrap(), "app");
+    assert_eq!(cfg.get_str("db.host").unwrap(), "LOCAL");
+    assert!(cfg.get_str("db.password").is_err());
+
+    // Lines of keys that were left out are kept when saving, but not loaded again
+    let text = cfg.to_string();
+    assert_eq!(
+        text,
+        "name = ' app '\n[db]\nhost = local ; Comment\npassword = hunter2\nport = 5432\n"
+    );
+
+    let cfg = Config::new()
+        .transform(|key, value| (!key.starts_with("secret")).then(|| value.to_owned()))
+        .json(r#"{"secret": "x", "port": 80}"#)
+        .unwrap();
+    assert_eq!(cfg.get::<u16>("port").unwrap(), 80);
+    assert!(cfg.get_str("secret").is_err());
+}
@@ -0,0 +1,113 @@
+#![cfg(feature = "testing")]
+
+use simple_config_parser::testing::{self, ConfigGen, Mutation, TempFile, ValueKind};
+use simple_config_parser::{assert_key_eq, Config, ConfigError};
+
+#[test]
+/// Test building a fake config from typed values
+fn test_fake_config() {
+    let cfg = Config::fake()
+        .with("port", 8080)
+        .with("pi", 3.5)
+        .with_in("db", "user", "admin")
+        .with_in("db", "pool", 4)
+        .build();
+
+    assert_key_eq!(cfg, "port", 8080);
+    assert_key_eq!(cfg, "pi", 3.5);
+    let db = cfg.section("db").unwrap();
+    assert_eq!(db.get_str("user").unwrap(), "admin");
+    assert_eq!(db.get::<u8>("pool").unwrap(), 4);
+}
+
+#[test]
+#[should_panic]
+/// Test assert_key_eq fails on the wrong value
+fn test_assert_key_eq_fails() {
+    let cfg = Config::fake().with("port", 8080).build();
+    assert_key_eq!(cfg, "port", 80);
+}
+
+#[test]
+/// Test writing configs to temp files and reading them back
+fn test_temp_file_roundtrip() {
+    let fake = Config::fake().with("hello", "world");
+    let file = fake.write_temp().unwrap();
+    let path = file.path().to_owned();
+
+    assert!(path.exists());
+    assert_key_eq!(file.load().unwrap(), "hello", String::from("world"));
+
+    drop(file);
+    assert!(!path.exists());
+
+    let other = TempFile::new("a = 1").unwrap();
+    assert_ne!(other.path(), path);
+    assert_key_eq!(testing::roundtrip("a = 1").unwrap(), "a", 1);
+}
+
+/// Generator used by the tests below
+fn test_gen(seed: u64) -> ConfigGen {
+    ConfigGen::new(seed)
+        .key("port", ValueKind::Int(1, 65535))
+        .key("ratio", ValueKind::Float(0.0, 1.0))
+        .optional_key("debug", ValueKind::Bool)
+        .key("mode", ValueKind::OneOf(vec!["fast".into(), "safe".into()]))
+        .key_in("db", "user", ValueKind::Str(1, 8))
+}
+
+#[test]
+/// Test generated configs are valid and depend only on the seed
+fn test_generator_valid() {
+    let mut gen = test_gen(7);
+    let mut other = test_gen(7);
+
+    for _ in 0..100 {
+        let text = gen.generate();
+        assert_eq!(text, other.generate());
+
+        let cfg = Config::new().text(&text).unwrap();
+        assert!((1..=65535).contains(&cfg.get::<i64>("port").unwrap()));
+        assert!((0.0..=1.0).contains(&cfg.get::<f64>("ratio").unwrap()));
+        assert!(["fast", "safe"].contains(&cfg.get_str("mode").unwrap().as_str()));
+        assert!(cfg.get::<bool>("debug").is_ok() || cfg.get_str("debug").is_err());
+
+        let user = cfg.section("db").unwrap().get_str("user").unwrap();
+        assert!((1..=8).contains(&user.len()));
+    }
+
+    assert_ne!(test_gen(1).generate(), test_gen(2).generate());
+}
+
+#[test]
+/// Test adversarial configs are broken in the way they say
+fn test_generator_adversarial() {
+    let mut gen = test_gen(3);
+
+    for _ in 0..200 {
+        let (text, mutation) = gen.generate_adversarial();
+        let cfg = Config::new().text(&text);
+
+        match mutation {
+            Mutation::MissingEquals(_) | Mutation::UnclosedSection(_) => {
+                assert!(matches!(cfg, Err(ConfigError::InvalidConfig)), "{}", text)
+            }
+            Mutation::MissingKey(key) => {
+                let cfg = cfg.unwrap();
+                assert!(cfg.get_str(&key).is_err(), "{}", text);
+            }
+            Mutation::BadValue(key) => {
+                let cfg = cfg.unwrap();
+                let value = cfg.get_str(&key).unwrap();
+                let valid = match key.as_str() {
+                    "port" => value.parse::<i64>().is_ok_and(|i| (1..=65535).contains(&i)),
+                    "ratio" => value.parse::<f64>().is_ok_and(|i| (0.0..=1.0).contains(&i)),
+                    "debug" => value.parse::<bool>().is_ok(),
+                    "mode" => ["fast", "safe"].contains(&value.as_str()),
+                    _ => (1..=8).contains(&value.len()),
+                };
+                assert!(!valid, "{}", text);
+            }
+        }
+    }
+}
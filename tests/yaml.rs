@@ -0,0 +1,81 @@
+#![cfg(feature = "yaml")]
+use simple_config_parser::{Config, ConfigError};
+
+#[test]
+/// Test flattening YAML mappings
+fn test_yaml() {
+    let text = r#"
+# A ConfigMap
+---
+apiVersion: v1
+data:
+  log_level: "debug\tinfo"
+  name: 'it''s'
+  empty:
+  none: ~
+  url: http://localhost:8080/#top
+  ports: [80, 443]
+  hosts:
+  - a
+  - b # Comment
+  servers:
+    - host: x
+      port: 1
+    - host: y
+  script: |-
+    echo hi
+      indented
+
+    done
+  folded: >
+    one
+    two
+...
+ignored: true
+"#;
+
+    let cfg = Config::new().yaml(text).unwrap();
+    assert_eq!(cfg.get_str("apiversion").unwrap(), "v1");
+    assert_eq!(cfg.get_str("data.log_level").unwrap(), "debug\tinfo");
+    assert_eq!(cfg.get_str("data.name").unwrap(), "it's");
+    assert!(cfg.get_str("data.empty").is_err());
+    assert!(cfg.get_str("data.none").is_err());
+    assert_eq!(
+        cfg.get_str("data.url").unwrap(),
+        "http://localhost:8080/#top"
+    );
+    assert_eq!(cfg.get_vec::<u16>("data.ports").unwrap(), [80, 443]);
+    assert_eq!(cfg.get_vec::<String>("data.hosts").unwrap(), ["a", "b"]);
+    assert_eq!(cfg.index_len("data.servers"), 2);
+    assert_eq!(
+        cfg.get_indexed::<u16>("data.servers", 0, "port").unwrap(),
+        1
+    );
+    assert_eq!(
+        cfg.get_indexed::<String>("data.servers", 1, "host")
+            .unwrap(),
+        "y"
+    );
+    assert_eq!(
+        cfg.get_str("data.script").unwrap(),
+        "echo hi\n  indented\n\ndone"
+    );
+    assert_eq!(cfg.get_str("data.folded").unwrap(), "one two\n");
+    assert!(cfg.get_str("ignored").is_err());
+
+    assert!(Config::new().yaml("").unwrap().data.is_empty());
+    for i in [
+        "- a\n- b",
+        "a: {b: 1}",
+        "a: 1\n  b: 2",
+        "a: *ref",
+        "just text",
+        "a: \"open",
+    ] {
+        assert!(
+            matches!(Config::new().yaml(i), Err(ConfigError::InvalidConfig)),
+            "{}",
+            i
+        );
+    }
+}